@@ -0,0 +1,23 @@
+INSERT INTO permissions (name, description) VALUES
+    ('roles:read', 'List roles and their assignment history'),
+    ('roles:write', 'Create, edit and delete roles')
+ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO role_permissions (role_id, permission)
+SELECT roles.id, permissions.name
+FROM roles CROSS JOIN permissions
+WHERE roles.name = 'admin' AND permissions.name IN ('roles:read', 'roles:write')
+ON CONFLICT DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS role_assignment_events (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    role_id UUID NOT NULL,
+    role_name TEXT NOT NULL,
+    action TEXT NOT NULL,
+    actor_id UUID REFERENCES users (id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_role_assignment_events_role_id ON role_assignment_events (role_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_role_assignment_events_user_id ON role_assignment_events (user_id, created_at DESC);
@@ -0,0 +1,68 @@
+CREATE TABLE IF NOT EXISTS groups (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS group_user_members (
+    group_id UUID NOT NULL REFERENCES groups (id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (group_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_group_user_members_user_id ON group_user_members (user_id);
+
+-- `group_id` contains `member_group_id`; members of the child are members of the parent.
+CREATE TABLE IF NOT EXISTS group_group_members (
+    group_id UUID NOT NULL REFERENCES groups (id) ON DELETE CASCADE,
+    member_group_id UUID NOT NULL REFERENCES groups (id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (group_id, member_group_id),
+    CHECK (group_id <> member_group_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_group_group_members_member ON group_group_members (member_group_id);
+
+CREATE TABLE IF NOT EXISTS group_roles (
+    group_id UUID NOT NULL REFERENCES groups (id) ON DELETE CASCADE,
+    role_id UUID NOT NULL REFERENCES roles (id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (group_id, role_id)
+);
+
+-- Direct groups of a user plus every group that (transitively) contains them.
+CREATE OR REPLACE FUNCTION user_effective_groups(p_user_id UUID)
+RETURNS TABLE (group_id UUID)
+LANGUAGE SQL STABLE AS $$
+    WITH RECURSIVE effective (group_id) AS (
+        SELECT group_user_members.group_id FROM group_user_members WHERE group_user_members.user_id = p_user_id
+        UNION
+        SELECT group_group_members.group_id
+        FROM group_group_members JOIN effective ON group_group_members.member_group_id = effective.group_id
+    )
+    SELECT effective.group_id FROM effective
+$$;
+
+-- Directly assigned roles plus roles inherited through effective groups.
+CREATE OR REPLACE FUNCTION user_effective_role_ids(p_user_id UUID)
+RETURNS TABLE (role_id UUID)
+LANGUAGE SQL STABLE AS $$
+    SELECT user_roles.role_id FROM user_roles WHERE user_roles.user_id = p_user_id
+    UNION
+    SELECT group_roles.role_id FROM group_roles
+    WHERE group_roles.group_id IN (SELECT user_effective_groups.group_id FROM user_effective_groups(p_user_id))
+$$;
+
+INSERT INTO permissions (name, description) VALUES
+    ('groups:read', 'List groups, their members and effective memberships'),
+    ('groups:write', 'Create, edit and delete groups and manage their members')
+ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO role_permissions (role_id, permission)
+SELECT roles.id, permissions.name
+FROM roles CROSS JOIN permissions
+WHERE roles.name = 'admin' AND permissions.name IN ('groups:read', 'groups:write')
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,23 @@
+-- Set when the user has proven ownership of `email`, e.g. by accepting an invitation sent to it.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_verified_at TIMESTAMPTZ;
+
+CREATE TABLE IF NOT EXISTS invitations (
+    id UUID PRIMARY KEY,
+    org_id UUID NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    email TEXT NOT NULL,
+    role TEXT NOT NULL,
+    invited_by UUID REFERENCES users (id) ON DELETE SET NULL,
+    -- Rotated on every resend so that previously issued links stop working.
+    nonce UUID NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    accepted_at TIMESTAMPTZ,
+    accepted_by UUID REFERENCES users (id) ON DELETE SET NULL,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- At most one open invitation per address and organization.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_invitations_open
+    ON invitations (org_id, email)
+    WHERE accepted_at IS NULL AND revoked_at IS NULL;
@@ -0,0 +1,43 @@
+-- NULL means the grant is permanent; otherwise it stops counting at `expires_at` and is
+-- swept (with an `expired` history event) shortly after.
+ALTER TABLE user_roles ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_user_roles_expires_at ON user_roles (expires_at) WHERE expires_at IS NOT NULL;
+
+ALTER TABLE role_assignment_events ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+
+-- Expired grants never count, even before the sweeper has removed them. Callers that must
+-- not rely on temporary grants (e.g. the last-admin check) pass `p_include_temporary = FALSE`.
+DROP FUNCTION IF EXISTS user_effective_role_ids(UUID);
+
+CREATE FUNCTION user_effective_role_ids(p_user_id UUID, p_include_temporary BOOLEAN DEFAULT TRUE)
+RETURNS TABLE (role_id UUID)
+LANGUAGE SQL STABLE AS $$
+    SELECT user_roles.role_id FROM user_roles
+    WHERE user_roles.user_id = p_user_id
+      AND (user_roles.expires_at IS NULL OR (p_include_temporary AND user_roles.expires_at > NOW()))
+    UNION
+    SELECT group_roles.role_id FROM group_roles
+    WHERE group_roles.group_id IN (SELECT user_effective_groups.group_id FROM user_effective_groups(p_user_id))
+$$;
+
+CREATE TABLE IF NOT EXISTS elevation_requests (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    role_id UUID NOT NULL REFERENCES roles (id) ON DELETE CASCADE,
+    duration_minutes BIGINT NOT NULL CHECK (duration_minutes > 0),
+    reason TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    decided_by UUID REFERENCES users (id) ON DELETE SET NULL,
+    decision_note TEXT,
+    decided_at TIMESTAMPTZ,
+    grant_expires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_elevation_requests_status ON elevation_requests (status, created_at DESC);
+
+-- One open request per user and role.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_elevation_requests_pending
+    ON elevation_requests (user_id, role_id)
+    WHERE status = 'pending';
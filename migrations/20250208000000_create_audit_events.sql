@@ -0,0 +1,21 @@
+-- Append-only record of security-relevant events. User ids are not foreign keys so that
+-- the trail outlives the accounts it mentions.
+CREATE TABLE IF NOT EXISTS audit_events (
+    id UUID PRIMARY KEY,
+    event_type TEXT NOT NULL,
+    actor_id UUID,
+    impersonator_id UUID,
+    target_user_id UUID,
+    ip TEXT,
+    user_agent TEXT,
+    request_id TEXT,
+    before JSONB,
+    after JSONB,
+    metadata JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_events_created_at ON audit_events (created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_events_actor_id ON audit_events (actor_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_events_target_user_id ON audit_events (target_user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_events_event_type ON audit_events (event_type, created_at DESC);
@@ -0,0 +1,12 @@
+INSERT INTO permissions (name, description) VALUES
+    ('audit:read', 'Search and export the audit log')
+ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO role_permissions (role_id, permission)
+SELECT roles.id, permissions.name
+FROM roles CROSS JOIN permissions
+WHERE roles.name = 'admin' AND permissions.name = 'audit:read'
+ON CONFLICT DO NOTHING;
+
+CREATE INDEX IF NOT EXISTS idx_audit_events_login_ip ON audit_events (target_user_id, ip)
+    WHERE event_type = 'auth.login_succeeded';
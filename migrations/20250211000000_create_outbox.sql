@@ -0,0 +1,22 @@
+-- Domain events written in the same transaction as the change they describe and
+-- published afterwards by the relay.
+CREATE TABLE IF NOT EXISTS outbox (
+    position BIGSERIAL PRIMARY KEY,
+    event_id UUID NOT NULL UNIQUE,
+    event_type TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Hidden from relays until then: a retry delay, or the lease of the relay publishing it.
+    available_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    published_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_outbox_unpublished ON outbox (position) WHERE published_at IS NULL;
+
+-- Webhook fan-out is now fed by the relay, which may publish an event twice.
+ALTER TABLE webhook_deliveries ADD COLUMN IF NOT EXISTS event_id UUID;
+UPDATE webhook_deliveries SET event_id = (payload ->> 'id')::UUID WHERE event_id IS NULL;
+ALTER TABLE webhook_deliveries ALTER COLUMN event_id SET NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_webhook_deliveries_event ON webhook_deliveries (endpoint_id, event_id);
@@ -0,0 +1,8 @@
+-- Optional profile attributes, exposed under their OpenID Connect claim names.
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS display_name TEXT,
+    ADD COLUMN IF NOT EXISTS given_name TEXT,
+    ADD COLUMN IF NOT EXISTS family_name TEXT,
+    ADD COLUMN IF NOT EXISTS locale TEXT,
+    ADD COLUMN IF NOT EXISTS timezone TEXT,
+    ADD COLUMN IF NOT EXISTS avatar_url TEXT;
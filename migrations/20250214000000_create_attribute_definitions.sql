@@ -0,0 +1,28 @@
+-- Custom per-user data, keyed by the name of an attribute definition.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS attributes JSONB NOT NULL DEFAULT '{}';
+
+CREATE TABLE IF NOT EXISTS attribute_definitions (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    -- string, number, boolean or date; fixed once defined.
+    attribute_type TEXT NOT NULL,
+    description TEXT,
+    is_required BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Unique and indexed attributes get an expression index on users.attributes,
+    -- created and dropped along with the flag.
+    is_unique BOOLEAN NOT NULL DEFAULT FALSE,
+    is_indexed BOOLEAN NOT NULL DEFAULT FALSE,
+    user_editable BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO permissions (name, description) VALUES
+    ('attributes:manage', 'Define the custom attributes users carry')
+ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO role_permissions (role_id, permission)
+SELECT roles.id, permissions.name
+FROM roles CROSS JOIN permissions
+WHERE roles.name = 'admin' AND permissions.name = 'attributes:manage'
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,26 @@
+-- Email changes requested by users themselves. The new address only replaces the old one
+-- once it is confirmed; the old address receives a link that revokes the change.
+CREATE TABLE IF NOT EXISTS email_changes (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    old_email TEXT NOT NULL,
+    new_email TEXT NOT NULL,
+    -- Embedded in both signed links; a change can only be acted on with its own links.
+    nonce UUID NOT NULL,
+    -- Deadline for confirming the new address.
+    expires_at TIMESTAMPTZ NOT NULL,
+    -- Deadline for reverting from the old address, before or after confirmation.
+    revert_expires_at TIMESTAMPTZ NOT NULL,
+    confirmed_at TIMESTAMPTZ,
+    -- Set when the change is withdrawn before confirmation: reverted from the old address
+    -- or superseded by a newer request.
+    cancelled_at TIMESTAMPTZ,
+    -- Set when a confirmed change is undone from the old address.
+    reverted_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- At most one unconfirmed change per user; a new request cancels the previous one.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_email_changes_open
+    ON email_changes (user_id)
+    WHERE confirmed_at IS NULL AND cancelled_at IS NULL;
@@ -0,0 +1,17 @@
+-- `is_active` becomes an account status with a reason and, for suspensions, an end.
+-- Accounts deactivated so far keep that status.
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS status TEXT NOT NULL DEFAULT 'active'
+        CHECK (status IN ('pending_verification', 'active', 'suspended', 'locked', 'deactivated', 'deleted')),
+    ADD COLUMN IF NOT EXISTS status_reason TEXT,
+    ADD COLUMN IF NOT EXISTS status_until TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS status_changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ADD CONSTRAINT users_status_until_check CHECK (status_until IS NULL OR status = 'suspended');
+
+UPDATE users SET status = 'deactivated', status_changed_at = updated_at WHERE NOT is_active;
+
+ALTER TABLE users DROP COLUMN is_active;
+
+CREATE INDEX IF NOT EXISTS idx_users_status ON users (status);
+-- Suspensions the sweep has to lift.
+CREATE INDEX IF NOT EXISTS idx_users_status_until ON users (status_until) WHERE status_until IS NOT NULL;
@@ -0,0 +1,10 @@
+-- Status in force for a user, mirroring `AccountStatus::effective_state`: a suspension
+-- whose end has passed already counts as active, before the sweep records it.
+CREATE OR REPLACE FUNCTION user_effective_status(p_status TEXT, p_status_until TIMESTAMPTZ)
+RETURNS TEXT
+LANGUAGE SQL STABLE AS $$
+    SELECT CASE
+        WHEN p_status = 'suspended' AND p_status_until <= NOW() THEN 'active'
+        ELSE p_status
+    END
+$$;
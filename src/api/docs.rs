@@ -1,6 +1,47 @@
-use crate::api::dto::auth::{LoginRequest, LoginResponse, RefreshRequest, RegisterRequest};
-use crate::api::dto::user::{UpdateProfileRequest, UpdateUserRequest, UserResponse};
-use crate::api::handlers::{auth, users};
+use crate::api::dto::attribute::{
+    AttributeDefinitionResponse, CreateAttributeRequest, UpdateAttributeRequest,
+};
+use crate::api::dto::audit::{ActivityResponse, AuditEventPage, AuditEventResponse, ExportFormat};
+use crate::api::dto::auth::{
+    EmailChangeTokenRequest, ImpersonationResponse, LoginRequest, LoginResponse, RefreshRequest,
+    RegisterRequest, SetupPasswordRequest,
+};
+use crate::api::dto::elevation::{
+    CreateElevationRequest, DecideElevationRequest, ElevationResponse,
+};
+use crate::api::dto::group::{
+    CreateGroupRequest, GroupMembersResponse, GroupResponse, UpdateGroupRequest,
+};
+use crate::api::dto::invitation::{
+    AcceptInvitationRequest, CreateInvitationRequest, InvitationResponse,
+};
+use crate::api::dto::org::{
+    AddMemberRequest, CreateOrganizationRequest, MemberResponse, OrganizationResponse,
+    UpdateMemberRequest, UpdateOrganizationRequest,
+};
+use crate::api::dto::role::{
+    CreateRoleRequest, RoleAssignmentEventResponse, RoleResponse, UpdateRoleRequest,
+};
+use crate::api::dto::scim::{
+    ScimEmail, ScimGroup, ScimGroupList, ScimGroupRequest, ScimMember, ScimMemberRef, ScimMeta,
+    ScimUser, ScimUserList, ScimUserRequest,
+};
+use crate::api::dto::user::{
+    AvatarUploadForm, BulkActionParam, BulkUserRequest, BulkUserResponse, BulkUserResultResponse,
+    ChangeStatusRequest, ImportFormatParam, ImportReportResponse, ImportRowErrorResponse,
+    UpdateProfileRequest, UpdateUserRequest, UserPageResponse, UserResponse,
+};
+use crate::api::dto::webhook::{
+    CreateWebhookRequest, UpdateWebhookRequest, WebhookDeliveryResponse, WebhookResponse,
+};
+use crate::api::handlers::{
+    attributes, audit, auth, elevations, events, groups, invitations, orgs, roles, scim, users,
+    webhooks,
+};
+use crate::domain::{
+    AccountState, AttributeType, AuditEventType, BulkUserOutcome, DeliveryStatus, DomainEventType,
+    ElevationStatus, InvitationStatus, OrgRole, Permission, RoleAssignmentAction,
+};
 use utoipa::openapi::security::{Http, HttpAuthScheme, SecurityScheme};
 use utoipa::{Modify, OpenApi};
 
@@ -11,27 +52,184 @@ use utoipa::{Modify, OpenApi};
         auth::login_handler,
         auth::refresh_handler,
         auth::logout_handler,
+        auth::setup_password_handler,
+        auth::confirm_email_handler,
+        auth::revert_email_handler,
         users::get_me_handler,
         users::update_me_handler,
+        users::upload_avatar_handler,
+        users::get_avatar_handler,
         users::list_users_handler,
+        users::import_users_handler,
+        users::export_users_handler,
+        users::bulk_update_users_handler,
         users::get_user_handler,
         users::update_user_handler,
-        users::deactivate_user_handler
+        users::deactivate_user_handler,
+        users::change_user_status_handler,
+        users::deactivate_me_handler,
+        users::impersonate_user_handler,
+        roles::list_roles_handler,
+        roles::create_role_handler,
+        roles::get_role_handler,
+        roles::update_role_handler,
+        roles::delete_role_handler,
+        roles::role_history_handler,
+        roles::assign_role_handler,
+        roles::unassign_role_handler,
+        orgs::list_orgs_handler,
+        orgs::create_org_handler,
+        orgs::get_org_handler,
+        orgs::update_org_handler,
+        orgs::delete_org_handler,
+        orgs::switch_org_handler,
+        orgs::list_members_handler,
+        orgs::add_member_handler,
+        orgs::update_member_handler,
+        orgs::remove_member_handler,
+        elevations::request_elevation_handler,
+        elevations::list_elevations_handler,
+        elevations::my_elevations_handler,
+        elevations::approve_elevation_handler,
+        elevations::deny_elevation_handler,
+        invitations::list_invitations_handler,
+        invitations::create_invitation_handler,
+        invitations::resend_invitation_handler,
+        invitations::revoke_invitation_handler,
+        invitations::accept_invitation_handler,
+        groups::list_groups_handler,
+        groups::create_group_handler,
+        groups::get_group_handler,
+        groups::update_group_handler,
+        groups::delete_group_handler,
+        groups::list_group_members_handler,
+        groups::add_group_user_handler,
+        groups::remove_group_user_handler,
+        groups::add_group_group_handler,
+        groups::remove_group_group_handler,
+        groups::assign_group_role_handler,
+        groups::unassign_group_role_handler,
+        groups::my_groups_handler,
+        groups::user_groups_handler,
+        audit::search_audit_events_handler,
+        audit::export_audit_events_handler,
+        audit::my_activity_handler,
+        webhooks::list_webhooks_handler,
+        webhooks::create_webhook_handler,
+        webhooks::get_webhook_handler,
+        webhooks::update_webhook_handler,
+        webhooks::delete_webhook_handler,
+        webhooks::list_deliveries_handler,
+        webhooks::redeliver_handler,
+        attributes::list_attributes_handler,
+        attributes::create_attribute_handler,
+        attributes::get_attribute_handler,
+        attributes::update_attribute_handler,
+        attributes::delete_attribute_handler,
+        events::event_stream_handler,
+        scim::list_users_handler,
+        scim::create_user_handler,
+        scim::get_user_handler,
+        scim::replace_user_handler,
+        scim::patch_user_handler,
+        scim::delete_user_handler,
+        scim::list_groups_handler,
+        scim::create_group_handler,
+        scim::get_group_handler,
+        scim::replace_group_handler,
+        scim::patch_group_handler,
+        scim::delete_group_handler,
+        scim::service_provider_config_handler,
+        scim::resource_types_handler,
+        scim::schemas_handler
     ),
     components(
         schemas(
             RegisterRequest,
             LoginRequest,
             RefreshRequest,
+            SetupPasswordRequest,
+            EmailChangeTokenRequest,
             LoginResponse,
+            ImpersonationResponse,
             UserResponse,
+            UserPageResponse,
+            ImportFormatParam,
+            ImportReportResponse,
+            ImportRowErrorResponse,
+            BulkActionParam,
+            BulkUserRequest,
+            BulkUserResponse,
+            BulkUserResultResponse,
+            BulkUserOutcome,
             UpdateProfileRequest,
-            UpdateUserRequest
+            AvatarUploadForm,
+            UpdateUserRequest,
+            ChangeStatusRequest,
+            AccountState,
+            Permission,
+            RoleResponse,
+            CreateRoleRequest,
+            UpdateRoleRequest,
+            RoleAssignmentEventResponse,
+            RoleAssignmentAction,
+            OrganizationResponse,
+            CreateOrganizationRequest,
+            UpdateOrganizationRequest,
+            MemberResponse,
+            AddMemberRequest,
+            UpdateMemberRequest,
+            OrgRole,
+            ElevationResponse,
+            ElevationStatus,
+            CreateElevationRequest,
+            DecideElevationRequest,
+            InvitationResponse,
+            InvitationStatus,
+            CreateInvitationRequest,
+            AcceptInvitationRequest,
+            GroupResponse,
+            GroupMembersResponse,
+            CreateGroupRequest,
+            UpdateGroupRequest,
+            AuditEventResponse,
+            AuditEventPage,
+            AuditEventType,
+            ExportFormat,
+            ActivityResponse,
+            WebhookResponse,
+            CreateWebhookRequest,
+            UpdateWebhookRequest,
+            WebhookDeliveryResponse,
+            DomainEventType,
+            DeliveryStatus,
+            AttributeDefinitionResponse,
+            AttributeType,
+            CreateAttributeRequest,
+            UpdateAttributeRequest,
+            ScimMeta,
+            ScimEmail,
+            ScimUser,
+            ScimUserRequest,
+            ScimUserList,
+            ScimMember,
+            ScimMemberRef,
+            ScimGroup,
+            ScimGroupRequest,
+            ScimGroupList
         )
     ),
     tags(
         (name = "auth", description = "Authentication endpoints"),
-        (name = "users", description = "User management endpoints")
+        (name = "users", description = "User management endpoints"),
+        (name = "roles", description = "Role and permission management endpoints"),
+        (name = "orgs", description = "Organization and membership endpoints"),
+        (name = "groups", description = "Group and nested membership endpoints"),
+        (name = "audit", description = "Audit log search and export endpoints"),
+        (name = "webhooks", description = "Outgoing webhook endpoints and deliveries"),
+        (name = "events", description = "Live stream of user lifecycle events"),
+        (name = "attributes", description = "Custom user attribute definitions"),
+        (name = "scim", description = "SCIM 2.0 provisioning, authenticated by the SCIM token")
     ),
     modifiers(&SecurityAddon)
 )]
@@ -46,5 +244,9 @@ impl Modify for SecurityAddon {
             "bearer_auth",
             SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
         );
+        components.add_security_scheme(
+            "scim_token",
+            SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+        );
     }
 }
@@ -0,0 +1,68 @@
+use crate::domain::{AttributeDefinition, AttributeType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct AttributeDefinitionResponse {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub attribute_type: AttributeType,
+    pub description: Option<String>,
+    pub required: bool,
+    pub unique: bool,
+    pub indexed: bool,
+    pub user_editable: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<AttributeDefinition> for AttributeDefinitionResponse {
+    fn from(value: AttributeDefinition) -> Self {
+        Self {
+            id: value.id.to_string(),
+            name: value.name,
+            attribute_type: value.attribute_type,
+            description: value.description,
+            required: value.required,
+            unique: value.unique,
+            indexed: value.indexed,
+            user_editable: value.user_editable,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct CreateAttributeRequest {
+    /// Lowercase letters, digits and underscores, starting with a letter.
+    #[validate(length(min = 1, max = 40))]
+    pub name: String,
+    #[serde(rename = "type")]
+    pub attribute_type: AttributeType,
+    #[validate(length(max = 256))]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub unique: bool,
+    /// Allows filtering the user list on this attribute.
+    #[serde(default)]
+    pub indexed: bool,
+    /// Lets users set the attribute on themselves; otherwise they can only read it.
+    #[serde(default)]
+    pub user_editable: bool,
+}
+
+/// Name and type cannot change once defined.
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct UpdateAttributeRequest {
+    #[validate(length(max = 256))]
+    pub description: Option<String>,
+    pub required: Option<bool>,
+    pub unique: Option<bool>,
+    pub indexed: Option<bool>,
+    pub user_editable: Option<bool>,
+}
@@ -0,0 +1,121 @@
+use crate::domain::{ActivityEntry, AuditEvent, AuditEventType, Page};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct AuditEventResponse {
+    pub id: String,
+    pub event_type: AuditEventType,
+    pub actor_id: Option<String>,
+    pub impersonator_id: Option<String>,
+    pub target_user_id: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+    #[schema(value_type = Option<Object>)]
+    pub before: Option<Value>,
+    #[schema(value_type = Option<Object>)]
+    pub after: Option<Value>,
+    #[schema(value_type = Option<Object>)]
+    pub metadata: Option<Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AuditEvent> for AuditEventResponse {
+    fn from(value: AuditEvent) -> Self {
+        Self {
+            id: value.id.to_string(),
+            event_type: value.event_type,
+            actor_id: value.actor_id.map(|id| id.to_string()),
+            impersonator_id: value.impersonator_id.map(|id| id.to_string()),
+            target_user_id: value.target_user_id.map(|id| id.to_string()),
+            ip: value.ip,
+            user_agent: value.user_agent,
+            request_id: value.request_id,
+            before: value.before,
+            after: value.after,
+            metadata: value.metadata,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct AuditEventPage {
+    pub items: Vec<AuditEventResponse>,
+    /// Pass as `cursor` to fetch the next page; absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+impl From<Page<AuditEvent>> for AuditEventPage {
+    fn from(value: Page<AuditEvent>) -> Self {
+        Self {
+            items: value
+                .items
+                .into_iter()
+                .map(AuditEventResponse::from)
+                .collect(),
+            next_cursor: value.next_cursor.map(|cursor| cursor.encode()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuditSearchQuery {
+    pub actor_id: Option<Uuid>,
+    pub target_user_id: Option<Uuid>,
+    pub event_type: Option<AuditEventType>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Ndjson,
+    Csv,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+/// An entry of the caller's own security activity.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ActivityResponse {
+    pub id: String,
+    pub event_type: AuditEventType,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    /// Successful login from an address not seen in any earlier successful login.
+    pub new_ip: bool,
+    /// The action was taken by an administrator impersonating you.
+    pub impersonated: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ActivityEntry> for ActivityResponse {
+    fn from(value: ActivityEntry) -> Self {
+        Self {
+            id: value.event.id.to_string(),
+            event_type: value.event.event_type,
+            ip: value.event.ip,
+            user_agent: value.event.user_agent,
+            new_ip: value.new_ip,
+            impersonated: value.event.impersonator_id.is_some(),
+            created_at: value.event.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityQuery {
+    pub limit: Option<i64>,
+}
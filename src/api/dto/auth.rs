@@ -1,4 +1,5 @@
 use crate::api::dto::user::UserResponse;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
@@ -20,6 +21,22 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct SetupPasswordRequest {
+    /// Token from the account setup link.
+    #[validate(length(min = 10))]
+    pub token: String,
+    #[validate(length(min = 8))]
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct EmailChangeTokenRequest {
+    /// Token from the confirmation or revert link of an email change.
+    #[validate(length(min = 10))]
+    pub token: String,
+}
+
 #[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
 pub struct RefreshRequest {
     #[validate(length(min = 10))]
@@ -32,3 +49,12 @@ pub struct LoginResponse {
     pub refresh_token: String,
     pub user: UserResponse,
 }
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ImpersonationResponse {
+    /// Access token for `user` carrying an `act` claim that names the admin.
+    pub access_token: String,
+    pub expires_at: DateTime<Utc>,
+    pub actor_id: String,
+    pub user: UserResponse,
+}
@@ -0,0 +1,60 @@
+use crate::domain::{ElevationRequest, ElevationStatus};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ElevationResponse {
+    pub id: String,
+    pub user_id: String,
+    pub role_id: String,
+    pub role_name: String,
+    pub duration_minutes: i64,
+    pub reason: String,
+    pub status: ElevationStatus,
+    pub decided_by: Option<String>,
+    pub decision_note: Option<String>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub grant_expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ElevationRequest> for ElevationResponse {
+    fn from(value: ElevationRequest) -> Self {
+        Self {
+            id: value.id.to_string(),
+            user_id: value.user_id.to_string(),
+            role_id: value.role_id.to_string(),
+            role_name: value.role_name,
+            duration_minutes: value.duration_minutes,
+            reason: value.reason,
+            status: value.status,
+            decided_by: value.decided_by.map(|id| id.to_string()),
+            decision_note: value.decision_note,
+            decided_at: value.decided_at,
+            grant_expires_at: value.grant_expires_at,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct CreateElevationRequest {
+    pub role_id: String,
+    #[validate(range(min = 1, max = 10080))]
+    pub duration_minutes: i64,
+    #[validate(length(min = 1, max = 500))]
+    pub reason: String,
+}
+
+#[derive(Debug, Default, Deserialize, Validate, utoipa::ToSchema)]
+pub struct DecideElevationRequest {
+    #[validate(length(max = 500))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ElevationQuery {
+    pub status: Option<ElevationStatus>,
+    pub limit: Option<i64>,
+}
@@ -0,0 +1,7 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct EventStreamQuery {
+    /// Comma-separated event types; all types when absent.
+    pub types: Option<String>,
+}
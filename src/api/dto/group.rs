@@ -0,0 +1,59 @@
+use crate::api::dto::user::UserResponse;
+use crate::domain::{Group, GroupMembers};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct GroupResponse {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub roles: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Group> for GroupResponse {
+    fn from(value: Group) -> Self {
+        Self {
+            id: value.id.to_string(),
+            name: value.name,
+            description: value.description,
+            roles: value.roles,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct GroupMembersResponse {
+    pub users: Vec<UserResponse>,
+    pub groups: Vec<GroupResponse>,
+}
+
+impl From<GroupMembers> for GroupMembersResponse {
+    fn from(value: GroupMembers) -> Self {
+        Self {
+            users: value.users.into_iter().map(UserResponse::from).collect(),
+            groups: value.groups.into_iter().map(GroupResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct CreateGroupRequest {
+    #[validate(length(min = 1, max = 64))]
+    pub name: String,
+    #[validate(length(max = 256))]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct UpdateGroupRequest {
+    #[validate(length(min = 1, max = 64))]
+    pub name: Option<String>,
+    #[validate(length(max = 256))]
+    pub description: Option<String>,
+}
@@ -0,0 +1,65 @@
+use crate::app::services::invitation_service::IssuedInvitation;
+use crate::domain::{Invitation, InvitationStatus, OrgRole};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct InvitationResponse {
+    pub id: String,
+    pub org_id: String,
+    pub email: String,
+    pub role: OrgRole,
+    pub status: InvitationStatus,
+    pub invited_by: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    /// Signed accept link; only returned when the invitation is created or resent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Invitation> for InvitationResponse {
+    fn from(value: Invitation) -> Self {
+        Self {
+            id: value.id.to_string(),
+            org_id: value.org_id.to_string(),
+            status: value.status(),
+            email: value.email,
+            role: value.role,
+            invited_by: value.invited_by.map(|id| id.to_string()),
+            expires_at: value.expires_at,
+            url: None,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+impl From<IssuedInvitation> for InvitationResponse {
+    fn from(value: IssuedInvitation) -> Self {
+        Self {
+            url: Some(value.url),
+            ..Self::from(value.invitation)
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct CreateInvitationRequest {
+    #[validate(email)]
+    pub email: String,
+    pub role: OrgRole,
+}
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct AcceptInvitationRequest {
+    #[validate(length(min = 10))]
+    pub token: String,
+    /// Required when no account exists for the invited email yet.
+    #[validate(length(min = 3, max = 32))]
+    pub username: Option<String>,
+    #[validate(length(min = 8))]
+    pub password: String,
+}
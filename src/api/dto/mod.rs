@@ -1,2 +1,12 @@
+pub mod attribute;
+pub mod audit;
 pub mod auth;
-pub mod user;
\ No newline at end of file
+pub mod elevation;
+pub mod event;
+pub mod group;
+pub mod invitation;
+pub mod org;
+pub mod role;
+pub mod scim;
+pub mod user;
+pub mod webhook;
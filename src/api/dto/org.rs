@@ -0,0 +1,74 @@
+use crate::api::dto::user::UserResponse;
+use crate::domain::{Member, OrgRole, Organization};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct OrganizationResponse {
+    pub id: String,
+    pub name: String,
+    pub slug: String,
+    /// The caller's role in this organization.
+    pub role: Option<OrgRole>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl OrganizationResponse {
+    pub fn new(value: Organization, role: Option<OrgRole>) -> Self {
+        Self {
+            id: value.id.to_string(),
+            name: value.name,
+            slug: value.slug,
+            role,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct CreateOrganizationRequest {
+    #[validate(length(min = 1, max = 128))]
+    pub name: String,
+    #[validate(length(min = 2, max = 48))]
+    pub slug: String,
+}
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct UpdateOrganizationRequest {
+    #[validate(length(min = 1, max = 128))]
+    pub name: Option<String>,
+    #[validate(length(min = 2, max = 48))]
+    pub slug: Option<String>,
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct MemberResponse {
+    pub user: UserResponse,
+    pub role: OrgRole,
+    pub joined_at: DateTime<Utc>,
+}
+
+impl From<Member> for MemberResponse {
+    fn from(value: Member) -> Self {
+        Self {
+            user: UserResponse::from(value.user),
+            role: value.role,
+            joined_at: value.joined_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct AddMemberRequest {
+    #[validate(email)]
+    pub email: String,
+    pub role: OrgRole,
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct UpdateMemberRequest {
+    pub role: OrgRole,
+}
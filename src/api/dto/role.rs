@@ -0,0 +1,87 @@
+use crate::domain::{Permission, Role, RoleAssignmentAction, RoleAssignmentEvent};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct RoleResponse {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub is_builtin: bool,
+    pub permissions: Vec<Permission>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Role> for RoleResponse {
+    fn from(value: Role) -> Self {
+        Self {
+            id: value.id.to_string(),
+            name: value.name,
+            description: value.description,
+            is_builtin: value.is_builtin,
+            permissions: value.permissions,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct CreateRoleRequest {
+    #[validate(length(min = 2, max = 32))]
+    pub name: String,
+    #[validate(length(max = 256))]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub permissions: Vec<Permission>,
+}
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct UpdateRoleRequest {
+    #[validate(length(min = 2, max = 32))]
+    pub name: Option<String>,
+    #[validate(length(max = 256))]
+    pub description: Option<String>,
+    pub permissions: Option<Vec<Permission>>,
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct RoleAssignmentEventResponse {
+    pub id: String,
+    pub user_id: String,
+    pub role_id: String,
+    pub role_name: String,
+    pub action: RoleAssignmentAction,
+    pub actor_id: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<RoleAssignmentEvent> for RoleAssignmentEventResponse {
+    fn from(value: RoleAssignmentEvent) -> Self {
+        Self {
+            id: value.id.to_string(),
+            user_id: value.user_id.to_string(),
+            role_id: value.role_id.to_string(),
+            role_name: value.role_name,
+            action: value.action,
+            actor_id: value.actor_id.map(|id| id.to_string()),
+            expires_at: value.expires_at,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct HistoryQuery {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct AssignRoleQuery {
+    /// Makes the grant time-bound; omitted for a permanent assignment.
+    #[validate(range(min = 1, max = 10080))]
+    pub expires_in_minutes: Option<i64>,
+}
@@ -0,0 +1,317 @@
+use crate::app::services::scim_service::{primary_email, ScimGroupInput, ScimUserInput};
+use crate::domain::scim::PatchOperation;
+use crate::domain::{DomainError, Group, GroupMembers, User};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+pub const SCIM_CONTENT_TYPE: &str = "application/scim+json";
+pub const USER_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+pub const GROUP_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:Group";
+pub const LIST_RESPONSE_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+pub const ERROR_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+const SERVICE_PROVIDER_CONFIG_SCHEMA: &str =
+    "urn:ietf:params:scim:schemas:core:2.0:ServiceProviderConfig";
+const RESOURCE_TYPE_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:ResourceType";
+const SCHEMA_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:Schema";
+
+/// Largest page a SCIM list returns.
+pub const SCIM_MAX_RESULTS: i64 = 200;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimListQuery {
+    pub filter: Option<String>,
+    /// 1-based index of the first result.
+    pub start_index: Option<i64>,
+    pub count: Option<i64>,
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimMeta {
+    pub resource_type: String,
+    pub created: DateTime<Utc>,
+    pub last_modified: DateTime<Utc>,
+    pub location: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ScimEmail {
+    pub value: String,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub primary: bool,
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUser {
+    pub schemas: Vec<String>,
+    pub id: String,
+    pub user_name: String,
+    pub display_name: String,
+    pub active: bool,
+    pub emails: Vec<ScimEmail>,
+    pub meta: ScimMeta,
+}
+
+impl From<User> for ScimUser {
+    fn from(value: User) -> Self {
+        let active = value.is_active();
+        Self {
+            schemas: vec![USER_SCHEMA.to_string()],
+            id: value.id.to_string(),
+            display_name: value
+                .profile
+                .display_name
+                .unwrap_or_else(|| value.username.clone()),
+            user_name: value.username,
+            active,
+            emails: vec![ScimEmail {
+                value: value.email,
+                kind: Some("work".to_string()),
+                primary: true,
+            }],
+            meta: ScimMeta {
+                resource_type: "User".to_string(),
+                created: value.created_at,
+                last_modified: value.updated_at,
+                location: format!("/scim/v2/Users/{}", value.id),
+            },
+        }
+    }
+}
+
+/// Body of `POST` and `PUT /scim/v2/Users`. Attributes not listed here are ignored.
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUserRequest {
+    pub user_name: String,
+    #[serde(default)]
+    pub emails: Vec<ScimEmail>,
+    /// Defaults to `true`.
+    pub active: Option<bool>,
+    pub password: Option<String>,
+}
+
+impl TryFrom<ScimUserRequest> for ScimUserInput {
+    type Error = DomainError;
+
+    fn try_from(value: ScimUserRequest) -> Result<Self, Self::Error> {
+        let emails = serde_json::to_value(&value.emails)
+            .map_err(|err| DomainError::Internal(err.to_string()))?;
+        Ok(Self {
+            email: primary_email(&emails)?,
+            user_name: value.user_name,
+            active: value.active.unwrap_or(true),
+            password: value.password,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ScimMember {
+    pub value: String,
+    pub display: String,
+    /// `User` or `Group`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(rename = "$ref")]
+    pub reference: String,
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroup {
+    pub schemas: Vec<String>,
+    pub id: String,
+    pub display_name: String,
+    pub members: Vec<ScimMember>,
+    pub meta: ScimMeta,
+}
+
+impl From<(Group, GroupMembers)> for ScimGroup {
+    fn from((group, members): (Group, GroupMembers)) -> Self {
+        let users = members.users.into_iter().map(|user| ScimMember {
+            value: user.id.to_string(),
+            display: user.username,
+            kind: "User".to_string(),
+            reference: format!("/scim/v2/Users/{}", user.id),
+        });
+        let groups = members.groups.into_iter().map(|member| ScimMember {
+            value: member.id.to_string(),
+            display: member.name,
+            kind: "Group".to_string(),
+            reference: format!("/scim/v2/Groups/{}", member.id),
+        });
+
+        Self {
+            schemas: vec![GROUP_SCHEMA.to_string()],
+            id: group.id.to_string(),
+            display_name: group.name,
+            members: users.chain(groups).collect(),
+            meta: ScimMeta {
+                resource_type: "Group".to_string(),
+                created: group.created_at,
+                last_modified: group.updated_at,
+                location: format!("/scim/v2/Groups/{}", group.id),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct ScimMemberRef {
+    pub value: Uuid,
+}
+
+/// Body of `POST` and `PUT /scim/v2/Groups`.
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroupRequest {
+    pub display_name: String,
+    #[serde(default)]
+    pub members: Vec<ScimMemberRef>,
+}
+
+impl From<ScimGroupRequest> for ScimGroupInput {
+    fn from(value: ScimGroupRequest) -> Self {
+        Self {
+            name: value.display_name,
+            members: value
+                .members
+                .into_iter()
+                .map(|member| member.value)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScimPatchRequest {
+    #[serde(rename = "Operations")]
+    pub operations: Vec<PatchOperation>,
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[aliases(ScimUserList = ScimListResponse<ScimUser>, ScimGroupList = ScimListResponse<ScimGroup>)]
+pub struct ScimListResponse<T> {
+    pub schemas: Vec<String>,
+    pub total_results: i64,
+    pub start_index: i64,
+    pub items_per_page: i64,
+    #[serde(rename = "Resources")]
+    pub resources: Vec<T>,
+}
+
+impl<T> ScimListResponse<T> {
+    pub fn new(resources: Vec<T>, total_results: i64, start_index: i64) -> Self {
+        Self {
+            schemas: vec![LIST_RESPONSE_SCHEMA.to_string()],
+            total_results,
+            start_index,
+            items_per_page: resources.len() as i64,
+            resources,
+        }
+    }
+}
+
+pub fn service_provider_config() -> Value {
+    json!({
+        "schemas": [SERVICE_PROVIDER_CONFIG_SCHEMA],
+        "documentationUri": "https://datatracker.ietf.org/doc/html/rfc7644",
+        "patch": { "supported": true },
+        "bulk": { "supported": false, "maxOperations": 0, "maxPayloadSize": 0 },
+        "filter": { "supported": true, "maxResults": SCIM_MAX_RESULTS },
+        "changePassword": { "supported": true },
+        "sort": { "supported": false },
+        "etag": { "supported": false },
+        "authenticationSchemes": [{
+            "type": "oauthbearertoken",
+            "name": "Bearer token",
+            "description": "The token configured as SCIM_TOKEN",
+            "primary": true,
+        }],
+        "meta": { "resourceType": "ServiceProviderConfig", "location": "/scim/v2/ServiceProviderConfig" },
+    })
+}
+
+pub fn resource_types() -> Vec<Value> {
+    [("User", "/Users", USER_SCHEMA), ("Group", "/Groups", GROUP_SCHEMA)]
+        .into_iter()
+        .map(|(name, endpoint, schema)| {
+            json!({
+                "schemas": [RESOURCE_TYPE_SCHEMA],
+                "id": name,
+                "name": name,
+                "endpoint": endpoint,
+                "schema": schema,
+                "meta": { "resourceType": "ResourceType", "location": format!("/scim/v2/ResourceTypes/{name}") },
+            })
+        })
+        .collect()
+}
+
+pub fn schemas() -> Vec<Value> {
+    let attribute = |name: &str, kind: &str, required: bool, mutability: &str, uniqueness: &str| {
+        json!({
+            "name": name,
+            "type": kind,
+            "multiValued": false,
+            "required": required,
+            "caseExact": false,
+            "mutability": mutability,
+            "returned": if name == "password" { "never" } else { "default" },
+            "uniqueness": uniqueness,
+        })
+    };
+    let mut emails = attribute("emails", "complex", true, "readWrite", "server");
+    emails["multiValued"] = json!(true);
+    emails["subAttributes"] = json!([
+        attribute("value", "string", true, "readWrite", "server"),
+        attribute("type", "string", false, "readWrite", "none"),
+        attribute("primary", "boolean", false, "readWrite", "none"),
+    ]);
+    let mut members = attribute("members", "complex", false, "readWrite", "none");
+    members["multiValued"] = json!(true);
+    members["subAttributes"] = json!([
+        attribute("value", "string", true, "immutable", "none"),
+        attribute("display", "string", false, "readOnly", "none"),
+        attribute("type", "string", false, "immutable", "none"),
+    ]);
+
+    let schema = |id: &str, name: &str, attributes: Vec<Value>| {
+        json!({
+            "schemas": [SCHEMA_SCHEMA],
+            "id": id,
+            "name": name,
+            "attributes": attributes,
+            "meta": { "resourceType": "Schema", "location": format!("/scim/v2/Schemas/{id}") },
+        })
+    };
+    vec![
+        schema(
+            USER_SCHEMA,
+            "User",
+            vec![
+                attribute("userName", "string", true, "readWrite", "server"),
+                attribute("displayName", "string", false, "readOnly", "none"),
+                attribute("active", "boolean", false, "readWrite", "none"),
+                attribute("password", "string", false, "writeOnly", "none"),
+                emails,
+            ],
+        ),
+        schema(
+            GROUP_SCHEMA,
+            "Group",
+            vec![
+                attribute("displayName", "string", true, "readWrite", "server"),
+                members,
+            ],
+        ),
+    ]
+}
@@ -1,27 +1,77 @@
-use crate::domain::{Role, User};
+use crate::app::services::import_service::{ImportReport, ImportRowError};
+use crate::domain::{
+    AccountState, Attributes, BulkUserOutcome, BulkUserResult, Permission, User, UserProfile,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use validator::Validate;
+use uuid::Uuid;
+use validator::{Validate, ValidateUrl, ValidationError};
 
 #[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct UserResponse {
     pub id: String,
     pub email: String,
     pub username: String,
-    pub role: Role,
+    pub roles: Vec<String>,
+    pub permissions: Vec<Permission>,
+    /// Whether `status` is `active`.
     pub is_active: bool,
+    pub status: AccountState,
+    /// Why the account was suspended, locked or deactivated.
+    pub status_reason: Option<String>,
+    /// When a suspension ends on its own.
+    pub status_until: Option<DateTime<Utc>>,
+    pub status_changed_at: DateTime<Utc>,
+    pub email_verified: bool,
+    /// New address of an email change waiting for confirmation.
+    pub pending_email: Option<String>,
+    /// Display name.
+    pub name: Option<String>,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+    /// BCP 47 language tag.
+    pub locale: Option<String>,
+    /// IANA time zone.
+    pub zoneinfo: Option<String>,
+    /// Avatar URL.
+    pub picture: Option<String>,
+    /// Custom attributes, keyed by attribute name.
+    #[schema(value_type = Object)]
+    pub attributes: Attributes,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 impl From<User> for UserResponse {
     fn from(value: User) -> Self {
+        // An ended suspension shows as active even before the sweep has lifted it.
+        let status = value.status.effective_state(Utc::now());
+        let (status_reason, status_until) = if status == value.status.state {
+            (value.status.reason, value.status.until)
+        } else {
+            (None, None)
+        };
+
         Self {
             id: value.id.to_string(),
             email: value.email,
             username: value.username,
-            role: value.role,
-            is_active: value.is_active,
+            roles: value.roles,
+            permissions: value.permissions,
+            is_active: status == AccountState::Active,
+            status,
+            status_reason,
+            status_until,
+            status_changed_at: value.status.changed_at,
+            email_verified: value.email_verified_at.is_some(),
+            pending_email: value.pending_email,
+            name: value.profile.display_name,
+            given_name: value.profile.given_name,
+            family_name: value.profile.family_name,
+            locale: value.profile.locale,
+            zoneinfo: value.profile.timezone,
+            picture: value.profile.avatar_url,
+            attributes: value.attributes,
             created_at: value.created_at,
             updated_at: value.updated_at,
         }
@@ -30,10 +80,57 @@ impl From<User> for UserResponse {
 
 #[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
 pub struct UpdateProfileRequest {
+    /// A new address is not applied right away: it receives a confirmation link, and the
+    /// current address a link to undo the change. Until then it shows as `pending_email`.
     #[validate(email)]
     pub email: Option<String>,
     #[validate(length(min = 3, max = 32))]
     pub username: Option<String>,
+    /// Profile fields use their OpenID Connect claim names; an empty string clears one.
+    #[validate(length(max = 100))]
+    pub name: Option<String>,
+    #[validate(length(max = 64))]
+    pub given_name: Option<String>,
+    #[validate(length(max = 64))]
+    pub family_name: Option<String>,
+    /// BCP 47 language tag, e.g. `en-US`.
+    #[validate(custom(function = "validate_locale"))]
+    pub locale: Option<String>,
+    /// IANA time zone, e.g. `Europe/Paris`.
+    #[validate(custom(function = "validate_zoneinfo"))]
+    pub zoneinfo: Option<String>,
+    /// `http` or `https` URL of the avatar image.
+    #[validate(length(max = 2048), custom(function = "validate_picture"))]
+    pub picture: Option<String>,
+    /// Custom attributes to set; `null` removes one. Only user-editable attributes are
+    /// accepted, and others are kept as they are.
+    #[schema(value_type = Option<Object>)]
+    pub attributes: Option<Attributes>,
+}
+
+fn validate_locale(value: &str) -> Result<(), ValidationError> {
+    if value.is_empty() || UserProfile::is_valid_locale(value) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("locale").with_message("must be a BCP 47 language tag".into()))
+    }
+}
+
+fn validate_zoneinfo(value: &str) -> Result<(), ValidationError> {
+    if value.is_empty() || UserProfile::is_valid_timezone(value) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("zoneinfo").with_message("must be an IANA time zone".into()))
+    }
+}
+
+fn validate_picture(value: &str) -> Result<(), ValidationError> {
+    let is_web_url = value.starts_with("https://") || value.starts_with("http://");
+    if value.is_empty() || (is_web_url && value.validate_url()) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("picture").with_message("must be an http(s) URL".into()))
+    }
 }
 
 #[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
@@ -42,8 +139,12 @@ pub struct UpdateUserRequest {
     pub email: Option<String>,
     #[validate(length(min = 3, max = 32))]
     pub username: Option<String>,
-    pub role: Option<Role>,
+    /// Switches between `active` and `deactivated`; use `POST /users/{id}/status` for
+    /// the other states.
     pub is_active: Option<bool>,
+    /// Custom attributes to set; `null` removes one. Others are kept as they are.
+    #[schema(value_type = Option<Object>)]
+    pub attributes: Option<Attributes>,
 }
 
 #[derive(Debug, Deserialize, utoipa::ToSchema)]
@@ -51,3 +152,192 @@ pub struct PaginationQuery {
     pub page: Option<i64>,
     pub per_page: Option<i64>,
 }
+
+#[derive(Debug, Default, Deserialize)]
+pub struct UserListQuery {
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    pub search: Option<String>,
+    pub role: Option<String>,
+    pub is_active: Option<bool>,
+    pub status: Option<AccountState>,
+    pub created_from: Option<DateTime<Utc>>,
+    pub created_to: Option<DateTime<Utc>>,
+    pub email_domain: Option<String>,
+    pub sort: Option<String>,
+    /// `next_cursor` of the previous page; selects cursor pagination.
+    pub cursor: Option<String>,
+    /// Page size in cursor pagination; selects it for the first page.
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub include_total: bool,
+}
+
+impl UserListQuery {
+    pub fn has_filters(&self) -> bool {
+        self.search.is_some()
+            || self.role.is_some()
+            || self.is_active.is_some()
+            || self.status.is_some()
+            || self.created_from.is_some()
+            || self.created_to.is_some()
+            || self.email_domain.is_some()
+            || self.sort.is_some()
+    }
+
+    pub fn uses_cursor(&self) -> bool {
+        self.cursor.is_some() || self.limit.is_some()
+    }
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct UserPageResponse {
+    pub items: Vec<UserResponse>,
+    /// Pass as `cursor` to fetch the next page; absent on the last page.
+    pub next_cursor: Option<String>,
+    /// Users matching the filters; only with `include_total=true`.
+    pub total: Option<i64>,
+}
+
+/// Multipart body of `PUT /users/me/avatar`.
+#[derive(Debug, utoipa::ToSchema)]
+pub struct AvatarUploadForm {
+    /// JPEG, PNG or WebP image.
+    #[schema(value_type = String, format = Binary)]
+    pub avatar: Vec<u8>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct AvatarQuery {
+    /// Edge length in pixels; the closest stored size at least this large is served.
+    pub size: Option<u32>,
+}
+
+/// Explicit opt-in for operations that would lock the caller out.
+#[derive(Debug, Default, Deserialize)]
+pub struct ConfirmQuery {
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateUserQuery {
+    /// See [`ConfirmQuery`].
+    #[serde(default)]
+    pub confirm: bool,
+    /// Allows a reserved username or a blocked email domain.
+    #[serde(default)]
+    pub bypass_policy: bool,
+}
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct ChangeStatusRequest {
+    pub status: AccountState,
+    /// Required to suspend or lock an account.
+    #[validate(length(max = 500))]
+    pub reason: Option<String>,
+    /// Ends a suspension automatically; only allowed with `suspended`.
+    pub until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormatParam {
+    Csv,
+    Ndjson,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    pub format: Option<ImportFormatParam>,
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub invite: bool,
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ImportRowErrorResponse {
+    pub line: u64,
+    pub email: Option<String>,
+    pub error: String,
+}
+
+impl From<ImportRowError> for ImportRowErrorResponse {
+    fn from(value: ImportRowError) -> Self {
+        Self {
+            line: value.line,
+            email: value.email,
+            error: value.error,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ImportReportResponse {
+    pub dry_run: bool,
+    pub total: usize,
+    /// Accounts created (or, in a dry run, that would be), including invited ones.
+    pub created: usize,
+    pub invited: usize,
+    /// Rows whose email already has an account.
+    pub skipped: usize,
+    pub failed: usize,
+    pub errors: Vec<ImportRowErrorResponse>,
+}
+
+impl From<ImportReport> for ImportReportResponse {
+    fn from(value: ImportReport) -> Self {
+        Self {
+            dry_run: value.dry_run,
+            total: value.total,
+            created: value.created,
+            invited: value.invited,
+            skipped: value.skipped,
+            failed: value.failed,
+            errors: value
+                .errors
+                .into_iter()
+                .map(ImportRowErrorResponse::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkActionParam {
+    Deactivate,
+    Reactivate,
+    SetRole,
+}
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct BulkUserRequest {
+    pub action: BulkActionParam,
+    pub user_ids: Vec<Uuid>,
+    /// Required for `set_role`: the role that replaces the users' direct roles.
+    #[validate(length(min = 1))]
+    pub role: Option<String>,
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct BulkUserResultResponse {
+    pub user_id: String,
+    pub outcome: BulkUserOutcome,
+}
+
+impl From<BulkUserResult> for BulkUserResultResponse {
+    fn from(value: BulkUserResult) -> Self {
+        Self {
+            user_id: value.user_id.to_string(),
+            outcome: value.outcome,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct BulkUserResponse {
+    /// One entry per distinct id, in request order.
+    pub results: Vec<BulkUserResultResponse>,
+}
@@ -0,0 +1,104 @@
+use crate::domain::{DeliveryStatus, DomainEventType, WebhookDelivery, WebhookEndpoint};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use validator::Validate;
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct WebhookResponse {
+    pub id: String,
+    pub url: String,
+    pub event_types: Vec<DomainEventType>,
+    pub description: Option<String>,
+    pub is_active: bool,
+    pub created_by: Option<String>,
+    /// Signing secret; only returned when the webhook is created.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WebhookResponse {
+    pub fn with_secret(value: WebhookEndpoint) -> Self {
+        Self {
+            secret: Some(value.secret.clone()),
+            ..Self::from(value)
+        }
+    }
+}
+
+impl From<WebhookEndpoint> for WebhookResponse {
+    fn from(value: WebhookEndpoint) -> Self {
+        Self {
+            id: value.id.to_string(),
+            url: value.url,
+            event_types: value.event_types,
+            description: value.description,
+            is_active: value.is_active,
+            created_by: value.created_by.map(|id| id.to_string()),
+            secret: None,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct CreateWebhookRequest {
+    #[validate(url)]
+    pub url: String,
+    pub event_types: Vec<DomainEventType>,
+    #[validate(length(max = 256))]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, utoipa::ToSchema)]
+pub struct UpdateWebhookRequest {
+    #[validate(url)]
+    pub url: Option<String>,
+    pub event_types: Option<Vec<DomainEventType>>,
+    #[validate(length(max = 256))]
+    pub description: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct WebhookDeliveryResponse {
+    pub id: String,
+    pub webhook_id: String,
+    pub event_type: DomainEventType,
+    #[schema(value_type = Object)]
+    pub payload: Value,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    /// When the next attempt is due; meaningful while the delivery is pending.
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryResponse {
+    fn from(value: WebhookDelivery) -> Self {
+        Self {
+            id: value.id.to_string(),
+            webhook_id: value.endpoint_id.to_string(),
+            event_type: value.event_type,
+            payload: value.payload,
+            status: value.status,
+            attempts: value.attempts,
+            next_attempt_at: value.next_attempt_at,
+            last_status_code: value.last_status_code,
+            last_error: value.last_error,
+            delivered_at: value.delivered_at,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveriesQuery {
+    pub limit: Option<i64>,
+}
@@ -1,7 +1,10 @@
-use crate::domain::DomainError;
-use axum::http::StatusCode;
+use crate::api::dto::scim::{ERROR_SCHEMA, SCIM_CONTENT_TYPE};
+use crate::domain::{DomainError, ErrorCode};
+use axum::extract::rejection::JsonRejection;
+use axum::http::{header, StatusCode};
 use axum::response::{IntoResponse, Response};
 use serde::Serialize;
+use serde_json::json;
 
 #[derive(Debug)]
 pub enum AppError {
@@ -12,12 +15,16 @@ pub enum AppError {
     Forbidden(String),
     NotFound(String),
     Conflict(String),
+    Invariant(ErrorCode, String),
+    AccountUnavailable(ErrorCode, String),
     Internal(String),
 }
 
 #[derive(Serialize)]
 struct ErrorBody {
     message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<&'static str>,
 }
 
 impl From<DomainError> for AppError {
@@ -28,6 +35,10 @@ impl From<DomainError> for AppError {
             DomainError::Unauthorized(message) => AppError::Unauthorized(message),
             DomainError::Forbidden(message) => AppError::Forbidden(message),
             DomainError::Conflict(message) => AppError::Conflict(message),
+            DomainError::Invariant(code, message) => AppError::Invariant(code, message),
+            DomainError::AccountUnavailable(code, message) => {
+                AppError::AccountUnavailable(code, message)
+            }
             DomainError::Internal(message) => AppError::Internal(message),
         }
     }
@@ -35,18 +46,104 @@ impl From<DomainError> for AppError {
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        let (status, message) = match self {
-            AppError::Validation(message) => (StatusCode::BAD_REQUEST, message),
-            AppError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
-            AppError::Unauthorized(message) => (StatusCode::UNAUTHORIZED, message),
-            AppError::Forbidden(message) => (StatusCode::FORBIDDEN, message),
-            AppError::NotFound(message) => (StatusCode::NOT_FOUND, message),
-            AppError::Conflict(message) => (StatusCode::CONFLICT, message),
-            AppError::Internal(message) => (StatusCode::INTERNAL_SERVER_ERROR, message),
-            AppError::Domain(domain) => (StatusCode::INTERNAL_SERVER_ERROR, domain.to_string()),
+        let (status, message, code) = match self {
+            AppError::Validation(message) => (StatusCode::BAD_REQUEST, message, None),
+            AppError::BadRequest(message) => (StatusCode::BAD_REQUEST, message, None),
+            AppError::Unauthorized(message) => (StatusCode::UNAUTHORIZED, message, None),
+            AppError::Forbidden(message) => (StatusCode::FORBIDDEN, message, None),
+            AppError::NotFound(message) => (StatusCode::NOT_FOUND, message, None),
+            AppError::Conflict(message) => (StatusCode::CONFLICT, message, None),
+            AppError::Invariant(code, message) => {
+                (StatusCode::CONFLICT, message, Some(code.as_str()))
+            }
+            AppError::AccountUnavailable(code, message) => {
+                (StatusCode::FORBIDDEN, message, Some(code.as_str()))
+            }
+            AppError::Internal(message) => (StatusCode::INTERNAL_SERVER_ERROR, message, None),
+            AppError::Domain(domain) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, domain.to_string(), None)
+            }
         };
 
-        let body = axum::Json(ErrorBody { message });
+        let body = axum::Json(ErrorBody { message, code });
         (status, body).into_response()
     }
-}
\ No newline at end of file
+}
+
+/// Error of the `/scim/v2` endpoints, in the SCIM format (RFC 7644 §3.12).
+#[derive(Debug)]
+pub struct ScimError {
+    pub status: StatusCode,
+    /// Machine-readable detail for some `400` and `409` errors, e.g. `uniqueness`.
+    pub scim_type: Option<&'static str>,
+    pub detail: String,
+}
+
+impl ScimError {
+    pub fn new(status: StatusCode, detail: impl Into<String>) -> Self {
+        Self {
+            status,
+            scim_type: None,
+            detail: detail.into(),
+        }
+    }
+
+    pub fn invalid_filter(detail: impl Into<String>) -> Self {
+        Self {
+            scim_type: Some("invalidFilter"),
+            ..Self::new(StatusCode::BAD_REQUEST, detail)
+        }
+    }
+}
+
+impl From<DomainError> for ScimError {
+    fn from(value: DomainError) -> Self {
+        match value {
+            DomainError::ValidationError(message) => Self {
+                scim_type: Some("invalidValue"),
+                ..Self::new(StatusCode::BAD_REQUEST, message)
+            },
+            DomainError::NotFound(message) => Self::new(StatusCode::NOT_FOUND, message),
+            DomainError::Unauthorized(message) => Self::new(StatusCode::UNAUTHORIZED, message),
+            DomainError::Forbidden(message) => Self::new(StatusCode::FORBIDDEN, message),
+            DomainError::Conflict(message) => Self {
+                scim_type: Some("uniqueness"),
+                ..Self::new(StatusCode::CONFLICT, message)
+            },
+            DomainError::Invariant(_, message) => Self::new(StatusCode::CONFLICT, message),
+            DomainError::AccountUnavailable(_, message) => {
+                Self::new(StatusCode::FORBIDDEN, message)
+            }
+            DomainError::Internal(message) => Self::new(StatusCode::INTERNAL_SERVER_ERROR, message),
+        }
+    }
+}
+
+impl From<JsonRejection> for ScimError {
+    fn from(value: JsonRejection) -> Self {
+        Self {
+            scim_type: Some("invalidSyntax"),
+            ..Self::new(StatusCode::BAD_REQUEST, value.body_text())
+        }
+    }
+}
+
+impl IntoResponse for ScimError {
+    fn into_response(self) -> Response {
+        let mut body = json!({
+            "schemas": [ERROR_SCHEMA],
+            "status": self.status.as_u16().to_string(),
+            "detail": self.detail,
+        });
+        if let Some(scim_type) = self.scim_type {
+            body["scimType"] = json!(scim_type);
+        }
+
+        (
+            self.status,
+            [(header::CONTENT_TYPE, SCIM_CONTENT_TYPE)],
+            body.to_string(),
+        )
+            .into_response()
+    }
+}
@@ -0,0 +1,204 @@
+use crate::api::dto::attribute::{
+    AttributeDefinitionResponse, CreateAttributeRequest, UpdateAttributeRequest,
+};
+use crate::api::error::AppError;
+use crate::app::services::attribute_service::AttributeService;
+use crate::domain::{NewAttributeDefinition, UpdateAttributeDefinition};
+use crate::infra::db::attribute_repo::SqlxAttributeDefinitionRepository;
+use crate::AppState;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::api::middleware::auth::{perm, Authorized, CurrentUser};
+
+pub(crate) fn attribute_service(
+    state: &AppState,
+) -> AttributeService<SqlxAttributeDefinitionRepository> {
+    AttributeService::new(SqlxAttributeDefinitionRepository::new(state.db.clone()))
+}
+
+fn parse_attribute_id(value: &str) -> Result<Uuid, AppError> {
+    Uuid::parse_str(value).map_err(|_| AppError::BadRequest("invalid attribute id".to_string()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/attributes",
+    responses(
+        (status = 200, body = [AttributeDefinitionResponse]),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "attributes"
+)]
+pub async fn list_attributes_handler(
+    State(state): State<AppState>,
+    // Every user may read the schema, to know which attributes they can edit.
+    CurrentUser(_user): CurrentUser,
+) -> Result<impl IntoResponse, AppError> {
+    let definitions = attribute_service(&state).list_definitions().await?;
+    let response: Vec<AttributeDefinitionResponse> = definitions
+        .into_iter()
+        .map(AttributeDefinitionResponse::from)
+        .collect();
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/attributes",
+    request_body = CreateAttributeRequest,
+    responses(
+        (status = 201, body = AttributeDefinitionResponse),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 409, description = "Attribute exists, or users already share values of a unique attribute")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "attributes"
+)]
+pub async fn create_attribute_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::AttributesManage>,
+    Json(payload): Json<CreateAttributeRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    let definition = attribute_service(&state)
+        .create_definition(NewAttributeDefinition {
+            name: payload.name,
+            attribute_type: payload.attribute_type,
+            description: payload.description,
+            required: payload.required,
+            unique: payload.unique,
+            indexed: payload.indexed,
+            user_editable: payload.user_editable,
+        })
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(AttributeDefinitionResponse::from(definition)),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/attributes/{id}",
+    params(
+        ("id" = String, Path, description = "Attribute id")
+    ),
+    responses(
+        (status = 200, body = AttributeDefinitionResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "attributes"
+)]
+pub async fn get_attribute_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::AttributesManage>,
+    Path(attribute_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let attribute_id = parse_attribute_id(&attribute_id)?;
+    let definition = attribute_service(&state)
+        .get_definition(attribute_id)
+        .await?;
+
+    Ok(Json(AttributeDefinitionResponse::from(definition)))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/attributes/{id}",
+    request_body = UpdateAttributeRequest,
+    params(
+        ("id" = String, Path, description = "Attribute id")
+    ),
+    responses(
+        (status = 200, body = AttributeDefinitionResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found"),
+        (status = 409, description = "Users already share values of an attribute made unique")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "attributes"
+)]
+pub async fn update_attribute_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::AttributesManage>,
+    Path(attribute_id): Path<String>,
+    Json(payload): Json<UpdateAttributeRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let attribute_id = parse_attribute_id(&attribute_id)?;
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    let definition = attribute_service(&state)
+        .update_definition(
+            attribute_id,
+            UpdateAttributeDefinition {
+                description: payload.description,
+                required: payload.required,
+                unique: payload.unique,
+                indexed: payload.indexed,
+                user_editable: payload.user_editable,
+            },
+        )
+        .await?;
+
+    Ok(Json(AttributeDefinitionResponse::from(definition)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/attributes/{id}",
+    params(
+        ("id" = String, Path, description = "Attribute id")
+    ),
+    responses(
+        (status = 204, description = "Deleted, along with every user's value"),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "attributes"
+)]
+pub async fn delete_attribute_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::AttributesManage>,
+    Path(attribute_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let attribute_id = parse_attribute_id(&attribute_id)?;
+    attribute_service(&state)
+        .delete_definition(attribute_id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
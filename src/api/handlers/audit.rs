@@ -0,0 +1,221 @@
+use crate::api::dto::audit::{
+    ActivityQuery, ActivityResponse, AuditEventPage, AuditEventResponse, AuditSearchQuery,
+    ExportFormat, ExportQuery,
+};
+use crate::api::error::AppError;
+use crate::api::middleware::auth::{perm, Authorized, CurrentUser};
+use crate::app::services::audit_service::AuditService;
+use crate::domain::{AuditEvent, AuditQuery, Cursor, DomainError};
+use crate::infra::db::audit_repo::SqlxAuditRepository;
+use crate::utils::csv_field;
+use crate::AppState;
+use axum::body::Body;
+use axum::extract::{Query, State};
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::Json;
+use futures_util::{stream, StreamExt};
+
+/// Rows fetched per round trip while streaming an export.
+const EXPORT_BATCH_SIZE: i64 = 500;
+
+const CSV_HEADER: &str = "id,event_type,created_at,actor_id,impersonator_id,target_user_id,ip,user_agent,request_id,before,after,metadata\n";
+
+fn audit_service(state: &AppState) -> AuditService<SqlxAuditRepository> {
+    AuditService::new(SqlxAuditRepository::new(state.db.clone()))
+}
+
+fn to_query(params: AuditSearchQuery, limit: i64) -> Result<AuditQuery, AppError> {
+    let after = params.cursor.as_deref().map(Cursor::decode).transpose()?;
+
+    Ok(AuditQuery {
+        actor_id: params.actor_id,
+        target_user_id: params.target_user_id,
+        event_type: params.event_type,
+        from: params.from,
+        to: params.to,
+        after,
+        limit,
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/audit/events",
+    params(
+        ("actor_id" = Option<String>, Query, description = "Only events performed by this user"),
+        ("target_user_id" = Option<String>, Query, description = "Only events about this user"),
+        ("event_type" = Option<AuditEventType>, Query, description = "Only events of this type"),
+        ("from" = Option<String>, Query, description = "RFC 3339 timestamp, inclusive"),
+        ("to" = Option<String>, Query, description = "RFC 3339 timestamp, exclusive"),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page"),
+        ("limit" = Option<i64>, Query, description = "Items per page (default 50, max 200)")
+    ),
+    responses(
+        (status = 200, body = AuditEventPage),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "audit"
+)]
+pub async fn search_audit_events_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::AuditRead>,
+    Query(params): Query<AuditSearchQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let query = to_query(params, limit)?;
+
+    let page = audit_service(&state).search(&query).await?;
+
+    Ok(Json(AuditEventPage::from(page)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/audit/events/export",
+    params(
+        ("format" = Option<ExportFormat>, Query, description = "`ndjson` (default) or `csv`"),
+        ("actor_id" = Option<String>, Query, description = "Only events performed by this user"),
+        ("target_user_id" = Option<String>, Query, description = "Only events about this user"),
+        ("event_type" = Option<AuditEventType>, Query, description = "Only events of this type"),
+        ("from" = Option<String>, Query, description = "RFC 3339 timestamp, inclusive"),
+        ("to" = Option<String>, Query, description = "RFC 3339 timestamp, exclusive")
+    ),
+    responses(
+        (status = 200, description = "Matching events, newest first", content_type = ["application/x-ndjson", "text/csv"]),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "audit"
+)]
+pub async fn export_audit_events_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::AuditRead>,
+    Query(export): Query<ExportQuery>,
+    Query(params): Query<AuditSearchQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let query = to_query(params, EXPORT_BATCH_SIZE)?;
+    let service = audit_service(&state);
+
+    // The first batch is fetched up front so a bad filter still gets a proper error
+    // status; later batches are pulled as the client reads the body.
+    let first = service.search(&query).await?;
+
+    let (content_type, extension, header_row) = match export.format {
+        ExportFormat::Ndjson => ("application/x-ndjson", "ndjson", String::new()),
+        ExportFormat::Csv => ("text/csv; charset=utf-8", "csv", CSV_HEADER.to_string()),
+    };
+    let format = export.format;
+
+    let batches = stream::unfold(
+        Some((service, query, Some(first))),
+        move |state| async move {
+            let (service, mut query, page) = state?;
+            let page = match page {
+                Some(page) => page,
+                None => match service.search(&query).await {
+                    Ok(page) => page,
+                    Err(err) => {
+                        tracing::error!(error = %err, "audit export aborted");
+                        return Some((Err(err), None));
+                    }
+                },
+            };
+
+            let chunk = render(format, page.items);
+            let next = page.next_cursor.map(|cursor| {
+                query.after = Some(cursor);
+                (service, query, None)
+            });
+
+            Some((Ok(chunk), next))
+        },
+    );
+
+    let body = stream::iter([Ok::<_, DomainError>(header_row)]);
+    let body = Body::from_stream(body.chain(batches));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"audit-events.{extension}\""),
+            ),
+        ],
+        body,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/me/activity",
+    params(
+        ("limit" = Option<i64>, Query, description = "Number of events (default 20, max 100)")
+    ),
+    responses(
+        (status = 200, body = [ActivityResponse]),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "users"
+)]
+pub async fn my_activity_handler(
+    State(state): State<AppState>,
+    CurrentUser(current_user): CurrentUser,
+    Query(params): Query<ActivityQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+
+    let entries = audit_service(&state)
+        .activity(current_user.id, limit)
+        .await?;
+    let response: Vec<ActivityResponse> = entries.into_iter().map(ActivityResponse::from).collect();
+
+    Ok(Json(response))
+}
+
+fn render(format: ExportFormat, events: Vec<AuditEvent>) -> String {
+    let mut chunk = String::new();
+    for event in events.into_iter().map(AuditEventResponse::from) {
+        match format {
+            ExportFormat::Ndjson => {
+                chunk.push_str(&serde_json::to_string(&event).unwrap_or_default());
+            }
+            ExportFormat::Csv => {
+                let json = |value: Option<serde_json::Value>| {
+                    value.map(|value| value.to_string()).unwrap_or_default()
+                };
+                let fields = [
+                    event.id,
+                    event.event_type.to_string(),
+                    event.created_at.to_rfc3339(),
+                    event.actor_id.unwrap_or_default(),
+                    event.impersonator_id.unwrap_or_default(),
+                    event.target_user_id.unwrap_or_default(),
+                    event.ip.unwrap_or_default(),
+                    event.user_agent.unwrap_or_default(),
+                    event.request_id.unwrap_or_default(),
+                    json(event.before),
+                    json(event.after),
+                    json(event.metadata),
+                ];
+                let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+                chunk.push_str(&row.join(","));
+            }
+        }
+        chunk.push('\n');
+    }
+    chunk
+}
@@ -1,10 +1,18 @@
-use crate::api::dto::auth::{LoginRequest, LoginResponse, RefreshRequest, RegisterRequest};
+use crate::api::dto::auth::{
+    EmailChangeTokenRequest, LoginRequest, LoginResponse, RefreshRequest, RegisterRequest,
+    SetupPasswordRequest,
+};
 use crate::api::dto::user::UserResponse;
 use crate::api::error::AppError;
-use crate::app::services::auth_service::{AuthService, LoginInput, RegisterInput};
 use crate::api::middleware::auth::CurrentUser;
+use crate::app::services::auth_service::{AuthService, LoginInput, RegisterInput};
+use crate::app::services::email_change_service::{EmailChangeService, EmailChangeSettings};
+use crate::domain::AuditContext;
 use crate::infra::auth::jwt::JwtService;
+use crate::infra::db::audit_repo::SqlxAuditRepository;
+use crate::infra::db::email_change_repo::SqlxEmailChangeRepository;
 use crate::infra::db::user_repo::SqlxUserRepository;
+use crate::infra::mail::LogMailer;
 use crate::AppState;
 use axum::extract::State;
 use axum::http::StatusCode;
@@ -12,6 +20,28 @@ use axum::response::IntoResponse;
 use axum::Json;
 use validator::Validate;
 
+fn auth_service(state: &AppState) -> AuthService<SqlxUserRepository, SqlxAuditRepository> {
+    AuthService::new(
+        SqlxUserRepository::new(state.db.clone()),
+        SqlxAuditRepository::new(state.db.clone()),
+        JwtService::new(&state.config),
+        state.identity_policy.clone(),
+    )
+}
+
+pub(crate) fn email_change_service(
+    state: &AppState,
+) -> EmailChangeService<SqlxUserRepository, SqlxEmailChangeRepository, LogMailer> {
+    EmailChangeService::new(
+        SqlxUserRepository::new(state.db.clone()),
+        SqlxEmailChangeRepository::new(state.db.clone()),
+        JwtService::new(&state.config),
+        LogMailer,
+        EmailChangeSettings::from_config(&state.config),
+        state.identity_policy.clone(),
+    )
+}
+
 #[utoipa::path(
     post,
     path = "/auth/register",
@@ -25,21 +55,25 @@ use validator::Validate;
 )]
 pub async fn register_handler(
     State(state): State<AppState>,
+    audit: AuditContext,
     Json(payload): Json<RegisterRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     payload
         .validate()
         .map_err(|err| AppError::Validation(err.to_string()))?;
 
-    let repo = SqlxUserRepository::new(state.db.clone());
-    let service = AuthService::new(repo, JwtService::new(&state.config));
+    let service = auth_service(&state);
 
     let user = service
-        .register_user(RegisterInput {
-            email: payload.email,
-            username: payload.username,
-            password: payload.password,
-        })
+        .register_user(
+            RegisterInput {
+                email: payload.email,
+                username: payload.username,
+                password: payload.password,
+                email_verified: false,
+            },
+            &audit,
+        )
         .await?;
 
     let response = UserResponse::from(user);
@@ -59,20 +93,23 @@ pub async fn register_handler(
 )]
 pub async fn login_handler(
     State(state): State<AppState>,
+    audit: AuditContext,
     Json(payload): Json<LoginRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     payload
         .validate()
         .map_err(|err| AppError::Validation(err.to_string()))?;
 
-    let repo = SqlxUserRepository::new(state.db.clone());
-    let service = AuthService::new(repo, JwtService::new(&state.config));
+    let service = auth_service(&state);
 
     let response = service
-        .login(LoginInput {
-            email: payload.email,
-            password: payload.password,
-        })
+        .login(
+            LoginInput {
+                email: payload.email,
+                password: payload.password,
+            },
+            &audit,
+        )
         .await?;
 
     let body = LoginResponse {
@@ -97,16 +134,18 @@ pub async fn login_handler(
 )]
 pub async fn refresh_handler(
     State(state): State<AppState>,
+    audit: AuditContext,
     Json(payload): Json<RefreshRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     payload
         .validate()
         .map_err(|err| AppError::Validation(err.to_string()))?;
 
-    let repo = SqlxUserRepository::new(state.db.clone());
-    let service = AuthService::new(repo, JwtService::new(&state.config));
+    let service = auth_service(&state);
 
-    let response = service.refresh_tokens(payload.refresh_token).await?;
+    let response = service
+        .refresh_tokens(payload.refresh_token, &audit)
+        .await?;
 
     let body = LoginResponse {
         access_token: response.access_token,
@@ -128,6 +167,91 @@ pub async fn refresh_handler(
     ),
     tag = "auth"
 )]
-pub async fn logout_handler(CurrentUser(_user): CurrentUser) -> Result<impl IntoResponse, AppError> {
+pub async fn logout_handler(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    audit: AuditContext,
+) -> Result<impl IntoResponse, AppError> {
+    auth_service(&state).logout(&user, &audit).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/setup-password",
+    request_body = SetupPasswordRequest,
+    responses(
+        (status = 204, description = "Password set; the account can log in"),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Invalid or already used setup link")
+    ),
+    tag = "auth"
+)]
+pub async fn setup_password_handler(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Json(payload): Json<SetupPasswordRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    auth_service(&state)
+        .setup_password(&payload.token, &payload.password, &audit)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/confirm-email",
+    request_body = EmailChangeTokenRequest,
+    responses(
+        (status = 204, description = "The account now uses the new, verified address"),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Invalid, expired or superseded confirmation link"),
+        (status = 409, description = "The new address was taken in the meantime")
+    ),
+    tag = "auth"
+)]
+pub async fn confirm_email_handler(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Json(payload): Json<EmailChangeTokenRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    email_change_service(&state)
+        .confirm(&payload.token, &audit)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/revert-email",
+    request_body = EmailChangeTokenRequest,
+    responses(
+        (status = 204, description = "The change is cancelled, or the account is back on its old address"),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Invalid or expired revert link"),
+        (status = 409, description = "The old address was taken in the meantime")
+    ),
+    tag = "auth"
+)]
+pub async fn revert_email_handler(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Json(payload): Json<EmailChangeTokenRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    email_change_service(&state)
+        .revert(&payload.token, &audit)
+        .await?;
     Ok(StatusCode::NO_CONTENT)
 }
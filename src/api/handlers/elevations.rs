@@ -0,0 +1,207 @@
+use crate::api::dto::elevation::{
+    CreateElevationRequest, DecideElevationRequest, ElevationQuery, ElevationResponse,
+};
+use crate::api::error::AppError;
+use crate::app::services::elevation_service::ElevationService;
+use crate::domain::AuditContext;
+use crate::infra::db::elevation_repo::SqlxElevationRepository;
+use crate::infra::db::role_repo::SqlxRoleRepository;
+use crate::AppState;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::api::middleware::auth::{perm, Authorized, CurrentUser, DirectAuthorized, DirectUser};
+
+fn elevation_service(
+    state: &AppState,
+) -> ElevationService<SqlxElevationRepository, SqlxRoleRepository> {
+    ElevationService::new(
+        SqlxElevationRepository::new(state.db.clone()),
+        SqlxRoleRepository::new(state.db.clone()),
+    )
+}
+
+fn parse_id(value: &str, what: &str) -> Result<Uuid, AppError> {
+    Uuid::parse_str(value).map_err(|_| AppError::BadRequest(format!("invalid {what} id")))
+}
+
+#[utoipa::path(
+    post,
+    path = "/elevations",
+    request_body = CreateElevationRequest,
+    responses(
+        (status = 201, body = ElevationResponse),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Not allowed while impersonating"),
+        (status = 404, description = "Role not found"),
+        (status = 409, description = "Already held or already requested")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "roles"
+)]
+pub async fn request_elevation_handler(
+    State(state): State<AppState>,
+    DirectUser(current_user): DirectUser,
+    Json(payload): Json<CreateElevationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    let role_id = parse_id(&payload.role_id, "role")?;
+    let request = elevation_service(&state)
+        .request(
+            &current_user,
+            role_id,
+            payload.duration_minutes,
+            payload.reason,
+        )
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(ElevationResponse::from(request))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/elevations",
+    params(
+        ("status" = Option<ElevationStatus>, Query, description = "Only requests in this status"),
+        ("limit" = Option<i64>, Query, description = "Maximum number of requests")
+    ),
+    responses(
+        (status = 200, body = [ElevationResponse]),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "roles"
+)]
+pub async fn list_elevations_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::RolesAssign>,
+    Query(query): Query<ElevationQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    let requests = elevation_service(&state).list(query.status, limit).await?;
+    let response: Vec<ElevationResponse> =
+        requests.into_iter().map(ElevationResponse::from).collect();
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/me/elevations",
+    params(
+        ("limit" = Option<i64>, Query, description = "Maximum number of requests")
+    ),
+    responses(
+        (status = 200, body = [ElevationResponse]),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "users"
+)]
+pub async fn my_elevations_handler(
+    State(state): State<AppState>,
+    CurrentUser(current_user): CurrentUser,
+    Query(query): Query<ElevationQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    let requests = elevation_service(&state)
+        .list_for_user(current_user.id, limit)
+        .await?;
+    let response: Vec<ElevationResponse> =
+        requests.into_iter().map(ElevationResponse::from).collect();
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/elevations/{id}/approve",
+    request_body = DecideElevationRequest,
+    params(
+        ("id" = String, Path, description = "Elevation request id")
+    ),
+    responses(
+        (status = 200, body = ElevationResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden, e.g. deciding your own request or impersonating"),
+        (status = 404, description = "Not found"),
+        (status = 409, description = "Already decided")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "roles"
+)]
+pub async fn approve_elevation_handler(
+    State(state): State<AppState>,
+    DirectAuthorized(admin, _): DirectAuthorized<perm::RolesAssign>,
+    audit: AuditContext,
+    Path(request_id): Path<String>,
+    Json(payload): Json<DecideElevationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    let request_id = parse_id(&request_id, "elevation request")?;
+    let request = elevation_service(&state)
+        .approve(&admin, request_id, payload.note, &audit)
+        .await?;
+
+    Ok(Json(ElevationResponse::from(request)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/elevations/{id}/deny",
+    request_body = DecideElevationRequest,
+    params(
+        ("id" = String, Path, description = "Elevation request id")
+    ),
+    responses(
+        (status = 200, body = ElevationResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden, e.g. deciding your own request or impersonating"),
+        (status = 404, description = "Not found"),
+        (status = 409, description = "Already decided")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "roles"
+)]
+pub async fn deny_elevation_handler(
+    State(state): State<AppState>,
+    DirectAuthorized(admin, _): DirectAuthorized<perm::RolesAssign>,
+    audit: AuditContext,
+    Path(request_id): Path<String>,
+    Json(payload): Json<DecideElevationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    let request_id = parse_id(&request_id, "elevation request")?;
+    let request = elevation_service(&state)
+        .deny(&admin, request_id, payload.note, &audit)
+        .await?;
+
+    Ok(Json(ElevationResponse::from(request)))
+}
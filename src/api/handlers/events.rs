@@ -0,0 +1,91 @@
+use crate::api::dto::event::EventStreamQuery;
+use crate::api::error::AppError;
+use crate::api::middleware::auth::{perm, Authorized};
+use crate::app::services::event_stream_service::EventStreamService;
+use crate::domain::DomainEventType;
+use crate::infra::db::outbox_repo::SqlxOutboxRepository;
+use crate::AppState;
+use axum::extract::{Query, State};
+use axum::http::HeaderMap;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::IntoResponse;
+use futures_util::StreamExt;
+use std::convert::Infallible;
+use uuid::Uuid;
+
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+fn event_stream_service(state: &AppState) -> EventStreamService<SqlxOutboxRepository> {
+    EventStreamService::new(
+        SqlxOutboxRepository::new(state.db.clone()),
+        state.events.clone(),
+    )
+}
+
+fn parse_types(value: Option<&str>) -> Result<Vec<DomainEventType>, AppError> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| value.parse().map_err(AppError::BadRequest))
+        .collect()
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/events/stream",
+    params(
+        ("types" = Option<String>, Query, description = "Comma-separated event types to receive; all when absent"),
+        ("Last-Event-ID" = Option<String>, Header, description = "Id of the last event received; later events are replayed first")
+    ),
+    responses(
+        (status = 200, description = "Server-sent events; each event's `data` is the domain event as JSON", content_type = "text/event-stream"),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "events"
+)]
+pub async fn event_stream_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::AuditRead>,
+    headers: HeaderMap,
+    Query(query): Query<EventStreamQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let event_types = parse_types(query.types.as_deref())?;
+    let last_event_id = headers
+        .get(LAST_EVENT_ID_HEADER)
+        .map(|value| {
+            value
+                .to_str()
+                .ok()
+                .and_then(|value| Uuid::parse_str(value.trim()).ok())
+                .ok_or_else(|| AppError::BadRequest("invalid Last-Event-ID".to_string()))
+        })
+        .transpose()?;
+
+    let events = event_stream_service(&state)
+        .subscribe(event_types, last_event_id)
+        .await?;
+
+    let stream = events.filter_map(|event| async move {
+        match serde_json::to_string(&event) {
+            Ok(data) => Some(Ok::<_, Infallible>(
+                Event::default()
+                    .id(event.id.to_string())
+                    .event(event.event_type.as_str())
+                    .data(data),
+            )),
+            Err(err) => {
+                tracing::error!(error = %err, event_id = %event.id, "failed to serialize domain event");
+                None
+            }
+        }
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
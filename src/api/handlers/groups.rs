@@ -0,0 +1,481 @@
+use crate::api::dto::group::{
+    CreateGroupRequest, GroupMembersResponse, GroupResponse, UpdateGroupRequest,
+};
+use crate::api::error::AppError;
+use crate::app::services::group_service::GroupService;
+use crate::domain::{AuditContext, NewGroup, UpdateGroup};
+use crate::infra::db::group_repo::SqlxGroupRepository;
+use crate::infra::db::role_repo::SqlxRoleRepository;
+use crate::infra::db::user_repo::SqlxUserRepository;
+use crate::AppState;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::api::middleware::auth::{perm, Authorized, CurrentUser, DirectAuthorized};
+
+fn group_service(
+    state: &AppState,
+) -> GroupService<SqlxGroupRepository, SqlxUserRepository, SqlxRoleRepository> {
+    GroupService::new(
+        SqlxGroupRepository::new(state.db.clone()),
+        SqlxUserRepository::new(state.db.clone()),
+        SqlxRoleRepository::new(state.db.clone()),
+    )
+}
+
+fn parse_id(value: &str, what: &str) -> Result<Uuid, AppError> {
+    Uuid::parse_str(value).map_err(|_| AppError::BadRequest(format!("invalid {what} id")))
+}
+
+#[utoipa::path(
+    get,
+    path = "/groups",
+    responses(
+        (status = 200, body = [GroupResponse]),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "groups"
+)]
+pub async fn list_groups_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::GroupsRead>,
+) -> Result<impl IntoResponse, AppError> {
+    let groups = group_service(&state).list_groups().await?;
+    let response: Vec<GroupResponse> = groups.into_iter().map(GroupResponse::from).collect();
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/groups",
+    request_body = CreateGroupRequest,
+    responses(
+        (status = 201, body = GroupResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 409, description = "Conflict")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "groups"
+)]
+pub async fn create_group_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::GroupsWrite>,
+    Json(payload): Json<CreateGroupRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    let group = group_service(&state)
+        .create_group(NewGroup {
+            name: payload.name,
+            description: payload.description,
+        })
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(GroupResponse::from(group))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/groups/{id}",
+    params(
+        ("id" = String, Path, description = "Group id")
+    ),
+    responses(
+        (status = 200, body = GroupResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "groups"
+)]
+pub async fn get_group_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::GroupsRead>,
+    Path(group_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let group_id = parse_id(&group_id, "group")?;
+    let group = group_service(&state).get_group(group_id).await?;
+
+    Ok(Json(GroupResponse::from(group)))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/groups/{id}",
+    request_body = UpdateGroupRequest,
+    params(
+        ("id" = String, Path, description = "Group id")
+    ),
+    responses(
+        (status = 200, body = GroupResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found"),
+        (status = 409, description = "Conflict")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "groups"
+)]
+pub async fn update_group_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::GroupsWrite>,
+    Path(group_id): Path<String>,
+    Json(payload): Json<UpdateGroupRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    let group_id = parse_id(&group_id, "group")?;
+    let group = group_service(&state)
+        .update_group(
+            group_id,
+            UpdateGroup {
+                name: payload.name,
+                description: payload.description,
+            },
+        )
+        .await?;
+
+    Ok(Json(GroupResponse::from(group)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/groups/{id}",
+    params(
+        ("id" = String, Path, description = "Group id")
+    ),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "groups"
+)]
+pub async fn delete_group_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::GroupsWrite>,
+    audit: AuditContext,
+    Path(group_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let group_id = parse_id(&group_id, "group")?;
+    group_service(&state).delete_group(group_id, &audit).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/groups/{id}/members",
+    params(
+        ("id" = String, Path, description = "Group id")
+    ),
+    responses(
+        (status = 200, body = GroupMembersResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "groups"
+)]
+pub async fn list_group_members_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::GroupsRead>,
+    Path(group_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let group_id = parse_id(&group_id, "group")?;
+    let members = group_service(&state).members(group_id).await?;
+
+    Ok(Json(GroupMembersResponse::from(members)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/groups/{id}/users/{user_id}",
+    params(
+        ("id" = String, Path, description = "Group id"),
+        ("user_id" = String, Path, description = "User id")
+    ),
+    responses(
+        (status = 200, body = GroupMembersResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "groups"
+)]
+pub async fn add_group_user_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::GroupsWrite>,
+    audit: AuditContext,
+    Path((group_id, user_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    let group_id = parse_id(&group_id, "group")?;
+    let user_id = parse_id(&user_id, "user")?;
+    let members = group_service(&state)
+        .add_user(group_id, user_id, &audit)
+        .await?;
+
+    Ok(Json(GroupMembersResponse::from(members)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/groups/{id}/users/{user_id}",
+    params(
+        ("id" = String, Path, description = "Group id"),
+        ("user_id" = String, Path, description = "User id")
+    ),
+    responses(
+        (status = 200, body = GroupMembersResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "groups"
+)]
+pub async fn remove_group_user_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::GroupsWrite>,
+    audit: AuditContext,
+    Path((group_id, user_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    let group_id = parse_id(&group_id, "group")?;
+    let user_id = parse_id(&user_id, "user")?;
+    let members = group_service(&state)
+        .remove_user(group_id, user_id, &audit)
+        .await?;
+
+    Ok(Json(GroupMembersResponse::from(members)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/groups/{id}/groups/{member_id}",
+    params(
+        ("id" = String, Path, description = "Group id"),
+        ("member_id" = String, Path, description = "Nested group id")
+    ),
+    responses(
+        (status = 200, body = GroupMembersResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found"),
+        (status = 409, description = "Cycle")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "groups"
+)]
+pub async fn add_group_group_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::GroupsWrite>,
+    audit: AuditContext,
+    Path((group_id, member_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    let group_id = parse_id(&group_id, "group")?;
+    let member_id = parse_id(&member_id, "group")?;
+    let members = group_service(&state)
+        .add_group(group_id, member_id, &audit)
+        .await?;
+
+    Ok(Json(GroupMembersResponse::from(members)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/groups/{id}/groups/{member_id}",
+    params(
+        ("id" = String, Path, description = "Group id"),
+        ("member_id" = String, Path, description = "Nested group id")
+    ),
+    responses(
+        (status = 200, body = GroupMembersResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "groups"
+)]
+pub async fn remove_group_group_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::GroupsWrite>,
+    audit: AuditContext,
+    Path((group_id, member_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    let group_id = parse_id(&group_id, "group")?;
+    let member_id = parse_id(&member_id, "group")?;
+    let members = group_service(&state)
+        .remove_group(group_id, member_id, &audit)
+        .await?;
+
+    Ok(Json(GroupMembersResponse::from(members)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/groups/{id}/roles/{role_id}",
+    params(
+        ("id" = String, Path, description = "Group id"),
+        ("role_id" = String, Path, description = "Role id")
+    ),
+    responses(
+        (status = 200, body = GroupResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "groups"
+)]
+pub async fn assign_group_role_handler(
+    State(state): State<AppState>,
+    DirectAuthorized(_admin, _): DirectAuthorized<perm::GroupRolesAssign>,
+    audit: AuditContext,
+    Path((group_id, role_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    let group_id = parse_id(&group_id, "group")?;
+    let role_id = parse_id(&role_id, "role")?;
+    let group = group_service(&state)
+        .assign_role(group_id, role_id, &audit)
+        .await?;
+
+    Ok(Json(GroupResponse::from(group)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/groups/{id}/roles/{role_id}",
+    params(
+        ("id" = String, Path, description = "Group id"),
+        ("role_id" = String, Path, description = "Role id")
+    ),
+    responses(
+        (status = 200, body = GroupResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "groups"
+)]
+pub async fn unassign_group_role_handler(
+    State(state): State<AppState>,
+    DirectAuthorized(_admin, _): DirectAuthorized<perm::GroupRolesAssign>,
+    audit: AuditContext,
+    Path((group_id, role_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    let group_id = parse_id(&group_id, "group")?;
+    let role_id = parse_id(&role_id, "role")?;
+    let group = group_service(&state)
+        .unassign_role(group_id, role_id, &audit)
+        .await?;
+
+    Ok(Json(GroupResponse::from(group)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/me/groups",
+    responses(
+        (status = 200, body = [GroupResponse]),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "users"
+)]
+pub async fn my_groups_handler(
+    State(state): State<AppState>,
+    CurrentUser(current_user): CurrentUser,
+) -> Result<impl IntoResponse, AppError> {
+    let groups = group_service(&state)
+        .effective_groups(current_user.id)
+        .await?;
+    let response: Vec<GroupResponse> = groups.into_iter().map(GroupResponse::from).collect();
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/{id}/groups",
+    params(
+        ("id" = String, Path, description = "User id")
+    ),
+    responses(
+        (status = 200, body = [GroupResponse]),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "users"
+)]
+pub async fn user_groups_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::GroupsRead>,
+    Path(user_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = parse_id(&user_id, "user")?;
+    let groups = group_service(&state).effective_groups(user_id).await?;
+    let response: Vec<GroupResponse> = groups.into_iter().map(GroupResponse::from).collect();
+
+    Ok(Json(response))
+}
@@ -0,0 +1,244 @@
+use crate::api::dto::auth::LoginResponse;
+use crate::api::dto::invitation::{
+    AcceptInvitationRequest, CreateInvitationRequest, InvitationResponse,
+};
+use crate::api::dto::user::UserResponse;
+use crate::api::error::AppError;
+use crate::app::services::auth_service::AuthService;
+use crate::app::services::invitation_service::{
+    AcceptInvitationInput, InvitationService, InvitationSettings,
+};
+use crate::app::services::org_service::OrganizationService;
+use crate::domain::AuditContext;
+use crate::infra::auth::jwt::JwtService;
+use crate::infra::db::audit_repo::SqlxAuditRepository;
+use crate::infra::db::invitation_repo::SqlxInvitationRepository;
+use crate::infra::db::org_repo::SqlxOrganizationRepository;
+use crate::infra::db::user_repo::SqlxUserRepository;
+use crate::infra::mail::LogMailer;
+use crate::AppState;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::api::middleware::auth::CurrentUser;
+
+fn invitation_service(
+    state: &AppState,
+) -> InvitationService<
+    SqlxInvitationRepository,
+    SqlxOrganizationRepository,
+    SqlxUserRepository,
+    LogMailer,
+    SqlxAuditRepository,
+> {
+    let users = SqlxUserRepository::new(state.db.clone());
+    let jwt = JwtService::new(&state.config);
+
+    InvitationService::new(
+        SqlxInvitationRepository::new(state.db.clone()),
+        OrganizationService::new(
+            SqlxOrganizationRepository::new(state.db.clone()),
+            users.clone(),
+        ),
+        users.clone(),
+        AuthService::new(
+            users,
+            SqlxAuditRepository::new(state.db.clone()),
+            jwt.clone(),
+            state.identity_policy.clone(),
+        ),
+        jwt,
+        LogMailer,
+        InvitationSettings::from_config(&state.config),
+    )
+}
+
+fn parse_id(value: &str, what: &str) -> Result<Uuid, AppError> {
+    Uuid::parse_str(value).map_err(|_| AppError::BadRequest(format!("invalid {what} id")))
+}
+
+#[utoipa::path(
+    get,
+    path = "/orgs/{id}/invitations",
+    params(
+        ("id" = String, Path, description = "Organization id")
+    ),
+    responses(
+        (status = 200, body = [InvitationResponse]),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "orgs"
+)]
+pub async fn list_invitations_handler(
+    State(state): State<AppState>,
+    CurrentUser(current_user): CurrentUser,
+    Path(org_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let org_id = parse_id(&org_id, "organization")?;
+    let invitations = invitation_service(&state)
+        .list_pending(&current_user, org_id)
+        .await?;
+    let response: Vec<InvitationResponse> = invitations
+        .into_iter()
+        .map(InvitationResponse::from)
+        .collect();
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/orgs/{id}/invitations",
+    request_body = CreateInvitationRequest,
+    params(
+        ("id" = String, Path, description = "Organization id")
+    ),
+    responses(
+        (status = 201, body = InvitationResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found"),
+        (status = 409, description = "Already a member or already invited")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "orgs"
+)]
+pub async fn create_invitation_handler(
+    State(state): State<AppState>,
+    CurrentUser(current_user): CurrentUser,
+    Path(org_id): Path<String>,
+    Json(payload): Json<CreateInvitationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    let org_id = parse_id(&org_id, "organization")?;
+    let issued = invitation_service(&state)
+        .invite(&current_user, org_id, payload.email, payload.role)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(InvitationResponse::from(issued))))
+}
+
+#[utoipa::path(
+    post,
+    path = "/orgs/{id}/invitations/{invitation_id}/resend",
+    params(
+        ("id" = String, Path, description = "Organization id"),
+        ("invitation_id" = String, Path, description = "Invitation id")
+    ),
+    responses(
+        (status = 200, body = InvitationResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "orgs"
+)]
+pub async fn resend_invitation_handler(
+    State(state): State<AppState>,
+    CurrentUser(current_user): CurrentUser,
+    Path((org_id, invitation_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    let org_id = parse_id(&org_id, "organization")?;
+    let invitation_id = parse_id(&invitation_id, "invitation")?;
+
+    let issued = invitation_service(&state)
+        .resend(&current_user, org_id, invitation_id)
+        .await?;
+
+    Ok(Json(InvitationResponse::from(issued)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/orgs/{id}/invitations/{invitation_id}",
+    params(
+        ("id" = String, Path, description = "Organization id"),
+        ("invitation_id" = String, Path, description = "Invitation id")
+    ),
+    responses(
+        (status = 204, description = "Revoked"),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "orgs"
+)]
+pub async fn revoke_invitation_handler(
+    State(state): State<AppState>,
+    CurrentUser(current_user): CurrentUser,
+    Path((org_id, invitation_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    let org_id = parse_id(&org_id, "organization")?;
+    let invitation_id = parse_id(&invitation_id, "invitation")?;
+
+    invitation_service(&state)
+        .revoke(&current_user, org_id, invitation_id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/invitations/accept",
+    request_body = AcceptInvitationRequest,
+    responses(
+        (status = 200, body = LoginResponse),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Invalid, expired or revoked invitation, or wrong password"),
+        (status = 409, description = "Already a member or username taken")
+    ),
+    tag = "auth"
+)]
+pub async fn accept_invitation_handler(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Json(payload): Json<AcceptInvitationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    let response = invitation_service(&state)
+        .accept(
+            AcceptInvitationInput {
+                token: payload.token,
+                username: payload.username,
+                password: payload.password,
+            },
+            &audit,
+        )
+        .await?;
+
+    let body = LoginResponse {
+        access_token: response.access_token,
+        refresh_token: response.refresh_token,
+        user: UserResponse::from(response.user),
+    };
+
+    Ok(Json(body))
+}
@@ -1,2 +1,12 @@
+pub mod attributes;
+pub mod audit;
 pub mod auth;
-pub mod users;
\ No newline at end of file
+pub mod elevations;
+pub mod events;
+pub mod groups;
+pub mod invitations;
+pub mod orgs;
+pub mod roles;
+pub mod scim;
+pub mod users;
+pub mod webhooks;
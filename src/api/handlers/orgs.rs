@@ -0,0 +1,405 @@
+use crate::api::dto::auth::LoginResponse;
+use crate::api::dto::org::{
+    AddMemberRequest, CreateOrganizationRequest, MemberResponse, OrganizationResponse,
+    UpdateMemberRequest, UpdateOrganizationRequest,
+};
+use crate::api::dto::user::{PaginationQuery, UserResponse};
+use crate::api::error::AppError;
+use crate::app::services::auth_service::AuthService;
+use crate::app::services::org_service::OrganizationService;
+use crate::domain::{NewOrganization, OrgRole, UpdateOrganization};
+use crate::infra::auth::jwt::{JwtService, TokenContext};
+use crate::infra::db::audit_repo::SqlxAuditRepository;
+use crate::infra::db::org_repo::SqlxOrganizationRepository;
+use crate::infra::db::user_repo::SqlxUserRepository;
+use crate::AppState;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::api::middleware::auth::{CurrentUser, DirectUser};
+
+fn org_service(
+    state: &AppState,
+) -> OrganizationService<SqlxOrganizationRepository, SqlxUserRepository> {
+    OrganizationService::new(
+        SqlxOrganizationRepository::new(state.db.clone()),
+        SqlxUserRepository::new(state.db.clone()),
+    )
+}
+
+fn parse_id(value: &str, what: &str) -> Result<Uuid, AppError> {
+    Uuid::parse_str(value).map_err(|_| AppError::BadRequest(format!("invalid {what} id")))
+}
+
+#[utoipa::path(
+    get,
+    path = "/orgs",
+    responses(
+        (status = 200, body = [OrganizationResponse]),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "orgs"
+)]
+pub async fn list_orgs_handler(
+    State(state): State<AppState>,
+    CurrentUser(current_user): CurrentUser,
+) -> Result<impl IntoResponse, AppError> {
+    let orgs = org_service(&state).list_for_user(current_user.id).await?;
+    let response: Vec<OrganizationResponse> = orgs
+        .into_iter()
+        .map(|(org, role)| OrganizationResponse::new(org, Some(role)))
+        .collect();
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/orgs",
+    request_body = CreateOrganizationRequest,
+    responses(
+        (status = 201, body = OrganizationResponse),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "Conflict")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "orgs"
+)]
+pub async fn create_org_handler(
+    State(state): State<AppState>,
+    CurrentUser(current_user): CurrentUser,
+    Json(payload): Json<CreateOrganizationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    let org = org_service(&state)
+        .create_organization(
+            &current_user,
+            NewOrganization {
+                name: payload.name,
+                slug: payload.slug,
+            },
+        )
+        .await?;
+
+    let response = OrganizationResponse::new(org, Some(OrgRole::Owner));
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/orgs/{id}",
+    params(
+        ("id" = String, Path, description = "Organization id")
+    ),
+    responses(
+        (status = 200, body = OrganizationResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "orgs"
+)]
+pub async fn get_org_handler(
+    State(state): State<AppState>,
+    CurrentUser(current_user): CurrentUser,
+    Path(org_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let org_id = parse_id(&org_id, "organization")?;
+    let (org, role) = org_service(&state)
+        .get_organization(&current_user, org_id)
+        .await?;
+
+    Ok(Json(OrganizationResponse::new(org, Some(role))))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/orgs/{id}",
+    request_body = UpdateOrganizationRequest,
+    params(
+        ("id" = String, Path, description = "Organization id")
+    ),
+    responses(
+        (status = 200, body = OrganizationResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found"),
+        (status = 409, description = "Conflict")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "orgs"
+)]
+pub async fn update_org_handler(
+    State(state): State<AppState>,
+    CurrentUser(current_user): CurrentUser,
+    Path(org_id): Path<String>,
+    Json(payload): Json<UpdateOrganizationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    let org_id = parse_id(&org_id, "organization")?;
+    let service = org_service(&state);
+    let org = service
+        .update_organization(
+            &current_user,
+            org_id,
+            UpdateOrganization {
+                name: payload.name,
+                slug: payload.slug,
+            },
+        )
+        .await?;
+    let role = service.effective_role(&current_user, org_id).await?;
+
+    Ok(Json(OrganizationResponse::new(org, role)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/orgs/{id}",
+    params(
+        ("id" = String, Path, description = "Organization id")
+    ),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "orgs"
+)]
+pub async fn delete_org_handler(
+    State(state): State<AppState>,
+    CurrentUser(current_user): CurrentUser,
+    Path(org_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let org_id = parse_id(&org_id, "organization")?;
+    org_service(&state)
+        .delete_organization(&current_user, org_id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/orgs/{id}/token",
+    params(
+        ("id" = String, Path, description = "Organization id")
+    ),
+    responses(
+        (status = 200, body = LoginResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Not allowed while impersonating"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "orgs"
+)]
+pub async fn switch_org_handler(
+    State(state): State<AppState>,
+    DirectUser(current_user): DirectUser,
+    Path(org_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let org_id = parse_id(&org_id, "organization")?;
+    org_service(&state)
+        .get_organization(&current_user, org_id)
+        .await?;
+
+    let service = AuthService::new(
+        SqlxUserRepository::new(state.db.clone()),
+        SqlxAuditRepository::new(state.db.clone()),
+        JwtService::new(&state.config),
+        state.identity_policy.clone(),
+    );
+    let response = service.issue_tokens(
+        current_user,
+        &TokenContext {
+            org_id: Some(org_id),
+        },
+    )?;
+
+    let body = LoginResponse {
+        access_token: response.access_token,
+        refresh_token: response.refresh_token,
+        user: UserResponse::from(response.user),
+    };
+
+    Ok(Json(body))
+}
+
+#[utoipa::path(
+    get,
+    path = "/orgs/{id}/members",
+    params(
+        ("id" = String, Path, description = "Organization id"),
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("per_page" = Option<i64>, Query, description = "Items per page")
+    ),
+    responses(
+        (status = 200, body = [MemberResponse]),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "orgs"
+)]
+pub async fn list_members_handler(
+    State(state): State<AppState>,
+    CurrentUser(current_user): CurrentUser,
+    Path(org_id): Path<String>,
+    Query(pagination): Query<PaginationQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let org_id = parse_id(&org_id, "organization")?;
+    let page = pagination.page.unwrap_or(1).max(1);
+    let per_page = pagination.per_page.unwrap_or(20).clamp(1, 100);
+    let offset = (page - 1) * per_page;
+
+    let members = org_service(&state)
+        .list_members(&current_user, org_id, per_page, offset)
+        .await?;
+    let response: Vec<MemberResponse> = members.into_iter().map(MemberResponse::from).collect();
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/orgs/{id}/members",
+    request_body = AddMemberRequest,
+    params(
+        ("id" = String, Path, description = "Organization id")
+    ),
+    responses(
+        (status = 201, body = MemberResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found"),
+        (status = 409, description = "Conflict")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "orgs"
+)]
+pub async fn add_member_handler(
+    State(state): State<AppState>,
+    CurrentUser(current_user): CurrentUser,
+    Path(org_id): Path<String>,
+    Json(payload): Json<AddMemberRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    let org_id = parse_id(&org_id, "organization")?;
+    let member = org_service(&state)
+        .add_member(&current_user, org_id, &payload.email, payload.role)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(MemberResponse::from(member))))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/orgs/{id}/members/{user_id}",
+    request_body = UpdateMemberRequest,
+    params(
+        ("id" = String, Path, description = "Organization id"),
+        ("user_id" = String, Path, description = "User id")
+    ),
+    responses(
+        (status = 200, body = MemberResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found"),
+        (status = 409, description = "Last owner")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "orgs"
+)]
+pub async fn update_member_handler(
+    State(state): State<AppState>,
+    CurrentUser(current_user): CurrentUser,
+    Path((org_id, user_id)): Path<(String, String)>,
+    Json(payload): Json<UpdateMemberRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let org_id = parse_id(&org_id, "organization")?;
+    let user_id = parse_id(&user_id, "user")?;
+
+    let member = org_service(&state)
+        .change_member_role(&current_user, org_id, user_id, payload.role)
+        .await?;
+
+    Ok(Json(MemberResponse::from(member)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/orgs/{id}/members/{user_id}",
+    params(
+        ("id" = String, Path, description = "Organization id"),
+        ("user_id" = String, Path, description = "User id")
+    ),
+    responses(
+        (status = 204, description = "Removed"),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found"),
+        (status = 409, description = "Last owner")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "orgs"
+)]
+pub async fn remove_member_handler(
+    State(state): State<AppState>,
+    CurrentUser(current_user): CurrentUser,
+    Path((org_id, user_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    let org_id = parse_id(&org_id, "organization")?;
+    let user_id = parse_id(&user_id, "user")?;
+
+    org_service(&state)
+        .remove_member(&current_user, org_id, user_id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,319 @@
+use crate::api::dto::role::{
+    AssignRoleQuery, CreateRoleRequest, HistoryQuery, RoleAssignmentEventResponse, RoleResponse,
+    UpdateRoleRequest,
+};
+use crate::api::dto::user::{ConfirmQuery, UserResponse};
+use crate::api::error::AppError;
+use crate::app::services::role_service::RoleService;
+use crate::domain::{AuditContext, NewRole, UpdateRole};
+use crate::infra::db::role_repo::SqlxRoleRepository;
+use crate::infra::db::user_repo::SqlxUserRepository;
+use crate::AppState;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::api::middleware::auth::{perm, Authorized, DirectAuthorized};
+
+fn role_service(state: &AppState) -> RoleService<SqlxRoleRepository, SqlxUserRepository> {
+    RoleService::new(
+        SqlxRoleRepository::new(state.db.clone()),
+        SqlxUserRepository::new(state.db.clone()),
+    )
+}
+
+fn parse_id(value: &str, what: &str) -> Result<Uuid, AppError> {
+    Uuid::parse_str(value).map_err(|_| AppError::BadRequest(format!("invalid {what} id")))
+}
+
+#[utoipa::path(
+    get,
+    path = "/roles",
+    responses(
+        (status = 200, body = [RoleResponse]),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "roles"
+)]
+pub async fn list_roles_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::RolesRead>,
+) -> Result<impl IntoResponse, AppError> {
+    let roles = role_service(&state).list_roles().await?;
+    let response: Vec<RoleResponse> = roles.into_iter().map(RoleResponse::from).collect();
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/roles",
+    request_body = CreateRoleRequest,
+    responses(
+        (status = 201, body = RoleResponse),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 409, description = "Conflict")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "roles"
+)]
+pub async fn create_role_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::RolesWrite>,
+    audit: AuditContext,
+    Json(payload): Json<CreateRoleRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    let role = role_service(&state)
+        .create_role(
+            NewRole {
+                name: payload.name,
+                description: payload.description,
+                permissions: payload.permissions,
+            },
+            &audit,
+        )
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(RoleResponse::from(role))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/roles/{id}",
+    params(
+        ("id" = String, Path, description = "Role id")
+    ),
+    responses(
+        (status = 200, body = RoleResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "roles"
+)]
+pub async fn get_role_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::RolesRead>,
+    Path(role_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let role_id = parse_id(&role_id, "role")?;
+    let role = role_service(&state).get_role(role_id).await?;
+
+    Ok(Json(RoleResponse::from(role)))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/roles/{id}",
+    request_body = UpdateRoleRequest,
+    params(
+        ("id" = String, Path, description = "Role id")
+    ),
+    responses(
+        (status = 200, body = RoleResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden, or renaming or changing the permissions of a built-in role"),
+        (status = 404, description = "Not found"),
+        (status = 409, description = "Conflict")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "roles"
+)]
+pub async fn update_role_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::RolesWrite>,
+    audit: AuditContext,
+    Path(role_id): Path<String>,
+    Json(payload): Json<UpdateRoleRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    let role_id = parse_id(&role_id, "role")?;
+    let role = role_service(&state)
+        .update_role(
+            role_id,
+            UpdateRole {
+                name: payload.name,
+                description: payload.description,
+                permissions: payload.permissions,
+            },
+            &audit,
+        )
+        .await?;
+
+    Ok(Json(RoleResponse::from(role)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/roles/{id}",
+    params(
+        ("id" = String, Path, description = "Role id")
+    ),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden or built-in role"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "roles"
+)]
+pub async fn delete_role_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::RolesWrite>,
+    audit: AuditContext,
+    Path(role_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let role_id = parse_id(&role_id, "role")?;
+    role_service(&state).delete_role(role_id, &audit).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/roles/{id}/history",
+    params(
+        ("id" = String, Path, description = "Role id"),
+        ("limit" = Option<i64>, Query, description = "Maximum number of events")
+    ),
+    responses(
+        (status = 200, body = [RoleAssignmentEventResponse]),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "roles"
+)]
+pub async fn role_history_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::RolesRead>,
+    Path(role_id): Path<String>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let role_id = parse_id(&role_id, "role")?;
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+
+    let events = role_service(&state).role_history(role_id, limit).await?;
+    let response: Vec<RoleAssignmentEventResponse> = events
+        .into_iter()
+        .map(RoleAssignmentEventResponse::from)
+        .collect();
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    put,
+    path = "/roles/{id}/users/{user_id}",
+    params(
+        ("id" = String, Path, description = "Role id"),
+        ("user_id" = String, Path, description = "User id"),
+        ("expires_in_minutes" = Option<i64>, Query, description = "Grant the role for this many minutes only (max 10080)")
+    ),
+    responses(
+        (status = 200, body = UserResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "roles"
+)]
+pub async fn assign_role_handler(
+    State(state): State<AppState>,
+    DirectAuthorized(_admin, _): DirectAuthorized<perm::RolesAssign>,
+    audit: AuditContext,
+    Path((role_id, user_id)): Path<(String, String)>,
+    Query(query): Query<AssignRoleQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    query
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    let role_id = parse_id(&role_id, "role")?;
+    let user_id = parse_id(&user_id, "user")?;
+    let expires_at = query
+        .expires_in_minutes
+        .map(|minutes| Utc::now() + Duration::minutes(minutes));
+
+    let user = role_service(&state)
+        .assign_role(user_id, role_id, expires_at, &audit)
+        .await?;
+
+    Ok(Json(UserResponse::from(user)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/roles/{id}/users/{user_id}",
+    params(
+        ("id" = String, Path, description = "Role id"),
+        ("user_id" = String, Path, description = "User id"),
+        ("confirm" = Option<bool>, Query, description = "Required to remove one of your own roles")
+    ),
+    responses(
+        (status = 200, body = UserResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found"),
+        (status = 409, description = "Conflict (`last_admin`, `confirmation_required`)")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "roles"
+)]
+pub async fn unassign_role_handler(
+    State(state): State<AppState>,
+    DirectAuthorized(_admin, _): DirectAuthorized<perm::RolesAssign>,
+    audit: AuditContext,
+    Path((role_id, user_id)): Path<(String, String)>,
+    Query(confirm): Query<ConfirmQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let role_id = parse_id(&role_id, "role")?;
+    let user_id = parse_id(&user_id, "user")?;
+
+    let user = role_service(&state)
+        .unassign_role(user_id, role_id, confirm.confirm, &audit)
+        .await?;
+
+    Ok(Json(UserResponse::from(user)))
+}
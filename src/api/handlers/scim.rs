@@ -0,0 +1,503 @@
+use crate::api::dto::scim::{
+    self, ScimGroup, ScimGroupRequest, ScimListQuery, ScimListResponse, ScimPatchRequest, ScimUser,
+    ScimUserRequest, SCIM_CONTENT_TYPE, SCIM_MAX_RESULTS,
+};
+use crate::api::error::ScimError;
+use crate::api::middleware::auth::ScimClient;
+use crate::app::services::scim_service::{ScimService, ScimUserInput};
+use crate::domain::AuditContext;
+use crate::infra::db::group_repo::SqlxGroupRepository;
+use crate::infra::db::user_repo::SqlxUserRepository;
+use crate::AppState;
+use axum::extract::rejection::{JsonRejection, QueryRejection};
+use axum::extract::{Path, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+use uuid::Uuid;
+
+fn scim_service(state: &AppState) -> ScimService<SqlxUserRepository, SqlxGroupRepository> {
+    ScimService::new(
+        SqlxUserRepository::new(state.db.clone()),
+        SqlxGroupRepository::new(state.db.clone()),
+    )
+}
+
+/// SCIM clients address resources by id only, so an id that is not a UUID is simply unknown.
+fn parse_id(value: &str) -> Result<Uuid, ScimError> {
+    Uuid::parse_str(value).map_err(|_| ScimError::new(StatusCode::NOT_FOUND, "resource not found"))
+}
+
+fn scim_response<T: Serialize>(status: StatusCode, body: &T) -> Result<Response, ScimError> {
+    let body = serde_json::to_string(body)
+        .map_err(|err| ScimError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    Ok((status, [(header::CONTENT_TYPE, SCIM_CONTENT_TYPE)], body).into_response())
+}
+
+/// `startIndex` and `count` with the defaults of RFC 7644 §3.4.2.4; both are clamped.
+fn page(query: &ScimListQuery) -> (i64, i64) {
+    let start_index = query.start_index.unwrap_or(1).max(1);
+    let count = query
+        .count
+        .unwrap_or(SCIM_MAX_RESULTS)
+        .clamp(0, SCIM_MAX_RESULTS);
+    (start_index, count)
+}
+
+fn list_query(
+    query: Result<Query<ScimListQuery>, QueryRejection>,
+) -> Result<ScimListQuery, ScimError> {
+    query.map(|Query(query)| query).map_err(|err| ScimError {
+        scim_type: Some("invalidValue"),
+        ..ScimError::new(StatusCode::BAD_REQUEST, err.body_text())
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/scim/v2/Users",
+    params(
+        ("filter" = Option<String>, Query, description = "SCIM filter, e.g. `userName eq \"alice\"`"),
+        ("startIndex" = Option<i64>, Query, description = "1-based index of the first result"),
+        ("count" = Option<i64>, Query, description = "Page size, at most 200")
+    ),
+    responses(
+        (status = 200, body = scim::ScimUserList),
+        (status = 400, description = "Invalid filter"),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("scim_token" = [])
+    ),
+    tag = "scim"
+)]
+pub async fn list_users_handler(
+    State(state): State<AppState>,
+    _client: ScimClient,
+    query: Result<Query<ScimListQuery>, QueryRejection>,
+) -> Result<Response, ScimError> {
+    let query = list_query(query)?;
+    let filter = query
+        .filter
+        .as_deref()
+        .map(ScimService::<SqlxUserRepository, SqlxGroupRepository>::user_filter)
+        .transpose()
+        .map_err(ScimError::invalid_filter)?;
+    let (start_index, count) = page(&query);
+
+    let (users, total) = scim_service(&state)
+        .list_users(filter, start_index, count)
+        .await?;
+    let users = users.into_iter().map(ScimUser::from).collect();
+
+    scim_response(
+        StatusCode::OK,
+        &ScimListResponse::new(users, total, start_index),
+    )
+}
+
+#[utoipa::path(
+    post,
+    path = "/scim/v2/Users",
+    request_body = ScimUserRequest,
+    responses(
+        (status = 201, body = ScimUser),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "userName or email already exists")
+    ),
+    security(
+        ("scim_token" = [])
+    ),
+    tag = "scim"
+)]
+pub async fn create_user_handler(
+    State(state): State<AppState>,
+    _client: ScimClient,
+    audit: AuditContext,
+    payload: Result<Json<ScimUserRequest>, JsonRejection>,
+) -> Result<Response, ScimError> {
+    let Json(payload) = payload?;
+    let input = ScimUserInput::try_from(payload)?;
+    let user = scim_service(&state).create_user(input, &audit).await?;
+
+    scim_response(StatusCode::CREATED, &ScimUser::from(user))
+}
+
+#[utoipa::path(
+    get,
+    path = "/scim/v2/Users/{id}",
+    params(
+        ("id" = String, Path, description = "User id")
+    ),
+    responses(
+        (status = 200, body = ScimUser),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("scim_token" = [])
+    ),
+    tag = "scim"
+)]
+pub async fn get_user_handler(
+    State(state): State<AppState>,
+    _client: ScimClient,
+    Path(id): Path<String>,
+) -> Result<Response, ScimError> {
+    let user = scim_service(&state).get_user(parse_id(&id)?).await?;
+
+    scim_response(StatusCode::OK, &ScimUser::from(user))
+}
+
+#[utoipa::path(
+    put,
+    path = "/scim/v2/Users/{id}",
+    params(
+        ("id" = String, Path, description = "User id")
+    ),
+    request_body = ScimUserRequest,
+    responses(
+        (status = 200, body = ScimUser),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Not found"),
+        (status = 409, description = "userName or email already exists")
+    ),
+    security(
+        ("scim_token" = [])
+    ),
+    tag = "scim"
+)]
+pub async fn replace_user_handler(
+    State(state): State<AppState>,
+    _client: ScimClient,
+    audit: AuditContext,
+    Path(id): Path<String>,
+    payload: Result<Json<ScimUserRequest>, JsonRejection>,
+) -> Result<Response, ScimError> {
+    let id = parse_id(&id)?;
+    let Json(payload) = payload?;
+    let input = ScimUserInput::try_from(payload)?;
+    let user = scim_service(&state).replace_user(id, input, &audit).await?;
+
+    scim_response(StatusCode::OK, &ScimUser::from(user))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/scim/v2/Users/{id}",
+    params(
+        ("id" = String, Path, description = "User id")
+    ),
+    request_body(content = serde_json::Value, description = "SCIM PatchOp; `active: false` deactivates the user"),
+    responses(
+        (status = 200, body = ScimUser),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("scim_token" = [])
+    ),
+    tag = "scim"
+)]
+pub async fn patch_user_handler(
+    State(state): State<AppState>,
+    _client: ScimClient,
+    audit: AuditContext,
+    Path(id): Path<String>,
+    payload: Result<Json<ScimPatchRequest>, JsonRejection>,
+) -> Result<Response, ScimError> {
+    let id = parse_id(&id)?;
+    let Json(payload) = payload?;
+    let user = scim_service(&state)
+        .patch_user(id, payload.operations, &audit)
+        .await?;
+
+    scim_response(StatusCode::OK, &ScimUser::from(user))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/scim/v2/Users/{id}",
+    params(
+        ("id" = String, Path, description = "User id")
+    ),
+    responses(
+        (status = 204, description = "User deactivated"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("scim_token" = [])
+    ),
+    tag = "scim"
+)]
+pub async fn delete_user_handler(
+    State(state): State<AppState>,
+    _client: ScimClient,
+    audit: AuditContext,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ScimError> {
+    scim_service(&state)
+        .deactivate_user(parse_id(&id)?, &audit)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/scim/v2/Groups",
+    params(
+        ("filter" = Option<String>, Query, description = "SCIM filter, e.g. `displayName eq \"ops\"`"),
+        ("startIndex" = Option<i64>, Query, description = "1-based index of the first result"),
+        ("count" = Option<i64>, Query, description = "Page size, at most 200")
+    ),
+    responses(
+        (status = 200, body = scim::ScimGroupList),
+        (status = 400, description = "Invalid filter"),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("scim_token" = [])
+    ),
+    tag = "scim"
+)]
+pub async fn list_groups_handler(
+    State(state): State<AppState>,
+    _client: ScimClient,
+    query: Result<Query<ScimListQuery>, QueryRejection>,
+) -> Result<Response, ScimError> {
+    let query = list_query(query)?;
+    let filter = query
+        .filter
+        .as_deref()
+        .map(ScimService::<SqlxUserRepository, SqlxGroupRepository>::group_filter)
+        .transpose()
+        .map_err(ScimError::invalid_filter)?;
+    let (start_index, count) = page(&query);
+
+    let (groups, total) = scim_service(&state)
+        .list_groups(filter, start_index, count)
+        .await?;
+    let groups = groups.into_iter().map(ScimGroup::from).collect();
+
+    scim_response(
+        StatusCode::OK,
+        &ScimListResponse::new(groups, total, start_index),
+    )
+}
+
+#[utoipa::path(
+    post,
+    path = "/scim/v2/Groups",
+    request_body = ScimGroupRequest,
+    responses(
+        (status = 201, body = ScimGroup),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "displayName already exists")
+    ),
+    security(
+        ("scim_token" = [])
+    ),
+    tag = "scim"
+)]
+pub async fn create_group_handler(
+    State(state): State<AppState>,
+    _client: ScimClient,
+    audit: AuditContext,
+    payload: Result<Json<ScimGroupRequest>, JsonRejection>,
+) -> Result<Response, ScimError> {
+    let Json(payload) = payload?;
+    let group = scim_service(&state)
+        .create_group(payload.into(), &audit)
+        .await?;
+
+    scim_response(StatusCode::CREATED, &ScimGroup::from(group))
+}
+
+#[utoipa::path(
+    get,
+    path = "/scim/v2/Groups/{id}",
+    params(
+        ("id" = String, Path, description = "Group id")
+    ),
+    responses(
+        (status = 200, body = ScimGroup),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("scim_token" = [])
+    ),
+    tag = "scim"
+)]
+pub async fn get_group_handler(
+    State(state): State<AppState>,
+    _client: ScimClient,
+    Path(id): Path<String>,
+) -> Result<Response, ScimError> {
+    let group = scim_service(&state).get_group(parse_id(&id)?).await?;
+
+    scim_response(StatusCode::OK, &ScimGroup::from(group))
+}
+
+#[utoipa::path(
+    put,
+    path = "/scim/v2/Groups/{id}",
+    params(
+        ("id" = String, Path, description = "Group id")
+    ),
+    request_body = ScimGroupRequest,
+    responses(
+        (status = 200, body = ScimGroup),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Not found"),
+        (status = 409, description = "displayName already exists")
+    ),
+    security(
+        ("scim_token" = [])
+    ),
+    tag = "scim"
+)]
+pub async fn replace_group_handler(
+    State(state): State<AppState>,
+    _client: ScimClient,
+    audit: AuditContext,
+    Path(id): Path<String>,
+    payload: Result<Json<ScimGroupRequest>, JsonRejection>,
+) -> Result<Response, ScimError> {
+    let id = parse_id(&id)?;
+    let Json(payload) = payload?;
+    let group = scim_service(&state)
+        .replace_group(id, payload.into(), &audit)
+        .await?;
+
+    scim_response(StatusCode::OK, &ScimGroup::from(group))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/scim/v2/Groups/{id}",
+    params(
+        ("id" = String, Path, description = "Group id")
+    ),
+    request_body(content = serde_json::Value, description = "SCIM PatchOp on `displayName` and `members`"),
+    responses(
+        (status = 200, body = ScimGroup),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("scim_token" = [])
+    ),
+    tag = "scim"
+)]
+pub async fn patch_group_handler(
+    State(state): State<AppState>,
+    _client: ScimClient,
+    audit: AuditContext,
+    Path(id): Path<String>,
+    payload: Result<Json<ScimPatchRequest>, JsonRejection>,
+) -> Result<Response, ScimError> {
+    let id = parse_id(&id)?;
+    let Json(payload) = payload?;
+    let group = scim_service(&state)
+        .patch_group(id, payload.operations, &audit)
+        .await?;
+
+    scim_response(StatusCode::OK, &ScimGroup::from(group))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/scim/v2/Groups/{id}",
+    params(
+        ("id" = String, Path, description = "Group id")
+    ),
+    responses(
+        (status = 204, description = "Group deleted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("scim_token" = [])
+    ),
+    tag = "scim"
+)]
+pub async fn delete_group_handler(
+    State(state): State<AppState>,
+    _client: ScimClient,
+    audit: AuditContext,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ScimError> {
+    scim_service(&state)
+        .delete_group(parse_id(&id)?, &audit)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/scim/v2/ServiceProviderConfig",
+    responses(
+        (status = 200, body = serde_json::Value),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("scim_token" = [])
+    ),
+    tag = "scim"
+)]
+pub async fn service_provider_config_handler(_client: ScimClient) -> Result<Response, ScimError> {
+    scim_response(StatusCode::OK, &scim::service_provider_config())
+}
+
+#[utoipa::path(
+    get,
+    path = "/scim/v2/ResourceTypes",
+    responses(
+        (status = 200, body = serde_json::Value),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("scim_token" = [])
+    ),
+    tag = "scim"
+)]
+pub async fn resource_types_handler(_client: ScimClient) -> Result<Response, ScimError> {
+    let resource_types = scim::resource_types();
+    let total = resource_types.len() as i64;
+
+    scim_response(
+        StatusCode::OK,
+        &ScimListResponse::new(resource_types, total, 1),
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/scim/v2/Schemas",
+    responses(
+        (status = 200, body = serde_json::Value),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("scim_token" = [])
+    ),
+    tag = "scim"
+)]
+pub async fn schemas_handler(_client: ScimClient) -> Result<Response, ScimError> {
+    let schemas = scim::schemas();
+    let total = schemas.len() as i64;
+
+    scim_response(StatusCode::OK, &ScimListResponse::new(schemas, total, 1))
+}
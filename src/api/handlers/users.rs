@@ -1,16 +1,46 @@
-use crate::api::dto::user::{PaginationQuery, UpdateProfileRequest, UpdateUserRequest, UserResponse};
+use crate::api::dto::audit::{ExportFormat, ExportQuery};
+use crate::api::dto::auth::ImpersonationResponse;
+use crate::api::dto::user::{
+    AvatarQuery, BulkActionParam, BulkUserRequest, BulkUserResponse, BulkUserResultResponse,
+    ChangeStatusRequest, ConfirmQuery, ImportFormatParam, ImportQuery, ImportReportResponse,
+    UpdateProfileRequest, UpdateUserQuery, UpdateUserRequest, UserListQuery, UserPageResponse,
+    UserResponse,
+};
 use crate::api::error::AppError;
+use crate::api::handlers::attributes::attribute_service;
+use crate::api::handlers::auth::email_change_service;
+use crate::app::services::attribute_service::AttributeWriter;
+use crate::app::services::auth_service::AuthService;
+use crate::app::services::avatar_service::AvatarService;
+use crate::app::services::import_service::{
+    ImportFormat, ImportOptions, ImportSettings, UserImportService,
+};
+use crate::app::services::org_service::OrganizationService;
 use crate::app::services::user_service::UserService;
-use crate::domain::{AdminUpdateUser, UpdateProfile};
+use crate::domain::{
+    AdminUpdateUser, AuditContext, BulkUserAction, Cursor, DomainError, Permission, StatusChange,
+    UpdateProfile, User, UserPaging, UserQuery, UserSort,
+};
+use crate::infra::auth::jwt::JwtService;
+use crate::infra::db::audit_repo::SqlxAuditRepository;
+use crate::infra::db::org_repo::SqlxOrganizationRepository;
+use crate::infra::db::role_repo::SqlxRoleRepository;
 use crate::infra::db::user_repo::SqlxUserRepository;
+use crate::infra::mail::LogMailer;
+use crate::utils::csv_field;
 use crate::AppState;
-use axum::extract::{Path, Query, State};
-use axum::response::IntoResponse;
+use axum::body::Body;
+use axum::extract::multipart::MultipartError;
+use axum::extract::{Multipart, OriginalUri, Path, Query, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
 use axum::Json;
+use futures_util::{stream, StreamExt};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 use validator::Validate;
 
-use crate::api::middleware::auth::{AdminGuard, CurrentUser};
+use crate::api::middleware::auth::{perm, Authorized, CurrentUser, DirectUser, TenantScope};
 
 #[utoipa::path(
     get,
@@ -37,7 +67,9 @@ pub async fn get_me_handler(
     responses(
         (status = 200, body = UserResponse),
         (status = 400, description = "Validation error"),
-        (status = 401, description = "Unauthorized")
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Not allowed while impersonating, or the attribute is read-only"),
+        (status = 409, description = "The email, username or a unique attribute value is taken")
     ),
     security(
         ("bearer_auth" = [])
@@ -46,38 +78,185 @@ pub async fn get_me_handler(
 )]
 pub async fn update_me_handler(
     State(state): State<AppState>,
-    CurrentUser(current_user): CurrentUser,
+    DirectUser(current_user): DirectUser,
+    audit: AuditContext,
     Json(payload): Json<UpdateProfileRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     payload
         .validate()
         .map_err(|err| AppError::Validation(err.to_string()))?;
+    if let Some(ref attributes) = payload.attributes {
+        attribute_service(&state)
+            .check_changes(&current_user.attributes, attributes, AttributeWriter::Owner)
+            .await?;
+    }
+    // A new address only takes effect once confirmed; see `confirm_email_handler`. It is
+    // checked now but only requested, and mailed, once the rest of the update is stored.
+    let email_changes = email_change_service(&state);
+    let new_email = match payload.email.filter(|email| *email != current_user.email) {
+        Some(email) => Some(email_changes.check(&current_user, &email).await?),
+        None => None,
+    };
 
     let repo = SqlxUserRepository::new(state.db.clone());
-    let service = UserService::new(repo);
+    let service = UserService::new(repo, state.identity_policy.clone());
 
-    let updated = service
+    let mut updated = service
         .update_profile(
             current_user.id,
             UpdateProfile {
-                email: payload.email,
                 username: payload.username,
+                display_name: payload.name,
+                given_name: payload.given_name,
+                family_name: payload.family_name,
+                locale: payload.locale,
+                timezone: payload.zoneinfo,
+                avatar_url: payload.picture,
+                attributes: payload.attributes,
             },
+            &audit,
         )
         .await?;
 
+    if let Some(email) = new_email {
+        let change = email_changes.request(&updated, email, &audit).await?;
+        updated.pending_email = Some(change.new_email);
+    }
+
     Ok(Json(UserResponse::from(updated)))
 }
 
+/// Avatar URLs change with every upload, so browsers and CDNs may keep an image for a day.
+const AVATAR_CACHE_CONTROL: &str = "public, max-age=86400";
+
+fn avatar_service(state: &AppState) -> AvatarService<SqlxUserRepository> {
+    AvatarService::new(
+        SqlxUserRepository::new(state.db.clone()),
+        state.blobs.clone(),
+        state.config.avatar_max_bytes,
+    )
+}
+
+#[utoipa::path(
+    put,
+    path = "/users/me/avatar",
+    request_body(content = crate::api::dto::user::AvatarUploadForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, body = UserResponse),
+        (status = 400, description = "Missing, oversized, unsupported or invalid image"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Not allowed while impersonating")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "users"
+)]
+pub async fn upload_avatar_handler(
+    State(state): State<AppState>,
+    DirectUser(current_user): DirectUser,
+    audit: AuditContext,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, AppError> {
+    let max_bytes = state.config.avatar_max_bytes;
+    let invalid = |err: MultipartError| match err.status() {
+        StatusCode::PAYLOAD_TOO_LARGE => {
+            AppError::Validation(format!("avatar must be at most {max_bytes} bytes"))
+        }
+        _ => AppError::BadRequest(err.body_text()),
+    };
+
+    while let Some(field) = multipart.next_field().await.map_err(invalid)? {
+        if field.name() != Some("avatar") {
+            continue;
+        }
+        let content_type = field.content_type().unwrap_or_default().to_string();
+        let bytes = field.bytes().await.map_err(invalid)?;
+
+        let user = avatar_service(&state)
+            .upload(current_user.id, &content_type, bytes.to_vec(), &audit)
+            .await?;
+        return Ok(Json(UserResponse::from(user)));
+    }
+
+    Err(AppError::BadRequest(
+        "missing `avatar` file field".to_string(),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/{id}/avatar",
+    params(
+        ("id" = String, Path, description = "User id"),
+        ("size" = Option<u32>, Query, description = "Edge length in pixels (512, 128 or 64); larger stored sizes are served for others")
+    ),
+    responses(
+        (status = 200, description = "Square JPEG avatar", content_type = "image/jpeg"),
+        (status = 304, description = "Not modified"),
+        (status = 400, description = "Bad request"),
+        (status = 404, description = "No uploaded avatar")
+    ),
+    tag = "users"
+)]
+pub async fn get_avatar_handler(
+    State(state): State<AppState>,
+    Path(user_id): Path<String>,
+    Query(query): Query<AvatarQuery>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let user_id = Uuid::parse_str(&user_id)
+        .map_err(|_| AppError::BadRequest("invalid user id".to_string()))?;
+
+    let blob = avatar_service(&state).get(user_id, query.size).await?;
+    let etag = format!("\"{}\"", hex::encode(&Sha256::digest(&blob.bytes)[..16]));
+    let cache_headers = [
+        (header::ETAG, etag.clone()),
+        (header::CACHE_CONTROL, AVATAR_CACHE_CONTROL.to_string()),
+    ];
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag));
+    if not_modified {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+
+    Ok((
+        cache_headers,
+        [
+            (header::CONTENT_TYPE, blob.content_type),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        blob.bytes,
+    )
+        .into_response())
+}
+
 #[utoipa::path(
     get,
     path = "/users",
     params(
         ("page" = Option<i64>, Query, description = "Page number"),
-        ("per_page" = Option<i64>, Query, description = "Items per page")
+        ("per_page" = Option<i64>, Query, description = "Items per page"),
+        ("search" = Option<String>, Query, description = "Fuzzy, case-insensitive match on email and username"),
+        ("role" = Option<String>, Query, description = "Only users holding this role, directly or through a group"),
+        ("is_active" = Option<bool>, Query, description = "Only active or only inactive users"),
+        ("status" = Option<AccountState>, Query, description = "Only users with this account status"),
+        ("created_from" = Option<String>, Query, description = "Created at or after this RFC 3339 timestamp"),
+        ("created_to" = Option<String>, Query, description = "Created before this RFC 3339 timestamp"),
+        ("email_domain" = Option<String>, Query, description = "Only emails at this domain"),
+        ("attr.{name}" = Option<String>, Query, description = "Only users whose indexed custom attribute `name` equals this value; repeatable for several attributes"),
+        ("sort" = Option<String>, Query, description = "`created_at`, `email` or `username`; prefix with `-` for descending. Defaults to best match when searching, else `-created_at`"),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page; selects cursor pagination"),
+        ("limit" = Option<i64>, Query, description = "Page size for cursor pagination (default 20, max 100); selects it"),
+        ("include_total" = Option<bool>, Query, description = "Count all matches: `total` in cursor mode, `X-Total-Count` otherwise"),
+        ("X-Org-Id" = Option<String>, Header, description = "Scope the list to this organization; only page/per_page are supported there")
     ),
     responses(
-        (status = 200, body = [UserResponse]),
+        (status = 200, description = "A `UserPageResponse` in cursor mode, else an array of `UserResponse`. `Link` headers point to adjacent pages", body = [UserResponse]),
+        (status = 400, description = "Bad request"),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Forbidden")
     ),
@@ -88,20 +267,414 @@ pub async fn update_me_handler(
 )]
 pub async fn list_users_handler(
     State(state): State<AppState>,
-    AdminGuard(_admin): AdminGuard,
-    Query(pagination): Query<PaginationQuery>,
+    scope: TenantScope,
+    OriginalUri(uri): OriginalUri,
+    Query(params): Query<UserListQuery>,
+    Query(pairs): Query<Vec<(String, String)>>,
+) -> Result<Response, AppError> {
+    let attribute_pairs = attribute_params(pairs);
+    if params.uses_cursor() && (params.page.is_some() || params.per_page.is_some()) {
+        return Err(AppError::BadRequest(
+            "use either cursor/limit or page/per_page".to_string(),
+        ));
+    }
+
+    if let Some(org_id) = scope.org_id {
+        if !scope.can_manage_members() && !scope.user.has_permission(Permission::UsersRead) {
+            return Err(AppError::Forbidden(
+                "organization admin role required".to_string(),
+            ));
+        }
+        if params.has_filters()
+            || !attribute_pairs.is_empty()
+            || params.uses_cursor()
+            || params.include_total
+        {
+            return Err(AppError::BadRequest(
+                "organization member lists only support page/per_page".to_string(),
+            ));
+        }
+
+        let page = params.page.unwrap_or(1).max(1);
+        let per_page = params.per_page.unwrap_or(20).clamp(1, 100);
+        let service = OrganizationService::new(
+            SqlxOrganizationRepository::new(state.db.clone()),
+            SqlxUserRepository::new(state.db.clone()),
+        );
+        let users: Vec<UserResponse> = service
+            .list_members(&scope.user, org_id, per_page, (page - 1) * per_page)
+            .await?
+            .into_iter()
+            .map(|member| UserResponse::from(member.user))
+            .collect();
+
+        // Without a look-ahead row, a full page is assumed to have a successor.
+        let has_next = users.len() as i64 == per_page;
+        let headers = page_links(&uri, page, has_next);
+        return Ok((headers, Json(users)).into_response());
+    }
+
+    if !scope.user.has_permission(Permission::UsersRead) {
+        return Err(AppError::Forbidden(format!(
+            "missing permission: {}",
+            Permission::UsersRead
+        )));
+    }
+
+    let sort = params
+        .sort
+        .as_deref()
+        .map(str::parse::<UserSort>)
+        .transpose()
+        .map_err(AppError::BadRequest)?;
+    let page = params.page.unwrap_or(1).max(1);
+    let (paging, limit) = if params.uses_cursor() {
+        let after = params.cursor.as_deref().map(Cursor::decode).transpose()?;
+        (
+            UserPaging::After(after),
+            params.limit.unwrap_or(20).clamp(1, 100),
+        )
+    } else {
+        let per_page = params.per_page.unwrap_or(20).clamp(1, 100);
+        (UserPaging::Offset((page - 1) * per_page), per_page)
+    };
+    let query = UserQuery {
+        search: params.search,
+        role: params.role,
+        is_active: params.is_active,
+        status: params.status,
+        created_from: params.created_from,
+        created_to: params.created_to,
+        email_domain: params.email_domain,
+        attributes: attribute_service(&state)
+            .resolve_filters(attribute_pairs)
+            .await?,
+        filter: None,
+        sort,
+        paging,
+        limit,
+    };
+
+    let service = UserService::new(
+        SqlxUserRepository::new(state.db.clone()),
+        state.identity_policy.clone(),
+    );
+    let total = if params.include_total {
+        Some(service.count_users(query.clone()).await?)
+    } else {
+        None
+    };
+    let users = service.list_users(query).await?;
+    let items: Vec<UserResponse> = users.items.into_iter().map(UserResponse::from).collect();
+
+    if let UserPaging::Offset(_) = paging {
+        let mut headers = page_links(&uri, page, users.next_cursor.is_some());
+        if let Some(total) = total {
+            headers.insert(TOTAL_COUNT_HEADER, HeaderValue::from(total));
+        }
+        return Ok((headers, Json(items)).into_response());
+    }
+
+    let next_cursor = users.next_cursor.map(|cursor| cursor.encode());
+    let links: Vec<(String, &str)> = next_cursor
+        .iter()
+        .map(|cursor| (with_query_param(&uri, "cursor", cursor), "next"))
+        .collect();
+    let response = UserPageResponse {
+        items,
+        next_cursor,
+        total,
+    };
+
+    Ok((link_headers(&links), Json(response)).into_response())
+}
+
+/// Users fetched per round trip while streaming an export.
+const EXPORT_BATCH_SIZE: i64 = 500;
+
+const CSV_HEADER: &str =
+    "id,email,username,roles,is_active,status,email_verified,created_at,updated_at\n";
+
+#[utoipa::path(
+    get,
+    path = "/users/export",
+    params(
+        ("format" = Option<ExportFormat>, Query, description = "`ndjson` (default) or `csv`"),
+        ("search" = Option<String>, Query, description = "Fuzzy, case-insensitive match on email and username"),
+        ("role" = Option<String>, Query, description = "Only users holding this role, directly or through a group"),
+        ("is_active" = Option<bool>, Query, description = "Only active or only inactive users"),
+        ("status" = Option<AccountState>, Query, description = "Only users with this account status"),
+        ("created_from" = Option<String>, Query, description = "Created at or after this RFC 3339 timestamp"),
+        ("created_to" = Option<String>, Query, description = "Created before this RFC 3339 timestamp"),
+        ("email_domain" = Option<String>, Query, description = "Only emails at this domain"),
+        ("attr.{name}" = Option<String>, Query, description = "Only users whose indexed custom attribute `name` equals this value; repeatable for several attributes")
+    ),
+    responses(
+        (status = 200, description = "Matching users, newest first", content_type = ["application/x-ndjson", "text/csv"]),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "users"
+)]
+pub async fn export_users_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::UsersRead>,
+    Query(export): Query<ExportQuery>,
+    Query(params): Query<UserListQuery>,
+    Query(pairs): Query<Vec<(String, String)>>,
 ) -> Result<impl IntoResponse, AppError> {
-    let page = pagination.page.unwrap_or(1).max(1);
-    let per_page = pagination.per_page.unwrap_or(20).clamp(1, 100);
-    let offset = (page - 1) * per_page;
+    if params.page.is_some()
+        || params.per_page.is_some()
+        || params.uses_cursor()
+        || params.include_total
+    {
+        return Err(AppError::BadRequest(
+            "exports include every match; paging parameters are not supported".to_string(),
+        ));
+    }
+    if params.sort.is_some() {
+        return Err(AppError::BadRequest(
+            "exports are always ordered by `-created_at`".to_string(),
+        ));
+    }
 
-    let repo = SqlxUserRepository::new(state.db.clone());
-    let service = UserService::new(repo);
+    let query = UserQuery {
+        search: params.search,
+        role: params.role,
+        is_active: params.is_active,
+        status: params.status,
+        created_from: params.created_from,
+        created_to: params.created_to,
+        email_domain: params.email_domain,
+        attributes: attribute_service(&state)
+            .resolve_filters(attribute_params(pairs))
+            .await?,
+        filter: None,
+        sort: None,
+        paging: UserPaging::After(None),
+        limit: EXPORT_BATCH_SIZE,
+    };
+    let service = UserService::new(
+        SqlxUserRepository::new(state.db.clone()),
+        state.identity_policy.clone(),
+    );
+
+    // As with audit exports, the first batch is fetched before answering so that bad
+    // filters get an error status; the rest follow as the client reads.
+    let first = service.list_users(query.clone()).await?;
+
+    let (content_type, extension, header_row) = match export.format {
+        ExportFormat::Ndjson => ("application/x-ndjson", "ndjson", String::new()),
+        ExportFormat::Csv => ("text/csv; charset=utf-8", "csv", CSV_HEADER.to_string()),
+    };
+    let format = export.format;
 
-    let users = service.list_users(per_page, offset).await?;
-    let response: Vec<UserResponse> = users.into_iter().map(UserResponse::from).collect();
+    let batches = stream::unfold(
+        Some((service, query, Some(first))),
+        move |state| async move {
+            let (service, mut query, page) = state?;
+            let page = match page {
+                Some(page) => page,
+                None => match service.list_users(query.clone()).await {
+                    Ok(page) => page,
+                    Err(err) => {
+                        tracing::error!(error = %err, "user export aborted");
+                        return Some((Err(err), None));
+                    }
+                },
+            };
 
-    Ok(Json(response))
+            let chunk = render_users(format, page.items);
+            let next = page.next_cursor.map(|cursor| {
+                query.paging = UserPaging::After(Some(cursor));
+                (service, query, None)
+            });
+
+            Some((Ok(chunk), next))
+        },
+    );
+
+    let body = stream::iter([Ok::<_, DomainError>(header_row)]);
+    let body = Body::from_stream(body.chain(batches));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"users.{extension}\""),
+            ),
+        ],
+        body,
+    ))
+}
+
+fn render_users(format: ExportFormat, users: Vec<User>) -> String {
+    let mut chunk = String::new();
+    for user in users.into_iter().map(UserResponse::from) {
+        match format {
+            ExportFormat::Ndjson => {
+                chunk.push_str(&serde_json::to_string(&user).unwrap_or_default());
+            }
+            ExportFormat::Csv => {
+                let fields = [
+                    user.id,
+                    user.email,
+                    user.username,
+                    user.roles.join(";"),
+                    user.is_active.to_string(),
+                    user.status.to_string(),
+                    user.email_verified.to_string(),
+                    user.created_at.to_rfc3339(),
+                    user.updated_at.to_rfc3339(),
+                ];
+                let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+                chunk.push_str(&row.join(","));
+            }
+        }
+        chunk.push('\n');
+    }
+    chunk
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/bulk",
+    params(
+        ("confirm" = Option<bool>, Query, description = "Required to deactivate your own account")
+    ),
+    request_body = BulkUserRequest,
+    responses(
+        (status = 200, body = BulkUserResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 409, description = "Conflict (`last_admin`, `confirmation_required`); nothing was changed")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "users"
+)]
+pub async fn bulk_update_users_handler(
+    State(state): State<AppState>,
+    Authorized(admin, _): Authorized<perm::UsersWrite>,
+    audit: AuditContext,
+    Query(confirm): Query<ConfirmQuery>,
+    Json(payload): Json<BulkUserRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    let (action, permission) = match (payload.action, payload.role) {
+        (BulkActionParam::Deactivate, None) => {
+            (BulkUserAction::Deactivate, Permission::UsersDeactivate)
+        }
+        (BulkActionParam::Reactivate, None) => (BulkUserAction::Reactivate, Permission::UsersWrite),
+        (BulkActionParam::SetRole, Some(role)) => {
+            (BulkUserAction::SetRole(role), Permission::RolesAssign)
+        }
+        (BulkActionParam::SetRole, None) => {
+            return Err(AppError::BadRequest(
+                "`set_role` requires `role`".to_string(),
+            ));
+        }
+        (_, Some(_)) => {
+            return Err(AppError::BadRequest(
+                "`role` is only allowed with `set_role`".to_string(),
+            ));
+        }
+    };
+    if !admin.has_permission(permission) {
+        return Err(AppError::Forbidden(format!(
+            "missing permission: {permission}"
+        )));
+    }
+
+    let service = UserService::new(
+        SqlxUserRepository::new(state.db.clone()),
+        state.identity_policy.clone(),
+    );
+    let results = service
+        .bulk_update(&admin, payload.user_ids, action, confirm.confirm, &audit)
+        .await?;
+
+    Ok(Json(BulkUserResponse {
+        results: results
+            .into_iter()
+            .map(BulkUserResultResponse::from)
+            .collect(),
+    }))
+}
+
+/// Total number of matches in the `page`/`per_page` mode, which returns a bare array.
+const TOTAL_COUNT_HEADER: &str = "x-total-count";
+
+/// Query parameters filtering on custom attributes are named `attr.<name>`.
+const ATTRIBUTE_PARAM_PREFIX: &str = "attr.";
+
+/// `(name, value)` of every `attr.<name>=<value>` query parameter.
+fn attribute_params(pairs: Vec<(String, String)>) -> Vec<(String, String)> {
+    pairs
+        .into_iter()
+        .filter_map(|(key, value)| {
+            Some((key.strip_prefix(ATTRIBUTE_PARAM_PREFIX)?.to_string(), value))
+        })
+        .collect()
+}
+
+/// `uri` with `key` set to `value`, keeping every other query parameter.
+fn with_query_param(uri: &Uri, key: &str, value: &str) -> String {
+    let param = format!("{key}={value}");
+    let mut pairs: Vec<&str> = uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|pair| !pair.is_empty() && pair.split('=').next() != Some(key))
+        .collect();
+    pairs.push(&param);
+    format!("{}?{}", uri.path(), pairs.join("&"))
+}
+
+fn page_links(uri: &Uri, page: i64, has_next: bool) -> HeaderMap {
+    let mut links = Vec::new();
+    if page > 1 {
+        links.push((
+            with_query_param(uri, "page", &(page - 1).to_string()),
+            "prev",
+        ));
+    }
+    if has_next {
+        links.push((
+            with_query_param(uri, "page", &(page + 1).to_string()),
+            "next",
+        ));
+    }
+    link_headers(&links)
+}
+
+/// RFC 8288 `Link` header for `(target, rel)` pairs; empty when there are none.
+fn link_headers(links: &[(String, &str)]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if links.is_empty() {
+        return headers;
+    }
+
+    let value = links
+        .iter()
+        .map(|(target, rel)| format!("<{target}>; rel=\"{rel}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    // Targets are built from the request URI, so they are valid header characters.
+    if let Ok(value) = HeaderValue::from_str(&value) {
+        headers.insert(header::LINK, value);
+    }
+    headers
 }
 
 #[utoipa::path(
@@ -124,14 +697,14 @@ pub async fn list_users_handler(
 )]
 pub async fn get_user_handler(
     State(state): State<AppState>,
-    AdminGuard(_admin): AdminGuard,
+    Authorized(_admin, _): Authorized<perm::UsersRead>,
     Path(user_id): Path<String>,
 ) -> Result<impl IntoResponse, AppError> {
     let user_id = Uuid::parse_str(&user_id)
         .map_err(|_| AppError::BadRequest("invalid user id".to_string()))?;
 
     let repo = SqlxUserRepository::new(state.db.clone());
-    let service = UserService::new(repo);
+    let service = UserService::new(repo, state.identity_policy.clone());
 
     let user = service.get_profile(user_id).await?;
 
@@ -143,14 +716,17 @@ pub async fn get_user_handler(
     path = "/users/{id}",
     request_body = UpdateUserRequest,
     params(
-        ("id" = String, Path, description = "User id")
+        ("id" = String, Path, description = "User id"),
+        ("confirm" = Option<bool>, Query, description = "Required to deactivate your own account"),
+        ("bypass_policy" = Option<bool>, Query, description = "Allow a reserved username or blocked email domain")
     ),
     responses(
         (status = 200, body = UserResponse),
-        (status = 400, description = "Bad request"),
+        (status = 400, description = "Bad request, or the username or email domain is not allowed"),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Forbidden"),
-        (status = 404, description = "Not found")
+        (status = 404, description = "Not found"),
+        (status = 409, description = "Conflict (`last_admin`, `confirmation_required`)")
     ),
     security(
         ("bearer_auth" = [])
@@ -159,8 +735,10 @@ pub async fn get_user_handler(
 )]
 pub async fn update_user_handler(
     State(state): State<AppState>,
-    AdminGuard(_admin): AdminGuard,
+    Authorized(admin, _): Authorized<perm::UsersWrite>,
+    audit: AuditContext,
     Path(user_id): Path<String>,
+    Query(query): Query<UpdateUserQuery>,
     Json(payload): Json<UpdateUserRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     payload
@@ -171,17 +749,28 @@ pub async fn update_user_handler(
         .map_err(|_| AppError::BadRequest("invalid user id".to_string()))?;
 
     let repo = SqlxUserRepository::new(state.db.clone());
-    let service = UserService::new(repo);
+    let service = UserService::new(repo, state.identity_policy.clone());
+
+    if let Some(ref attributes) = payload.attributes {
+        let current = service.get_profile(user_id).await?;
+        attribute_service(&state)
+            .check_changes(&current.attributes, attributes, AttributeWriter::Admin)
+            .await?;
+    }
 
     let user = service
         .update_user(
+            &admin,
             user_id,
             AdminUpdateUser {
                 email: payload.email,
                 username: payload.username,
-                role: payload.role,
                 is_active: payload.is_active,
+                attributes: payload.attributes,
             },
+            query.confirm,
+            query.bypass_policy,
+            &audit,
         )
         .await?;
 
@@ -192,14 +781,16 @@ pub async fn update_user_handler(
     delete,
     path = "/users/{id}",
     params(
-        ("id" = String, Path, description = "User id")
+        ("id" = String, Path, description = "User id"),
+        ("confirm" = Option<bool>, Query, description = "Required to deactivate your own account")
     ),
     responses(
         (status = 204, description = "Deactivated"),
         (status = 400, description = "Bad request"),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Forbidden"),
-        (status = 404, description = "Not found")
+        (status = 404, description = "Not found"),
+        (status = 409, description = "Conflict (`last_admin`, `confirmation_required`)")
     ),
     security(
         ("bearer_auth" = [])
@@ -208,16 +799,219 @@ pub async fn update_user_handler(
 )]
 pub async fn deactivate_user_handler(
     State(state): State<AppState>,
-    AdminGuard(_admin): AdminGuard,
+    Authorized(admin, _): Authorized<perm::UsersDeactivate>,
+    audit: AuditContext,
+    Path(user_id): Path<String>,
+    Query(confirm): Query<ConfirmQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = Uuid::parse_str(&user_id)
+        .map_err(|_| AppError::BadRequest("invalid user id".to_string()))?;
+
+    let repo = SqlxUserRepository::new(state.db.clone());
+    let service = UserService::new(repo, state.identity_policy.clone());
+
+    service
+        .deactivate_user(&admin, user_id, confirm.confirm, &audit)
+        .await?;
+
+    Ok(axum::http::StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/{id}/status",
+    request_body = ChangeStatusRequest,
+    params(
+        ("id" = String, Path, description = "User id"),
+        ("confirm" = Option<bool>, Query, description = "Required to block your own account")
+    ),
+    responses(
+        (status = 200, body = UserResponse),
+        (status = 400, description = "Bad request, e.g. a suspension without a reason"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found"),
+        (status = 409, description = "Conflict (`invalid_status_transition`, `last_admin`, `confirmation_required`)")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "users"
+)]
+pub async fn change_user_status_handler(
+    State(state): State<AppState>,
+    Authorized(admin, _): Authorized<perm::UsersDeactivate>,
+    audit: AuditContext,
     Path(user_id): Path<String>,
+    Query(confirm): Query<ConfirmQuery>,
+    Json(payload): Json<ChangeStatusRequest>,
 ) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
     let user_id = Uuid::parse_str(&user_id)
         .map_err(|_| AppError::BadRequest("invalid user id".to_string()))?;
 
     let repo = SqlxUserRepository::new(state.db.clone());
-    let service = UserService::new(repo);
+    let service = UserService::new(repo, state.identity_policy.clone());
+
+    let change = StatusChange {
+        state: payload.status,
+        reason: payload.reason,
+        until: payload.until,
+    };
+    let user = service
+        .change_status(&admin, user_id, change, confirm.confirm, &audit)
+        .await?;
+
+    Ok(Json(UserResponse::from(user)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/me/deactivate",
+    params(
+        ("confirm" = bool, Query, description = "Must be `true`")
+    ),
+    responses(
+        (status = 204, description = "Deactivated; only an admin can reactivate the account"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden under impersonation"),
+        (status = 409, description = "Conflict (`last_admin`, `confirmation_required`)")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "users"
+)]
+pub async fn deactivate_me_handler(
+    State(state): State<AppState>,
+    DirectUser(current_user): DirectUser,
+    audit: AuditContext,
+    Query(confirm): Query<ConfirmQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let repo = SqlxUserRepository::new(state.db.clone());
+    let service = UserService::new(repo, state.identity_policy.clone());
 
-    service.deactivate_user(user_id).await?;
+    service
+        .deactivate_user(&current_user, current_user.id, confirm.confirm, &audit)
+        .await?;
 
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
+
+#[utoipa::path(
+    post,
+    path = "/users/{id}/impersonate",
+    params(
+        ("id" = String, Path, description = "User id")
+    ),
+    responses(
+        (status = 200, body = ImpersonationResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found"),
+        (status = 409, description = "User is inactive")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "users"
+)]
+pub async fn impersonate_user_handler(
+    State(state): State<AppState>,
+    DirectUser(current_user): DirectUser,
+    audit: AuditContext,
+    Path(user_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    if !current_user.has_permission(Permission::UsersImpersonate) {
+        return Err(AppError::Forbidden(format!(
+            "missing permission: {}",
+            Permission::UsersImpersonate
+        )));
+    }
+
+    let user_id = Uuid::parse_str(&user_id)
+        .map_err(|_| AppError::BadRequest("invalid user id".to_string()))?;
+
+    let service = AuthService::new(
+        SqlxUserRepository::new(state.db.clone()),
+        SqlxAuditRepository::new(state.db.clone()),
+        JwtService::new(&state.config),
+        state.identity_policy.clone(),
+    );
+    let token = service.impersonate(&current_user, user_id, &audit).await?;
+
+    Ok(Json(ImpersonationResponse {
+        access_token: token.access_token,
+        expires_at: token.expires_at,
+        actor_id: current_user.id.to_string(),
+        user: UserResponse::from(token.user),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/import",
+    params(
+        ("format" = Option<ImportFormatParam>, Query, description = "`csv` or `ndjson`; defaults to the Content-Type (`text/csv` or `application/x-ndjson`)"),
+        ("dry_run" = Option<bool>, Query, description = "Validate and report without creating anything"),
+        ("invite" = Option<bool>, Query, description = "Rows without `password_hash` get an account setup link by email")
+    ),
+    request_body(content = String, description = "Rows with `email`, `username` and optional `role`, `is_active`, `password_hash`", content_type = "text/csv"),
+    responses(
+        (status = 200, body = ImportReportResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "users"
+)]
+pub async fn import_users_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::UsersImport>,
+    audit: AuditContext,
+    headers: HeaderMap,
+    Query(query): Query<ImportQuery>,
+    body: String,
+) -> Result<impl IntoResponse, AppError> {
+    let format = match query.format {
+        Some(ImportFormatParam::Csv) => ImportFormat::Csv,
+        Some(ImportFormatParam::Ndjson) => ImportFormat::Ndjson,
+        None => {
+            let content_type = headers
+                .get(header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default();
+            if content_type.starts_with("text/csv") {
+                ImportFormat::Csv
+            } else if content_type.starts_with("application/x-ndjson") {
+                ImportFormat::Ndjson
+            } else {
+                return Err(AppError::BadRequest(
+                    "set `format` or send text/csv or application/x-ndjson".to_string(),
+                ));
+            }
+        }
+    };
+
+    let service = UserImportService::new(
+        SqlxUserRepository::new(state.db.clone()),
+        SqlxRoleRepository::new(state.db.clone()),
+        LogMailer,
+        JwtService::new(&state.config),
+        ImportSettings::from_config(&state.config),
+    );
+    let options = ImportOptions {
+        dry_run: query.dry_run,
+        invite: query.invite,
+    };
+    let report = service.import(format, &body, options, &audit).await?;
+
+    Ok(Json(ImportReportResponse::from(report)))
+}
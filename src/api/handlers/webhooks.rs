@@ -0,0 +1,275 @@
+use crate::api::dto::webhook::{
+    CreateWebhookRequest, DeliveriesQuery, UpdateWebhookRequest, WebhookDeliveryResponse,
+    WebhookResponse,
+};
+use crate::api::error::AppError;
+use crate::app::services::webhook_service::{CreateWebhookInput, WebhookService, WebhookSettings};
+use crate::domain::UpdateWebhookEndpoint;
+use crate::infra::db::webhook_repo::SqlxWebhookRepository;
+use crate::infra::webhook::HttpWebhookSender;
+use crate::AppState;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::api::middleware::auth::{perm, Authorized};
+
+fn webhook_service(state: &AppState) -> WebhookService<SqlxWebhookRepository, HttpWebhookSender> {
+    let settings = WebhookSettings::from_config(&state.config);
+    WebhookService::new(
+        SqlxWebhookRepository::new(state.db.clone()),
+        HttpWebhookSender::new(settings.timeout),
+        settings,
+    )
+}
+
+fn parse_id(value: &str, what: &str) -> Result<Uuid, AppError> {
+    Uuid::parse_str(value).map_err(|_| AppError::BadRequest(format!("invalid {what} id")))
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks",
+    responses(
+        (status = 200, body = [WebhookResponse]),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "webhooks"
+)]
+pub async fn list_webhooks_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::WebhooksManage>,
+) -> Result<impl IntoResponse, AppError> {
+    let endpoints = webhook_service(&state).list_endpoints().await?;
+    let response: Vec<WebhookResponse> = endpoints.into_iter().map(WebhookResponse::from).collect();
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/webhooks",
+    request_body = CreateWebhookRequest,
+    responses(
+        (status = 201, body = WebhookResponse),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "webhooks"
+)]
+pub async fn create_webhook_handler(
+    State(state): State<AppState>,
+    Authorized(admin, _): Authorized<perm::WebhooksManage>,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    let endpoint = webhook_service(&state)
+        .create_endpoint(
+            &admin,
+            CreateWebhookInput {
+                url: payload.url,
+                event_types: payload.event_types,
+                description: payload.description,
+            },
+        )
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(WebhookResponse::with_secret(endpoint)),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks/{id}",
+    params(
+        ("id" = String, Path, description = "Webhook id")
+    ),
+    responses(
+        (status = 200, body = WebhookResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "webhooks"
+)]
+pub async fn get_webhook_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::WebhooksManage>,
+    Path(webhook_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let webhook_id = parse_id(&webhook_id, "webhook")?;
+    let endpoint = webhook_service(&state).get_endpoint(webhook_id).await?;
+
+    Ok(Json(WebhookResponse::from(endpoint)))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/webhooks/{id}",
+    request_body = UpdateWebhookRequest,
+    params(
+        ("id" = String, Path, description = "Webhook id")
+    ),
+    responses(
+        (status = 200, body = WebhookResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "webhooks"
+)]
+pub async fn update_webhook_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::WebhooksManage>,
+    Path(webhook_id): Path<String>,
+    Json(payload): Json<UpdateWebhookRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let webhook_id = parse_id(&webhook_id, "webhook")?;
+    payload
+        .validate()
+        .map_err(|err| AppError::Validation(err.to_string()))?;
+
+    let endpoint = webhook_service(&state)
+        .update_endpoint(
+            webhook_id,
+            UpdateWebhookEndpoint {
+                url: payload.url,
+                event_types: payload.event_types,
+                description: payload.description,
+                is_active: payload.is_active,
+            },
+        )
+        .await?;
+
+    Ok(Json(WebhookResponse::from(endpoint)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/webhooks/{id}",
+    params(
+        ("id" = String, Path, description = "Webhook id")
+    ),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "webhooks"
+)]
+pub async fn delete_webhook_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::WebhooksManage>,
+    Path(webhook_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let webhook_id = parse_id(&webhook_id, "webhook")?;
+    webhook_service(&state).delete_endpoint(webhook_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks/{id}/deliveries",
+    params(
+        ("id" = String, Path, description = "Webhook id"),
+        ("limit" = Option<i64>, Query, description = "Number of deliveries (default 50, max 200)")
+    ),
+    responses(
+        (status = 200, body = [WebhookDeliveryResponse]),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "webhooks"
+)]
+pub async fn list_deliveries_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::WebhooksManage>,
+    Path(webhook_id): Path<String>,
+    Query(params): Query<DeliveriesQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let webhook_id = parse_id(&webhook_id, "webhook")?;
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+
+    let deliveries = webhook_service(&state)
+        .list_deliveries(webhook_id, limit)
+        .await?;
+    let response: Vec<WebhookDeliveryResponse> = deliveries
+        .into_iter()
+        .map(WebhookDeliveryResponse::from)
+        .collect();
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/webhooks/{id}/deliveries/{delivery_id}/redeliver",
+    params(
+        ("id" = String, Path, description = "Webhook id"),
+        ("delivery_id" = String, Path, description = "Delivery id")
+    ),
+    responses(
+        (status = 202, body = WebhookDeliveryResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not found"),
+        (status = 409, description = "Delivery is still pending")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "webhooks"
+)]
+pub async fn redeliver_handler(
+    State(state): State<AppState>,
+    Authorized(_admin, _): Authorized<perm::WebhooksManage>,
+    Path((webhook_id, delivery_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    let webhook_id = parse_id(&webhook_id, "webhook")?;
+    let delivery_id = parse_id(&delivery_id, "delivery")?;
+
+    let delivery = webhook_service(&state)
+        .redeliver(webhook_id, delivery_id)
+        .await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(WebhookDeliveryResponse::from(delivery)),
+    ))
+}
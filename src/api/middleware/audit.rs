@@ -0,0 +1,57 @@
+use crate::domain::AuditContext;
+use crate::infra::auth::jwt::Claims;
+use crate::AppState;
+use axum::extract::{ConnectInfo, FromRef, FromRequestParts};
+use axum::http::{header, request::Parts};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use uuid::Uuid;
+
+/// Request id header; `SetRequestIdLayer` fills it in when the client sent none.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+const FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+
+/// Describes the request for the audit log. The caller is taken from the verified token,
+/// so place this extractor after [`CurrentUser`](super::auth::CurrentUser) or
+/// [`Authorized`](super::auth::Authorized) in handler arguments.
+#[async_trait::async_trait]
+impl<S> FromRequestParts<S> for AuditContext
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let state = AppState::from_ref(state);
+        let header_value = |name: &str| {
+            parts
+                .headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+
+        // Only trust the forwarded address behind a proxy that overwrites it.
+        let forwarded_ip = header_value(FORWARDED_FOR_HEADER)
+            .filter(|_| state.config.trust_proxy_headers)
+            .and_then(|value| value.split(',').next().map(|ip| ip.trim().to_string()));
+        let ip = forwarded_ip.or_else(|| {
+            parts
+                .extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip().to_string())
+        });
+
+        let claims = parts.extensions.get::<Claims>();
+
+        Ok(AuditContext {
+            actor_id: claims.and_then(|claims| Uuid::parse_str(&claims.sub).ok()),
+            impersonator_id: claims.and_then(Claims::actor_id),
+            ip,
+            user_agent: header_value(header::USER_AGENT.as_str()),
+            request_id: header_value(REQUEST_ID_HEADER),
+        })
+    }
+}
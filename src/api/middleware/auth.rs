@@ -1,18 +1,107 @@
-use crate::api::error::AppError;
-use crate::domain::{User, UserRepository};
-use crate::infra::auth::jwt::{JwtService, TokenType};
+use crate::api::error::{AppError, ScimError};
+use crate::app::services::org_service::OrganizationService;
+use crate::domain::{OrgRole, Permission, User, UserRepository};
+use crate::infra::auth::jwt::{Claims, JwtService, TokenType};
+use crate::infra::db::org_repo::SqlxOrganizationRepository;
 use crate::infra::db::user_repo::SqlxUserRepository;
 use crate::AppState;
-use axum::extract::FromRequestParts;
-use axum::http::{header, request::Parts};
 use axum::extract::FromRef;
+use axum::extract::FromRequestParts;
+use axum::http::{header, request::Parts, StatusCode};
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use std::marker::PhantomData;
 use uuid::Uuid;
 
+/// Header selecting the active organization; takes precedence over the `org_id` claim.
+pub const ORG_ID_HEADER: &str = "x-org-id";
+
 #[derive(Debug, Clone)]
 pub struct CurrentUser(pub User);
 
+/// Authenticated user signed in with their own credentials. Impersonation tokens are
+/// refused, which keeps sensitive endpoints (credentials, token minting) out of reach
+/// of an admin acting as someone else.
+#[derive(Debug, Clone)]
+pub struct DirectUser(pub User);
+
+/// Caller together with the organization selected for this request, if any.
+/// `org_role` is the caller's effective role there; `orgs:manage` holders act as owners.
+#[derive(Debug, Clone)]
+pub struct TenantScope {
+    pub user: User,
+    pub org_id: Option<Uuid>,
+    pub org_role: Option<OrgRole>,
+}
+
+impl TenantScope {
+    pub fn can_manage_members(&self) -> bool {
+        self.org_role.is_some_and(|role| role.can_manage_members())
+    }
+}
+
+/// Set of permissions a handler requires, declared as a marker type for [`Authorized`].
+pub trait RequiredPermissions: Send + Sync + 'static {
+    const PERMISSIONS: &'static [Permission];
+}
+
+macro_rules! required_permissions {
+    ($($name:ident => [$($permission:ident),+ $(,)?]),* $(,)?) => {
+        $(
+            #[derive(Debug, Clone, Copy)]
+            pub struct $name;
+
+            impl RequiredPermissions for $name {
+                const PERMISSIONS: &'static [Permission] = &[$(Permission::$permission),+];
+            }
+        )*
+    };
+}
+
+pub mod perm {
+    use super::RequiredPermissions;
+    use crate::domain::Permission;
+
+    required_permissions! {
+        UsersRead => [UsersRead],
+        UsersWrite => [UsersWrite],
+        UsersDeactivate => [UsersDeactivate],
+        UsersImpersonate => [UsersImpersonate],
+        UsersImport => [UsersWrite, RolesAssign],
+        RolesRead => [RolesRead],
+        RolesWrite => [RolesWrite],
+        RolesAssign => [RolesAssign],
+        OrgsManage => [OrgsManage],
+        GroupsRead => [GroupsRead],
+        GroupsWrite => [GroupsWrite],
+        GroupRolesAssign => [GroupsWrite, RolesAssign],
+        AuditRead => [AuditRead],
+        WebhooksManage => [WebhooksManage],
+        AttributesManage => [AttributesManage],
+    }
+}
+
+/// Authenticated user holding every permission in `P`.
+#[derive(Debug, Clone)]
+pub struct Authorized<P>(pub User, pub PhantomData<P>);
+
+/// Like [`Authorized`], but refuses impersonation tokens as [`DirectUser`] does. Used on
+/// endpoints that grant privileges, so an admin cannot act as a peer to approve or
+/// assign roles to themselves.
 #[derive(Debug, Clone)]
-pub struct AdminGuard(pub User);
+pub struct DirectAuthorized<P>(pub User, pub PhantomData<P>);
+
+fn ensure_permissions<P: RequiredPermissions>(user: &User) -> Result<(), AppError> {
+    match P::PERMISSIONS
+        .iter()
+        .find(|permission| !user.has_permission(**permission))
+    {
+        Some(missing) => Err(AppError::Forbidden(format!(
+            "missing permission: {missing}"
+        ))),
+        None => Ok(()),
+    }
+}
 
 #[async_trait::async_trait]
 impl<S> FromRequestParts<S> for CurrentUser
@@ -50,16 +139,87 @@ where
             .await?
             .ok_or_else(|| AppError::Unauthorized("invalid token".to_string()))?;
 
-        if !user_with_password.user.is_active() {
-            return Err(AppError::Unauthorized("user is inactive".to_string()));
+        user_with_password.user.status.ensure_usable(Utc::now())?;
+
+        let span = tracing::Span::current();
+        span.record("user_id", tracing::field::display(user_id));
+        if let Some(actor_id) = claims.actor_id() {
+            span.record("actor_id", tracing::field::display(actor_id));
+            tracing::info!(
+                %user_id,
+                %actor_id,
+                method = %parts.method,
+                uri = %parts.uri,
+                "request under impersonation"
+            );
         }
 
+        parts.extensions.insert(claims);
         Ok(CurrentUser(user_with_password.user))
     }
 }
 
 #[async_trait::async_trait]
-impl<S> FromRequestParts<S> for AdminGuard
+impl<S> FromRequestParts<S> for DirectUser
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let CurrentUser(user) = CurrentUser::from_request_parts(parts, state).await?;
+
+        if parts
+            .extensions
+            .get::<Claims>()
+            .is_some_and(Claims::is_impersonation)
+        {
+            return Err(AppError::Forbidden(
+                "not allowed while impersonating".to_string(),
+            ));
+        }
+
+        Ok(DirectUser(user))
+    }
+}
+
+#[async_trait::async_trait]
+impl<S, P> FromRequestParts<S> for Authorized<P>
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+    P: RequiredPermissions,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let CurrentUser(user) = CurrentUser::from_request_parts(parts, state).await?;
+        ensure_permissions::<P>(&user)?;
+
+        Ok(Authorized(user, PhantomData))
+    }
+}
+
+#[async_trait::async_trait]
+impl<S, P> FromRequestParts<S> for DirectAuthorized<P>
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+    P: RequiredPermissions,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let DirectUser(user) = DirectUser::from_request_parts(parts, state).await?;
+        ensure_permissions::<P>(&user)?;
+
+        Ok(DirectAuthorized(user, PhantomData))
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> FromRequestParts<S> for TenantScope
 where
     AppState: FromRef<S>,
     S: Send + Sync,
@@ -69,10 +229,78 @@ where
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         let CurrentUser(user) = CurrentUser::from_request_parts(parts, state).await?;
 
-        if !user.role.can_manage_users() {
-            return Err(AppError::Forbidden("admin access required".to_string()));
+        let org_id = match parts.headers.get(ORG_ID_HEADER) {
+            Some(value) => {
+                let value = value
+                    .to_str()
+                    .map_err(|_| AppError::BadRequest("invalid organization id".to_string()))?;
+                Some(
+                    Uuid::parse_str(value)
+                        .map_err(|_| AppError::BadRequest("invalid organization id".to_string()))?,
+                )
+            }
+            None => parts.extensions.get::<Claims>().and_then(Claims::org_id),
+        };
+
+        let Some(org_id) = org_id else {
+            return Ok(TenantScope {
+                user,
+                org_id: None,
+                org_role: None,
+            });
+        };
+
+        let state = AppState::from_ref(state);
+        let service = OrganizationService::new(
+            SqlxOrganizationRepository::new(state.db.clone()),
+            SqlxUserRepository::new(state.db.clone()),
+        );
+        let org_role = service
+            .effective_role(&user, org_id)
+            .await?
+            .ok_or_else(|| AppError::Forbidden("not a member of this organization".to_string()))?;
+
+        Ok(TenantScope {
+            user,
+            org_id: Some(org_id),
+            org_role: Some(org_role),
+        })
+    }
+}
+
+/// Caller of the `/scim/v2` endpoints, authenticated by the configured SCIM bearer token.
+#[derive(Debug, Clone, Copy)]
+pub struct ScimClient;
+
+#[async_trait::async_trait]
+impl<S> FromRequestParts<S> for ScimClient
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = ScimError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let unauthorized = || ScimError::new(StatusCode::UNAUTHORIZED, "invalid SCIM token");
+        let state = AppState::from_ref(state);
+        let expected = state
+            .config
+            .scim_token
+            .as_deref()
+            .ok_or_else(unauthorized)?;
+
+        let token = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(unauthorized)?;
+
+        // Comparing digests keeps the time taken independent of where the tokens differ.
+        if Sha256::digest(token.as_bytes()) != Sha256::digest(expected.as_bytes()) {
+            return Err(unauthorized());
         }
 
-        Ok(AdminGuard(user))
+        Ok(ScimClient)
     }
 }
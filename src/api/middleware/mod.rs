@@ -1 +1,2 @@
-pub mod auth;
\ No newline at end of file
+pub mod audit;
+pub mod auth;
@@ -1,5 +1,5 @@
-pub mod dto;
 pub mod docs;
+pub mod dto;
 pub mod error;
 pub mod handlers;
 pub mod middleware;
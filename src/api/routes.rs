@@ -1,31 +1,228 @@
 use crate::api::docs::ApiDoc;
-use crate::api::handlers::{auth, users};
+use crate::api::handlers::{
+    attributes, audit, auth, elevations, events, groups, invitations, orgs, roles, scim, users,
+    webhooks,
+};
 use crate::AppState;
-use axum::routing::{delete, get, patch, post};
+use axum::extract::DefaultBodyLimit;
+use axum::routing::{delete, get, patch, post, put};
 use axum::Router;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
+/// Import files are far larger than regular request bodies.
+const IMPORT_BODY_LIMIT: usize = 32 * 1024 * 1024;
+
+/// Room for multipart boundaries and headers on top of `AVATAR_MAX_BYTES`.
+const MULTIPART_OVERHEAD: usize = 64 * 1024;
+
 pub fn create_router(state: AppState) -> Router {
     let auth_routes = Router::new()
         .route("/register", post(auth::register_handler))
         .route("/login", post(auth::login_handler))
         .route("/refresh", post(auth::refresh_handler))
-        .route("/logout", post(auth::logout_handler));
+        .route("/logout", post(auth::logout_handler))
+        .route("/setup-password", post(auth::setup_password_handler))
+        .route("/confirm-email", post(auth::confirm_email_handler))
+        .route("/revert-email", post(auth::revert_email_handler));
 
     let user_routes = Router::new()
-        .route("/me", get(users::get_me_handler).patch(users::update_me_handler))
+        .route(
+            "/me",
+            get(users::get_me_handler).patch(users::update_me_handler),
+        )
+        .route("/me/groups", get(groups::my_groups_handler))
+        .route("/me/elevations", get(elevations::my_elevations_handler))
+        .route("/me/activity", get(audit::my_activity_handler))
+        .route("/me/deactivate", post(users::deactivate_me_handler))
+        .route(
+            "/me/avatar",
+            put(users::upload_avatar_handler).layer(DefaultBodyLimit::max(
+                state.config.avatar_max_bytes + MULTIPART_OVERHEAD,
+            )),
+        )
         .route("/", get(users::list_users_handler))
         .route(
             "/:id",
             get(users::get_user_handler)
                 .patch(users::update_user_handler)
                 .delete(users::deactivate_user_handler),
+        )
+        .route("/:id/groups", get(groups::user_groups_handler))
+        .route("/:id/avatar", get(users::get_avatar_handler))
+        .route("/:id/status", post(users::change_user_status_handler))
+        .route("/:id/impersonate", post(users::impersonate_user_handler))
+        .route("/export", get(users::export_users_handler))
+        .route("/bulk", post(users::bulk_update_users_handler))
+        .route(
+            "/import",
+            post(users::import_users_handler).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        );
+
+    let role_routes = Router::new()
+        .route(
+            "/",
+            get(roles::list_roles_handler).post(roles::create_role_handler),
+        )
+        .route(
+            "/:id",
+            get(roles::get_role_handler)
+                .patch(roles::update_role_handler)
+                .delete(roles::delete_role_handler),
+        )
+        .route("/:id/history", get(roles::role_history_handler))
+        .route(
+            "/:id/users/:user_id",
+            put(roles::assign_role_handler).delete(roles::unassign_role_handler),
+        );
+
+    let org_routes = Router::new()
+        .route(
+            "/",
+            get(orgs::list_orgs_handler).post(orgs::create_org_handler),
+        )
+        .route(
+            "/:id",
+            get(orgs::get_org_handler)
+                .patch(orgs::update_org_handler)
+                .delete(orgs::delete_org_handler),
+        )
+        .route("/:id/token", post(orgs::switch_org_handler))
+        .route(
+            "/:id/members",
+            get(orgs::list_members_handler).post(orgs::add_member_handler),
+        )
+        .route(
+            "/:id/members/:user_id",
+            patch(orgs::update_member_handler).delete(orgs::remove_member_handler),
+        )
+        .route(
+            "/:id/invitations",
+            get(invitations::list_invitations_handler).post(invitations::create_invitation_handler),
+        )
+        .route(
+            "/:id/invitations/:invitation_id",
+            delete(invitations::revoke_invitation_handler),
+        )
+        .route(
+            "/:id/invitations/:invitation_id/resend",
+            post(invitations::resend_invitation_handler),
         );
 
+    let group_routes = Router::new()
+        .route(
+            "/",
+            get(groups::list_groups_handler).post(groups::create_group_handler),
+        )
+        .route(
+            "/:id",
+            get(groups::get_group_handler)
+                .patch(groups::update_group_handler)
+                .delete(groups::delete_group_handler),
+        )
+        .route("/:id/members", get(groups::list_group_members_handler))
+        .route(
+            "/:id/users/:user_id",
+            put(groups::add_group_user_handler).delete(groups::remove_group_user_handler),
+        )
+        .route(
+            "/:id/groups/:member_id",
+            put(groups::add_group_group_handler).delete(groups::remove_group_group_handler),
+        )
+        .route(
+            "/:id/roles/:role_id",
+            put(groups::assign_group_role_handler).delete(groups::unassign_group_role_handler),
+        );
+
+    let elevation_routes = Router::new()
+        .route(
+            "/",
+            get(elevations::list_elevations_handler).post(elevations::request_elevation_handler),
+        )
+        .route("/:id/approve", post(elevations::approve_elevation_handler))
+        .route("/:id/deny", post(elevations::deny_elevation_handler));
+
+    let audit_routes = Router::new()
+        .route("/events", get(audit::search_audit_events_handler))
+        .route("/events/export", get(audit::export_audit_events_handler));
+
+    let admin_routes = Router::new().route("/events/stream", get(events::event_stream_handler));
+
+    let webhook_routes = Router::new()
+        .route(
+            "/",
+            get(webhooks::list_webhooks_handler).post(webhooks::create_webhook_handler),
+        )
+        .route(
+            "/:id",
+            get(webhooks::get_webhook_handler)
+                .patch(webhooks::update_webhook_handler)
+                .delete(webhooks::delete_webhook_handler),
+        )
+        .route("/:id/deliveries", get(webhooks::list_deliveries_handler))
+        .route(
+            "/:id/deliveries/:delivery_id/redeliver",
+            post(webhooks::redeliver_handler),
+        );
+
+    let attribute_routes = Router::new()
+        .route(
+            "/",
+            get(attributes::list_attributes_handler).post(attributes::create_attribute_handler),
+        )
+        .route(
+            "/:id",
+            get(attributes::get_attribute_handler)
+                .patch(attributes::update_attribute_handler)
+                .delete(attributes::delete_attribute_handler),
+        );
+
+    let scim_routes = Router::new()
+        .route(
+            "/Users",
+            get(scim::list_users_handler).post(scim::create_user_handler),
+        )
+        .route(
+            "/Users/:id",
+            get(scim::get_user_handler)
+                .put(scim::replace_user_handler)
+                .patch(scim::patch_user_handler)
+                .delete(scim::delete_user_handler),
+        )
+        .route(
+            "/Groups",
+            get(scim::list_groups_handler).post(scim::create_group_handler),
+        )
+        .route(
+            "/Groups/:id",
+            get(scim::get_group_handler)
+                .put(scim::replace_group_handler)
+                .patch(scim::patch_group_handler)
+                .delete(scim::delete_group_handler),
+        )
+        .route(
+            "/ServiceProviderConfig",
+            get(scim::service_provider_config_handler),
+        )
+        .route("/ResourceTypes", get(scim::resource_types_handler))
+        .route("/Schemas", get(scim::schemas_handler));
+
     Router::new()
         .nest("/auth", auth_routes)
         .nest("/users", user_routes)
+        .nest("/roles", role_routes)
+        .nest("/elevations", elevation_routes)
+        .nest("/orgs", org_routes)
+        .route(
+            "/invitations/accept",
+            post(invitations::accept_invitation_handler),
+        )
+        .nest("/groups", group_routes)
+        .nest("/audit", audit_routes)
+        .nest("/webhooks", webhook_routes)
+        .nest("/attributes", attribute_routes)
+        .nest("/admin", admin_routes)
+        .nest("/scim/v2", scim_routes)
         .merge(SwaggerUi::new("/swagger").url("/api-doc/openapi.json", ApiDoc::openapi()))
         .with_state(state)
 }
@@ -0,0 +1,118 @@
+use crate::app::services::outbox_service::OutboxRelay;
+use crate::app::services::role_service::RoleService;
+use crate::app::services::user_service::UserService;
+use crate::app::services::webhook_service::{WebhookService, WebhookSettings};
+use crate::infra::db::outbox_repo::SqlxOutboxRepository;
+use crate::infra::db::role_repo::SqlxRoleRepository;
+use crate::infra::db::user_repo::SqlxUserRepository;
+use crate::infra::db::webhook_repo::SqlxWebhookRepository;
+use crate::infra::webhook::HttpWebhookSender;
+use crate::AppState;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// Periodically removes expired time-bound role grants. Queries already ignore expired
+/// grants; the sweep deletes them and records the `expired` history events.
+pub fn spawn_role_grant_sweeper(state: AppState) -> JoinHandle<()> {
+    let period = Duration::from_secs(state.config.role_sweep_interval_seconds.max(1));
+
+    tokio::spawn(async move {
+        let service = RoleService::new(
+            SqlxRoleRepository::new(state.db.clone()),
+            SqlxUserRepository::new(state.db.clone()),
+        );
+        let mut interval = tokio::time::interval(period);
+
+        loop {
+            interval.tick().await;
+            match service.sweep_expired_grants().await {
+                Ok(0) => {}
+                Ok(swept) => tracing::info!(swept, "expired role grants removed"),
+                Err(err) => tracing::warn!(error = %err, "role grant sweep failed"),
+            }
+        }
+    })
+}
+
+/// Periodically reactivates accounts whose suspension has ended. Sign-in already treats
+/// them as active; the sweep writes the change and records it in the audit log.
+pub fn spawn_suspension_sweeper(state: AppState) -> JoinHandle<()> {
+    let period = Duration::from_secs(state.config.status_sweep_interval_seconds.max(1));
+
+    tokio::spawn(async move {
+        let service = UserService::new(
+            SqlxUserRepository::new(state.db.clone()),
+            state.identity_policy.clone(),
+        );
+        let mut interval = tokio::time::interval(period);
+
+        loop {
+            interval.tick().await;
+            match service.lift_expired_suspensions().await {
+                Ok(0) => {}
+                Ok(lifted) => tracing::info!(lifted, "ended suspensions lifted"),
+                Err(err) => tracing::warn!(error = %err, "suspension sweep failed"),
+            }
+        }
+    })
+}
+
+/// Publishes committed domain events from the outbox to webhook fan-out and to the
+/// in-process event bus.
+pub fn spawn_outbox_relay(state: AppState) -> JoinHandle<()> {
+    let period = Duration::from_secs(state.config.outbox_poll_interval_seconds.max(1));
+
+    tokio::spawn(async move {
+        let relay = OutboxRelay::new(
+            SqlxOutboxRepository::new(state.db.clone()),
+            (
+                SqlxWebhookRepository::new(state.db.clone()),
+                state.events.clone(),
+            ),
+        );
+        let mut interval = tokio::time::interval(period);
+
+        loop {
+            interval.tick().await;
+            loop {
+                match relay.relay_pending().await {
+                    Ok(0) => break,
+                    Ok(published) => tracing::debug!(published, "domain events published"),
+                    Err(err) => {
+                        tracing::warn!(error = %err, "outbox relay failed");
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Delivers queued webhooks. Each round drains everything due before sleeping again.
+pub fn spawn_webhook_dispatcher(state: AppState) -> JoinHandle<()> {
+    let period = Duration::from_secs(state.config.webhook_poll_interval_seconds.max(1));
+
+    tokio::spawn(async move {
+        let settings = WebhookSettings::from_config(&state.config);
+        let service = WebhookService::new(
+            SqlxWebhookRepository::new(state.db.clone()),
+            HttpWebhookSender::new(settings.timeout),
+            settings,
+        );
+        let mut interval = tokio::time::interval(period);
+
+        loop {
+            interval.tick().await;
+            loop {
+                match service.dispatch_due().await {
+                    Ok(0) => break,
+                    Ok(attempted) => tracing::debug!(attempted, "webhook deliveries attempted"),
+                    Err(err) => {
+                        tracing::warn!(error = %err, "webhook dispatch failed");
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
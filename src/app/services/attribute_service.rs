@@ -0,0 +1,156 @@
+use crate::domain::{
+    AttributeDefinition, AttributeDefinitionRepository, AttributeFilter, Attributes, DomainError,
+    NewAttributeDefinition, UpdateAttributeDefinition,
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Who is writing a user's attributes, which decides what they may change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeWriter {
+    /// The user themselves; read-only attributes are off limits.
+    Owner,
+    Admin,
+}
+
+pub struct AttributeService<A> {
+    definitions: A,
+}
+
+impl<A> AttributeService<A>
+where
+    A: AttributeDefinitionRepository,
+{
+    pub fn new(definitions: A) -> Self {
+        Self { definitions }
+    }
+
+    pub async fn list_definitions(&self) -> Result<Vec<AttributeDefinition>, DomainError> {
+        self.definitions.list().await
+    }
+
+    pub async fn get_definition(&self, id: Uuid) -> Result<AttributeDefinition, DomainError> {
+        self.definitions
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("attribute not found".to_string()))
+    }
+
+    pub async fn create_definition(
+        &self,
+        input: NewAttributeDefinition,
+    ) -> Result<AttributeDefinition, DomainError> {
+        if !AttributeDefinition::is_valid_name(&input.name) {
+            return Err(DomainError::ValidationError(
+                "attribute names are 1-40 lowercase letters, digits or underscores, starting with a letter".to_string(),
+            ));
+        }
+
+        self.definitions.create(input).await
+    }
+
+    pub async fn update_definition(
+        &self,
+        id: Uuid,
+        input: UpdateAttributeDefinition,
+    ) -> Result<AttributeDefinition, DomainError> {
+        self.definitions.update(id, input).await
+    }
+
+    pub async fn delete_definition(&self, id: Uuid) -> Result<(), DomainError> {
+        self.definitions.delete(id).await
+    }
+
+    /// Checks `changes` to a user currently holding `current`: every key must be defined
+    /// and writable by `writer`, every value must match its type (`null` removes it), and
+    /// the required attributes `writer` can set must remain set afterwards.
+    pub async fn check_changes(
+        &self,
+        current: &Attributes,
+        changes: &Attributes,
+        writer: AttributeWriter,
+    ) -> Result<(), DomainError> {
+        let definitions = self.definitions.list().await?;
+        let writable = |definition: &AttributeDefinition| {
+            writer == AttributeWriter::Admin || definition.user_editable
+        };
+
+        for (name, value) in changes {
+            let definition = definitions
+                .iter()
+                .find(|definition| &definition.name == name)
+                .ok_or_else(|| {
+                    DomainError::ValidationError(format!("unknown attribute: {name}"))
+                })?;
+            if !writable(definition) {
+                return Err(DomainError::Forbidden(format!(
+                    "attribute `{name}` is read-only"
+                )));
+            }
+            if !value.is_null() && !definition.attribute_type.accepts(value) {
+                return Err(DomainError::ValidationError(format!(
+                    "attribute `{name}` must be a valid {}",
+                    definition.attribute_type
+                )));
+            }
+        }
+
+        for definition in definitions
+            .iter()
+            .filter(|definition| definition.required && writable(definition))
+        {
+            let value = changes
+                .get(&definition.name)
+                .or_else(|| current.get(&definition.name))
+                .unwrap_or(&Value::Null);
+            if value.is_null() {
+                return Err(DomainError::ValidationError(format!(
+                    "attribute `{}` is required",
+                    definition.name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Turns `(name, value)` query pairs into user list filters; only indexed attributes
+    /// can be filtered on.
+    pub async fn resolve_filters(
+        &self,
+        pairs: Vec<(String, String)>,
+    ) -> Result<Vec<AttributeFilter>, DomainError> {
+        if pairs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let definitions = self.definitions.list().await?;
+        pairs
+            .into_iter()
+            .map(|(name, value)| {
+                let definition = definitions
+                    .iter()
+                    .find(|definition| definition.name == name)
+                    .ok_or_else(|| {
+                        DomainError::ValidationError(format!("unknown attribute: {name}"))
+                    })?;
+                if !definition.is_filterable() {
+                    return Err(DomainError::ValidationError(format!(
+                        "attribute `{name}` is not indexed"
+                    )));
+                }
+                if !definition.attribute_type.accepts_text(&value) {
+                    return Err(DomainError::ValidationError(format!(
+                        "attribute `{name}` must be a valid {}",
+                        definition.attribute_type
+                    )));
+                }
+                Ok(AttributeFilter {
+                    name,
+                    attribute_type: definition.attribute_type,
+                    value,
+                })
+            })
+            .collect()
+    }
+}
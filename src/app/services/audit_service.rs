@@ -0,0 +1,36 @@
+use crate::domain::{ActivityEntry, AuditEvent, AuditQuery, AuditRepository, DomainError, Page};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct AuditService<A> {
+    audit: A,
+}
+
+impl<A> AuditService<A>
+where
+    A: AuditRepository,
+{
+    pub fn new(audit: A) -> Self {
+        Self { audit }
+    }
+
+    pub async fn search(&self, query: &AuditQuery) -> Result<Page<AuditEvent>, DomainError> {
+        if let (Some(from), Some(to)) = (query.from, query.to) {
+            if from >= to {
+                return Err(DomainError::ValidationError(
+                    "`from` must be earlier than `to`".to_string(),
+                ));
+            }
+        }
+
+        self.audit.search(query).await
+    }
+
+    pub async fn activity(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<ActivityEntry>, DomainError> {
+        self.audit.activity(user_id, limit).await
+    }
+}
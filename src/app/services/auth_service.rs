@@ -1,6 +1,12 @@
-use crate::domain::{DomainError, NewUser, Role, User, UserRepository};
-use crate::infra::auth::jwt::{Claims, JwtService, TokenType};
+use crate::domain::{
+    AccountState, AuditContext, AuditEventType, AuditSink, DomainError, IdentityPolicy, NewUser,
+    Role, StatusChange, User, UserRepository,
+};
+use crate::infra::auth::jwt::{AccountSetupClaims, Claims, JwtService, TokenContext, TokenType};
 use crate::infra::security::password;
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use std::sync::Arc;
 use uuid::Uuid;
 
 #[derive(Debug, Clone)]
@@ -8,6 +14,8 @@ pub struct RegisterInput {
     pub email: String,
     pub username: String,
     pub password: String,
+    /// Skips email verification; used when an invitation already proved the address.
+    pub email_verified: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -23,60 +31,141 @@ pub struct LoginResponse {
     pub user: User,
 }
 
-pub struct AuthService<R> {
+/// Access token that lets an admin act as another user; there is no refresh token.
+#[derive(Debug, Clone)]
+pub struct ImpersonationToken {
+    pub access_token: String,
+    pub expires_at: DateTime<Utc>,
+    pub user: User,
+}
+
+pub struct AuthService<R, A> {
     repo: R,
+    audit: A,
     jwt: JwtService,
+    policy: Arc<IdentityPolicy>,
 }
 
-impl<R> AuthService<R>
+impl<R, A> AuthService<R, A>
 where
     R: UserRepository,
+    A: AuditSink,
 {
-    pub fn new(repo: R, jwt: JwtService) -> Self {
-        Self { repo, jwt }
+    pub fn new(repo: R, audit: A, jwt: JwtService, policy: Arc<IdentityPolicy>) -> Self {
+        Self {
+            repo,
+            audit,
+            jwt,
+            policy,
+        }
     }
 
-    pub async fn register_user(&self, input: RegisterInput) -> Result<User, DomainError> {
-        if self.repo.find_by_email(&input.email).await?.is_some() {
+    /// Creates an account with the `user` role. The email and username must pass the
+    /// identity policy; there is no bypass for self-registration.
+    pub async fn register_user(
+        &self,
+        input: RegisterInput,
+        audit: &AuditContext,
+    ) -> Result<User, DomainError> {
+        let email = User::normalize_email(&input.email)?;
+        let username = User::normalize_username(&input.username);
+        self.policy.check_email(&email)?;
+        self.policy.check_username(&username)?;
+
+        if self.repo.find_by_email(&email).await?.is_some() {
             return Err(DomainError::Conflict("email already exists".to_string()));
         }
 
-        if self
-            .repo
-            .find_by_username(&input.username)
-            .await?
-            .is_some()
-        {
+        if self.repo.find_by_username(&username).await?.is_some() {
             return Err(DomainError::Conflict("username already exists".to_string()));
         }
 
         let password_hash = password::hash_password(&input.password)?;
         let new_user = NewUser {
-            email: input.email,
-            username: input.username,
+            email,
+            username,
             password_hash,
-            role: Role::User,
-            is_active: true,
+            roles: vec![Role::USER.to_string()],
+            state: AccountState::Active,
+            email_verified: input.email_verified,
         };
 
-        self.repo.create(new_user).await
+        self.repo.create(new_user, audit).await
     }
 
-    pub async fn login(&self, input: LoginInput) -> Result<LoginResponse, DomainError> {
-        let user_with_password = self
+    /// Sets the first password of an account through its setup link. The link is bound
+    /// to the password hash it was issued for, so it works only once. An account pending
+    /// verification becomes active, since its owner has now claimed it.
+    pub async fn setup_password(
+        &self,
+        token: &str,
+        password: &str,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError> {
+        let claims = self.jwt.decode_account_setup_token(token)?;
+        let user_id = claims.user_id()?;
+
+        let user = self
             .repo
-            .find_by_email(&input.email)
+            .find_by_id(user_id)
             .await?
-            .ok_or_else(|| DomainError::Unauthorized("invalid credentials".to_string()))?;
+            .filter(|user| {
+                AccountSetupClaims::fingerprint(&user.password_hash) == claims.fingerprint
+            })
+            .ok_or_else(|| {
+                DomainError::Unauthorized("setup link is no longer valid".to_string())
+            })?;
+
+        let password_hash = password::hash_password(password)?;
+        let audit = audit.with_actor(user.user.id);
+        self.repo
+            .set_password(user.user.id, password_hash, &audit)
+            .await?;
+        if user.user.status.state == AccountState::PendingVerification {
+            self.repo
+                .set_status(user.user.id, StatusChange::to(AccountState::Active), &audit)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks credentials; failures are audited with the reason before being reported.
+    pub async fn login(
+        &self,
+        input: LoginInput,
+        audit: &AuditContext,
+    ) -> Result<LoginResponse, DomainError> {
+        let email = User::normalize_email(&input.email).unwrap_or(input.email);
+        let Some(user_with_password) = self.repo.find_by_email(&email).await? else {
+            self.login_failed(audit, None, &email, "unknown_email")
+                .await?;
+            return Err(DomainError::Unauthorized("invalid credentials".to_string()));
+        };
+        let user_id = user_with_password.user.id;
 
         if !password::verify_password(&user_with_password.password_hash, &input.password)? {
+            self.login_failed(audit, Some(user_id), &email, "invalid_password")
+                .await?;
             return Err(DomainError::Unauthorized("invalid credentials".to_string()));
         }
 
-        if !user_with_password.user.is_active() {
-            return Err(DomainError::Unauthorized("user is inactive".to_string()));
+        let now = Utc::now();
+        if let Err(err) = user_with_password.user.status.ensure_usable(now) {
+            let state = user_with_password.user.status.effective_state(now);
+            self.login_failed(audit, Some(user_id), &email, state.as_str())
+                .await?;
+            return Err(err);
         }
 
+        self.audit
+            .record(
+                audit
+                    .with_actor(user_id)
+                    .event(AuditEventType::LoginSucceeded, Some(user_id)),
+            )
+            .await?;
+
         let access_token = self.jwt.create_access_token(&user_with_password.user)?;
         let refresh_token = self.jwt.create_refresh_token(&user_with_password.user)?;
 
@@ -87,7 +176,11 @@ where
         })
     }
 
-    pub async fn refresh_tokens(&self, refresh_token: String) -> Result<LoginResponse, DomainError> {
+    pub async fn refresh_tokens(
+        &self,
+        refresh_token: String,
+        audit: &AuditContext,
+    ) -> Result<LoginResponse, DomainError> {
         let claims = self.jwt.decode_token(&refresh_token)?;
         Self::validate_refresh(&claims)?;
 
@@ -100,25 +193,126 @@ where
             .await?
             .ok_or_else(|| DomainError::Unauthorized("invalid token".to_string()))?;
 
-        if !user_with_password.user.is_active() {
-            return Err(DomainError::Unauthorized("user is inactive".to_string()));
+        user_with_password.user.status.ensure_usable(Utc::now())?;
+
+        self.audit
+            .record(
+                audit
+                    .with_actor(user_id)
+                    .event(AuditEventType::TokenRefreshed, Some(user_id)),
+            )
+            .await?;
+
+        let context = TokenContext {
+            org_id: claims.org_id(),
+        };
+        self.issue_tokens(user_with_password.user, &context)
+    }
+
+    /// Logout is stateless; this only leaves a trace of it.
+    pub async fn logout(&self, user: &User, audit: &AuditContext) -> Result<(), DomainError> {
+        self.audit
+            .record(audit.event(AuditEventType::Logout, Some(user.id)))
+            .await
+    }
+
+    /// Lets `actor` act as `user_id`. The target must be another active account holding
+    /// no permission the actor lacks, so impersonation never widens the actor's access.
+    pub async fn impersonate(
+        &self,
+        actor: &User,
+        user_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<ImpersonationToken, DomainError> {
+        if actor.id == user_id {
+            return Err(DomainError::ValidationError(
+                "cannot impersonate yourself".to_string(),
+            ));
         }
 
-        let access_token = self.jwt.create_access_token(&user_with_password.user)?;
-        let refresh_token = self.jwt.create_refresh_token(&user_with_password.user)?;
+        let user = self
+            .repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("user not found".to_string()))?
+            .user;
+
+        if !user.is_active() {
+            return Err(DomainError::Conflict(format!(
+                "user is {}",
+                user.status.effective_state(Utc::now())
+            )));
+        }
+
+        if let Some(missing) = user
+            .permissions
+            .iter()
+            .find(|permission| !actor.has_permission(**permission))
+        {
+            return Err(DomainError::Forbidden(format!(
+                "cannot impersonate a user holding {missing}"
+            )));
+        }
+
+        let (access_token, expires_at) = self.jwt.create_impersonation_token(&user, actor)?;
+        tracing::info!(user_id = %user.id, actor_id = %actor.id, %expires_at, "impersonation started");
+        self.audit
+            .record(
+                audit
+                    .with_actor(actor.id)
+                    .event(AuditEventType::ImpersonationStarted, Some(user.id))
+                    .with_metadata(json!({ "expires_at": expires_at })),
+            )
+            .await?;
+
+        Ok(ImpersonationToken {
+            access_token,
+            expires_at,
+            user,
+        })
+    }
+
+    /// Mints a fresh access/refresh pair carrying `context`, e.g. when switching the
+    /// active organization.
+    pub fn issue_tokens(
+        &self,
+        user: User,
+        context: &TokenContext,
+    ) -> Result<LoginResponse, DomainError> {
+        let access_token = self.jwt.create_access_token_with(&user, context)?;
+        let refresh_token = self.jwt.create_refresh_token_with(&user, context)?;
 
         Ok(LoginResponse {
             access_token,
             refresh_token,
-            user: user_with_password.user,
+            user,
         })
     }
 
+    async fn login_failed(
+        &self,
+        audit: &AuditContext,
+        user_id: Option<Uuid>,
+        email: &str,
+        reason: &str,
+    ) -> Result<(), DomainError> {
+        let event = audit
+            .event(AuditEventType::LoginFailed, user_id)
+            .with_metadata(json!({ "email": email, "reason": reason }));
+        self.audit.record(event).await
+    }
+
     fn validate_refresh(claims: &Claims) -> Result<(), DomainError> {
         if claims.token_type != TokenType::Refresh {
             return Err(DomainError::Unauthorized("invalid token".to_string()));
         }
 
+        if claims.is_impersonation() {
+            return Err(DomainError::Unauthorized(
+                "impersonation tokens cannot be refreshed".to_string(),
+            ));
+        }
+
         Ok(())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,149 @@
+use crate::domain::{AuditContext, DomainError, UpdateProfile, User, UserRepository};
+use crate::infra::storage::{Blob, BlobStore};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits, Rgb, RgbImage};
+use sha2::{Digest, Sha256};
+use std::io::Cursor;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Edge lengths of the square renditions kept for every avatar, largest first.
+pub const AVATAR_SIZES: [u32; 3] = [512, 128, 64];
+
+/// Uploads larger than this in either dimension are refused before decoding.
+const MAX_SOURCE_DIMENSION: u32 = 8192;
+const AVATAR_CONTENT_TYPE: &str = "image/jpeg";
+const AVATAR_QUALITY: u8 = 85;
+
+pub struct AvatarService<U> {
+    users: U,
+    blobs: Arc<dyn BlobStore>,
+    max_bytes: usize,
+}
+
+impl<U> AvatarService<U>
+where
+    U: UserRepository,
+{
+    pub fn new(users: U, blobs: Arc<dyn BlobStore>, max_bytes: usize) -> Self {
+        Self {
+            users,
+            blobs,
+            max_bytes,
+        }
+    }
+
+    /// Stores `bytes` as the user's avatar and points the profile `picture` at it. The
+    /// image is re-encoded, which drops EXIF and any other embedded metadata.
+    pub async fn upload(
+        &self,
+        user_id: Uuid,
+        content_type: &str,
+        bytes: Vec<u8>,
+        audit: &AuditContext,
+    ) -> Result<User, DomainError> {
+        if bytes.len() > self.max_bytes {
+            return Err(DomainError::ValidationError(format!(
+                "avatar must be at most {} bytes",
+                self.max_bytes
+            )));
+        }
+        let format = match content_type {
+            "image/jpeg" => ImageFormat::Jpeg,
+            "image/png" => ImageFormat::Png,
+            "image/webp" => ImageFormat::WebP,
+            _ => {
+                return Err(DomainError::ValidationError(
+                    "avatar must be image/jpeg, image/png or image/webp".to_string(),
+                ))
+            }
+        };
+        if image::guess_format(&bytes).ok() != Some(format) {
+            return Err(DomainError::ValidationError(format!(
+                "file is not {content_type}"
+            )));
+        }
+
+        let renditions = tokio::task::spawn_blocking(move || render(&bytes, format))
+            .await
+            .map_err(|err| DomainError::Internal(err.to_string()))??;
+
+        // Every upload gets a new URL, so clients never keep showing a cached old avatar.
+        let version = hex::encode(&Sha256::digest(&renditions[0].1)[..6]);
+        for (size, bytes) in renditions {
+            let blob = Blob {
+                content_type: AVATAR_CONTENT_TYPE.to_string(),
+                bytes,
+            };
+            self.blobs.put(&avatar_key(user_id, size), blob).await?;
+        }
+
+        let update = UpdateProfile {
+            avatar_url: Some(format!("/users/{user_id}/avatar?v={version}")),
+            ..Default::default()
+        };
+        self.users.update_profile(user_id, update, audit).await
+    }
+
+    /// The rendition closest to `size` pixels, rounding up; the largest by default.
+    pub async fn get(&self, user_id: Uuid, size: Option<u32>) -> Result<Blob, DomainError> {
+        let size = size.map_or(AVATAR_SIZES[0], |size| {
+            AVATAR_SIZES
+                .iter()
+                .rev()
+                .copied()
+                .find(|candidate| *candidate >= size)
+                .unwrap_or(AVATAR_SIZES[0])
+        });
+
+        self.blobs
+            .get(&avatar_key(user_id, size))
+            .await?
+            .ok_or_else(|| DomainError::NotFound("avatar not found".to_string()))
+    }
+}
+
+fn avatar_key(user_id: Uuid, size: u32) -> String {
+    format!("avatars/{user_id}/{size}.jpg")
+}
+
+/// Decodes the upload, applies its EXIF orientation and renders every size as a
+/// center-cropped square JPEG, in the order of [`AVATAR_SIZES`].
+fn render(bytes: &[u8], format: ImageFormat) -> Result<Vec<(u32, Vec<u8>)>, DomainError> {
+    let invalid =
+        |err: image::ImageError| DomainError::ValidationError(format!("invalid image: {err}"));
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_DIMENSION);
+    limits.max_image_height = Some(MAX_SOURCE_DIMENSION);
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), format);
+    reader.limits(limits);
+
+    let mut decoder = reader.into_decoder().map_err(invalid)?;
+    let orientation = decoder.orientation().map_err(invalid)?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(invalid)?;
+    image.apply_orientation(orientation);
+
+    // JPEG has no alpha channel; transparent areas become white.
+    let rgba = image.to_rgba8();
+    let flattened = RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let blend =
+            |channel: u8| ((channel as u16 * a as u16 + 255 * (255 - a as u16)) / 255) as u8;
+        Rgb([blend(r), blend(g), blend(b)])
+    });
+    let image = DynamicImage::ImageRgb8(flattened);
+
+    AVATAR_SIZES
+        .iter()
+        .map(|&size| {
+            let mut output = Vec::new();
+            image
+                .resize_to_fill(size, size, FilterType::Lanczos3)
+                .write_with_encoder(JpegEncoder::new_with_quality(&mut output, AVATAR_QUALITY))
+                .map_err(|err| DomainError::Internal(format!("encoding avatar failed: {err}")))?;
+            Ok((size, output))
+        })
+        .collect()
+}
@@ -0,0 +1,127 @@
+use crate::domain::{
+    AuditContext, DomainError, ElevationRepository, ElevationRequest, ElevationStatus,
+    NewElevationRequest, RoleRepository, User,
+};
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+pub struct ElevationService<E, R> {
+    elevations: E,
+    roles: R,
+}
+
+impl<E, R> ElevationService<E, R>
+where
+    E: ElevationRepository,
+    R: RoleRepository,
+{
+    pub fn new(elevations: E, roles: R) -> Self {
+        Self { elevations, roles }
+    }
+
+    pub async fn request(
+        &self,
+        actor: &User,
+        role_id: Uuid,
+        duration_minutes: i64,
+        reason: String,
+    ) -> Result<ElevationRequest, DomainError> {
+        let role = self
+            .roles
+            .find_by_id(role_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("role not found".to_string()))?;
+
+        if actor.has_role(&role.name) {
+            return Err(DomainError::Conflict(
+                "you already hold this role".to_string(),
+            ));
+        }
+
+        self.elevations
+            .create(NewElevationRequest {
+                user_id: actor.id,
+                role_id,
+                duration_minutes,
+                reason,
+            })
+            .await
+            .map_err(|err| match err {
+                DomainError::Conflict(_) => DomainError::Conflict(
+                    "an elevation request for this role is already pending".to_string(),
+                ),
+                other => other,
+            })
+    }
+
+    pub async fn list(
+        &self,
+        status: Option<ElevationStatus>,
+        limit: i64,
+    ) -> Result<Vec<ElevationRequest>, DomainError> {
+        self.elevations.list(status, limit).await
+    }
+
+    pub async fn list_for_user(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<ElevationRequest>, DomainError> {
+        self.elevations.list_for_user(user_id, limit).await
+    }
+
+    /// Approves a pending request; the grant runs for the requested duration from now.
+    pub async fn approve(
+        &self,
+        actor: &User,
+        request_id: Uuid,
+        note: Option<String>,
+        audit: &AuditContext,
+    ) -> Result<ElevationRequest, DomainError> {
+        let request = self.find_decidable(actor, request_id, audit).await?;
+        let grant_expires_at = Utc::now() + Duration::minutes(request.duration_minutes);
+
+        self.elevations
+            .approve(request_id, actor.id, note, grant_expires_at)
+            .await
+    }
+
+    pub async fn deny(
+        &self,
+        actor: &User,
+        request_id: Uuid,
+        note: Option<String>,
+        audit: &AuditContext,
+    ) -> Result<ElevationRequest, DomainError> {
+        self.find_decidable(actor, request_id, audit).await?;
+        self.elevations.deny(request_id, actor.id, note).await
+    }
+
+    async fn find_decidable(
+        &self,
+        actor: &User,
+        request_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<ElevationRequest, DomainError> {
+        let request = self
+            .elevations
+            .find_by_id(request_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("elevation request not found".to_string()))?;
+
+        // An admin impersonating a peer still counts as deciding their own request.
+        if request.user_id == actor.id || audit.impersonator_id == Some(request.user_id) {
+            return Err(DomainError::Forbidden(
+                "elevation requests must be decided by another admin".to_string(),
+            ));
+        }
+
+        if request.status != ElevationStatus::Pending {
+            return Err(DomainError::Conflict(
+                "elevation request was already decided".to_string(),
+            ));
+        }
+
+        Ok(request)
+    }
+}
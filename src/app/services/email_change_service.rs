@@ -0,0 +1,195 @@
+use crate::config::AppConfig;
+use crate::domain::{
+    AuditContext, DomainError, EmailChange, EmailChangeRepository, IdentityPolicy, NewEmailChange,
+    User, UserRepository,
+};
+use crate::infra::auth::jwt::{EmailChangeLink, JwtService};
+use crate::infra::mail::{Email, Mailer};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub struct EmailChangeSettings {
+    pub ttl: Duration,
+    pub revert_window: Duration,
+    pub confirm_url: String,
+    pub revert_url: String,
+}
+
+impl EmailChangeSettings {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            ttl: Duration::hours(config.email_change_ttl_hours),
+            revert_window: Duration::days(config.email_change_revert_days),
+            confirm_url: config.email_change_confirm_url.clone(),
+            revert_url: config.email_change_revert_url.clone(),
+        }
+    }
+}
+
+/// Email changes requested by users themselves. The new address must confirm before the
+/// account moves to it, and the old one is told and can undo the change for a while, so
+/// a hijacked session alone cannot take over the account.
+pub struct EmailChangeService<U, C, M> {
+    users: U,
+    changes: C,
+    jwt: JwtService,
+    mailer: M,
+    settings: EmailChangeSettings,
+    policy: Arc<IdentityPolicy>,
+}
+
+impl<U, C, M> EmailChangeService<U, C, M>
+where
+    U: UserRepository,
+    C: EmailChangeRepository,
+    M: Mailer,
+{
+    pub fn new(
+        users: U,
+        changes: C,
+        jwt: JwtService,
+        mailer: M,
+        settings: EmailChangeSettings,
+        policy: Arc<IdentityPolicy>,
+    ) -> Self {
+        Self {
+            users,
+            changes,
+            jwt,
+            mailer,
+            settings,
+            policy,
+        }
+    }
+
+    /// Normalized `new_email` if `user` may change to it: it must differ from the current
+    /// address, pass the identity policy and be free.
+    pub async fn check(&self, user: &User, new_email: &str) -> Result<String, DomainError> {
+        let new_email = User::normalize_email(new_email)?;
+        if new_email == user.email {
+            return Err(DomainError::ValidationError(
+                "new email is the current email".to_string(),
+            ));
+        }
+        self.policy.check_email(&new_email)?;
+        if self.users.find_by_email(&new_email).await?.is_some() {
+            return Err(DomainError::Conflict("email already exists".to_string()));
+        }
+
+        Ok(new_email)
+    }
+
+    /// Starts a change to `new_email`, replacing any change still awaiting confirmation,
+    /// and mails both addresses. The new address is [`check`](Self::check)ed first.
+    pub async fn request(
+        &self,
+        user: &User,
+        new_email: String,
+        audit: &AuditContext,
+    ) -> Result<EmailChange, DomainError> {
+        let new_email = self.check(user, &new_email).await?;
+
+        let now = Utc::now();
+        let change = self
+            .changes
+            .create(
+                NewEmailChange {
+                    user_id: user.id,
+                    old_email: user.email.clone(),
+                    new_email,
+                    expires_at: now + self.settings.ttl,
+                    revert_expires_at: now + self.settings.revert_window,
+                },
+                audit,
+            )
+            .await?;
+
+        self.deliver(user, &change).await?;
+        Ok(change)
+    }
+
+    /// Commits the change whose confirmation link carried `token`.
+    pub async fn confirm(&self, token: &str, audit: &AuditContext) -> Result<User, DomainError> {
+        let claims = self
+            .jwt
+            .decode_email_change_token(token, EmailChangeLink::Confirm)?;
+        let nonce = claims.nonce()?;
+        let change = self
+            .changes
+            .find_by_id(claims.change_id()?)
+            .await?
+            .filter(|change| change.nonce == nonce && change.is_pending())
+            .ok_or_else(|| {
+                DomainError::Unauthorized("email change link is no longer valid".to_string())
+            })?;
+
+        self.changes
+            .confirm(change.id, nonce, &audit.with_actor(change.user_id))
+            .await
+    }
+
+    /// Cancels or undoes the change whose revert link carried `token`; returns the user
+    /// when their old address was restored.
+    pub async fn revert(
+        &self,
+        token: &str,
+        audit: &AuditContext,
+    ) -> Result<Option<User>, DomainError> {
+        let claims = self
+            .jwt
+            .decode_email_change_token(token, EmailChangeLink::Revert)?;
+        let nonce = claims.nonce()?;
+        let change = self
+            .changes
+            .find_by_id(claims.change_id()?)
+            .await?
+            .filter(|change| change.nonce == nonce && change.is_revertible())
+            .ok_or_else(|| {
+                DomainError::Unauthorized("email change link is no longer valid".to_string())
+            })?;
+
+        self.changes
+            .revert(change.id, nonce, &audit.with_actor(change.user_id))
+            .await
+    }
+
+    async fn deliver(&self, user: &User, change: &EmailChange) -> Result<(), DomainError> {
+        let confirm_token = self
+            .jwt
+            .create_email_change_token(change, EmailChangeLink::Confirm)?;
+        let revert_token = self
+            .jwt
+            .create_email_change_token(change, EmailChangeLink::Revert)?;
+
+        self.mailer
+            .send(Email {
+                to: change.new_email.clone(),
+                subject: "Confirm your new email address".to_string(),
+                body: format!(
+                    "Confirm that {} should become the email address of the account {}.\n\nThe link is valid until {}:\n{}?token={}\n",
+                    change.new_email,
+                    user.username,
+                    change.expires_at.to_rfc2822(),
+                    self.settings.confirm_url,
+                    confirm_token
+                ),
+            })
+            .await?;
+
+        self.mailer
+            .send(Email {
+                to: change.old_email.clone(),
+                subject: "Your email address is being changed".to_string(),
+                body: format!(
+                    "A change of the email address of the account {} to {} was requested. It takes effect once the new address is confirmed.\n\nIf you did not ask for this, undo it before {}:\n{}?token={}\n",
+                    user.username,
+                    change.new_email,
+                    change.revert_expires_at.to_rfc2822(),
+                    self.settings.revert_url,
+                    revert_token
+                ),
+            })
+            .await
+    }
+}
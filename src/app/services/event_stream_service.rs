@@ -0,0 +1,61 @@
+use crate::domain::{DomainError, DomainEvent, DomainEventType, OutboxRepository};
+use crate::infra::events::EventBus;
+use futures_util::stream::{self, BoxStream};
+use futures_util::{future, StreamExt};
+use std::collections::HashSet;
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+
+/// Most events replayed when a client resumes; older gaps are left to the audit log.
+const REPLAY_LIMIT: i64 = 1000;
+
+pub struct EventStreamService<O> {
+    outbox: O,
+    bus: EventBus,
+}
+
+impl<O> EventStreamService<O>
+where
+    O: OutboxRepository,
+{
+    pub fn new(outbox: O, bus: EventBus) -> Self {
+        Self { outbox, bus }
+    }
+
+    /// Live events of the given types (all when empty). With `last_event_id`, the events
+    /// written after it are replayed first. The stream ends if the subscriber falls too far
+    /// behind; clients reconnect with the last id they saw.
+    pub async fn subscribe(
+        &self,
+        event_types: Vec<DomainEventType>,
+        last_event_id: Option<Uuid>,
+    ) -> Result<BoxStream<'static, DomainEvent>, DomainError> {
+        // Subscribe before reading the backlog so nothing published in between is lost;
+        // an event found in both is sent once.
+        let receiver = self.bus.subscribe();
+        let backlog = match last_event_id {
+            Some(id) => self.outbox.events_after(id, REPLAY_LIMIT).await?,
+            None => Vec::new(),
+        };
+        let mut replayed: HashSet<Uuid> = backlog.iter().map(|event| event.id).collect();
+
+        let live = stream::unfold(receiver, |mut receiver| async move {
+            match receiver.recv().await {
+                Ok(event) => Some((event, receiver)),
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "event stream subscriber lagged; closing");
+                    None
+                }
+                Err(RecvError::Closed) => None,
+            }
+        })
+        .filter(move |event| future::ready(!replayed.remove(&event.id)));
+
+        Ok(stream::iter(backlog)
+            .chain(live)
+            .filter(move |event| {
+                future::ready(event_types.is_empty() || event_types.contains(&event.event_type))
+            })
+            .boxed())
+    }
+}
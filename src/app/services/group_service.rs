@@ -0,0 +1,180 @@
+use crate::domain::{
+    AuditContext, DomainError, Group, GroupMembers, GroupRepository, NewGroup, RoleRepository,
+    UpdateGroup, UserRepository,
+};
+use uuid::Uuid;
+
+pub struct GroupService<G, U, R> {
+    groups: G,
+    users: U,
+    roles: R,
+}
+
+impl<G, U, R> GroupService<G, U, R>
+where
+    G: GroupRepository,
+    U: UserRepository,
+    R: RoleRepository,
+{
+    pub fn new(groups: G, users: U, roles: R) -> Self {
+        Self {
+            groups,
+            users,
+            roles,
+        }
+    }
+
+    pub async fn list_groups(&self) -> Result<Vec<Group>, DomainError> {
+        self.groups.list().await
+    }
+
+    pub async fn get_group(&self, group_id: Uuid) -> Result<Group, DomainError> {
+        self.groups
+            .find_by_id(group_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("group not found".to_string()))
+    }
+
+    pub async fn create_group(&self, input: NewGroup) -> Result<Group, DomainError> {
+        if self.groups.find_by_name(&input.name).await?.is_some() {
+            return Err(DomainError::Conflict("group already exists".to_string()));
+        }
+
+        self.groups.create(input).await
+    }
+
+    pub async fn update_group(
+        &self,
+        group_id: Uuid,
+        input: UpdateGroup,
+    ) -> Result<Group, DomainError> {
+        if let Some(ref name) = input.name {
+            if let Some(existing) = self.groups.find_by_name(name).await? {
+                if existing.id != group_id {
+                    return Err(DomainError::Conflict("group already exists".to_string()));
+                }
+            }
+        }
+
+        self.groups.update(group_id, input).await
+    }
+
+    pub async fn delete_group(
+        &self,
+        group_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError> {
+        self.groups.delete(group_id, audit).await
+    }
+
+    pub async fn members(&self, group_id: Uuid) -> Result<GroupMembers, DomainError> {
+        self.get_group(group_id).await?;
+        self.groups.members(group_id).await
+    }
+
+    pub async fn add_user(
+        &self,
+        group_id: Uuid,
+        user_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<GroupMembers, DomainError> {
+        self.get_group(group_id).await?;
+        self.ensure_user(user_id).await?;
+
+        self.groups.add_user(group_id, user_id, audit).await?;
+        self.groups.members(group_id).await
+    }
+
+    pub async fn remove_user(
+        &self,
+        group_id: Uuid,
+        user_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<GroupMembers, DomainError> {
+        self.get_group(group_id).await?;
+
+        self.groups.remove_user(group_id, user_id, audit).await?;
+        self.groups.members(group_id).await
+    }
+
+    pub async fn add_group(
+        &self,
+        group_id: Uuid,
+        member_group_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<GroupMembers, DomainError> {
+        if group_id == member_group_id {
+            return Err(DomainError::Conflict(
+                "group nesting would create a cycle".to_string(),
+            ));
+        }
+
+        self.get_group(group_id).await?;
+        self.get_group(member_group_id).await?;
+
+        self.groups
+            .add_group(group_id, member_group_id, audit)
+            .await?;
+        self.groups.members(group_id).await
+    }
+
+    pub async fn remove_group(
+        &self,
+        group_id: Uuid,
+        member_group_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<GroupMembers, DomainError> {
+        self.get_group(group_id).await?;
+
+        self.groups
+            .remove_group(group_id, member_group_id, audit)
+            .await?;
+        self.groups.members(group_id).await
+    }
+
+    pub async fn assign_role(
+        &self,
+        group_id: Uuid,
+        role_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<Group, DomainError> {
+        self.get_group(group_id).await?;
+        self.ensure_role(role_id).await?;
+
+        self.groups.assign_role(group_id, role_id, audit).await?;
+        self.get_group(group_id).await
+    }
+
+    pub async fn unassign_role(
+        &self,
+        group_id: Uuid,
+        role_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<Group, DomainError> {
+        self.get_group(group_id).await?;
+
+        self.groups.unassign_role(group_id, role_id, audit).await?;
+        self.get_group(group_id).await
+    }
+
+    pub async fn effective_groups(&self, user_id: Uuid) -> Result<Vec<Group>, DomainError> {
+        self.ensure_user(user_id).await?;
+        self.groups.effective_groups(user_id).await
+    }
+
+    async fn ensure_user(&self, user_id: Uuid) -> Result<(), DomainError> {
+        self.users
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("user not found".to_string()))?;
+        Ok(())
+    }
+
+    async fn ensure_role(&self, role_id: Uuid) -> Result<(), DomainError> {
+        self.roles
+            .find_by_id(role_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("role not found".to_string()))?;
+        Ok(())
+    }
+}
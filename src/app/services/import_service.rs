@@ -0,0 +1,386 @@
+use crate::config::AppConfig;
+use crate::domain::{
+    AccountState, AuditContext, DomainError, NewUser, Role, RoleRepository, User, UserRepository,
+};
+use crate::infra::auth::jwt::JwtService;
+use crate::infra::mail::{Email, Mailer};
+use crate::infra::security::password;
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use std::collections::HashSet;
+use validator::ValidateEmail;
+
+/// Rows created per transaction. A failing chunk rolls back only its own rows.
+const IMPORT_CHUNK_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Csv,
+    Ndjson,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImportOptions {
+    /// Validate everything, including against existing accounts, but create nothing.
+    pub dry_run: bool,
+    /// Rows without `password_hash` get an account without a password and an email with
+    /// a link to choose one.
+    pub invite: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportSettings {
+    pub setup_url: String,
+    pub setup_ttl: Duration,
+}
+
+impl ImportSettings {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            setup_url: config.account_setup_url.clone(),
+            setup_ttl: Duration::hours(config.invitation_ttl_hours),
+        }
+    }
+}
+
+/// One user in an import file. CSV files name the columns in a header row.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportRow {
+    pub email: String,
+    pub username: String,
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub is_active: Option<bool>,
+    /// Hash taken over from the old system: argon2 (PHC format) or bcrypt.
+    #[serde(default)]
+    pub password_hash: Option<String>,
+}
+
+/// A row with the line it starts on, or the reason it could not be read.
+pub type ParsedRow = (u64, Result<ImportRow, String>);
+
+#[derive(Debug, Clone)]
+pub struct ImportRowError {
+    /// Line in the input, starting at 1.
+    pub line: u64,
+    pub email: Option<String>,
+    pub error: String,
+}
+
+/// Outcome of an import. In a dry run, `created` and `invited` count what would happen.
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub total: usize,
+    /// Accounts created, including invited ones.
+    pub created: usize,
+    pub invited: usize,
+    /// Rows whose email already has an account; re-running an import skips them.
+    pub skipped: usize,
+    pub failed: usize,
+    /// Rows that were not imported, plus created accounts whose setup email failed.
+    pub errors: Vec<ImportRowError>,
+}
+
+impl ImportReport {
+    fn fail(&mut self, line: u64, email: Option<String>, error: impl Into<String>) {
+        self.failed += 1;
+        self.errors.push(ImportRowError {
+            line,
+            email,
+            error: error.into(),
+        });
+    }
+}
+
+/// A row that passed validation.
+struct ValidRow {
+    line: u64,
+    row: ImportRow,
+    role: String,
+}
+
+pub struct UserImportService<U, R, M> {
+    users: U,
+    roles: R,
+    mailer: M,
+    jwt: JwtService,
+    settings: ImportSettings,
+}
+
+impl<U, R, M> UserImportService<U, R, M>
+where
+    U: UserRepository,
+    R: RoleRepository,
+    M: Mailer,
+{
+    pub fn new(users: U, roles: R, mailer: M, jwt: JwtService, settings: ImportSettings) -> Self {
+        Self {
+            users,
+            roles,
+            mailer,
+            jwt,
+            settings,
+        }
+    }
+
+    pub async fn import(
+        &self,
+        format: ImportFormat,
+        input: &str,
+        options: ImportOptions,
+        audit: &AuditContext,
+    ) -> Result<ImportReport, DomainError> {
+        let rows = parse_rows(format, input)?;
+        let mut report = ImportReport {
+            dry_run: options.dry_run,
+            total: rows.len(),
+            ..Default::default()
+        };
+
+        let valid = self.validate(rows, options, &mut report).await?;
+        for chunk in valid.chunks(IMPORT_CHUNK_SIZE) {
+            self.import_chunk(chunk, options, audit, &mut report)
+                .await?;
+        }
+
+        report.errors.sort_by_key(|error| error.line);
+        Ok(report)
+    }
+
+    /// Checks every row on its own and against earlier rows of the same file.
+    async fn validate(
+        &self,
+        rows: Vec<ParsedRow>,
+        options: ImportOptions,
+        report: &mut ImportReport,
+    ) -> Result<Vec<ValidRow>, DomainError> {
+        let known_roles: HashSet<String> = self
+            .roles
+            .list()
+            .await?
+            .into_iter()
+            .map(|role| role.name)
+            .collect();
+        let mut emails = HashSet::new();
+        let mut usernames = HashSet::new();
+        let mut valid = Vec::new();
+
+        for (line, row) in rows {
+            let mut row = match row {
+                Ok(row) => row,
+                Err(error) => {
+                    report.fail(line, None, error);
+                    continue;
+                }
+            };
+            let role = row.role.clone().unwrap_or_else(|| Role::USER.to_string());
+
+            let email_valid = match User::normalize_email(&row.email) {
+                Ok(email) if row.email.validate_email() => {
+                    row.email = email;
+                    true
+                }
+                _ => false,
+            };
+            row.username = User::normalize_username(&row.username);
+
+            let problem = if !email_valid {
+                Some("invalid email".to_string())
+            } else if !(3..=32).contains(&row.username.chars().count()) {
+                Some("username must be 3 to 32 characters".to_string())
+            } else if !known_roles.contains(&role) {
+                Some(format!("unknown role: {role}"))
+            } else if let Some(hash) = &row.password_hash {
+                password::validate_imported_hash(hash).err()
+            } else if !options.invite {
+                Some("password_hash is required unless invite is set".to_string())
+            } else {
+                None
+            };
+            let problem = problem.or_else(|| {
+                if !emails.insert(row.email.clone()) {
+                    Some("duplicate email in file".to_string())
+                } else if !usernames.insert(User::username_skeleton(&row.username)) {
+                    Some("duplicate username in file".to_string())
+                } else {
+                    None
+                }
+            });
+
+            match problem {
+                Some(error) => report.fail(line, Some(row.email), error),
+                None => valid.push(ValidRow { line, row, role }),
+            }
+        }
+
+        Ok(valid)
+    }
+
+    /// Skips rows whose account exists, then creates the rest in one transaction.
+    async fn import_chunk(
+        &self,
+        chunk: &[ValidRow],
+        options: ImportOptions,
+        audit: &AuditContext,
+        report: &mut ImportReport,
+    ) -> Result<(), DomainError> {
+        let emails: Vec<String> = chunk.iter().map(|valid| valid.row.email.clone()).collect();
+        let usernames: Vec<String> = chunk
+            .iter()
+            .map(|valid| valid.row.username.clone())
+            .collect();
+        let existing = self.users.find_existing(&emails, &usernames).await?;
+
+        let mut pending = Vec::new();
+        for valid in chunk {
+            if existing.emails.contains(&valid.row.email) {
+                report.skipped += 1;
+            } else if existing
+                .usernames
+                .contains(&User::username_skeleton(&valid.row.username))
+            {
+                report.fail(
+                    valid.line,
+                    Some(valid.row.email.clone()),
+                    "username already exists",
+                );
+            } else {
+                pending.push(valid);
+            }
+        }
+
+        if options.dry_run {
+            report.created += pending.len();
+            report.invited += pending
+                .iter()
+                .filter(|valid| valid.row.password_hash.is_none())
+                .count();
+            return Ok(());
+        }
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let new_users = pending
+            .iter()
+            .map(|valid| NewUser {
+                email: valid.row.email.clone(),
+                username: valid.row.username.clone(),
+                password_hash: valid
+                    .row
+                    .password_hash
+                    .clone()
+                    .unwrap_or_else(|| password::UNUSABLE_PASSWORD_HASH.to_string()),
+                roles: vec![valid.role.clone()],
+                state: match (valid.row.is_active, &valid.row.password_hash) {
+                    (Some(false), _) => AccountState::Deactivated,
+                    // Claimed by its owner through the setup link.
+                    (_, None) => AccountState::PendingVerification,
+                    (_, Some(_)) => AccountState::Active,
+                },
+                email_verified: false,
+            })
+            .collect();
+
+        let users = match self.users.create_many(new_users, audit).await {
+            Ok(users) => users,
+            Err(DomainError::Internal(error)) => return Err(DomainError::Internal(error)),
+            Err(err) => {
+                for valid in pending {
+                    report.fail(
+                        valid.line,
+                        Some(valid.row.email.clone()),
+                        format!("chunk rolled back: {err}"),
+                    );
+                }
+                return Ok(());
+            }
+        };
+        report.created += users.len();
+
+        for (valid, user) in pending.iter().zip(users) {
+            if valid.row.password_hash.is_some() {
+                continue;
+            }
+            report.invited += 1;
+            if let Err(err) = self.send_setup_link(&user).await {
+                tracing::warn!(user_id = %user.id, error = %err, "sending account setup link failed");
+                report.errors.push(ImportRowError {
+                    line: valid.line,
+                    email: Some(user.email.clone()),
+                    error: format!("account created, but sending the setup link failed: {err}"),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn send_setup_link(&self, user: &User) -> Result<(), DomainError> {
+        let expires_at = Utc::now() + self.settings.setup_ttl;
+        let token = self.jwt.create_account_setup_token(
+            user.id,
+            password::UNUSABLE_PASSWORD_HASH,
+            expires_at,
+        )?;
+        let url = format!("{}?token={}", self.settings.setup_url, token);
+
+        self.mailer
+            .send(Email {
+                to: user.email.clone(),
+                subject: "Set up your account".to_string(),
+                body: format!(
+                    "An account was created for you as {}.\n\nChoose a password before {}:\n{}\n",
+                    user.username,
+                    expires_at.to_rfc2822(),
+                    url
+                ),
+            })
+            .await
+    }
+}
+
+/// Splits `input` into rows, keeping the line each one starts on. Rows that cannot be
+/// read are returned as errors so the rest of the file still gets a report.
+pub fn parse_rows(format: ImportFormat, input: &str) -> Result<Vec<ParsedRow>, DomainError> {
+    match format {
+        ImportFormat::Ndjson => Ok(input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                let row = serde_json::from_str::<ImportRow>(line)
+                    .map_err(|err| format!("invalid JSON: {err}"));
+                (index as u64 + 1, row)
+            })
+            .collect()),
+        ImportFormat::Csv => {
+            let mut reader = csv::ReaderBuilder::new()
+                .trim(csv::Trim::All)
+                .from_reader(input.as_bytes());
+            let headers = reader
+                .headers()
+                .map_err(|err| DomainError::ValidationError(format!("invalid CSV header: {err}")))?
+                .clone();
+
+            Ok(reader
+                .records()
+                .map(|record| match record {
+                    Ok(record) => {
+                        let line = record.position().map_or(0, |position| position.line());
+                        let row = record
+                            .deserialize::<ImportRow>(Some(&headers))
+                            .map_err(|err| format!("invalid row: {err}"));
+                        (line, row)
+                    }
+                    Err(err) => {
+                        let line = err.position().map_or(0, |position| position.line());
+                        (line, Err(format!("invalid CSV: {err}")))
+                    }
+                })
+                .collect())
+        }
+    }
+}
@@ -0,0 +1,289 @@
+use crate::app::services::auth_service::{AuthService, LoginInput, LoginResponse, RegisterInput};
+use crate::app::services::org_service::OrganizationService;
+use crate::config::AppConfig;
+use crate::domain::{
+    AuditContext, AuditSink, DomainError, Invitation, InvitationRepository, InvitationStatus,
+    NewInvitation, OrgRole, OrganizationRepository, User, UserRepository,
+};
+use crate::infra::auth::jwt::{JwtService, TokenContext};
+use crate::infra::mail::{Email, Mailer};
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct InvitationSettings {
+    pub ttl: Duration,
+    pub accept_url: String,
+}
+
+impl InvitationSettings {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            ttl: Duration::hours(config.invitation_ttl_hours),
+            accept_url: config.invitation_accept_url.clone(),
+        }
+    }
+}
+
+/// An invitation together with the signed link that was sent out for it.
+#[derive(Debug, Clone)]
+pub struct IssuedInvitation {
+    pub invitation: Invitation,
+    pub url: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct AcceptInvitationInput {
+    pub token: String,
+    /// Required only when no account exists for the invited address yet.
+    pub username: Option<String>,
+    pub password: String,
+}
+
+pub struct InvitationService<I, O, U, M, A> {
+    invitations: I,
+    orgs: OrganizationService<O, U>,
+    users: U,
+    auth: AuthService<U, A>,
+    jwt: JwtService,
+    mailer: M,
+    settings: InvitationSettings,
+}
+
+impl<I, O, U, M, A> InvitationService<I, O, U, M, A>
+where
+    I: InvitationRepository,
+    O: OrganizationRepository,
+    U: UserRepository,
+    M: Mailer,
+    A: AuditSink,
+{
+    pub fn new(
+        invitations: I,
+        orgs: OrganizationService<O, U>,
+        users: U,
+        auth: AuthService<U, A>,
+        jwt: JwtService,
+        mailer: M,
+        settings: InvitationSettings,
+    ) -> Self {
+        Self {
+            invitations,
+            orgs,
+            users,
+            auth,
+            jwt,
+            mailer,
+            settings,
+        }
+    }
+
+    pub async fn invite(
+        &self,
+        actor: &User,
+        org_id: Uuid,
+        email: String,
+        role: OrgRole,
+    ) -> Result<IssuedInvitation, DomainError> {
+        let actor_role = self.orgs.authorize(actor, org_id, OrgRole::Admin).await?;
+        OrganizationService::<O, U>::ensure_can_grant(actor_role, role)?;
+        let email = User::normalize_email(&email)?;
+
+        if let Some(existing) = self.users.find_by_email(&email).await? {
+            if self.orgs.is_member(org_id, existing.user.id).await? {
+                return Err(DomainError::Conflict(
+                    "user is already a member".to_string(),
+                ));
+            }
+        }
+
+        if self.invitations.find_open(org_id, &email).await?.is_some() {
+            return Err(DomainError::Conflict(
+                "an invitation is already pending for this email".to_string(),
+            ));
+        }
+
+        let invitation = self
+            .invitations
+            .create(NewInvitation {
+                org_id,
+                email,
+                role,
+                invited_by: actor.id,
+                expires_at: Utc::now() + self.settings.ttl,
+            })
+            .await?;
+
+        self.deliver(actor, invitation).await
+    }
+
+    pub async fn list_pending(
+        &self,
+        actor: &User,
+        org_id: Uuid,
+    ) -> Result<Vec<Invitation>, DomainError> {
+        self.orgs.authorize(actor, org_id, OrgRole::Admin).await?;
+        self.invitations.list_open(org_id).await
+    }
+
+    /// Sends a fresh link with a new expiry. Links sent earlier stop working.
+    pub async fn resend(
+        &self,
+        actor: &User,
+        org_id: Uuid,
+        invitation_id: Uuid,
+    ) -> Result<IssuedInvitation, DomainError> {
+        self.find_manageable(actor, org_id, invitation_id).await?;
+
+        let invitation = self
+            .invitations
+            .renew(invitation_id, Utc::now() + self.settings.ttl)
+            .await?;
+
+        self.deliver(actor, invitation).await
+    }
+
+    pub async fn revoke(
+        &self,
+        actor: &User,
+        org_id: Uuid,
+        invitation_id: Uuid,
+    ) -> Result<(), DomainError> {
+        self.find_manageable(actor, org_id, invitation_id).await?;
+        self.invitations.revoke(invitation_id).await
+    }
+
+    /// Accepts an invitation, either by creating an account for the invited address or by
+    /// attaching the existing one after checking its password. Returns tokens scoped to
+    /// the organization.
+    pub async fn accept(
+        &self,
+        input: AcceptInvitationInput,
+        audit: &AuditContext,
+    ) -> Result<LoginResponse, DomainError> {
+        let claims = self.jwt.decode_invitation_token(&input.token)?;
+        let invitation_id = claims.invitation_id()?;
+        let nonce = claims.nonce()?;
+
+        let invitation = self
+            .invitations
+            .find_by_id(invitation_id)
+            .await?
+            .filter(|invitation| {
+                invitation.nonce == nonce && invitation.status() == InvitationStatus::Pending
+            })
+            .ok_or_else(|| {
+                DomainError::Unauthorized("invitation is no longer valid".to_string())
+            })?;
+
+        let user = match self.users.find_by_email(&invitation.email).await? {
+            Some(_) => {
+                let session = self
+                    .auth
+                    .login(
+                        LoginInput {
+                            email: invitation.email.clone(),
+                            password: input.password,
+                        },
+                        audit,
+                    )
+                    .await?;
+
+                if self
+                    .orgs
+                    .is_member(invitation.org_id, session.user.id)
+                    .await?
+                {
+                    return Err(DomainError::Conflict(
+                        "user is already a member".to_string(),
+                    ));
+                }
+
+                session.user
+            }
+            None => {
+                let username = input.username.ok_or_else(|| {
+                    DomainError::ValidationError(
+                        "username is required to create an account".to_string(),
+                    )
+                })?;
+
+                self.auth
+                    .register_user(
+                        RegisterInput {
+                            email: invitation.email.clone(),
+                            username,
+                            password: input.password,
+                            email_verified: true,
+                        },
+                        audit,
+                    )
+                    .await?
+            }
+        };
+
+        self.invitations
+            .accept(invitation.id, nonce, user.id)
+            .await?;
+
+        let user = self
+            .users
+            .find_by_id(user.id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("user not found".to_string()))?
+            .user;
+
+        self.auth.issue_tokens(
+            user,
+            &TokenContext {
+                org_id: Some(invitation.org_id),
+            },
+        )
+    }
+
+    async fn find_manageable(
+        &self,
+        actor: &User,
+        org_id: Uuid,
+        invitation_id: Uuid,
+    ) -> Result<Invitation, DomainError> {
+        let actor_role = self.orgs.authorize(actor, org_id, OrgRole::Admin).await?;
+
+        let invitation = self
+            .invitations
+            .find_by_id(invitation_id)
+            .await?
+            .filter(|invitation| invitation.org_id == org_id && invitation.is_open())
+            .ok_or_else(|| DomainError::NotFound("invitation not found".to_string()))?;
+
+        OrganizationService::<O, U>::ensure_can_grant(actor_role, invitation.role)?;
+        Ok(invitation)
+    }
+
+    async fn deliver(
+        &self,
+        actor: &User,
+        invitation: Invitation,
+    ) -> Result<IssuedInvitation, DomainError> {
+        let (org, _) = self.orgs.get_organization(actor, invitation.org_id).await?;
+        let token = self.jwt.create_invitation_token(&invitation)?;
+        let url = format!("{}?token={}", self.settings.accept_url, token);
+
+        self.mailer
+            .send(Email {
+                to: invitation.email.clone(),
+                subject: format!("You have been invited to join {}", org.name),
+                body: format!(
+                    "{} invited you to join {} as {}.\n\nAccept the invitation before {}:\n{}\n",
+                    actor.username,
+                    org.name,
+                    invitation.role,
+                    invitation.expires_at.to_rfc2822(),
+                    url
+                ),
+            })
+            .await?;
+
+        Ok(IssuedInvitation { invitation, url })
+    }
+}
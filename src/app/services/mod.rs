@@ -1,2 +1,16 @@
+pub mod attribute_service;
+pub mod audit_service;
 pub mod auth_service;
-pub mod user_service;
\ No newline at end of file
+pub mod avatar_service;
+pub mod elevation_service;
+pub mod email_change_service;
+pub mod event_stream_service;
+pub mod group_service;
+pub mod import_service;
+pub mod invitation_service;
+pub mod org_service;
+pub mod outbox_service;
+pub mod role_service;
+pub mod scim_service;
+pub mod user_service;
+pub mod webhook_service;
@@ -0,0 +1,267 @@
+use crate::domain::{
+    DomainError, Member, Membership, NewOrganization, OrgRole, Organization,
+    OrganizationRepository, Permission, UpdateOrganization, User, UserRepository,
+};
+use uuid::Uuid;
+
+pub struct OrganizationService<O, U> {
+    orgs: O,
+    users: U,
+}
+
+impl<O, U> OrganizationService<O, U>
+where
+    O: OrganizationRepository,
+    U: UserRepository,
+{
+    pub fn new(orgs: O, users: U) -> Self {
+        Self { orgs, users }
+    }
+
+    pub async fn create_organization(
+        &self,
+        actor: &User,
+        input: NewOrganization,
+    ) -> Result<Organization, DomainError> {
+        Self::validate_slug(&input.slug)?;
+
+        if self.orgs.find_by_slug(&input.slug).await?.is_some() {
+            return Err(DomainError::Conflict(
+                "organization slug already exists".to_string(),
+            ));
+        }
+
+        self.orgs.create(input, actor.id).await
+    }
+
+    pub async fn list_for_user(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<(Organization, OrgRole)>, DomainError> {
+        self.orgs.list_for_user(user_id).await
+    }
+
+    pub async fn get_organization(
+        &self,
+        actor: &User,
+        org_id: Uuid,
+    ) -> Result<(Organization, OrgRole), DomainError> {
+        let role = self.authorize(actor, org_id, OrgRole::Member).await?;
+        let org = self.find_organization(org_id).await?;
+        Ok((org, role))
+    }
+
+    pub async fn update_organization(
+        &self,
+        actor: &User,
+        org_id: Uuid,
+        input: UpdateOrganization,
+    ) -> Result<Organization, DomainError> {
+        self.authorize(actor, org_id, OrgRole::Admin).await?;
+
+        if let Some(ref slug) = input.slug {
+            Self::validate_slug(slug)?;
+
+            if let Some(existing) = self.orgs.find_by_slug(slug).await? {
+                if existing.id != org_id {
+                    return Err(DomainError::Conflict(
+                        "organization slug already exists".to_string(),
+                    ));
+                }
+            }
+        }
+
+        self.orgs.update(org_id, input).await
+    }
+
+    pub async fn delete_organization(&self, actor: &User, org_id: Uuid) -> Result<(), DomainError> {
+        self.authorize(actor, org_id, OrgRole::Owner).await?;
+        self.orgs.delete(org_id).await
+    }
+
+    pub async fn list_members(
+        &self,
+        actor: &User,
+        org_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Member>, DomainError> {
+        self.authorize(actor, org_id, OrgRole::Member).await?;
+        self.orgs.list_members(org_id, limit, offset).await
+    }
+
+    pub async fn add_member(
+        &self,
+        actor: &User,
+        org_id: Uuid,
+        email: &str,
+        role: OrgRole,
+    ) -> Result<Member, DomainError> {
+        let actor_role = self.authorize(actor, org_id, OrgRole::Admin).await?;
+        Self::ensure_can_grant(actor_role, role)?;
+
+        let user = self
+            .users
+            .find_by_email(email)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("user not found".to_string()))?
+            .user;
+
+        if self.orgs.find_membership(org_id, user.id).await?.is_some() {
+            return Err(DomainError::Conflict(
+                "user is already a member".to_string(),
+            ));
+        }
+
+        let membership = self.orgs.add_member(org_id, user.id, role).await?;
+        Ok(Self::member(user, membership))
+    }
+
+    pub async fn change_member_role(
+        &self,
+        actor: &User,
+        org_id: Uuid,
+        user_id: Uuid,
+        role: OrgRole,
+    ) -> Result<Member, DomainError> {
+        let actor_role = self.authorize(actor, org_id, OrgRole::Admin).await?;
+        let current = self.find_membership(org_id, user_id).await?;
+
+        Self::ensure_can_grant(actor_role, current.role)?;
+        Self::ensure_can_grant(actor_role, role)?;
+
+        if current.role == OrgRole::Owner && role != OrgRole::Owner {
+            self.ensure_not_last_owner(org_id).await?;
+        }
+
+        let membership = self.orgs.set_member_role(org_id, user_id, role).await?;
+        let user = self.find_user(user_id).await?;
+        Ok(Self::member(user, membership))
+    }
+
+    pub async fn remove_member(
+        &self,
+        actor: &User,
+        org_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), DomainError> {
+        let current = self.find_membership(org_id, user_id).await?;
+
+        if actor.id != user_id {
+            let actor_role = self.authorize(actor, org_id, OrgRole::Admin).await?;
+            Self::ensure_can_grant(actor_role, current.role)?;
+        }
+
+        if current.role == OrgRole::Owner {
+            self.ensure_not_last_owner(org_id).await?;
+        }
+
+        self.orgs.remove_member(org_id, user_id).await
+    }
+
+    /// Resolves the caller's effective role in `org_id`. Holders of `orgs:manage` act as
+    /// owners of every organization; everyone else needs a membership.
+    pub async fn effective_role(
+        &self,
+        actor: &User,
+        org_id: Uuid,
+    ) -> Result<Option<OrgRole>, DomainError> {
+        if actor.has_permission(Permission::OrgsManage) {
+            return Ok(Some(OrgRole::Owner));
+        }
+
+        Ok(self
+            .orgs
+            .find_membership(org_id, actor.id)
+            .await?
+            .map(|membership| membership.role))
+    }
+
+    /// Requires the caller to hold at least `required` in `org_id`. Non-members get a
+    /// not-found so organization ids are not disclosed.
+    pub async fn authorize(
+        &self,
+        actor: &User,
+        org_id: Uuid,
+        required: OrgRole,
+    ) -> Result<OrgRole, DomainError> {
+        self.find_organization(org_id).await?;
+
+        match self.effective_role(actor, org_id).await? {
+            Some(role) if role.at_least(required) => Ok(role),
+            Some(_) => Err(DomainError::Forbidden(format!(
+                "organization {required} role required"
+            ))),
+            None => Err(DomainError::NotFound("organization not found".to_string())),
+        }
+    }
+
+    async fn find_organization(&self, org_id: Uuid) -> Result<Organization, DomainError> {
+        self.orgs
+            .find_by_id(org_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("organization not found".to_string()))
+    }
+
+    async fn find_membership(
+        &self,
+        org_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Membership, DomainError> {
+        self.orgs
+            .find_membership(org_id, user_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("membership not found".to_string()))
+    }
+
+    async fn find_user(&self, user_id: Uuid) -> Result<User, DomainError> {
+        self.users
+            .find_by_id(user_id)
+            .await?
+            .map(|user_with_password| user_with_password.user)
+            .ok_or_else(|| DomainError::NotFound("user not found".to_string()))
+    }
+
+    async fn ensure_not_last_owner(&self, org_id: Uuid) -> Result<(), DomainError> {
+        if self.orgs.count_owners(org_id).await? <= 1 {
+            return Err(DomainError::Conflict(
+                "organization must keep at least one owner".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub async fn is_member(&self, org_id: Uuid, user_id: Uuid) -> Result<bool, DomainError> {
+        Ok(self.orgs.find_membership(org_id, user_id).await?.is_some())
+    }
+
+    /// Only owners may hand out or take away the owner role.
+    pub fn ensure_can_grant(actor_role: OrgRole, role: OrgRole) -> Result<(), DomainError> {
+        if role == OrgRole::Owner && actor_role != OrgRole::Owner {
+            return Err(DomainError::Forbidden(
+                "organization owner role required".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn member(user: User, membership: Membership) -> Member {
+        Member {
+            user,
+            role: membership.role,
+            joined_at: membership.created_at,
+        }
+    }
+
+    fn validate_slug(slug: &str) -> Result<(), DomainError> {
+        if !Organization::is_valid_slug(slug) {
+            return Err(DomainError::ValidationError(
+                "slug must be 2-48 characters of a-z, 0-9 or '-'".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
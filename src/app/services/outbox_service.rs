@@ -0,0 +1,71 @@
+use crate::domain::{DomainError, EventPublisher, OutboxRepository};
+use chrono::{Duration, Utc};
+
+/// Messages published per relay round.
+const RELAY_BATCH_SIZE: i64 = 100;
+
+/// How long a claimed batch stays hidden from other relays.
+const RELAY_LEASE_SECONDS: i64 = 60;
+
+/// Upper bound for the delay before a failed message is published again.
+const MAX_RETRY_DELAY_SECONDS: i64 = 300;
+
+/// Publishes outbox messages in insertion order. A message is marked published only after
+/// the publisher accepted it, so events are delivered at least once.
+pub struct OutboxRelay<O, P> {
+    outbox: O,
+    publisher: P,
+}
+
+impl<O, P> OutboxRelay<O, P>
+where
+    O: OutboxRepository,
+    P: EventPublisher,
+{
+    pub fn new(outbox: O, publisher: P) -> Self {
+        Self { outbox, publisher }
+    }
+
+    /// Publishes one batch of pending messages and returns how many were published.
+    /// A failed publish is retried later and ends the batch, so later events are not
+    /// published ahead of it.
+    pub async fn relay_pending(&self) -> Result<usize, DomainError> {
+        let messages = self
+            .outbox
+            .claim(
+                RELAY_BATCH_SIZE,
+                Utc::now() + Duration::seconds(RELAY_LEASE_SECONDS),
+            )
+            .await?;
+
+        let mut published = 0;
+        for message in messages {
+            match self.publisher.publish(&message.event).await {
+                Ok(()) => {
+                    self.outbox.mark_published(message.position).await?;
+                    published += 1;
+                }
+                Err(err) => {
+                    let attempts = message.attempts + 1;
+                    tracing::warn!(
+                        event_id = %message.event.id,
+                        attempts,
+                        error = %err,
+                        "publishing domain event failed"
+                    );
+                    let delay = (1_i64 << attempts.clamp(0, 16)).min(MAX_RETRY_DELAY_SECONDS);
+                    self.outbox
+                        .mark_failed(
+                            message.position,
+                            &err.to_string(),
+                            Utc::now() + Duration::seconds(delay),
+                        )
+                        .await?;
+                    break;
+                }
+            }
+        }
+
+        Ok(published)
+    }
+}
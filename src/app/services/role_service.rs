@@ -0,0 +1,183 @@
+use crate::domain::{
+    AuditContext, DomainError, ErrorCode, NewRole, Role, RoleAssignmentEvent, RoleRepository,
+    UpdateRole, User, UserRepository,
+};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+pub struct RoleService<R, U> {
+    roles: R,
+    users: U,
+}
+
+impl<R, U> RoleService<R, U>
+where
+    R: RoleRepository,
+    U: UserRepository,
+{
+    pub fn new(roles: R, users: U) -> Self {
+        Self { roles, users }
+    }
+
+    pub async fn list_roles(&self) -> Result<Vec<Role>, DomainError> {
+        self.roles.list().await
+    }
+
+    pub async fn get_role(&self, role_id: Uuid) -> Result<Role, DomainError> {
+        self.roles
+            .find_by_id(role_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("role not found".to_string()))
+    }
+
+    pub async fn create_role(
+        &self,
+        mut input: NewRole,
+        audit: &AuditContext,
+    ) -> Result<Role, DomainError> {
+        Self::validate_name(&input.name)?;
+
+        if self.roles.find_by_name(&input.name).await?.is_some() {
+            return Err(DomainError::Conflict("role already exists".to_string()));
+        }
+
+        input
+            .permissions
+            .sort_by_key(|permission| permission.as_str());
+        input.permissions.dedup();
+        self.roles.create(input, audit).await
+    }
+
+    /// Built-in roles keep their name and permissions: `admin` must always be able to do
+    /// everything, or the deployment could be left without anyone to manage it.
+    pub async fn update_role(
+        &self,
+        role_id: Uuid,
+        mut input: UpdateRole,
+        audit: &AuditContext,
+    ) -> Result<Role, DomainError> {
+        let role = self.get_role(role_id).await?;
+
+        if role.is_builtin && input.permissions.is_some() {
+            return Err(DomainError::Forbidden(
+                "permissions of built-in roles cannot be changed".to_string(),
+            ));
+        }
+
+        if let Some(ref name) = input.name {
+            if role.is_builtin && name != &role.name {
+                return Err(DomainError::Forbidden(
+                    "built-in roles cannot be renamed".to_string(),
+                ));
+            }
+
+            Self::validate_name(name)?;
+
+            if let Some(existing) = self.roles.find_by_name(name).await? {
+                if existing.id != role_id {
+                    return Err(DomainError::Conflict("role already exists".to_string()));
+                }
+            }
+        }
+
+        if let Some(ref mut permissions) = input.permissions {
+            permissions.sort_by_key(|permission| permission.as_str());
+            permissions.dedup();
+        }
+
+        self.roles.update(role_id, input, audit).await
+    }
+
+    pub async fn delete_role(
+        &self,
+        role_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError> {
+        let role = self.get_role(role_id).await?;
+
+        if role.is_builtin {
+            return Err(DomainError::Forbidden(
+                "built-in roles cannot be deleted".to_string(),
+            ));
+        }
+
+        self.roles.delete(role_id, audit).await
+    }
+
+    /// Grants the role permanently, or until `expires_at` for time-bound elevation.
+    pub async fn assign_role(
+        &self,
+        user_id: Uuid,
+        role_id: Uuid,
+        expires_at: Option<DateTime<Utc>>,
+        audit: &AuditContext,
+    ) -> Result<User, DomainError> {
+        if expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+            return Err(DomainError::ValidationError(
+                "expiry must be in the future".to_string(),
+            ));
+        }
+
+        self.get_role(role_id).await?;
+        self.get_user(user_id).await?;
+
+        self.roles
+            .assign(user_id, role_id, expires_at, audit)
+            .await?;
+        self.get_user(user_id).await
+    }
+
+    /// Removing one of your own roles needs `confirmed`; taking the last active admin's
+    /// `admin` role is refused by the repository.
+    pub async fn unassign_role(
+        &self,
+        user_id: Uuid,
+        role_id: Uuid,
+        confirmed: bool,
+        audit: &AuditContext,
+    ) -> Result<User, DomainError> {
+        if audit.actor_id == Some(user_id) && !confirmed {
+            return Err(DomainError::Invariant(
+                ErrorCode::ConfirmationRequired,
+                "removing one of your own roles requires confirmation".to_string(),
+            ));
+        }
+
+        self.get_role(role_id).await?;
+        self.get_user(user_id).await?;
+
+        self.roles.unassign(user_id, role_id, audit).await?;
+        self.get_user(user_id).await
+    }
+
+    pub async fn role_history(
+        &self,
+        role_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<RoleAssignmentEvent>, DomainError> {
+        self.get_role(role_id).await?;
+        self.roles.assignment_history(role_id, limit).await
+    }
+
+    pub async fn sweep_expired_grants(&self) -> Result<u64, DomainError> {
+        self.roles.sweep_expired().await
+    }
+
+    async fn get_user(&self, user_id: Uuid) -> Result<User, DomainError> {
+        self.users
+            .find_by_id(user_id)
+            .await?
+            .map(|user_with_password| user_with_password.user)
+            .ok_or_else(|| DomainError::NotFound("user not found".to_string()))
+    }
+
+    fn validate_name(name: &str) -> Result<(), DomainError> {
+        if !Role::is_valid_name(name) {
+            return Err(DomainError::ValidationError(
+                "role name must be 2-32 characters of a-z, 0-9, '_' or '-'".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
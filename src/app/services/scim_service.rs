@@ -0,0 +1,644 @@
+use crate::domain::scim::{
+    parse_filter, parse_patch_path, AttrPath, CompareOp, FilterValue, PatchOp, PatchOperation,
+    PatchPath,
+};
+use crate::domain::{
+    AccountState, AdminUpdateUser, AuditContext, DomainError, Group, GroupField, GroupFilter,
+    GroupMembers, GroupRepository, NewGroup, NewUser, Role, StatusChange, UpdateGroup, User,
+    UserField, UserFilter, UserPaging, UserQuery, UserRepository, UserSort, UserSortField,
+};
+use crate::infra::security::password;
+use chrono::DateTime;
+use serde_json::Value;
+use uuid::Uuid;
+use validator::ValidateEmail;
+
+/// The user attributes a SCIM client manages; other attributes it sends are ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScimUserInput {
+    pub user_name: String,
+    pub email: String,
+    pub active: bool,
+    pub password: Option<String>,
+}
+
+/// A group as a SCIM client wants it: its name and direct members, users or groups.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScimGroupInput {
+    pub name: String,
+    pub members: Vec<Uuid>,
+}
+
+/// Maps SCIM provisioning onto the user and group repositories. Users are never deleted;
+/// deprovisioning deactivates them.
+pub struct ScimService<U, G> {
+    users: U,
+    groups: G,
+}
+
+impl<U, G> ScimService<U, G>
+where
+    U: UserRepository,
+    G: GroupRepository,
+{
+    pub fn new(users: U, groups: G) -> Self {
+        Self { users, groups }
+    }
+
+    /// Parses a `filter` on users; the error describes why it cannot be used.
+    pub fn user_filter(filter: &str) -> Result<UserFilter, String> {
+        parse_filter(filter)?.try_map(
+            &mut |path: AttrPath, comparison: Option<(CompareOp, &FilterValue)>| {
+                let field = match (path.attr.as_str(), path.sub_attr.as_deref()) {
+                    ("id", None) => UserField::Id,
+                    ("username", None) | ("displayname", None) => UserField::Username,
+                    ("emails", None) | ("emails", Some("value")) => UserField::Email,
+                    ("active", None) => UserField::IsActive,
+                    ("meta", Some("created")) => UserField::CreatedAt,
+                    ("meta", Some("lastmodified")) => UserField::UpdatedAt,
+                    _ => {
+                        return Err(format!(
+                            "unsupported filter attribute: {}",
+                            display_path(&path)
+                        ))
+                    }
+                };
+
+                let supported = match comparison {
+                    None => true,
+                    Some((op, FilterValue::Null)) => matches!(op, CompareOp::Eq | CompareOp::Ne),
+                    Some((op, FilterValue::Bool(_))) => {
+                        field == UserField::IsActive && matches!(op, CompareOp::Eq | CompareOp::Ne)
+                    }
+                    Some((op, FilterValue::String(value))) => match field {
+                        UserField::IsActive => false,
+                        UserField::CreatedAt | UserField::UpdatedAt => {
+                            !op.is_substring() && DateTime::parse_from_rfc3339(value).is_ok()
+                        }
+                        _ => true,
+                    },
+                    Some((_, FilterValue::Number(_))) => false,
+                };
+                if !supported {
+                    return Err(format!("unsupported comparison on {}", display_path(&path)));
+                }
+                Ok(field)
+            },
+        )
+    }
+
+    /// Parses a `filter` on groups, which supports `id` and `displayName`.
+    pub fn group_filter(filter: &str) -> Result<GroupFilter, String> {
+        parse_filter(filter)?.try_map(&mut |path: AttrPath, _| match (
+            path.attr.as_str(),
+            path.sub_attr.as_deref(),
+        ) {
+            ("id", None) => Ok(GroupField::Id),
+            ("displayname", None) => Ok(GroupField::Name),
+            _ => Err(format!(
+                "unsupported filter attribute: {}",
+                display_path(&path)
+            )),
+        })
+    }
+
+    /// One page of matching users, oldest first, and the number of matches. `start_index`
+    /// counts from 1.
+    pub async fn list_users(
+        &self,
+        filter: Option<UserFilter>,
+        start_index: i64,
+        count: i64,
+    ) -> Result<(Vec<User>, i64), DomainError> {
+        let query = UserQuery {
+            filter,
+            sort: Some(UserSort {
+                field: UserSortField::CreatedAt,
+                descending: false,
+            }),
+            paging: UserPaging::Offset(start_index - 1),
+            limit: count,
+            ..Default::default()
+        };
+
+        let total = self.users.count(&query).await?;
+        let users = if count > 0 {
+            self.users.list(&query).await?.items
+        } else {
+            Vec::new()
+        };
+        Ok((users, total))
+    }
+
+    pub async fn get_user(&self, id: Uuid) -> Result<User, DomainError> {
+        self.users
+            .find_by_id(id)
+            .await?
+            .map(|user| user.user)
+            .ok_or_else(|| DomainError::NotFound("user not found".to_string()))
+    }
+
+    /// Provisions a user with the `user` role. Without a password the account has no usable
+    /// one, which suits users who sign in through the identity provider.
+    pub async fn create_user(
+        &self,
+        input: ScimUserInput,
+        audit: &AuditContext,
+    ) -> Result<User, DomainError> {
+        let input = Self::validate_user(input)?;
+        if self.users.find_by_email(&input.email).await?.is_some() {
+            return Err(DomainError::Conflict("email already exists".to_string()));
+        }
+        if self
+            .users
+            .find_by_username(&input.user_name)
+            .await?
+            .is_some()
+        {
+            return Err(DomainError::Conflict("username already exists".to_string()));
+        }
+
+        let password_hash = match &input.password {
+            Some(password) => password::hash_password(password)?,
+            None => password::UNUSABLE_PASSWORD_HASH.to_string(),
+        };
+        self.users
+            .create(
+                NewUser {
+                    email: input.email,
+                    username: input.user_name,
+                    password_hash,
+                    roles: vec![Role::USER.to_string()],
+                    state: if input.active {
+                        AccountState::Active
+                    } else {
+                        AccountState::Deactivated
+                    },
+                    email_verified: false,
+                },
+                audit,
+            )
+            .await
+    }
+
+    pub async fn replace_user(
+        &self,
+        id: Uuid,
+        input: ScimUserInput,
+        audit: &AuditContext,
+    ) -> Result<User, DomainError> {
+        let current = self.get_user(id).await?;
+        self.apply_user(current, input, audit).await
+    }
+
+    pub async fn patch_user(
+        &self,
+        id: Uuid,
+        operations: Vec<PatchOperation>,
+        audit: &AuditContext,
+    ) -> Result<User, DomainError> {
+        let current = self.get_user(id).await?;
+        let mut input = ScimUserInput {
+            user_name: current.username.clone(),
+            email: current.email.clone(),
+            active: current.is_active(),
+            password: None,
+        };
+        for operation in operations {
+            Self::patch_user_input(&mut input, operation)?;
+        }
+
+        self.apply_user(current, input, audit).await
+    }
+
+    /// Deprovisioning: the account is deactivated, not deleted.
+    pub async fn deactivate_user(&self, id: Uuid, audit: &AuditContext) -> Result<(), DomainError> {
+        self.get_user(id).await?;
+        self.users
+            .set_status(id, StatusChange::to(AccountState::Deactivated), audit)
+            .await?;
+        Ok(())
+    }
+
+    /// One page of matching groups, by name, and the number of matches.
+    pub async fn list_groups(
+        &self,
+        filter: Option<GroupFilter>,
+        start_index: i64,
+        count: i64,
+    ) -> Result<(Vec<(Group, GroupMembers)>, i64), DomainError> {
+        // Groups are few enough that the repository lists them whole.
+        let groups: Vec<Group> = self
+            .groups
+            .list()
+            .await?
+            .into_iter()
+            .filter(|group| {
+                filter.as_ref().is_none_or(|filter| {
+                    filter.matches(&|field: &GroupField| Some(group.field(*field)))
+                })
+            })
+            .collect();
+        let total = groups.len() as i64;
+
+        let mut page = Vec::new();
+        for group in groups
+            .into_iter()
+            .skip((start_index - 1) as usize)
+            .take(count as usize)
+        {
+            let members = self.groups.members(group.id).await?;
+            page.push((group, members));
+        }
+        Ok((page, total))
+    }
+
+    pub async fn get_group(&self, id: Uuid) -> Result<(Group, GroupMembers), DomainError> {
+        let group = self
+            .groups
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("group not found".to_string()))?;
+        let members = self.groups.members(id).await?;
+        Ok((group, members))
+    }
+
+    pub async fn create_group(
+        &self,
+        input: ScimGroupInput,
+        audit: &AuditContext,
+    ) -> Result<(Group, GroupMembers), DomainError> {
+        if self.groups.find_by_name(&input.name).await?.is_some() {
+            return Err(DomainError::Conflict("group already exists".to_string()));
+        }
+
+        let group = self
+            .groups
+            .create(NewGroup {
+                name: input.name.clone(),
+                description: None,
+            })
+            .await?;
+        let members = GroupMembers {
+            users: Vec::new(),
+            groups: Vec::new(),
+        };
+        self.apply_group(group, members, input, audit).await
+    }
+
+    pub async fn replace_group(
+        &self,
+        id: Uuid,
+        input: ScimGroupInput,
+        audit: &AuditContext,
+    ) -> Result<(Group, GroupMembers), DomainError> {
+        let (group, members) = self.get_group(id).await?;
+        self.apply_group(group, members, input, audit).await
+    }
+
+    pub async fn patch_group(
+        &self,
+        id: Uuid,
+        operations: Vec<PatchOperation>,
+        audit: &AuditContext,
+    ) -> Result<(Group, GroupMembers), DomainError> {
+        let (group, members) = self.get_group(id).await?;
+        let mut input = ScimGroupInput {
+            name: group.name.clone(),
+            members: members
+                .users
+                .iter()
+                .map(|user| user.id)
+                .chain(members.groups.iter().map(|group| group.id))
+                .collect(),
+        };
+        for operation in operations {
+            Self::patch_group_input(&mut input, operation)?;
+        }
+
+        self.apply_group(group, members, input, audit).await
+    }
+
+    pub async fn delete_group(&self, id: Uuid, audit: &AuditContext) -> Result<(), DomainError> {
+        self.groups.delete(id, audit).await
+    }
+
+    /// Checks `input` and returns it with its email and userName normalized.
+    fn validate_user(mut input: ScimUserInput) -> Result<ScimUserInput, DomainError> {
+        if !input.email.validate_email() {
+            return Err(DomainError::ValidationError("invalid email".to_string()));
+        }
+        input.email = User::normalize_email(&input.email)?;
+        input.user_name = User::normalize_username(&input.user_name);
+        if !(3..=32).contains(&input.user_name.chars().count()) {
+            return Err(DomainError::ValidationError(
+                "userName must be 3 to 32 characters".to_string(),
+            ));
+        }
+        if input
+            .password
+            .as_ref()
+            .is_some_and(|password| password.chars().count() < 8)
+        {
+            return Err(DomainError::ValidationError(
+                "password must be at least 8 characters".to_string(),
+            ));
+        }
+        Ok(input)
+    }
+
+    async fn apply_user(
+        &self,
+        current: User,
+        input: ScimUserInput,
+        audit: &AuditContext,
+    ) -> Result<User, DomainError> {
+        let input = Self::validate_user(input)?;
+
+        let email = (input.email != current.email).then_some(input.email);
+        let username = (input.user_name != current.username).then_some(input.user_name);
+        if let Some(ref email) = email {
+            if self.users.find_by_email(email).await?.is_some() {
+                return Err(DomainError::Conflict("email already exists".to_string()));
+            }
+        }
+        if let Some(ref username) = username {
+            // A change of case keeps the skeleton and so finds the user itself.
+            if self
+                .users
+                .find_by_username(username)
+                .await?
+                .is_some_and(|existing| existing.id != current.id)
+            {
+                return Err(DomainError::Conflict("username already exists".to_string()));
+            }
+        }
+
+        let update = AdminUpdateUser {
+            email,
+            username,
+            is_active: (input.active != current.is_active()).then_some(input.active),
+            attributes: None,
+        };
+        if update.email.is_some() || update.username.is_some() || update.is_active.is_some() {
+            self.users.update_user(current.id, update, audit).await?;
+        }
+        if let Some(password) = input.password {
+            self.users
+                .set_password(current.id, password::hash_password(&password)?, audit)
+                .await?;
+        }
+
+        self.get_user(current.id).await
+    }
+
+    fn patch_user_input(
+        input: &mut ScimUserInput,
+        operation: PatchOperation,
+    ) -> Result<(), DomainError> {
+        let Some(path) = operation.path else {
+            if operation.op == PatchOp::Remove {
+                return Err(DomainError::ValidationError(
+                    "`remove` requires a path".to_string(),
+                ));
+            }
+            let Some(Value::Object(values)) = operation.value else {
+                return Err(DomainError::ValidationError(
+                    "`value` must be an object without a path".to_string(),
+                ));
+            };
+            for (name, value) in values {
+                Self::set_user_attribute(input, &patch_path(&name)?, value)?;
+            }
+            return Ok(());
+        };
+
+        let path = patch_path(&path)?;
+        if operation.op == PatchOp::Remove {
+            return match path.attr.as_str() {
+                "username" | "emails" | "active" => Err(DomainError::ValidationError(format!(
+                    "{} cannot be removed",
+                    path.attr
+                ))),
+                _ => Ok(()),
+            };
+        }
+        Self::set_user_attribute(input, &path, operation.value.unwrap_or(Value::Null))
+    }
+
+    fn set_user_attribute(
+        input: &mut ScimUserInput,
+        path: &PatchPath,
+        value: Value,
+    ) -> Result<(), DomainError> {
+        match (path.attr.as_str(), path.sub_attr.as_deref()) {
+            ("username", None) => input.user_name = string_value(value, "userName")?,
+            ("active", None) => input.active = bool_value(value)?,
+            ("password", None) => input.password = Some(string_value(value, "password")?),
+            ("emails", Some("value")) => input.email = string_value(value, "emails.value")?,
+            ("emails", None) => input.email = primary_email(&value)?,
+            // Attributes this service does not store are accepted and ignored.
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn patch_group_input(
+        input: &mut ScimGroupInput,
+        operation: PatchOperation,
+    ) -> Result<(), DomainError> {
+        let Some(path) = operation.path else {
+            if operation.op == PatchOp::Remove {
+                return Err(DomainError::ValidationError(
+                    "`remove` requires a path".to_string(),
+                ));
+            }
+            let Some(Value::Object(values)) = operation.value else {
+                return Err(DomainError::ValidationError(
+                    "`value` must be an object without a path".to_string(),
+                ));
+            };
+            for (name, value) in values {
+                let path = patch_path(&name)?;
+                match (path.attr.as_str(), operation.op) {
+                    ("displayname", _) => input.name = string_value(value, "displayName")?,
+                    ("members", PatchOp::Add) => input.members.extend(member_ids(&value)?),
+                    ("members", _) => input.members = member_ids(&value)?,
+                    _ => {}
+                }
+            }
+            return Ok(());
+        };
+
+        let path = patch_path(&path)?;
+        let value = operation.value.unwrap_or(Value::Null);
+        match (path.attr.as_str(), operation.op, &path.filter) {
+            ("displayname", PatchOp::Remove, _) => {
+                return Err(DomainError::ValidationError(
+                    "displayName cannot be removed".to_string(),
+                ));
+            }
+            ("displayname", _, _) => input.name = string_value(value, "displayName")?,
+            ("members", PatchOp::Add, None) => input.members.extend(member_ids(&value)?),
+            ("members", PatchOp::Replace, None) => input.members = member_ids(&value)?,
+            ("members", PatchOp::Remove, None) if value.is_null() => input.members.clear(),
+            ("members", PatchOp::Remove, None) => {
+                let removed = member_ids(&value)?;
+                input.members.retain(|id| !removed.contains(id));
+            }
+            ("members", PatchOp::Remove, Some(filter)) => {
+                input.members.retain(|id| {
+                    !filter.matches(&|path: &AttrPath| {
+                        path.is("members", Some("value"))
+                            .then(|| FilterValue::String(id.to_string()))
+                    })
+                });
+            }
+            ("members", _, Some(_)) => {
+                return Err(DomainError::ValidationError(
+                    "member filters are only supported with `remove`".to_string(),
+                ));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn apply_group(
+        &self,
+        group: Group,
+        members: GroupMembers,
+        input: ScimGroupInput,
+        audit: &AuditContext,
+    ) -> Result<(Group, GroupMembers), DomainError> {
+        if input.name.trim().is_empty() {
+            return Err(DomainError::ValidationError(
+                "displayName must not be empty".to_string(),
+            ));
+        }
+        if input.name != group.name {
+            if self.groups.find_by_name(&input.name).await?.is_some() {
+                return Err(DomainError::Conflict("group already exists".to_string()));
+            }
+            self.groups
+                .update(
+                    group.id,
+                    UpdateGroup {
+                        name: Some(input.name),
+                        description: None,
+                    },
+                )
+                .await?;
+        }
+
+        let current_users: Vec<Uuid> = members.users.iter().map(|user| user.id).collect();
+        let current_groups: Vec<Uuid> = members.groups.iter().map(|group| group.id).collect();
+        let mut wanted_users = Vec::new();
+        let mut wanted_groups = Vec::new();
+        for id in input.members {
+            if wanted_users.contains(&id) || wanted_groups.contains(&id) {
+                continue;
+            }
+            if current_users.contains(&id) || self.users.find_by_id(id).await?.is_some() {
+                wanted_users.push(id);
+            } else if current_groups.contains(&id) || self.groups.find_by_id(id).await?.is_some() {
+                wanted_groups.push(id);
+            } else {
+                return Err(DomainError::ValidationError(format!(
+                    "unknown member: {id}"
+                )));
+            }
+        }
+
+        for id in wanted_users.iter().filter(|id| !current_users.contains(id)) {
+            self.groups.add_user(group.id, *id, audit).await?;
+        }
+        for id in wanted_groups
+            .iter()
+            .filter(|id| !current_groups.contains(id))
+        {
+            self.groups.add_group(group.id, *id, audit).await?;
+        }
+        for id in current_users.iter().filter(|id| !wanted_users.contains(id)) {
+            self.groups.remove_user(group.id, *id, audit).await?;
+        }
+        for id in current_groups
+            .iter()
+            .filter(|id| !wanted_groups.contains(id))
+        {
+            self.groups.remove_group(group.id, *id, audit).await?;
+        }
+
+        self.get_group(group.id).await
+    }
+}
+
+fn display_path(path: &AttrPath) -> String {
+    match &path.sub_attr {
+        Some(sub_attr) => format!("{}.{sub_attr}", path.attr),
+        None => path.attr.clone(),
+    }
+}
+
+fn patch_path(path: &str) -> Result<PatchPath, DomainError> {
+    parse_patch_path(path)
+        .map_err(|err| DomainError::ValidationError(format!("invalid path: {err}")))
+}
+
+fn string_value(value: Value, name: &str) -> Result<String, DomainError> {
+    match value {
+        Value::String(value) => Ok(value),
+        _ => Err(DomainError::ValidationError(format!(
+            "{name} must be a string"
+        ))),
+    }
+}
+
+/// Some providers send booleans as `"True"`/`"False"`.
+fn bool_value(value: Value) -> Result<bool, DomainError> {
+    match value {
+        Value::Bool(value) => Ok(value),
+        Value::String(value) if value.eq_ignore_ascii_case("true") => Ok(true),
+        Value::String(value) if value.eq_ignore_ascii_case("false") => Ok(false),
+        _ => Err(DomainError::ValidationError(
+            "active must be a boolean".to_string(),
+        )),
+    }
+}
+
+/// The `value` of the primary email in a multi-valued `emails`, or of the first one.
+pub fn primary_email(value: &Value) -> Result<String, DomainError> {
+    let email = match value {
+        Value::Array(emails) => emails
+            .iter()
+            .find(|email| email["primary"] == Value::Bool(true))
+            .or_else(|| emails.first()),
+        Value::Object(_) => Some(value),
+        _ => None,
+    };
+
+    email
+        .and_then(|email| email["value"].as_str())
+        .map(str::to_string)
+        .ok_or_else(|| DomainError::ValidationError("an email is required".to_string()))
+}
+
+fn member_ids(value: &Value) -> Result<Vec<Uuid>, DomainError> {
+    let invalid = || {
+        DomainError::ValidationError("members must be a list of `{ \"value\": id }`".to_string())
+    };
+    let members = match value {
+        Value::Array(members) => members.as_slice(),
+        Value::Object(_) => std::slice::from_ref(value),
+        _ => return Err(invalid()),
+    };
+
+    members
+        .iter()
+        .map(|member| {
+            let id = member["value"].as_str().ok_or_else(invalid)?;
+            Uuid::parse_str(id)
+                .map_err(|_| DomainError::ValidationError(format!("unknown member: {id}")))
+        })
+        .collect()
+}
@@ -1,16 +1,31 @@
-use crate::domain::{AdminUpdateUser, DomainError, Role, UpdateProfile, User, UserRepository};
+use crate::domain::{
+    AccountState, AdminUpdateUser, AuditContext, BulkUserAction, BulkUserResult, DomainError,
+    ErrorCode, IdentityPolicy, Page, StatusChange, UpdateProfile, User, UserPaging, UserQuery,
+    UserRepository, UserSort,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
 use uuid::Uuid;
 
+/// Most users one bulk request may change, keeping its transaction short.
+pub const MAX_BULK_USERS: usize = 100;
+
+/// Account administration. The service owns the checks that depend on the caller, such
+/// as confirming a self-lockout and the identity policy. The last-admin rule is
+/// [`Role::ensure_admin_remains`](crate::domain::Role::ensure_admin_remains), applied by
+/// the [`UserRepository`] inside the transaction making the change, since only that
+/// transaction can serialize against concurrent ones.
 pub struct UserService<R> {
     repo: R,
+    policy: Arc<IdentityPolicy>,
 }
 
 impl<R> UserService<R>
 where
     R: UserRepository,
 {
-    pub fn new(repo: R) -> Self {
-        Self { repo }
+    pub fn new(repo: R, policy: Arc<IdentityPolicy>) -> Self {
+        Self { repo, policy }
     }
 
     pub async fn get_profile(&self, user_id: Uuid) -> Result<User, DomainError> {
@@ -22,63 +37,198 @@ where
         Ok(user_with_password.user)
     }
 
+    /// A new username must pass the identity policy; keeping the current one, in any
+    /// case, always works.
     pub async fn update_profile(
         &self,
         user_id: Uuid,
-        input: UpdateProfile,
+        mut input: UpdateProfile,
+        audit: &AuditContext,
     ) -> Result<User, DomainError> {
-        if let Some(ref email) = input.email {
-            if let Some(existing) = self.repo.find_by_email(email).await? {
-                if existing.user.id != user_id {
-                    return Err(DomainError::Conflict("email already exists".to_string()));
-                }
-            }
-        }
-
+        input.username = input
+            .username
+            .map(|username| User::normalize_username(&username));
         if let Some(ref username) = input.username {
-            if let Some(existing) = self.repo.find_by_username(username).await? {
-                if existing.id != user_id {
+            match self.repo.find_by_username(username).await? {
+                Some(existing) if existing.id != user_id => {
                     return Err(DomainError::Conflict("username already exists".to_string()));
                 }
+                Some(_) => {}
+                None => self.policy.check_username(username)?,
             }
         }
 
-        self.repo.update_profile(user_id, input).await
+        self.repo.update_profile(user_id, input, audit).await
+    }
+
+    pub async fn list_users(&self, query: UserQuery) -> Result<Page<User>, DomainError> {
+        let query = Self::normalize_query(query)?;
+        self.repo.list(&query).await
     }
 
-    pub async fn list_users(&self, limit: i64, offset: i64) -> Result<Vec<User>, DomainError> {
-        self.repo.list(limit, offset).await
+    pub async fn count_users(&self, query: UserQuery) -> Result<i64, DomainError> {
+        let query = Self::normalize_query(query)?;
+        self.repo.count(&query).await
     }
 
+    /// Admin update of another account. A new email or username must pass the identity
+    /// policy unless `bypass_policy` is set.
     pub async fn update_user(
         &self,
+        actor: &User,
         user_id: Uuid,
-        input: AdminUpdateUser,
+        mut input: AdminUpdateUser,
+        confirmed: bool,
+        bypass_policy: bool,
+        audit: &AuditContext,
     ) -> Result<User, DomainError> {
+        if input.is_active == Some(false) {
+            Self::ensure_confirmed_self_lockout(actor, user_id, confirmed)?;
+        }
+
+        input.email = input
+            .email
+            .map(|email| User::normalize_email(&email))
+            .transpose()?;
+        input.username = input
+            .username
+            .map(|username| User::normalize_username(&username));
+        if bypass_policy {
+            tracing::info!(%user_id, actor_id = %actor.id, "identity policy bypassed");
+        }
+
         if let Some(ref email) = input.email {
-            if let Some(existing) = self.repo.find_by_email(email).await? {
-                if existing.user.id != user_id {
+            match self.repo.find_by_email(email).await? {
+                Some(existing) if existing.user.id != user_id => {
                     return Err(DomainError::Conflict("email already exists".to_string()));
                 }
+                Some(_) => {}
+                None if !bypass_policy => self.policy.check_email(email)?,
+                None => {}
             }
         }
 
         if let Some(ref username) = input.username {
-            if let Some(existing) = self.repo.find_by_username(username).await? {
-                if existing.id != user_id {
+            match self.repo.find_by_username(username).await? {
+                Some(existing) if existing.id != user_id => {
                     return Err(DomainError::Conflict("username already exists".to_string()));
                 }
+                Some(_) => {}
+                None if !bypass_policy => self.policy.check_username(username)?,
+                None => {}
             }
         }
 
-        self.repo.update_user(user_id, input).await
+        self.repo.update_user(user_id, input, audit).await
+    }
+
+    pub async fn deactivate_user(
+        &self,
+        actor: &User,
+        user_id: Uuid,
+        confirmed: bool,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError> {
+        Self::ensure_confirmed_self_lockout(actor, user_id, confirmed)?;
+        self.repo
+            .set_status(user_id, StatusChange::to(AccountState::Deactivated), audit)
+            .await?;
+        Ok(())
     }
 
-    pub async fn set_user_role(&self, user_id: Uuid, role: Role) -> Result<User, DomainError> {
-        self.repo.set_role(user_id, role).await
+    /// Moves an account to another status. Blocking your own account needs `confirmed`;
+    /// leaving the deployment without an active admin is refused by the repository.
+    pub async fn change_status(
+        &self,
+        actor: &User,
+        user_id: Uuid,
+        change: StatusChange,
+        confirmed: bool,
+        audit: &AuditContext,
+    ) -> Result<User, DomainError> {
+        if change.state != AccountState::Active {
+            Self::ensure_confirmed_self_lockout(actor, user_id, confirmed)?;
+        }
+        self.repo.set_status(user_id, change, audit).await
     }
 
-    pub async fn deactivate_user(&self, user_id: Uuid) -> Result<(), DomainError> {
-        self.repo.set_active(user_id, false).await
+    /// Reactivates accounts whose suspension has ended; returns how many.
+    pub async fn lift_expired_suspensions(&self) -> Result<u64, DomainError> {
+        self.repo
+            .lift_expired_suspensions(&AuditContext::default())
+            .await
     }
-}
\ No newline at end of file
+
+    /// Applies `action` to every listed user at once. Duplicate ids are applied once;
+    /// deactivating yourself needs `confirmed`, as it does for a single user.
+    pub async fn bulk_update(
+        &self,
+        actor: &User,
+        mut user_ids: Vec<Uuid>,
+        action: BulkUserAction,
+        confirmed: bool,
+        audit: &AuditContext,
+    ) -> Result<Vec<BulkUserResult>, DomainError> {
+        let mut seen = HashSet::new();
+        user_ids.retain(|id| seen.insert(*id));
+        if user_ids.is_empty() {
+            return Err(DomainError::ValidationError(
+                "`user_ids` must not be empty".to_string(),
+            ));
+        }
+        if user_ids.len() > MAX_BULK_USERS {
+            return Err(DomainError::ValidationError(format!(
+                "at most {MAX_BULK_USERS} users can be changed at once"
+            )));
+        }
+        if action == BulkUserAction::Deactivate && user_ids.contains(&actor.id) {
+            Self::ensure_confirmed_self_lockout(actor, actor.id, confirmed)?;
+        }
+
+        self.repo.bulk_update(&user_ids, &action, audit).await
+    }
+
+    fn ensure_confirmed_self_lockout(
+        actor: &User,
+        user_id: Uuid,
+        confirmed: bool,
+    ) -> Result<(), DomainError> {
+        if actor.id == user_id && !confirmed {
+            return Err(DomainError::Invariant(
+                ErrorCode::ConfirmationRequired,
+                "blocking your own account requires confirmation".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn normalize_query(mut query: UserQuery) -> Result<UserQuery, DomainError> {
+        if let (Some(from), Some(to)) = (query.created_from, query.created_to) {
+            if from >= to {
+                return Err(DomainError::ValidationError(
+                    "`created_from` must be earlier than `created_to`".to_string(),
+                ));
+            }
+        }
+        if matches!(query.paging, UserPaging::After(_))
+            && query.sort.is_some_and(|sort| sort != UserSort::default())
+        {
+            return Err(DomainError::ValidationError(
+                "cursor pagination only supports the `-created_at` order".to_string(),
+            ));
+        }
+
+        let non_empty = |value: Option<String>| {
+            value
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        query.search = non_empty(query.search);
+        query.role = non_empty(query.role);
+        query.email_domain =
+            non_empty(query.email_domain).map(|domain| domain.trim_start_matches('@').to_string());
+
+        Ok(query)
+    }
+}
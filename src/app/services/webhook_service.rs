@@ -0,0 +1,277 @@
+use crate::config::AppConfig;
+use crate::domain::{
+    DeliveryAttempt, DeliveryStatus, DomainError, DomainEventType, NewWebhookEndpoint,
+    UpdateWebhookEndpoint, User, WebhookDelivery, WebhookEndpoint, WebhookRepository,
+};
+use crate::infra::webhook::{WebhookRequest, WebhookSender};
+use chrono::{Duration, Utc};
+use rand::RngCore;
+use uuid::Uuid;
+
+/// Deliveries attempted per dispatch round.
+const DISPATCH_BATCH_SIZE: i64 = 50;
+
+/// Upper bound for the delay between two attempts.
+const MAX_RETRY_DELAY_HOURS: i64 = 6;
+
+#[derive(Debug, Clone)]
+pub struct WebhookSettings {
+    pub max_attempts: i32,
+    pub retry_base: Duration,
+    pub timeout: std::time::Duration,
+}
+
+impl WebhookSettings {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            max_attempts: config.webhook_max_attempts.max(1),
+            retry_base: Duration::seconds(config.webhook_retry_base_seconds.max(1)),
+            timeout: std::time::Duration::from_secs(config.webhook_timeout_seconds.max(1)),
+        }
+    }
+
+    /// Delay after the `attempts`-th failed attempt: the base, doubled per attempt.
+    fn retry_delay(&self, attempts: i32) -> Duration {
+        let factor = 1_i32
+            .checked_shl(attempts.saturating_sub(1).clamp(0, 30) as u32)
+            .unwrap_or(i32::MAX);
+        (self.retry_base * factor).min(Duration::hours(MAX_RETRY_DELAY_HOURS))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateWebhookInput {
+    pub url: String,
+    pub event_types: Vec<DomainEventType>,
+    pub description: Option<String>,
+}
+
+pub struct WebhookService<W, S> {
+    repo: W,
+    sender: S,
+    settings: WebhookSettings,
+}
+
+impl<W, S> WebhookService<W, S>
+where
+    W: WebhookRepository,
+    S: WebhookSender,
+{
+    pub fn new(repo: W, sender: S, settings: WebhookSettings) -> Self {
+        Self {
+            repo,
+            sender,
+            settings,
+        }
+    }
+
+    pub async fn list_endpoints(&self) -> Result<Vec<WebhookEndpoint>, DomainError> {
+        self.repo.list_endpoints().await
+    }
+
+    pub async fn get_endpoint(&self, id: Uuid) -> Result<WebhookEndpoint, DomainError> {
+        self.repo
+            .find_endpoint(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("webhook not found".to_string()))
+    }
+
+    /// Registers an endpoint with a freshly generated signing secret.
+    pub async fn create_endpoint(
+        &self,
+        actor: &User,
+        input: CreateWebhookInput,
+    ) -> Result<WebhookEndpoint, DomainError> {
+        Self::validate_url(&input.url)?;
+        let event_types = Self::normalize_event_types(input.event_types)?;
+
+        let mut secret = [0_u8; 32];
+        rand::thread_rng().fill_bytes(&mut secret);
+
+        self.repo
+            .create_endpoint(NewWebhookEndpoint {
+                url: input.url,
+                secret: format!("whsec_{}", hex::encode(secret)),
+                event_types,
+                description: input.description,
+                created_by: actor.id,
+            })
+            .await
+    }
+
+    pub async fn update_endpoint(
+        &self,
+        id: Uuid,
+        mut input: UpdateWebhookEndpoint,
+    ) -> Result<WebhookEndpoint, DomainError> {
+        if let Some(ref url) = input.url {
+            Self::validate_url(url)?;
+        }
+        input.event_types = input
+            .event_types
+            .map(Self::normalize_event_types)
+            .transpose()?;
+
+        self.repo.update_endpoint(id, input).await
+    }
+
+    pub async fn delete_endpoint(&self, id: Uuid) -> Result<(), DomainError> {
+        self.repo.delete_endpoint(id).await
+    }
+
+    pub async fn list_deliveries(
+        &self,
+        endpoint_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, DomainError> {
+        self.get_endpoint(endpoint_id).await?;
+        self.repo.list_deliveries(endpoint_id, limit).await
+    }
+
+    /// Queues a finished delivery again, e.g. once a dead endpoint is fixed.
+    pub async fn redeliver(
+        &self,
+        endpoint_id: Uuid,
+        delivery_id: Uuid,
+    ) -> Result<WebhookDelivery, DomainError> {
+        let delivery = self
+            .repo
+            .find_delivery(delivery_id)
+            .await?
+            .filter(|delivery| delivery.endpoint_id == endpoint_id)
+            .ok_or_else(|| DomainError::NotFound("delivery not found".to_string()))?;
+
+        if delivery.status == DeliveryStatus::Pending {
+            return Err(DomainError::Conflict(
+                "delivery is already queued".to_string(),
+            ));
+        }
+
+        self.repo.redeliver(delivery.id).await
+    }
+
+    /// Attempts every due delivery once and schedules retries for the failures.
+    /// Returns the number of deliveries attempted.
+    pub async fn dispatch_due(&self) -> Result<usize, DomainError> {
+        // Long enough for a request to time out before another worker may pick it up.
+        let lease = Duration::from_std(self.settings.timeout * 2)
+            .map_err(|err| DomainError::Internal(err.to_string()))?;
+        let deliveries = self
+            .repo
+            .claim_due(DISPATCH_BATCH_SIZE, Utc::now() + lease)
+            .await?;
+
+        for delivery in &deliveries {
+            let attempt = self.attempt(delivery).await?;
+            let attempts = delivery.attempts + 1;
+
+            let retry_at = if attempt.succeeded() || attempts >= self.settings.max_attempts {
+                None
+            } else {
+                Some(Utc::now() + self.settings.retry_delay(attempts))
+            };
+
+            if !attempt.succeeded() {
+                tracing::warn!(
+                    delivery_id = %delivery.id,
+                    attempts,
+                    status_code = ?attempt.status_code,
+                    error = ?attempt.error,
+                    dead = retry_at.is_none(),
+                    "webhook delivery failed"
+                );
+            }
+
+            self.repo
+                .record_attempt(delivery.id, attempt, retry_at)
+                .await?;
+        }
+
+        Ok(deliveries.len())
+    }
+
+    async fn attempt(&self, delivery: &WebhookDelivery) -> Result<DeliveryAttempt, DomainError> {
+        let Some(endpoint) = self.repo.find_endpoint(delivery.endpoint_id).await? else {
+            return Ok(DeliveryAttempt {
+                status_code: None,
+                error: Some("webhook was deleted".to_string()),
+            });
+        };
+
+        if !endpoint.is_active {
+            return Ok(DeliveryAttempt {
+                status_code: None,
+                error: Some("webhook is disabled".to_string()),
+            });
+        }
+
+        let request = WebhookRequest::new(
+            endpoint.url,
+            &endpoint.secret,
+            delivery.id.to_string(),
+            delivery.event_type.to_string(),
+            Utc::now().timestamp(),
+            delivery.payload.to_string(),
+        );
+
+        Ok(match self.sender.send(&request).await {
+            Ok(status) if (200..300).contains(&status) => DeliveryAttempt {
+                status_code: Some(i32::from(status)),
+                error: None,
+            },
+            Ok(status) => DeliveryAttempt {
+                status_code: Some(i32::from(status)),
+                error: Some(format!("endpoint responded with {status}")),
+            },
+            Err(error) => DeliveryAttempt {
+                status_code: None,
+                error: Some(error),
+            },
+        })
+    }
+
+    fn validate_url(url: &str) -> Result<(), DomainError> {
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err(DomainError::ValidationError(
+                "webhook url must use http or https".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn normalize_event_types(
+        mut event_types: Vec<DomainEventType>,
+    ) -> Result<Vec<DomainEventType>, DomainError> {
+        event_types.sort_by_key(|event_type| event_type.as_str());
+        event_types.dedup();
+
+        if event_types.is_empty() {
+            return Err(DomainError::ValidationError(
+                "at least one event type is required".to_string(),
+            ));
+        }
+        Ok(event_types)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_up_to_the_cap() {
+        let settings = WebhookSettings {
+            max_attempts: 8,
+            retry_base: Duration::seconds(30),
+            timeout: std::time::Duration::from_secs(10),
+        };
+
+        assert_eq!(settings.retry_delay(1), Duration::seconds(30));
+        assert_eq!(settings.retry_delay(2), Duration::seconds(60));
+        assert_eq!(settings.retry_delay(4), Duration::seconds(240));
+        assert_eq!(
+            settings.retry_delay(40),
+            Duration::hours(MAX_RETRY_DELAY_HOURS)
+        );
+    }
+}
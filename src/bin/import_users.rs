@@ -0,0 +1,84 @@
+//! Imports users from a CSV or NDJSON file, the same way `POST /users/import` does.
+//!
+//! Usage: import_users <file> [--format csv|ndjson] [--dry-run] [--invite]
+
+use std::path::Path;
+use user_management_backend_rust::api::dto::user::ImportReportResponse;
+use user_management_backend_rust::app::services::import_service::{
+    ImportFormat, ImportOptions, ImportSettings, UserImportService,
+};
+use user_management_backend_rust::config::AppConfig;
+use user_management_backend_rust::domain::AuditContext;
+use user_management_backend_rust::infra::auth::jwt::JwtService;
+use user_management_backend_rust::infra::db::{
+    self, role_repo::SqlxRoleRepository, user_repo::SqlxUserRepository,
+};
+use user_management_backend_rust::infra::mail::LogMailer;
+
+const USAGE: &str = "usage: import_users <file> [--format csv|ndjson] [--dry-run] [--invite]";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut path = None;
+    let mut format = None;
+    let mut options = ImportOptions::default();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dry-run" => options.dry_run = true,
+            "--invite" => options.invite = true,
+            "--format" => format = Some(parse_format(&args.next().ok_or(USAGE)?)?),
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => return Err(USAGE.into()),
+        }
+    }
+    let path = path.ok_or(USAGE)?;
+    let format = match format {
+        Some(format) => format,
+        None => {
+            let extension = Path::new(&path)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or_default();
+            parse_format(extension)
+                .map_err(|_| "cannot tell the format from the file name; pass --format")?
+        }
+    };
+
+    tracing_subscriber::fmt().with_target(false).init();
+
+    let config = AppConfig::load()?;
+    let input = std::fs::read_to_string(&path)?;
+    let pool = db::create_pool(&config.database_url).await?;
+    sqlx::migrate!("./migrations").run(&pool).await?;
+
+    let service = UserImportService::new(
+        SqlxUserRepository::new(pool.clone()),
+        SqlxRoleRepository::new(pool),
+        LogMailer,
+        JwtService::new(&config),
+        ImportSettings::from_config(&config),
+    );
+    let report = service
+        .import(format, &input, options, &AuditContext::default())
+        .await?;
+    let failed = report.failed;
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&ImportReportResponse::from(report))?
+    );
+    if failed > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn parse_format(value: &str) -> Result<ImportFormat, String> {
+    match value {
+        "csv" => Ok(ImportFormat::Csv),
+        "ndjson" | "jsonl" => Ok(ImportFormat::Ndjson),
+        other => Err(format!("unknown format: {other}")),
+    }
+}
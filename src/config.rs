@@ -8,11 +8,109 @@ pub struct AppConfig {
     pub database_url: String,
     pub jwt_secret: String,
     pub access_token_minutes: i64,
+    /// Lifetime of impersonation tokens; they cannot be refreshed.
+    pub impersonation_token_minutes: i64,
     pub refresh_token_days: i64,
-    #[serde(default, deserialize_with = "deserialize_origins")]
+    pub jwt_groups_claim: bool,
+    pub invitation_ttl_hours: i64,
+    pub role_sweep_interval_seconds: u64,
+    /// How often suspensions that have reached their end are lifted.
+    pub status_sweep_interval_seconds: u64,
+    /// Public page that accepts invitations; the signed token is appended as `?token=`.
+    pub invitation_accept_url: String,
+    /// Public page where imported users choose a password; the token is appended as `?token=`.
+    pub account_setup_url: String,
+    /// How long the new address has to confirm an email change.
+    pub email_change_ttl_hours: i64,
+    /// How long the old address can undo an email change, confirmed or not.
+    pub email_change_revert_days: i64,
+    /// Public page that confirms an email change; the token is appended as `?token=`.
+    pub email_change_confirm_url: String,
+    /// Public page that reverts an email change; the token is appended as `?token=`.
+    pub email_change_revert_url: String,
+    /// Take the client address from `X-Forwarded-For`; enable only behind a trusted proxy.
+    pub trust_proxy_headers: bool,
+    /// Failed webhook deliveries are retried this many times in total before going dead.
+    pub webhook_max_attempts: i32,
+    /// Delay before the first retry; it doubles with every further attempt.
+    pub webhook_retry_base_seconds: i64,
+    pub webhook_poll_interval_seconds: u64,
+    pub webhook_timeout_seconds: u64,
+    pub outbox_poll_interval_seconds: u64,
+    /// Bearer token the identity provider uses for SCIM provisioning; SCIM is off when unset.
+    pub scim_token: Option<String>,
+    /// Where uploaded files such as avatars are kept.
+    pub blob_store: BlobStoreKind,
+    /// Root directory of the `local` blob store.
+    pub blob_local_dir: String,
+    /// Base URL of the S3-compatible service used by the `s3` blob store.
+    pub s3_endpoint: Option<String>,
+    pub s3_bucket: Option<String>,
+    pub s3_region: String,
+    pub s3_access_key_id: Option<String>,
+    pub s3_secret_access_key: Option<String>,
+    /// Largest accepted avatar upload, in bytes.
+    pub avatar_max_bytes: usize,
+    /// Usernames nobody may register or rename to; look-alikes are refused too.
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub reserved_usernames: Vec<String>,
+    /// Words, such as profanity, that may not appear anywhere in a username.
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub blocked_username_words: Vec<String>,
+    /// Email domains accounts may not use, including their subdomains.
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub blocked_email_domains: Vec<String>,
+    /// When set, the only email domains accounts may use.
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub allowed_email_domains: Vec<String>,
+    /// File listing disposable email domains, one per line; `#` starts a comment.
+    pub disposable_email_domains_file: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_list")]
     pub cors_allowed_origins: Vec<String>,
 }
 
+/// Names that look official or belong to well-known mailboxes.
+const DEFAULT_RESERVED_USERNAMES: &[&str] = &[
+    "abuse",
+    "admin",
+    "administrator",
+    "anonymous",
+    "api",
+    "billing",
+    "contact",
+    "help",
+    "helpdesk",
+    "hostmaster",
+    "info",
+    "mail",
+    "me",
+    "moderator",
+    "noreply",
+    "no-reply",
+    "null",
+    "official",
+    "owner",
+    "postmaster",
+    "root",
+    "security",
+    "settings",
+    "staff",
+    "superuser",
+    "support",
+    "sysadmin",
+    "system",
+    "undefined",
+    "webmaster",
+    "www",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlobStoreKind {
+    Local,
+    S3,
+}
+
 impl AppConfig {
     pub fn load() -> Result<Self, ConfigError> {
         dotenvy::dotenv().ok();
@@ -21,7 +119,42 @@ impl AppConfig {
             .set_default("app_host", "0.0.0.0")?
             .set_default("app_port", 8080)?
             .set_default("access_token_minutes", 15)?
+            .set_default("impersonation_token_minutes", 10)?
             .set_default("refresh_token_days", 7)?
+            .set_default("jwt_groups_claim", false)?
+            .set_default("invitation_ttl_hours", 72)?
+            .set_default("role_sweep_interval_seconds", 60)?
+            .set_default("status_sweep_interval_seconds", 60)?
+            .set_default(
+                "invitation_accept_url",
+                "http://localhost:3000/invitations/accept",
+            )?
+            .set_default("account_setup_url", "http://localhost:3000/account/setup")?
+            .set_default("email_change_ttl_hours", 24)?
+            .set_default("email_change_revert_days", 7)?
+            .set_default(
+                "email_change_confirm_url",
+                "http://localhost:3000/account/confirm-email",
+            )?
+            .set_default(
+                "email_change_revert_url",
+                "http://localhost:3000/account/revert-email",
+            )?
+            .set_default("trust_proxy_headers", false)?
+            .set_default("webhook_max_attempts", 8)?
+            .set_default("webhook_retry_base_seconds", 30)?
+            .set_default("webhook_poll_interval_seconds", 5)?
+            .set_default("webhook_timeout_seconds", 10)?
+            .set_default("outbox_poll_interval_seconds", 1)?
+            .set_default("blob_store", "local")?
+            .set_default("blob_local_dir", "./data/blobs")?
+            .set_default("s3_region", "us-east-1")?
+            .set_default("avatar_max_bytes", 5 * 1024 * 1024)?
+            .set_default("reserved_usernames", DEFAULT_RESERVED_USERNAMES.to_vec())?
+            .set_default(
+                "disposable_email_domains_file",
+                "./config/disposable_email_domains.txt",
+            )?
             .set_default("cors_allowed_origins", vec!["http://localhost:3000"])?
             .add_source(Environment::default().separator("__"))
             .build()?;
@@ -30,23 +163,24 @@ impl AppConfig {
     }
 }
 
-fn deserialize_origins<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+/// Accepts a list or, as environment variables provide it, a comma-separated string.
+fn deserialize_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
 where
     D: Deserializer<'de>,
 {
     #[derive(Deserialize)]
     #[serde(untagged)]
-    enum Origins {
+    enum List {
         One(String),
         Many(Vec<String>),
     }
 
-    match Origins::deserialize(deserializer)? {
-        Origins::One(value) => Ok(value
+    match List::deserialize(deserializer)? {
+        List::One(value) => Ok(value
             .split(',')
             .map(|item| item.trim().to_string())
             .filter(|item| !item.is_empty())
             .collect()),
-        Origins::Many(values) => Ok(values),
+        List::Many(values) => Ok(values),
     }
 }
@@ -0,0 +1,396 @@
+use crate::domain::errors::{DomainError, ErrorCode};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Longest accepted status reason, in characters.
+pub const MAX_STATUS_REASON_LEN: usize = 500;
+
+/// Where an account is in its lifecycle. Only `Active` accounts may sign in and use
+/// their tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountState {
+    /// Created but not yet claimed by its owner, e.g. imported and waiting for the
+    /// account setup link. Accounts only ever start in this state.
+    PendingVerification,
+    Active,
+    /// Blocked by an administrator for a reason, either indefinitely or until a set time.
+    Suspended,
+    /// Blocked for security reasons, e.g. a suspected compromise.
+    Locked,
+    /// Switched off by its owner or an administrator; can be reactivated.
+    Deactivated,
+    /// Removed for good. The row is kept so the email and username stay taken and the
+    /// audit trail stays intact.
+    Deleted,
+}
+
+impl AccountState {
+    pub const ALL: &'static [AccountState] = &[
+        AccountState::PendingVerification,
+        AccountState::Active,
+        AccountState::Suspended,
+        AccountState::Locked,
+        AccountState::Deactivated,
+        AccountState::Deleted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountState::PendingVerification => "pending_verification",
+            AccountState::Active => "active",
+            AccountState::Suspended => "suspended",
+            AccountState::Locked => "locked",
+            AccountState::Deactivated => "deactivated",
+            AccountState::Deleted => "deleted",
+        }
+    }
+
+    /// States this one may change to, besides itself.
+    pub fn transitions(&self) -> &'static [AccountState] {
+        use AccountState::*;
+
+        match self {
+            PendingVerification => &[Active, Suspended, Locked, Deactivated, Deleted],
+            Active => &[Suspended, Locked, Deactivated, Deleted],
+            Suspended => &[Active, Locked, Deactivated, Deleted],
+            Locked => &[Active, Suspended, Deactivated, Deleted],
+            Deactivated => &[Active, Deleted],
+            Deleted => &[],
+        }
+    }
+
+    /// Changing to the same state is allowed, to amend its reason or end, except for the
+    /// states nobody can move into or out of.
+    pub fn can_become(&self, next: AccountState) -> bool {
+        if next == AccountState::PendingVerification || *self == AccountState::Deleted {
+            return false;
+        }
+        *self == next || self.transitions().contains(&next)
+    }
+
+    fn requires_reason(&self) -> bool {
+        matches!(self, AccountState::Suspended | AccountState::Locked)
+    }
+}
+
+impl fmt::Display for AccountState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AccountState {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        AccountState::ALL
+            .iter()
+            .copied()
+            .find(|state| state.as_str() == value)
+            .ok_or_else(|| format!("invalid account status: {value}"))
+    }
+}
+
+/// Current status of an account with the details of its last change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountStatus {
+    pub state: AccountState,
+    /// Why the account was suspended, locked or deactivated; shown to admins only.
+    pub reason: Option<String>,
+    /// End of a suspension. Once passed the account counts as active again, and a
+    /// background sweep records the change.
+    pub until: Option<DateTime<Utc>>,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl AccountStatus {
+    pub fn active(changed_at: DateTime<Utc>) -> Self {
+        Self {
+            state: AccountState::Active,
+            reason: None,
+            until: None,
+            changed_at,
+        }
+    }
+
+    /// The state in force at `now`: an expired suspension is already over, even before
+    /// the sweep has written it. The `user_effective_status` SQL function mirrors this.
+    pub fn effective_state(&self, now: DateTime<Utc>) -> AccountState {
+        match (self.state, self.until) {
+            (AccountState::Suspended, Some(until)) if until <= now => AccountState::Active,
+            (state, _) => state,
+        }
+    }
+
+    /// The change a plain `active` flag asks for at `now`. It only switches between
+    /// active and deactivated; a suspension or lock is left for an admin to lift.
+    pub fn activation_change(&self, active: bool, now: DateTime<Utc>) -> Option<StatusChange> {
+        match (active, self.effective_state(now)) {
+            (true, AccountState::Deactivated) => Some(StatusChange::to(AccountState::Active)),
+            (false, AccountState::Active) => Some(StatusChange::to(AccountState::Deactivated)),
+            _ => None,
+        }
+    }
+
+    /// Refuses a sign-in or request of an account that is not active at `now`, with an
+    /// error code per state. The reason stays out of the message.
+    pub fn ensure_usable(&self, now: DateTime<Utc>) -> Result<(), DomainError> {
+        let (code, message) = match self.effective_state(now) {
+            AccountState::Active => return Ok(()),
+            AccountState::PendingVerification => (
+                ErrorCode::AccountPendingVerification,
+                "account is pending verification".to_string(),
+            ),
+            AccountState::Suspended => (
+                ErrorCode::AccountSuspended,
+                match self.until {
+                    Some(until) => format!(
+                        "account is suspended until {}",
+                        until.to_rfc3339_opts(SecondsFormat::Secs, true)
+                    ),
+                    None => "account is suspended".to_string(),
+                },
+            ),
+            AccountState::Locked => (ErrorCode::AccountLocked, "account is locked".to_string()),
+            AccountState::Deactivated => (
+                ErrorCode::AccountDeactivated,
+                "account is deactivated".to_string(),
+            ),
+            AccountState::Deleted => (ErrorCode::AccountDeleted, "account is deleted".to_string()),
+        };
+
+        Err(DomainError::AccountUnavailable(code, message))
+    }
+
+    /// The status after applying `change` at `now`, if the transition is allowed and the
+    /// change carries what its target state needs.
+    pub fn transition(
+        &self,
+        change: StatusChange,
+        now: DateTime<Utc>,
+    ) -> Result<AccountStatus, DomainError> {
+        if !self.state.can_become(change.state) {
+            return Err(DomainError::Invariant(
+                ErrorCode::InvalidStatusTransition,
+                format!(
+                    "cannot change an account from {} to {}",
+                    self.state, change.state
+                ),
+            ));
+        }
+
+        let reason = change
+            .reason
+            .map(|reason| reason.trim().to_string())
+            .filter(|reason| !reason.is_empty());
+        if reason
+            .as_ref()
+            .is_some_and(|reason| reason.chars().count() > MAX_STATUS_REASON_LEN)
+        {
+            return Err(DomainError::ValidationError(format!(
+                "`reason` must be at most {MAX_STATUS_REASON_LEN} characters"
+            )));
+        }
+        if change.state.requires_reason() && reason.is_none() {
+            return Err(DomainError::ValidationError(format!(
+                "a reason is required to change an account to {}",
+                change.state
+            )));
+        }
+        match change.until {
+            Some(_) if change.state != AccountState::Suspended => {
+                return Err(DomainError::ValidationError(
+                    "`until` is only allowed for suspensions".to_string(),
+                ));
+            }
+            Some(until) if until <= now => {
+                return Err(DomainError::ValidationError(
+                    "`until` must be in the future".to_string(),
+                ));
+            }
+            _ => {}
+        }
+
+        Ok(AccountStatus {
+            state: change.state,
+            reason: reason.filter(|_| change.state != AccountState::Active),
+            until: change.until,
+            changed_at: now,
+        })
+    }
+}
+
+/// A requested status change. `reason` is required for suspensions and locks; `until`
+/// ends a suspension automatically.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusChange {
+    pub state: AccountState,
+    pub reason: Option<String>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl StatusChange {
+    pub fn to(state: AccountState) -> Self {
+        Self {
+            state,
+            reason: None,
+            until: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn status(state: AccountState) -> AccountStatus {
+        AccountStatus {
+            state,
+            ..AccountStatus::active(Utc::now())
+        }
+    }
+
+    fn change(
+        state: AccountState,
+        reason: Option<&str>,
+        until: Option<DateTime<Utc>>,
+    ) -> StatusChange {
+        StatusChange {
+            state,
+            reason: reason.map(str::to_string),
+            until,
+        }
+    }
+
+    fn suspension(reason: Option<&str>, until: Option<DateTime<Utc>>) -> StatusChange {
+        change(AccountState::Suspended, reason, until)
+    }
+
+    #[test]
+    fn state_string_round_trip() {
+        for state in AccountState::ALL {
+            assert_eq!(AccountState::from_str(state.as_str()), Ok(*state));
+        }
+    }
+
+    #[test]
+    fn transitions_follow_the_table() {
+        let now = Utc::now();
+        let active = status(AccountState::Active);
+
+        let locked = active
+            .transition(
+                change(AccountState::Locked, Some("leaked password"), None),
+                now,
+            )
+            .unwrap();
+        assert_eq!(locked.state, AccountState::Locked);
+        assert_eq!(locked.reason.as_deref(), Some("leaked password"));
+        assert!(locked
+            .transition(StatusChange::to(AccountState::Active), now)
+            .unwrap()
+            .reason
+            .is_none());
+
+        let deactivated = active
+            .transition(StatusChange::to(AccountState::Deactivated), now)
+            .unwrap();
+        for state in [AccountState::Locked, AccountState::PendingVerification] {
+            let result = deactivated.transition(change(state, Some("reason"), None), now);
+            assert!(
+                matches!(
+                    result,
+                    Err(DomainError::Invariant(
+                        ErrorCode::InvalidStatusTransition,
+                        _
+                    ))
+                ),
+                "{state}"
+            );
+        }
+
+        let deleted = deactivated
+            .transition(StatusChange::to(AccountState::Deleted), now)
+            .unwrap();
+        for state in AccountState::ALL {
+            assert!(
+                deleted.transition(StatusChange::to(*state), now).is_err(),
+                "{state}"
+            );
+        }
+    }
+
+    #[test]
+    fn suspensions_need_a_reason_and_a_future_end() {
+        let now = Utc::now();
+        let active = status(AccountState::Active);
+
+        for change in [
+            suspension(None, None),
+            suspension(Some("  "), None),
+            suspension(Some("spam"), Some(now - Duration::minutes(1))),
+            change(
+                AccountState::Deactivated,
+                None,
+                Some(now + Duration::days(1)),
+            ),
+        ] {
+            assert!(matches!(
+                active.transition(change, now),
+                Err(DomainError::ValidationError(_))
+            ));
+        }
+
+        let until = now + Duration::days(7);
+        let suspended = active
+            .transition(suspension(Some(" spam "), Some(until)), now)
+            .unwrap();
+        assert_eq!(suspended.reason.as_deref(), Some("spam"));
+        assert_eq!(suspended.until, Some(until));
+
+        // Suspending again amends the reason and end.
+        let amended = suspended
+            .transition(suspension(Some("repeated spam"), None), now)
+            .unwrap();
+        assert_eq!(amended.until, None);
+    }
+
+    #[test]
+    fn expired_suspensions_are_no_longer_enforced() {
+        let now = Utc::now();
+        let mut suspended = status(AccountState::Suspended);
+        suspended.until = Some(now + Duration::hours(1));
+
+        assert!(matches!(
+            suspended.ensure_usable(now),
+            Err(DomainError::AccountUnavailable(
+                ErrorCode::AccountSuspended,
+                _
+            ))
+        ));
+        assert_eq!(
+            suspended.effective_state(now + Duration::hours(2)),
+            AccountState::Active
+        );
+        assert!(suspended.ensure_usable(now + Duration::hours(2)).is_ok());
+
+        for (state, code) in [
+            (
+                AccountState::PendingVerification,
+                ErrorCode::AccountPendingVerification,
+            ),
+            (AccountState::Locked, ErrorCode::AccountLocked),
+            (AccountState::Deactivated, ErrorCode::AccountDeactivated),
+            (AccountState::Deleted, ErrorCode::AccountDeleted),
+        ] {
+            assert!(
+                matches!(status(state).ensure_usable(now), Err(DomainError::AccountUnavailable(found, _)) if found == code),
+                "{state}"
+            );
+        }
+    }
+}
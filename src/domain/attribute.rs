@@ -0,0 +1,195 @@
+use crate::domain::errors::DomainError;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Custom attribute values of a user, keyed by attribute name.
+pub type Attributes = Map<String, Value>;
+
+/// Longest value a `string` attribute holds, in characters.
+pub const MAX_STRING_ATTRIBUTE_LENGTH: usize = 1024;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeType {
+    String,
+    Number,
+    Boolean,
+    /// Calendar date written as `YYYY-MM-DD`.
+    Date,
+}
+
+impl AttributeType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttributeType::String => "string",
+            AttributeType::Number => "number",
+            AttributeType::Boolean => "boolean",
+            AttributeType::Date => "date",
+        }
+    }
+
+    pub fn accepts(&self, value: &Value) -> bool {
+        match (self, value) {
+            (AttributeType::String, Value::String(value)) => {
+                value.chars().count() <= MAX_STRING_ATTRIBUTE_LENGTH
+            }
+            (AttributeType::Number, Value::Number(_)) => true,
+            (AttributeType::Boolean, Value::Bool(_)) => true,
+            (AttributeType::Date, Value::String(value)) => {
+                NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether `value`, taken from a query string, is a valid value of this type.
+    pub fn accepts_text(&self, value: &str) -> bool {
+        match self {
+            AttributeType::String => value.chars().count() <= MAX_STRING_ATTRIBUTE_LENGTH,
+            AttributeType::Number => value.parse::<f64>().is_ok_and(f64::is_finite),
+            AttributeType::Boolean => matches!(value, "true" | "false"),
+            AttributeType::Date => NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok(),
+        }
+    }
+}
+
+impl fmt::Display for AttributeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AttributeType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "string" => Ok(AttributeType::String),
+            "number" => Ok(AttributeType::Number),
+            "boolean" => Ok(AttributeType::Boolean),
+            "date" => Ok(AttributeType::Date),
+            _ => Err(format!("invalid attribute type: {value}")),
+        }
+    }
+}
+
+/// Admin-managed schema entry for one key of [`Attributes`].
+#[derive(Debug, Clone)]
+pub struct AttributeDefinition {
+    pub id: Uuid,
+    pub name: String,
+    pub attribute_type: AttributeType,
+    pub description: Option<String>,
+    /// Writes that touch attributes must leave this one set.
+    pub required: bool,
+    /// No two users may hold the same value.
+    pub unique: bool,
+    /// Backed by an index, so it can filter the user list.
+    pub indexed: bool,
+    /// Users may set it on themselves; otherwise only admins can, and users just read it.
+    pub user_editable: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl AttributeDefinition {
+    /// Attribute names end up in index names and query parameters, so they are kept to
+    /// lowercase letters, digits and underscores, starting with a letter.
+    pub fn is_valid_name(name: &str) -> bool {
+        (1..=40).contains(&name.len())
+            && name.starts_with(|c: char| c.is_ascii_lowercase())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    }
+
+    /// Whether the user list can be filtered on it; a unique attribute has an index too.
+    pub fn is_filterable(&self) -> bool {
+        self.indexed || self.unique
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewAttributeDefinition {
+    pub name: String,
+    pub attribute_type: AttributeType,
+    pub description: Option<String>,
+    pub required: bool,
+    pub unique: bool,
+    pub indexed: bool,
+    pub user_editable: bool,
+}
+
+/// Name and type are fixed once defined, since stored values and indexes depend on them.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateAttributeDefinition {
+    pub description: Option<String>,
+    pub required: Option<bool>,
+    pub unique: Option<bool>,
+    pub indexed: Option<bool>,
+    pub user_editable: Option<bool>,
+}
+
+/// Equality test on an indexed attribute, with the value as given in the query string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeFilter {
+    pub name: String,
+    pub attribute_type: AttributeType,
+    pub value: String,
+}
+
+#[async_trait]
+pub trait AttributeDefinitionRepository: Send + Sync {
+    async fn list(&self) -> Result<Vec<AttributeDefinition>, DomainError>;
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<AttributeDefinition>, DomainError>;
+    /// Also creates the indexes the definition asks for; creating a unique index fails
+    /// with a conflict when users already share a value.
+    async fn create(
+        &self,
+        input: NewAttributeDefinition,
+    ) -> Result<AttributeDefinition, DomainError>;
+    async fn update(
+        &self,
+        id: Uuid,
+        input: UpdateAttributeDefinition,
+    ) -> Result<AttributeDefinition, DomainError>;
+    /// Drops the definition, its indexes and every user's value for it.
+    async fn delete(&self, id: Uuid) -> Result<(), DomainError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn attribute_values_are_type_checked() {
+        assert!(AttributeType::String.accepts(&json!("engineering")));
+        assert!(!AttributeType::String.accepts(&json!(42)));
+        assert!(!AttributeType::String.accepts(&json!("x".repeat(MAX_STRING_ATTRIBUTE_LENGTH + 1))));
+        assert!(AttributeType::Number.accepts(&json!(4.5)));
+        assert!(!AttributeType::Number.accepts(&json!("4.5")));
+        assert!(AttributeType::Boolean.accepts(&json!(true)));
+        assert!(AttributeType::Date.accepts(&json!("2025-02-28")));
+        assert!(!AttributeType::Date.accepts(&json!("2025-02-30")));
+
+        assert!(AttributeType::Number.accepts_text("-12.5"));
+        assert!(!AttributeType::Number.accepts_text("NaN"));
+        assert!(!AttributeType::Boolean.accepts_text("yes"));
+    }
+
+    #[test]
+    fn attribute_name_validation() {
+        for name in ["department", "employee_id", "plan2"] {
+            assert!(AttributeDefinition::is_valid_name(name), "{name}");
+        }
+        for name in ["", "2fa", "_plan", "Department", "cost-center", "a'b"] {
+            assert!(!AttributeDefinition::is_valid_name(name), "{name}");
+        }
+    }
+}
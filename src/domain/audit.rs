@@ -0,0 +1,332 @@
+use crate::domain::errors::DomainError;
+use crate::domain::pagination::{Cursor, Page};
+use crate::domain::role::Role;
+use crate::domain::user::User;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, utoipa::ToSchema)]
+pub enum AuditEventType {
+    #[serde(rename = "user.registered")]
+    UserRegistered,
+    #[serde(rename = "auth.login_succeeded")]
+    LoginSucceeded,
+    #[serde(rename = "auth.login_failed")]
+    LoginFailed,
+    #[serde(rename = "auth.token_refreshed")]
+    TokenRefreshed,
+    #[serde(rename = "auth.logout")]
+    Logout,
+    #[serde(rename = "auth.impersonation_started")]
+    ImpersonationStarted,
+    #[serde(rename = "auth.password_set")]
+    PasswordSet,
+    #[serde(rename = "user.profile_updated")]
+    ProfileUpdated,
+    #[serde(rename = "user.updated")]
+    UserUpdated,
+    #[serde(rename = "user.deactivated")]
+    UserDeactivated,
+    #[serde(rename = "user.status_changed")]
+    UserStatusChanged,
+    #[serde(rename = "user.email_change_requested")]
+    EmailChangeRequested,
+    #[serde(rename = "user.email_changed")]
+    EmailChanged,
+    #[serde(rename = "user.email_change_cancelled")]
+    EmailChangeCancelled,
+    #[serde(rename = "user.email_change_reverted")]
+    EmailChangeReverted,
+    #[serde(rename = "role.assigned")]
+    RoleAssigned,
+    #[serde(rename = "role.unassigned")]
+    RoleUnassigned,
+    #[serde(rename = "role.created")]
+    RoleCreated,
+    #[serde(rename = "role.updated")]
+    RoleUpdated,
+    #[serde(rename = "role.deleted")]
+    RoleDeleted,
+}
+
+impl AuditEventType {
+    pub const ALL: &'static [AuditEventType] = &[
+        AuditEventType::UserRegistered,
+        AuditEventType::LoginSucceeded,
+        AuditEventType::LoginFailed,
+        AuditEventType::TokenRefreshed,
+        AuditEventType::Logout,
+        AuditEventType::ImpersonationStarted,
+        AuditEventType::PasswordSet,
+        AuditEventType::ProfileUpdated,
+        AuditEventType::UserUpdated,
+        AuditEventType::UserDeactivated,
+        AuditEventType::UserStatusChanged,
+        AuditEventType::EmailChangeRequested,
+        AuditEventType::EmailChanged,
+        AuditEventType::EmailChangeCancelled,
+        AuditEventType::EmailChangeReverted,
+        AuditEventType::RoleAssigned,
+        AuditEventType::RoleUnassigned,
+        AuditEventType::RoleCreated,
+        AuditEventType::RoleUpdated,
+        AuditEventType::RoleDeleted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEventType::UserRegistered => "user.registered",
+            AuditEventType::LoginSucceeded => "auth.login_succeeded",
+            AuditEventType::LoginFailed => "auth.login_failed",
+            AuditEventType::TokenRefreshed => "auth.token_refreshed",
+            AuditEventType::Logout => "auth.logout",
+            AuditEventType::ImpersonationStarted => "auth.impersonation_started",
+            AuditEventType::PasswordSet => "auth.password_set",
+            AuditEventType::ProfileUpdated => "user.profile_updated",
+            AuditEventType::UserUpdated => "user.updated",
+            AuditEventType::UserDeactivated => "user.deactivated",
+            AuditEventType::UserStatusChanged => "user.status_changed",
+            AuditEventType::EmailChangeRequested => "user.email_change_requested",
+            AuditEventType::EmailChanged => "user.email_changed",
+            AuditEventType::EmailChangeCancelled => "user.email_change_cancelled",
+            AuditEventType::EmailChangeReverted => "user.email_change_reverted",
+            AuditEventType::RoleAssigned => "role.assigned",
+            AuditEventType::RoleUnassigned => "role.unassigned",
+            AuditEventType::RoleCreated => "role.created",
+            AuditEventType::RoleUpdated => "role.updated",
+            AuditEventType::RoleDeleted => "role.deleted",
+        }
+    }
+}
+
+impl fmt::Display for AuditEventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditEventType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        AuditEventType::ALL
+            .iter()
+            .copied()
+            .find(|event_type| event_type.as_str() == value)
+            .ok_or_else(|| format!("invalid audit event type: {value}"))
+    }
+}
+
+/// Who made a request and from where. Built per request by the API layer and handed down
+/// to repositories so audit rows are written in the same transaction as the change.
+#[derive(Debug, Clone, Default)]
+pub struct AuditContext {
+    pub actor_id: Option<Uuid>,
+    /// Admin behind an impersonation token; `actor_id` is then the impersonated user.
+    pub impersonator_id: Option<Uuid>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+}
+
+impl AuditContext {
+    /// Same request attributed to `actor_id`, e.g. once a login has identified the caller.
+    pub fn with_actor(&self, actor_id: Uuid) -> Self {
+        Self {
+            actor_id: Some(actor_id),
+            ..self.clone()
+        }
+    }
+
+    pub fn event(&self, event_type: AuditEventType, target_user_id: Option<Uuid>) -> NewAuditEvent {
+        NewAuditEvent {
+            event_type,
+            actor_id: self.actor_id,
+            impersonator_id: self.impersonator_id,
+            target_user_id,
+            ip: self.ip.clone(),
+            user_agent: self.user_agent.clone(),
+            request_id: self.request_id.clone(),
+            before: None,
+            after: None,
+            metadata: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewAuditEvent {
+    pub event_type: AuditEventType,
+    pub actor_id: Option<Uuid>,
+    pub impersonator_id: Option<Uuid>,
+    pub target_user_id: Option<Uuid>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+    /// Changed fields before and after; unchanged fields are left out of both.
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub metadata: Option<Value>,
+}
+
+impl NewAuditEvent {
+    /// Records the fields that differ between two snapshots.
+    pub fn with_diff(mut self, before: &Value, after: &Value) -> Self {
+        let (before, after) = diff(before, after);
+        self.before = Some(before);
+        self.after = Some(after);
+        self
+    }
+
+    pub fn with_after(mut self, after: Value) -> Self {
+        self.after = Some(after);
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: Value) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditEvent {
+    pub id: Uuid,
+    pub event_type: AuditEventType,
+    pub actor_id: Option<Uuid>,
+    pub impersonator_id: Option<Uuid>,
+    pub target_user_id: Option<Uuid>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub metadata: Option<Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuditEvent {
+    pub fn cursor(&self) -> Cursor {
+        Cursor {
+            created_at: self.created_at,
+            id: self.id,
+        }
+    }
+}
+
+/// Filters for searching the audit log; every field is optional and they combine with AND.
+/// `from` is inclusive and `to` exclusive.
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery {
+    pub actor_id: Option<Uuid>,
+    pub target_user_id: Option<Uuid>,
+    pub event_type: Option<AuditEventType>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub after: Option<Cursor>,
+    pub limit: i64,
+}
+
+/// An event in a user's own activity feed.
+#[derive(Debug, Clone)]
+pub struct ActivityEntry {
+    pub event: AuditEvent,
+    /// Successful login from an address not used by any earlier successful login.
+    pub new_ip: bool,
+}
+
+#[async_trait]
+pub trait AuditRepository: Send + Sync {
+    /// Matching events, newest first.
+    async fn search(&self, query: &AuditQuery) -> Result<Page<AuditEvent>, DomainError>;
+    /// Most recent events targeting `user_id`.
+    async fn activity(&self, user_id: Uuid, limit: i64) -> Result<Vec<ActivityEntry>, DomainError>;
+}
+
+/// Destination for audit events that do not accompany a data change (logins, refreshes).
+/// Changes are audited by the repositories inside their own transaction instead.
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    async fn record(&self, event: NewAuditEvent) -> Result<(), DomainError>;
+}
+
+/// Audited view of an account; the password hash is deliberately absent.
+pub fn user_snapshot(user: &User) -> Value {
+    json!({
+        "email": user.email,
+        "username": user.username,
+        "roles": user.roles,
+        "status": user.status.state,
+        "status_reason": user.status.reason,
+        "status_until": user.status.until,
+        "email_verified": user.is_email_verified(),
+        "name": user.profile.display_name,
+        "given_name": user.profile.given_name,
+        "family_name": user.profile.family_name,
+        "locale": user.profile.locale,
+        "zoneinfo": user.profile.timezone,
+        "picture": user.profile.avatar_url,
+        "attributes": user.attributes,
+    })
+}
+
+/// Audited view of a role definition.
+pub fn role_snapshot(role: &Role) -> Value {
+    json!({
+        "name": role.name,
+        "description": role.description,
+        "permissions": role.permissions,
+    })
+}
+
+fn diff(before: &Value, after: &Value) -> (Value, Value) {
+    let empty = Map::new();
+    let before_fields = before.as_object().unwrap_or(&empty);
+    let after_fields = after.as_object().unwrap_or(&empty);
+
+    let mut changed_before = Map::new();
+    let mut changed_after = Map::new();
+    for key in before_fields.keys().chain(after_fields.keys()) {
+        let old = before_fields.get(key).unwrap_or(&Value::Null);
+        let new = after_fields.get(key).unwrap_or(&Value::Null);
+        if old != new && !changed_after.contains_key(key) {
+            changed_before.insert(key.clone(), old.clone());
+            changed_after.insert(key.clone(), new.clone());
+        }
+    }
+
+    (Value::Object(changed_before), Value::Object(changed_after))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_keeps_only_changed_fields() {
+        let before = json!({ "email": "a@example.com", "username": "alice", "is_active": true });
+        let after = json!({ "email": "b@example.com", "username": "alice", "is_active": true });
+
+        let event = AuditContext::default()
+            .event(AuditEventType::ProfileUpdated, None)
+            .with_diff(&before, &after);
+
+        assert_eq!(event.before, Some(json!({ "email": "a@example.com" })));
+        assert_eq!(event.after, Some(json!({ "email": "b@example.com" })));
+    }
+
+    #[test]
+    fn event_type_string_round_trip() {
+        for event_type in AuditEventType::ALL {
+            assert_eq!(
+                AuditEventType::from_str(event_type.as_str()),
+                Ok(*event_type)
+            );
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use crate::domain::errors::DomainError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ElevationStatus {
+    Pending,
+    Approved,
+    Denied,
+}
+
+impl fmt::Display for ElevationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElevationStatus::Pending => write!(f, "pending"),
+            ElevationStatus::Approved => write!(f, "approved"),
+            ElevationStatus::Denied => write!(f, "denied"),
+        }
+    }
+}
+
+impl FromStr for ElevationStatus {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "pending" => Ok(ElevationStatus::Pending),
+            "approved" => Ok(ElevationStatus::Approved),
+            "denied" => Ok(ElevationStatus::Denied),
+            _ => Err(format!("invalid elevation status: {value}")),
+        }
+    }
+}
+
+/// A user's request to hold a role for a limited time, decided by another admin.
+#[derive(Debug, Clone)]
+pub struct ElevationRequest {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub role_id: Uuid,
+    pub role_name: String,
+    pub duration_minutes: i64,
+    pub reason: String,
+    pub status: ElevationStatus,
+    pub decided_by: Option<Uuid>,
+    pub decision_note: Option<String>,
+    pub decided_at: Option<DateTime<Utc>>,
+    /// End of the resulting grant; set on approval.
+    pub grant_expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewElevationRequest {
+    pub user_id: Uuid,
+    pub role_id: Uuid,
+    pub duration_minutes: i64,
+    pub reason: String,
+}
+
+#[async_trait]
+pub trait ElevationRepository: Send + Sync {
+    async fn create(&self, input: NewElevationRequest) -> Result<ElevationRequest, DomainError>;
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<ElevationRequest>, DomainError>;
+    async fn list(
+        &self,
+        status: Option<ElevationStatus>,
+        limit: i64,
+    ) -> Result<Vec<ElevationRequest>, DomainError>;
+    async fn list_for_user(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<ElevationRequest>, DomainError>;
+    /// Marks a pending request approved and grants the role until `grant_expires_at`,
+    /// atomically. Fails with a conflict when the request was decided in the meantime.
+    async fn approve(
+        &self,
+        id: Uuid,
+        approver_id: Uuid,
+        note: Option<String>,
+        grant_expires_at: DateTime<Utc>,
+    ) -> Result<ElevationRequest, DomainError>;
+    async fn deny(
+        &self,
+        id: Uuid,
+        approver_id: Uuid,
+        note: Option<String>,
+    ) -> Result<ElevationRequest, DomainError>;
+}
@@ -0,0 +1,73 @@
+use crate::domain::audit::AuditContext;
+use crate::domain::errors::DomainError;
+use crate::domain::user::User;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// A user's request to move their account to another email address.
+#[derive(Debug, Clone)]
+pub struct EmailChange {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub old_email: String,
+    pub new_email: String,
+    /// Embedded in the signed links; links of another change do not match it.
+    pub nonce: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub revert_expires_at: DateTime<Utc>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub reverted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl EmailChange {
+    /// Still waiting for the new address to be confirmed.
+    pub fn is_pending(&self) -> bool {
+        self.confirmed_at.is_none() && self.cancelled_at.is_none() && self.expires_at > Utc::now()
+    }
+
+    /// The old address may still undo the change, whether or not it was confirmed.
+    pub fn is_revertible(&self) -> bool {
+        self.cancelled_at.is_none()
+            && self.reverted_at.is_none()
+            && self.revert_expires_at > Utc::now()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewEmailChange {
+    pub user_id: Uuid,
+    pub old_email: String,
+    pub new_email: String,
+    pub expires_at: DateTime<Utc>,
+    pub revert_expires_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait EmailChangeRepository: Send + Sync {
+    /// Records a pending change and cancels any earlier unconfirmed one of the same user.
+    async fn create(
+        &self,
+        input: NewEmailChange,
+        audit: &AuditContext,
+    ) -> Result<EmailChange, DomainError>;
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<EmailChange>, DomainError>;
+    /// Moves the user to the new address, marked as verified, provided they still use
+    /// the old one.
+    async fn confirm(
+        &self,
+        id: Uuid,
+        nonce: Uuid,
+        audit: &AuditContext,
+    ) -> Result<User, DomainError>;
+    /// Cancels a pending change, or restores the old address after a confirmed one and
+    /// returns the restored user.
+    async fn revert(
+        &self,
+        id: Uuid,
+        nonce: Uuid,
+        audit: &AuditContext,
+    ) -> Result<Option<User>, DomainError>;
+}
@@ -1,5 +1,47 @@
+use std::fmt;
 use thiserror::Error;
 
+/// Stable, machine-readable identifiers for errors that clients are expected to handle
+/// specifically rather than just display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// The change would leave the deployment without an active administrator.
+    LastAdmin,
+    /// The caller is about to lock themselves out and has to confirm explicitly.
+    ConfirmationRequired,
+    /// The requested account status cannot follow the current one.
+    InvalidStatusTransition,
+    /// The account has not been claimed by its owner yet.
+    AccountPendingVerification,
+    /// An administrator suspended the account.
+    AccountSuspended,
+    /// The account is locked for security reasons.
+    AccountLocked,
+    AccountDeactivated,
+    AccountDeleted,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::LastAdmin => "last_admin",
+            ErrorCode::ConfirmationRequired => "confirmation_required",
+            ErrorCode::InvalidStatusTransition => "invalid_status_transition",
+            ErrorCode::AccountPendingVerification => "account_pending_verification",
+            ErrorCode::AccountSuspended => "account_suspended",
+            ErrorCode::AccountLocked => "account_locked",
+            ErrorCode::AccountDeactivated => "account_deactivated",
+            ErrorCode::AccountDeleted => "account_deleted",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum DomainError {
     #[error("validation error: {0}")]
@@ -12,6 +54,13 @@ pub enum DomainError {
     Forbidden(String),
     #[error("conflict: {0}")]
     Conflict(String),
+    /// A conflict with a domain invariant, reported with a machine-readable code.
+    #[error("conflict ({0}): {1}")]
+    Invariant(ErrorCode, String),
+    /// The account's status does not allow signing in or acting, see
+    /// [`crate::domain::AccountStatus::ensure_usable`].
+    #[error("account unavailable ({0}): {1}")]
+    AccountUnavailable(ErrorCode, String),
     #[error("internal error: {0}")]
     Internal(String),
-}
\ No newline at end of file
+}
@@ -0,0 +1,209 @@
+use crate::domain::audit::{AuditEventType, NewAuditEvent};
+use crate::domain::errors::DomainError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// User lifecycle events published to the rest of the system and to integrators.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, utoipa::ToSchema)]
+pub enum DomainEventType {
+    #[serde(rename = "user.created")]
+    UserCreated,
+    #[serde(rename = "user.updated")]
+    UserUpdated,
+    #[serde(rename = "user.deactivated")]
+    UserDeactivated,
+    #[serde(rename = "user.status_changed")]
+    UserStatusChanged,
+    #[serde(rename = "user.role_changed")]
+    UserRoleChanged,
+    #[serde(rename = "user.logged_in")]
+    UserLoggedIn,
+}
+
+impl DomainEventType {
+    pub const ALL: &'static [DomainEventType] = &[
+        DomainEventType::UserCreated,
+        DomainEventType::UserUpdated,
+        DomainEventType::UserDeactivated,
+        DomainEventType::UserStatusChanged,
+        DomainEventType::UserRoleChanged,
+        DomainEventType::UserLoggedIn,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DomainEventType::UserCreated => "user.created",
+            DomainEventType::UserUpdated => "user.updated",
+            DomainEventType::UserDeactivated => "user.deactivated",
+            DomainEventType::UserStatusChanged => "user.status_changed",
+            DomainEventType::UserRoleChanged => "user.role_changed",
+            DomainEventType::UserLoggedIn => "user.logged_in",
+        }
+    }
+
+    /// The domain event published for an audit event, if any.
+    pub fn from_audit(event_type: AuditEventType) -> Option<Self> {
+        match event_type {
+            AuditEventType::UserRegistered => Some(DomainEventType::UserCreated),
+            AuditEventType::ProfileUpdated
+            | AuditEventType::UserUpdated
+            | AuditEventType::EmailChanged
+            | AuditEventType::EmailChangeReverted => Some(DomainEventType::UserUpdated),
+            AuditEventType::UserDeactivated => Some(DomainEventType::UserDeactivated),
+            AuditEventType::UserStatusChanged => Some(DomainEventType::UserStatusChanged),
+            AuditEventType::RoleAssigned | AuditEventType::RoleUnassigned => {
+                Some(DomainEventType::UserRoleChanged)
+            }
+            AuditEventType::LoginSucceeded => Some(DomainEventType::UserLoggedIn),
+            AuditEventType::LoginFailed
+            | AuditEventType::TokenRefreshed
+            | AuditEventType::Logout
+            | AuditEventType::ImpersonationStarted
+            | AuditEventType::PasswordSet
+            | AuditEventType::EmailChangeRequested
+            | AuditEventType::EmailChangeCancelled
+            | AuditEventType::RoleCreated
+            | AuditEventType::RoleUpdated
+            | AuditEventType::RoleDeleted => None,
+        }
+    }
+}
+
+impl fmt::Display for DomainEventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DomainEventType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        DomainEventType::ALL
+            .iter()
+            .copied()
+            .find(|event_type| event_type.as_str() == value)
+            .ok_or_else(|| format!("invalid event type: {value}"))
+    }
+}
+
+/// Something that happened to a user. `id` is unique per event, so consumers can use it
+/// to drop the duplicates an at-least-once relay may produce.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DomainEvent {
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub event_type: DomainEventType,
+    pub occurred_at: DateTime<Utc>,
+    pub user_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    pub data: Value,
+}
+
+impl DomainEvent {
+    /// Builds the domain event for an audit event that has one.
+    pub fn from_audit(id: Uuid, occurred_at: DateTime<Utc>, event: &NewAuditEvent) -> Option<Self> {
+        let event_type = DomainEventType::from_audit(event.event_type)?;
+
+        let mut data = Map::new();
+        match event.event_type {
+            AuditEventType::RoleAssigned => {
+                data.insert("change".to_string(), Value::from("assigned"));
+            }
+            AuditEventType::RoleUnassigned => {
+                data.insert("change".to_string(), Value::from("unassigned"));
+            }
+            _ => {}
+        }
+        for (key, value) in [
+            ("before", &event.before),
+            ("after", &event.after),
+            ("metadata", &event.metadata),
+        ] {
+            if let Some(value) = value {
+                data.insert(key.to_string(), value.clone());
+            }
+        }
+
+        Some(Self {
+            id,
+            event_type,
+            occurred_at,
+            user_id: event.target_user_id,
+            actor_id: event.actor_id,
+            data: Value::Object(data),
+        })
+    }
+
+    /// A `user.role_changed` event for a change with no audit row of its own, such as a
+    /// grant lapsing or a group edit. `data.change` says what happened.
+    pub fn role_changed(user_id: Uuid, actor_id: Option<Uuid>, data: Value) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            event_type: DomainEventType::UserRoleChanged,
+            occurred_at: Utc::now(),
+            user_id: Some(user_id),
+            actor_id,
+            data,
+        }
+    }
+}
+
+/// An event waiting in the outbox. `position` orders messages by insertion.
+#[derive(Debug, Clone)]
+pub struct OutboxMessage {
+    pub position: i64,
+    pub event: DomainEvent,
+    pub attempts: i32,
+}
+
+#[async_trait]
+pub trait OutboxRepository: Send + Sync {
+    /// Takes up to `limit` unpublished messages, oldest first, and hides them from other
+    /// relays until `lease_until`.
+    async fn claim(
+        &self,
+        limit: i64,
+        lease_until: DateTime<Utc>,
+    ) -> Result<Vec<OutboxMessage>, DomainError>;
+    async fn mark_published(&self, position: i64) -> Result<(), DomainError>;
+    /// Records a failed publish; the message becomes available again at `retry_at`.
+    async fn mark_failed(
+        &self,
+        position: i64,
+        error: &str,
+        retry_at: DateTime<Utc>,
+    ) -> Result<(), DomainError>;
+    /// Up to `limit` events written after the event `event_id`, oldest first, whether
+    /// published yet or not. Empty when `event_id` is unknown.
+    async fn events_after(
+        &self,
+        event_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<DomainEvent>, DomainError>;
+}
+
+/// Destination of relayed domain events. Delivery is at least once: a message whose
+/// publish succeeded may be published again if marking it failed.
+#[async_trait]
+pub trait EventPublisher: Send + Sync {
+    async fn publish(&self, event: &DomainEvent) -> Result<(), DomainError>;
+}
+
+/// Publishes to both, in order; a failure of either fails the publish.
+#[async_trait]
+impl<A, B> EventPublisher for (A, B)
+where
+    A: EventPublisher,
+    B: EventPublisher,
+{
+    async fn publish(&self, event: &DomainEvent) -> Result<(), DomainError> {
+        self.0.publish(event).await?;
+        self.1.publish(event).await
+    }
+}
@@ -0,0 +1,111 @@
+use crate::domain::audit::AuditContext;
+use crate::domain::errors::DomainError;
+use crate::domain::scim::{Filter, FilterValue};
+use crate::domain::user::User;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct Group {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    /// Roles assigned directly to this group; members inherit them transitively.
+    pub roles: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Group attributes a [`GroupFilter`] can test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupField {
+    Id,
+    Name,
+}
+
+/// Boolean expression over group attributes, evaluated in memory with [`Group::field`].
+pub type GroupFilter = Filter<GroupField>;
+
+impl Group {
+    pub fn field(&self, field: GroupField) -> FilterValue {
+        match field {
+            GroupField::Id => FilterValue::String(self.id.to_string()),
+            GroupField::Name => FilterValue::String(self.name.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GroupMembers {
+    pub users: Vec<User>,
+    pub groups: Vec<Group>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewGroup {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UpdateGroup {
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Persistence of groups. Removing a group, a member or a role can take the `admin` role
+/// away, so `delete`, `remove_user`, `remove_group` and `unassign_role` must refuse to
+/// leave no active admin, with `ErrorCode::LastAdmin`. Every method taking an
+/// [`AuditContext`] can change members' effective roles and must publish
+/// `user.role_changed` for those it changes, in the same transaction.
+#[async_trait]
+pub trait GroupRepository: Send + Sync {
+    async fn list(&self) -> Result<Vec<Group>, DomainError>;
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Group>, DomainError>;
+    async fn find_by_name(&self, name: &str) -> Result<Option<Group>, DomainError>;
+    async fn create(&self, input: NewGroup) -> Result<Group, DomainError>;
+    async fn update(&self, id: Uuid, input: UpdateGroup) -> Result<Group, DomainError>;
+    async fn delete(&self, id: Uuid, audit: &AuditContext) -> Result<(), DomainError>;
+    async fn members(&self, id: Uuid) -> Result<GroupMembers, DomainError>;
+    async fn add_user(
+        &self,
+        group_id: Uuid,
+        user_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError>;
+    async fn remove_user(
+        &self,
+        group_id: Uuid,
+        user_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError>;
+    /// Nests `member_group_id` inside `group_id`, refusing with a conflict when the edge
+    /// would close a cycle.
+    async fn add_group(
+        &self,
+        group_id: Uuid,
+        member_group_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError>;
+    async fn remove_group(
+        &self,
+        group_id: Uuid,
+        member_group_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError>;
+    async fn assign_role(
+        &self,
+        group_id: Uuid,
+        role_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError>;
+    async fn unassign_role(
+        &self,
+        group_id: Uuid,
+        role_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError>;
+    /// Direct groups of the user plus every group that transitively contains them.
+    async fn effective_groups(&self, user_id: Uuid) -> Result<Vec<Group>, DomainError>;
+}
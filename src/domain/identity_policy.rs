@@ -0,0 +1,156 @@
+use crate::domain::errors::DomainError;
+use crate::domain::user::User;
+use std::collections::HashSet;
+
+/// Which usernames and email domains new or renamed accounts may use. Usernames are
+/// compared by [`User::username_skeleton`], so look-alikes of a reserved name or a
+/// blocked word are refused too. Domains also cover their subdomains.
+#[derive(Debug, Clone, Default)]
+pub struct IdentityPolicy {
+    reserved_usernames: HashSet<String>,
+    blocked_words: HashSet<String>,
+    blocked_email_domains: HashSet<String>,
+    /// When not empty, the only domains accounts may use.
+    allowed_email_domains: HashSet<String>,
+    disposable_email_domains: HashSet<String>,
+}
+
+impl IdentityPolicy {
+    pub fn new(
+        reserved_usernames: &[String],
+        blocked_words: &[String],
+        blocked_email_domains: &[String],
+        allowed_email_domains: &[String],
+        disposable_email_domains: &[String],
+    ) -> Self {
+        let skeletons = |names: &[String]| {
+            names
+                .iter()
+                .map(|name| User::username_skeleton(name))
+                .filter(|name| !name.is_empty())
+                .collect()
+        };
+        let domains = |domains: &[String]| {
+            domains
+                .iter()
+                .filter_map(|domain| {
+                    idna::domain_to_ascii(domain.trim().trim_start_matches('@')).ok()
+                })
+                .filter(|domain| !domain.is_empty())
+                .collect()
+        };
+
+        Self {
+            reserved_usernames: skeletons(reserved_usernames),
+            blocked_words: skeletons(blocked_words),
+            blocked_email_domains: domains(blocked_email_domains),
+            allowed_email_domains: domains(allowed_email_domains),
+            disposable_email_domains: domains(disposable_email_domains),
+        }
+    }
+
+    pub fn check_username(&self, username: &str) -> Result<(), DomainError> {
+        let skeleton = User::username_skeleton(username);
+        if self.reserved_usernames.contains(&skeleton) {
+            return Err(DomainError::ValidationError(
+                "username is reserved".to_string(),
+            ));
+        }
+        if self
+            .blocked_words
+            .iter()
+            .any(|word| skeleton.contains(word.as_str()))
+        {
+            return Err(DomainError::ValidationError(
+                "username contains a blocked word".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Checks the domain of a normalized email, see [`User::normalize_email`].
+    pub fn check_email(&self, email: &str) -> Result<(), DomainError> {
+        let domain = email.rsplit_once('@').map_or(email, |(_, domain)| domain);
+        if !self.allowed_email_domains.is_empty()
+            && !Self::listed(&self.allowed_email_domains, domain)
+        {
+            return Err(DomainError::ValidationError(
+                "email domain is not allowed".to_string(),
+            ));
+        }
+        if Self::listed(&self.blocked_email_domains, domain) {
+            return Err(DomainError::ValidationError(
+                "email domain is blocked".to_string(),
+            ));
+        }
+        if Self::listed(&self.disposable_email_domains, domain) {
+            return Err(DomainError::ValidationError(
+                "disposable email addresses are not allowed".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Whether `domain` or one of its parent domains is in `domains`.
+    fn listed(domains: &HashSet<String>, domain: &str) -> bool {
+        let mut candidate = domain;
+        loop {
+            if domains.contains(candidate) {
+                return true;
+            }
+            match candidate.split_once('.') {
+                Some((_, parent)) => candidate = parent,
+                None => return false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn reserved_usernames_and_blocked_words_match_look_alikes() {
+        let policy = IdentityPolicy::new(
+            &list(&["admin", "root"]),
+            &list(&["badword"]),
+            &[],
+            &[],
+            &[],
+        );
+
+        for username in ["admin", "Admin", "аdmin", "ROOT", "r00t"] {
+            assert!(policy.check_username(username).is_err(), "{username}");
+        }
+        assert!(policy.check_username("my_badw0rd_99").is_err());
+        assert!(policy.check_username("administrator").is_ok());
+        assert!(policy.check_username("alice").is_ok());
+    }
+
+    #[test]
+    fn email_domains_cover_their_subdomains() {
+        let policy = IdentityPolicy::new(
+            &[],
+            &[],
+            &list(&["blocked.example"]),
+            &[],
+            &list(&["mailinator.com"]),
+        );
+        assert!(policy.check_email("a@blocked.example").is_err());
+        assert!(policy.check_email("a@mx.blocked.example").is_err());
+        assert!(policy.check_email("a@mailinator.com").is_err());
+        assert!(policy.check_email("a@notblocked.example").is_ok());
+
+        let policy = IdentityPolicy::new(&[], &[], &[], &list(&["@Corp.example"]), &[]);
+        assert!(policy.check_email("a@corp.example").is_ok());
+        assert!(policy.check_email("a@eu.corp.example").is_ok());
+        assert!(policy.check_email("a@gmail.com").is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use crate::domain::errors::DomainError;
+use crate::domain::organization::{Membership, OrgRole};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum InvitationStatus {
+    Pending,
+    Expired,
+    Accepted,
+    Revoked,
+}
+
+#[derive(Debug, Clone)]
+pub struct Invitation {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub email: String,
+    pub role: OrgRole,
+    pub invited_by: Option<Uuid>,
+    /// Embedded in the signed link; rotating it invalidates links sent earlier.
+    pub nonce: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub accepted_by: Option<Uuid>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Invitation {
+    pub fn status(&self) -> InvitationStatus {
+        if self.revoked_at.is_some() {
+            InvitationStatus::Revoked
+        } else if self.accepted_at.is_some() {
+            InvitationStatus::Accepted
+        } else if self.expires_at <= Utc::now() {
+            InvitationStatus::Expired
+        } else {
+            InvitationStatus::Pending
+        }
+    }
+
+    /// Open invitations can still be resent or revoked; expired ones included.
+    pub fn is_open(&self) -> bool {
+        self.accepted_at.is_none() && self.revoked_at.is_none()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewInvitation {
+    pub org_id: Uuid,
+    pub email: String,
+    pub role: OrgRole,
+    pub invited_by: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait InvitationRepository: Send + Sync {
+    async fn create(&self, input: NewInvitation) -> Result<Invitation, DomainError>;
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Invitation>, DomainError>;
+    /// The invitation for `email` in `org_id` that is neither accepted nor revoked.
+    async fn find_open(&self, org_id: Uuid, email: &str)
+        -> Result<Option<Invitation>, DomainError>;
+    async fn list_open(&self, org_id: Uuid) -> Result<Vec<Invitation>, DomainError>;
+    /// Extends the expiry and rotates the nonce.
+    async fn renew(&self, id: Uuid, expires_at: DateTime<Utc>) -> Result<Invitation, DomainError>;
+    async fn revoke(&self, id: Uuid) -> Result<(), DomainError>;
+    /// Marks the invitation accepted by `user_id`, adds the membership and marks the
+    /// user's email as verified, all in one transaction.
+    async fn accept(&self, id: Uuid, nonce: Uuid, user_id: Uuid)
+        -> Result<Membership, DomainError>;
+}
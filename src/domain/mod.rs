@@ -1,7 +1,54 @@
+pub mod account_status;
+pub mod attribute;
+pub mod audit;
+pub mod elevation;
+pub mod email_change;
 pub mod errors;
+pub mod event;
+pub mod group;
+pub mod identity_policy;
+pub mod invitation;
+pub mod organization;
+pub mod pagination;
+pub mod permission;
 pub mod role;
+pub mod scim;
 pub mod user;
+pub mod webhook;
 
-pub use errors::DomainError;
-pub use role::Role;
-pub use user::{AdminUpdateUser, NewUser, UpdateProfile, User, UserRepository, UserWithPassword};
\ No newline at end of file
+pub use account_status::{AccountState, AccountStatus, StatusChange};
+pub use attribute::{
+    AttributeDefinition, AttributeDefinitionRepository, AttributeFilter, AttributeType, Attributes,
+    NewAttributeDefinition, UpdateAttributeDefinition,
+};
+pub use audit::{
+    ActivityEntry, AuditContext, AuditEvent, AuditEventType, AuditQuery, AuditRepository,
+    AuditSink, NewAuditEvent,
+};
+pub use elevation::{ElevationRepository, ElevationRequest, ElevationStatus, NewElevationRequest};
+pub use email_change::{EmailChange, EmailChangeRepository, NewEmailChange};
+pub use errors::{DomainError, ErrorCode};
+pub use event::{DomainEvent, DomainEventType, EventPublisher, OutboxMessage, OutboxRepository};
+pub use group::{
+    Group, GroupField, GroupFilter, GroupMembers, GroupRepository, NewGroup, UpdateGroup,
+};
+pub use identity_policy::IdentityPolicy;
+pub use invitation::{Invitation, InvitationRepository, InvitationStatus, NewInvitation};
+pub use organization::{
+    Member, Membership, NewOrganization, OrgRole, Organization, OrganizationRepository,
+    UpdateOrganization,
+};
+pub use pagination::{Cursor, Page};
+pub use permission::Permission;
+pub use role::{
+    NewRole, Role, RoleAssignmentAction, RoleAssignmentEvent, RoleRepository, UpdateRole,
+};
+pub use user::{
+    AdminUpdateUser, BulkUserAction, BulkUserOutcome, BulkUserResult, ExistingIdentities, NewUser,
+    UpdateProfile, User, UserField, UserFilter, UserPaging, UserProfile, UserQuery, UserRepository,
+    UserSort, UserSortField, UserWithPassword,
+};
+pub use webhook::{
+    DeliveryAttempt, DeliveryStatus, NewWebhookEndpoint, UpdateWebhookEndpoint, WebhookDelivery,
+    WebhookEndpoint, WebhookRepository,
+};
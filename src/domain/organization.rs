@@ -0,0 +1,152 @@
+use crate::domain::errors::DomainError;
+use crate::domain::user::User;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct Organization {
+    pub id: Uuid,
+    pub name: String,
+    pub slug: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Organization {
+    pub fn is_valid_slug(slug: &str) -> bool {
+        (2..=48).contains(&slug.len())
+            && slug
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+            && !slug.starts_with('-')
+            && !slug.ends_with('-')
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OrgRole {
+    Member,
+    Admin,
+    Owner,
+}
+
+impl OrgRole {
+    fn rank(&self) -> u8 {
+        match self {
+            OrgRole::Member => 0,
+            OrgRole::Admin => 1,
+            OrgRole::Owner => 2,
+        }
+    }
+
+    pub fn at_least(&self, other: OrgRole) -> bool {
+        self.rank() >= other.rank()
+    }
+
+    pub fn can_manage_members(&self) -> bool {
+        self.at_least(OrgRole::Admin)
+    }
+}
+
+impl fmt::Display for OrgRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrgRole::Member => write!(f, "member"),
+            OrgRole::Admin => write!(f, "admin"),
+            OrgRole::Owner => write!(f, "owner"),
+        }
+    }
+}
+
+impl FromStr for OrgRole {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "member" => Ok(OrgRole::Member),
+            "admin" => Ok(OrgRole::Admin),
+            "owner" => Ok(OrgRole::Owner),
+            _ => Err(format!("invalid organization role: {value}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Membership {
+    pub org_id: Uuid,
+    pub user_id: Uuid,
+    pub role: OrgRole,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Member {
+    pub user: User,
+    pub role: OrgRole,
+    pub joined_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewOrganization {
+    pub name: String,
+    pub slug: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UpdateOrganization {
+    pub name: Option<String>,
+    pub slug: Option<String>,
+}
+
+#[async_trait]
+pub trait OrganizationRepository: Send + Sync {
+    /// Creates the organization and makes `owner_id` its first owner.
+    async fn create(
+        &self,
+        input: NewOrganization,
+        owner_id: Uuid,
+    ) -> Result<Organization, DomainError>;
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Organization>, DomainError>;
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<Organization>, DomainError>;
+    async fn list_for_user(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<(Organization, OrgRole)>, DomainError>;
+    async fn update(
+        &self,
+        id: Uuid,
+        input: UpdateOrganization,
+    ) -> Result<Organization, DomainError>;
+    async fn delete(&self, id: Uuid) -> Result<(), DomainError>;
+    async fn find_membership(
+        &self,
+        org_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<Membership>, DomainError>;
+    async fn add_member(
+        &self,
+        org_id: Uuid,
+        user_id: Uuid,
+        role: OrgRole,
+    ) -> Result<Membership, DomainError>;
+    async fn set_member_role(
+        &self,
+        org_id: Uuid,
+        user_id: Uuid,
+        role: OrgRole,
+    ) -> Result<Membership, DomainError>;
+    async fn remove_member(&self, org_id: Uuid, user_id: Uuid) -> Result<(), DomainError>;
+    async fn count_owners(&self, org_id: Uuid) -> Result<i64, DomainError>;
+    async fn list_members(
+        &self,
+        org_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Member>, DomainError>;
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, utoipa::ToSchema)]
+pub enum Permission {
+    #[serde(rename = "users:read")]
+    UsersRead,
+    #[serde(rename = "users:write")]
+    UsersWrite,
+    #[serde(rename = "users:deactivate")]
+    UsersDeactivate,
+    #[serde(rename = "users:impersonate")]
+    UsersImpersonate,
+    #[serde(rename = "roles:read")]
+    RolesRead,
+    #[serde(rename = "roles:write")]
+    RolesWrite,
+    #[serde(rename = "roles:assign")]
+    RolesAssign,
+    #[serde(rename = "orgs:manage")]
+    OrgsManage,
+    #[serde(rename = "groups:read")]
+    GroupsRead,
+    #[serde(rename = "groups:write")]
+    GroupsWrite,
+    #[serde(rename = "audit:read")]
+    AuditRead,
+    #[serde(rename = "webhooks:manage")]
+    WebhooksManage,
+    #[serde(rename = "attributes:manage")]
+    AttributesManage,
+}
+
+impl Permission {
+    pub const ALL: &'static [Permission] = &[
+        Permission::UsersRead,
+        Permission::UsersWrite,
+        Permission::UsersDeactivate,
+        Permission::UsersImpersonate,
+        Permission::RolesRead,
+        Permission::RolesWrite,
+        Permission::RolesAssign,
+        Permission::OrgsManage,
+        Permission::GroupsRead,
+        Permission::GroupsWrite,
+        Permission::AuditRead,
+        Permission::WebhooksManage,
+        Permission::AttributesManage,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::UsersRead => "users:read",
+            Permission::UsersWrite => "users:write",
+            Permission::UsersDeactivate => "users:deactivate",
+            Permission::UsersImpersonate => "users:impersonate",
+            Permission::RolesRead => "roles:read",
+            Permission::RolesWrite => "roles:write",
+            Permission::RolesAssign => "roles:assign",
+            Permission::OrgsManage => "orgs:manage",
+            Permission::GroupsRead => "groups:read",
+            Permission::GroupsWrite => "groups:write",
+            Permission::AuditRead => "audit:read",
+            Permission::WebhooksManage => "webhooks:manage",
+            Permission::AttributesManage => "attributes:manage",
+        }
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Permission {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Permission::ALL
+            .iter()
+            .copied()
+            .find(|permission| permission.as_str() == value)
+            .ok_or_else(|| format!("invalid permission: {value}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permission_string_round_trip() {
+        for permission in Permission::ALL {
+            assert_eq!(Permission::from_str(permission.as_str()), Ok(*permission));
+        }
+        assert!(Permission::from_str("users:delete").is_err());
+    }
+}
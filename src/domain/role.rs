@@ -1,37 +1,150 @@
+use crate::domain::audit::AuditContext;
+use crate::domain::errors::{DomainError, ErrorCode};
+use crate::domain::permission::Permission;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
+use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, utoipa::ToSchema)]
-#[serde(rename_all = "lowercase")]
-pub enum Role {
-    User,
-    Admin,
+#[derive(Debug, Clone)]
+pub struct Role {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub is_builtin: bool,
+    pub permissions: Vec<Permission>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
 impl Role {
-    pub fn can_manage_users(&self) -> bool {
-        matches!(self, Role::Admin)
+    pub const USER: &'static str = "user";
+    pub const ADMIN: &'static str = "admin";
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions.contains(&permission)
+    }
+
+    /// Role names are referenced from code and configuration, so they are kept to a
+    /// conservative `[a-z0-9_-]` alphabet.
+    pub fn is_valid_name(name: &str) -> bool {
+        (2..=32).contains(&name.len())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+    }
+
+    /// The last-admin invariant, given the active admins before and after a change.
+    /// Deployments that have not bootstrapped an admin yet are not affected.
+    pub fn ensure_admin_remains(admins_before: i64, admins_after: i64) -> Result<(), DomainError> {
+        if admins_before > 0 && admins_after == 0 {
+            return Err(DomainError::Invariant(
+                ErrorCode::LastAdmin,
+                "at least one active administrator must remain".to_string(),
+            ));
+        }
+
+        Ok(())
     }
 }
 
-impl fmt::Display for Role {
+#[derive(Debug, Clone)]
+pub struct NewRole {
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Vec<Permission>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UpdateRole {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub permissions: Option<Vec<Permission>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RoleAssignmentAction {
+    Assigned,
+    Unassigned,
+    /// A time-bound grant ran out and was swept.
+    Expired,
+}
+
+impl fmt::Display for RoleAssignmentAction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Role::User => write!(f, "user"),
-            Role::Admin => write!(f, "admin"),
+            RoleAssignmentAction::Assigned => write!(f, "assigned"),
+            RoleAssignmentAction::Unassigned => write!(f, "unassigned"),
+            RoleAssignmentAction::Expired => write!(f, "expired"),
         }
     }
 }
 
-impl FromStr for Role {
+impl FromStr for RoleAssignmentAction {
     type Err = String;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
         match value {
-            "user" => Ok(Role::User),
-            "admin" => Ok(Role::Admin),
-            _ => Err(format!("invalid role: {value}")),
+            "assigned" => Ok(RoleAssignmentAction::Assigned),
+            "unassigned" => Ok(RoleAssignmentAction::Unassigned),
+            "expired" => Ok(RoleAssignmentAction::Expired),
+            _ => Err(format!("invalid role assignment action: {value}")),
         }
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct RoleAssignmentEvent {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub role_id: Uuid,
+    pub role_name: String,
+    pub action: RoleAssignmentAction,
+    pub actor_id: Option<Uuid>,
+    /// End of the grant for time-bound assignments.
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait RoleRepository: Send + Sync {
+    async fn list(&self) -> Result<Vec<Role>, DomainError>;
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Role>, DomainError>;
+    async fn find_by_name(&self, name: &str) -> Result<Option<Role>, DomainError>;
+    async fn create(&self, new_role: NewRole, audit: &AuditContext) -> Result<Role, DomainError>;
+    async fn update(
+        &self,
+        id: Uuid,
+        input: UpdateRole,
+        audit: &AuditContext,
+    ) -> Result<Role, DomainError>;
+    /// Deletes the role and records an `unassigned` event for every holder.
+    async fn delete(&self, id: Uuid, audit: &AuditContext) -> Result<(), DomainError>;
+    /// Grants the role, until `expires_at` when given. Returns `false` when the user
+    /// already held it permanently. Granting an already time-bound role keeps the later
+    /// expiry, or makes it permanent.
+    async fn assign(
+        &self,
+        user_id: Uuid,
+        role_id: Uuid,
+        expires_at: Option<DateTime<Utc>>,
+        audit: &AuditContext,
+    ) -> Result<bool, DomainError>;
+    /// Revokes the grant; leaving no active admin is refused with `ErrorCode::LastAdmin`.
+    async fn unassign(
+        &self,
+        user_id: Uuid,
+        role_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError>;
+    async fn assignment_history(
+        &self,
+        role_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<RoleAssignmentEvent>, DomainError>;
+    /// Removes every expired time-bound grant, recording an `expired` event for each.
+    async fn sweep_expired(&self) -> Result<u64, DomainError>;
+}
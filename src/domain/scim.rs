@@ -0,0 +1,607 @@
+//! Protocol pieces of SCIM 2.0 that do not depend on HTTP: the filter grammar of
+//! RFC 7644 §3.4.2.2 and the operations of a PATCH request (§3.5.2).
+
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    /// Contains.
+    Co,
+    /// Starts with.
+    Sw,
+    /// Ends with.
+    Ew,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl CompareOp {
+    fn parse(word: &str) -> Option<Self> {
+        let op = match word.to_ascii_lowercase().as_str() {
+            "eq" => CompareOp::Eq,
+            "ne" => CompareOp::Ne,
+            "co" => CompareOp::Co,
+            "sw" => CompareOp::Sw,
+            "ew" => CompareOp::Ew,
+            "gt" => CompareOp::Gt,
+            "ge" => CompareOp::Ge,
+            "lt" => CompareOp::Lt,
+            "le" => CompareOp::Le,
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    pub fn is_substring(self) -> bool {
+        matches!(self, CompareOp::Co | CompareOp::Sw | CompareOp::Ew)
+    }
+
+    pub fn is_ordering(self) -> bool {
+        matches!(
+            self,
+            CompareOp::Gt | CompareOp::Ge | CompareOp::Lt | CompareOp::Le
+        )
+    }
+}
+
+/// Right-hand side of a comparison.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterValue {
+    String(String),
+    Bool(bool),
+    Number(f64),
+    Null,
+}
+
+/// Boolean expression over attributes of type `A`: attribute paths as parsed from a SCIM
+/// filter, or the fields a repository knows how to query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter<A> {
+    Compare(A, CompareOp, FilterValue),
+    /// The attribute has a value (`pr`).
+    Present(A),
+    And(Box<Filter<A>>, Box<Filter<A>>),
+    Or(Box<Filter<A>>, Box<Filter<A>>),
+    Not(Box<Filter<A>>),
+}
+
+impl<A> Filter<A> {
+    /// Replaces every attribute. `resolve` also sees the comparison (`None` for `pr`), so
+    /// it can refuse operators or values the attribute does not support.
+    pub fn try_map<B, E, F>(self, resolve: &mut F) -> Result<Filter<B>, E>
+    where
+        F: FnMut(A, Option<(CompareOp, &FilterValue)>) -> Result<B, E>,
+    {
+        Ok(match self {
+            Filter::Compare(attr, op, value) => {
+                let attr = resolve(attr, Some((op, &value)))?;
+                Filter::Compare(attr, op, value)
+            }
+            Filter::Present(attr) => Filter::Present(resolve(attr, None)?),
+            Filter::And(left, right) => Filter::And(
+                Box::new(left.try_map(resolve)?),
+                Box::new(right.try_map(resolve)?),
+            ),
+            Filter::Or(left, right) => Filter::Or(
+                Box::new(left.try_map(resolve)?),
+                Box::new(right.try_map(resolve)?),
+            ),
+            Filter::Not(inner) => Filter::Not(Box::new(inner.try_map(resolve)?)),
+        })
+    }
+
+    /// Evaluates the filter in memory. `value_of` returns the attribute's value, or `None`
+    /// when it is unassigned. Strings compare case-insensitively.
+    pub fn matches<F>(&self, value_of: &F) -> bool
+    where
+        F: Fn(&A) -> Option<FilterValue>,
+    {
+        match self {
+            Filter::Compare(attr, op, expected) => compare(value_of(attr), *op, expected),
+            Filter::Present(attr) => !matches!(value_of(attr), None | Some(FilterValue::Null)),
+            Filter::And(left, right) => left.matches(value_of) && right.matches(value_of),
+            Filter::Or(left, right) => left.matches(value_of) || right.matches(value_of),
+            Filter::Not(inner) => !inner.matches(value_of),
+        }
+    }
+}
+
+fn compare(actual: Option<FilterValue>, op: CompareOp, expected: &FilterValue) -> bool {
+    let actual = actual.unwrap_or(FilterValue::Null);
+    if op == CompareOp::Ne {
+        return !compare(Some(actual), CompareOp::Eq, expected);
+    }
+
+    let ordering = match (&actual, expected) {
+        (FilterValue::String(actual), FilterValue::String(expected)) => {
+            let (actual, expected) = (actual.to_lowercase(), expected.to_lowercase());
+            match op {
+                CompareOp::Co => return actual.contains(&expected),
+                CompareOp::Sw => return actual.starts_with(&expected),
+                CompareOp::Ew => return actual.ends_with(&expected),
+                _ => actual.cmp(&expected),
+            }
+        }
+        (FilterValue::Number(actual), FilterValue::Number(expected)) if !op.is_substring() => {
+            match actual.partial_cmp(expected) {
+                Some(ordering) => ordering,
+                None => return false,
+            }
+        }
+        (FilterValue::Bool(actual), FilterValue::Bool(expected)) if op == CompareOp::Eq => {
+            return actual == expected;
+        }
+        (FilterValue::Null, FilterValue::Null) if op == CompareOp::Eq => return true,
+        _ => return false,
+    };
+
+    match op {
+        CompareOp::Eq => ordering == Ordering::Equal,
+        CompareOp::Gt => ordering == Ordering::Greater,
+        CompareOp::Ge => ordering != Ordering::Less,
+        CompareOp::Lt => ordering == Ordering::Less,
+        CompareOp::Le => ordering != Ordering::Greater,
+        _ => false,
+    }
+}
+
+/// An attribute reference such as `userName`, `name.givenName` or, inside
+/// `emails[type eq "work"]`, `emails.type`. Names are lowercased, since SCIM attribute
+/// names are case-insensitive, and a leading schema URN is dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttrPath {
+    pub attr: String,
+    pub sub_attr: Option<String>,
+}
+
+impl AttrPath {
+    fn parse(raw: &str, parent: Option<&str>) -> Result<Self, String> {
+        let name = if raw.to_ascii_lowercase().starts_with("urn:") {
+            raw.rsplit_once(':').map_or(raw, |(_, name)| name)
+        } else {
+            raw
+        };
+        let name = name.to_ascii_lowercase();
+        let valid = |part: &str| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '$')
+        };
+
+        let (attr, sub_attr) = match name.split_once('.') {
+            Some((attr, sub_attr)) => (attr.to_string(), Some(sub_attr.to_string())),
+            None => (name, None),
+        };
+        if !valid(&attr) || !sub_attr.as_deref().is_none_or(valid) {
+            return Err(format!("invalid attribute path: {raw}"));
+        }
+
+        match parent {
+            Some(_) if sub_attr.is_some() => Err(format!("invalid attribute path: {raw}")),
+            Some(parent) => Ok(Self {
+                attr: parent.to_string(),
+                sub_attr: Some(attr),
+            }),
+            None => Ok(Self { attr, sub_attr }),
+        }
+    }
+
+    /// Whether this is `attr`, or `attr.sub_attr` when `sub_attr` is given.
+    pub fn is(&self, attr: &str, sub_attr: Option<&str>) -> bool {
+        self.attr == attr && self.sub_attr.as_deref() == sub_attr
+    }
+}
+
+/// Target of a PATCH operation: `attr`, `attr.sub`, `attr[filter]` or `attr[filter].sub`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchPath {
+    pub attr: String,
+    /// Selects values of a multi-valued attribute; its paths are `attr.<sub>`.
+    pub filter: Option<Filter<AttrPath>>,
+    pub sub_attr: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Str(String),
+    Open,
+    Close,
+    OpenBracket,
+    CloseBracket,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' | '[' | ']' => {
+                chars.next();
+                tokens.push(match c {
+                    '(' => Token::Open,
+                    ')' => Token::Close,
+                    '[' => Token::OpenBracket,
+                    _ => Token::CloseBracket,
+                });
+            }
+            '"' => {
+                // Strings are JSON strings, escapes included.
+                chars.next();
+                let mut escaped = false;
+                let end = loop {
+                    match chars.next() {
+                        Some((index, '"')) if !escaped => break index,
+                        Some((_, '\\')) if !escaped => escaped = true,
+                        Some(_) => escaped = false,
+                        None => return Err("unterminated string".to_string()),
+                    }
+                };
+                let value = serde_json::from_str::<String>(&input[start..=end])
+                    .map_err(|_| format!("invalid string: {}", &input[start..=end]))?;
+                tokens.push(Token::Str(value));
+            }
+            _ => {
+                let mut end = start;
+                while let Some(&(index, c)) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '(' | ')' | '[' | ']' | '"') {
+                        break;
+                    }
+                    end = index + c.len_utf8();
+                    chars.next();
+                }
+                tokens.push(Token::Word(input[start..end].to_string()));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    /// Attribute of the enclosing `attr[...]` value filter.
+    parent: Option<String>,
+}
+
+impl Parser {
+    fn new(input: &str) -> Result<Self, String> {
+        Ok(Self {
+            tokens: tokenize(input)?,
+            position: 0,
+            parent: None,
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn expect(&mut self, expected: Token, description: &str) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            _ => Err(format!("expected {description}")),
+        }
+    }
+
+    fn finish(&self) -> Result<(), String> {
+        match self.peek() {
+            None => Ok(()),
+            Some(token) => Err(format!("unexpected {token:?}")),
+        }
+    }
+
+    /// `or` binds loosest, then `and`, then `not`.
+    fn parse_or(&mut self) -> Result<Filter<AttrPath>, String> {
+        let mut filter = self.parse_and()?;
+        while self.peek_keyword("or") {
+            self.next();
+            filter = Filter::Or(Box::new(filter), Box::new(self.parse_and()?));
+        }
+        Ok(filter)
+    }
+
+    fn parse_and(&mut self) -> Result<Filter<AttrPath>, String> {
+        let mut filter = self.parse_unary()?;
+        while self.peek_keyword("and") {
+            self.next();
+            filter = Filter::And(Box::new(filter), Box::new(self.parse_unary()?));
+        }
+        Ok(filter)
+    }
+
+    fn parse_unary(&mut self) -> Result<Filter<AttrPath>, String> {
+        if self.peek_keyword("not") {
+            self.next();
+            self.expect(Token::Open, "`(` after `not`")?;
+            let inner = self.parse_or()?;
+            self.expect(Token::Close, "`)`")?;
+            return Ok(Filter::Not(Box::new(inner)));
+        }
+        if self.peek() == Some(&Token::Open) {
+            self.next();
+            let inner = self.parse_or()?;
+            self.expect(Token::Close, "`)`")?;
+            return Ok(inner);
+        }
+        self.parse_attr_expr()
+    }
+
+    fn parse_attr_expr(&mut self) -> Result<Filter<AttrPath>, String> {
+        let Some(Token::Word(raw)) = self.next() else {
+            return Err("expected an attribute".to_string());
+        };
+
+        if self.peek() == Some(&Token::OpenBracket) {
+            let inner = self.parse_value_filter(&raw)?;
+            return Ok(inner);
+        }
+
+        let path = AttrPath::parse(&raw, self.parent.as_deref())?;
+        let Some(Token::Word(word)) = self.next() else {
+            return Err(format!("expected an operator after {raw}"));
+        };
+        if word.eq_ignore_ascii_case("pr") {
+            return Ok(Filter::Present(path));
+        }
+        let op = CompareOp::parse(&word).ok_or_else(|| format!("unknown operator: {word}"))?;
+
+        let value = match self.next() {
+            Some(Token::Str(value)) => FilterValue::String(value),
+            Some(Token::Word(word)) => match word.as_str() {
+                "true" => FilterValue::Bool(true),
+                "false" => FilterValue::Bool(false),
+                "null" => FilterValue::Null,
+                number => FilterValue::Number(
+                    number
+                        .parse()
+                        .map_err(|_| format!("invalid value: {number}"))?,
+                ),
+            },
+            _ => return Err(format!("expected a value after {word}")),
+        };
+
+        Ok(Filter::Compare(path, op, value))
+    }
+
+    /// `attr[filter]`, whose paths become sub-attributes of `attr`.
+    fn parse_value_filter(&mut self, raw: &str) -> Result<Filter<AttrPath>, String> {
+        if self.parent.is_some() {
+            return Err("value filters cannot be nested".to_string());
+        }
+        let parent = AttrPath::parse(raw, None)?;
+        if parent.sub_attr.is_some() {
+            return Err(format!("invalid attribute path: {raw}"));
+        }
+
+        self.expect(Token::OpenBracket, "`[`")?;
+        self.parent = Some(parent.attr);
+        let inner = self.parse_or();
+        self.parent = None;
+        let inner = inner?;
+        self.expect(Token::CloseBracket, "`]`")?;
+        Ok(inner)
+    }
+}
+
+/// Parses a `filter` query parameter.
+pub fn parse_filter(input: &str) -> Result<Filter<AttrPath>, String> {
+    let mut parser = Parser::new(input)?;
+    let filter = parser.parse_or()?;
+    parser.finish()?;
+    Ok(filter)
+}
+
+/// Parses the `path` of a PATCH operation.
+pub fn parse_patch_path(input: &str) -> Result<PatchPath, String> {
+    let mut parser = Parser::new(input)?;
+    let Some(Token::Word(raw)) = parser.next() else {
+        return Err("expected an attribute".to_string());
+    };
+
+    if parser.peek() != Some(&Token::OpenBracket) {
+        parser.finish()?;
+        let path = AttrPath::parse(&raw, None)?;
+        return Ok(PatchPath {
+            attr: path.attr,
+            filter: None,
+            sub_attr: path.sub_attr,
+        });
+    }
+
+    let filter = parser.parse_value_filter(&raw)?;
+    let sub_attr = match parser.next() {
+        Some(Token::Word(rest)) => {
+            let sub_attr = rest
+                .strip_prefix('.')
+                .ok_or_else(|| format!("unexpected {rest}"))?;
+            Some(AttrPath::parse(sub_attr, None)?.attr)
+        }
+        Some(token) => return Err(format!("unexpected {token:?}")),
+        None => None,
+    };
+    parser.finish()?;
+
+    Ok(PatchPath {
+        attr: AttrPath::parse(&raw, None)?.attr,
+        filter: Some(filter),
+        sub_attr,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchOp {
+    Add,
+    Remove,
+    Replace,
+}
+
+impl<'de> Deserialize<'de> for PatchOp {
+    // Some providers capitalize operations (`"Replace"`), so they are matched loosely.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let op = String::deserialize(deserializer)?;
+        match op.to_ascii_lowercase().as_str() {
+            "add" => Ok(PatchOp::Add),
+            "remove" => Ok(PatchOp::Remove),
+            "replace" => Ok(PatchOp::Replace),
+            _ => Err(serde::de::Error::custom(format!(
+                "unknown patch operation: {op}"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PatchOperation {
+    pub op: PatchOp,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub value: Option<Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(attr: &str, sub_attr: Option<&str>) -> AttrPath {
+        AttrPath {
+            attr: attr.to_string(),
+            sub_attr: sub_attr.map(str::to_string),
+        }
+    }
+
+    fn string(value: &str) -> FilterValue {
+        FilterValue::String(value.to_string())
+    }
+
+    #[test]
+    fn parses_precedence_and_value_filters() {
+        let filter = parse_filter(
+            r#"userName eq "bjensen" or not (active eq false) and emails[type eq "work" and value co "@example.com"]"#,
+        )
+        .unwrap();
+
+        let expected = Filter::Or(
+            Box::new(Filter::Compare(
+                path("username", None),
+                CompareOp::Eq,
+                string("bjensen"),
+            )),
+            Box::new(Filter::And(
+                Box::new(Filter::Not(Box::new(Filter::Compare(
+                    path("active", None),
+                    CompareOp::Eq,
+                    FilterValue::Bool(false),
+                )))),
+                Box::new(Filter::And(
+                    Box::new(Filter::Compare(
+                        path("emails", Some("type")),
+                        CompareOp::Eq,
+                        string("work"),
+                    )),
+                    Box::new(Filter::Compare(
+                        path("emails", Some("value")),
+                        CompareOp::Co,
+                        string("@example.com"),
+                    )),
+                )),
+            )),
+        );
+        assert_eq!(filter, expected);
+
+        let filter =
+            parse_filter(r#"urn:ietf:params:scim:schemas:core:2.0:User:userName EQ "a \"b\"" "#)
+                .unwrap();
+        assert_eq!(
+            filter,
+            Filter::Compare(path("username", None), CompareOp::Eq, string("a \"b\""))
+        );
+        assert_eq!(
+            parse_filter("meta.lastModified pr").unwrap(),
+            Filter::Present(path("meta", Some("lastmodified")))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_filters() {
+        for input in [
+            "",
+            "userName",
+            r#"userName xx "a""#,
+            r#"userName eq "a" and"#,
+            r#"(userName eq "a""#,
+            r#"userName eq "a" extra"#,
+            r#"emails[value eq "a""#,
+            r#"emails[type[value eq "a"]]"#,
+            "userName eq bare",
+        ] {
+            assert!(parse_filter(input).is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn parses_patch_paths() {
+        let parsed = parse_patch_path(r#"emails[type eq "work"].value"#).unwrap();
+        assert_eq!(parsed.attr, "emails");
+        assert_eq!(parsed.sub_attr.as_deref(), Some("value"));
+        assert_eq!(
+            parsed.filter,
+            Some(Filter::Compare(
+                path("emails", Some("type")),
+                CompareOp::Eq,
+                string("work")
+            ))
+        );
+
+        let parsed = parse_patch_path("name.givenName").unwrap();
+        assert_eq!(
+            (parsed.attr.as_str(), parsed.sub_attr.as_deref()),
+            ("name", Some("givenname"))
+        );
+        assert!(parse_patch_path(r#"members[value eq "x"] extra"#).is_err());
+    }
+
+    #[test]
+    fn evaluates_in_memory() {
+        let filter = parse_filter(r#"displayName sw "eng" and not (id eq "2")"#).unwrap();
+        let value_of = |id: &'static str| {
+            move |path: &AttrPath| match path.attr.as_str() {
+                "displayname" => Some(string("Engineering")),
+                "id" => Some(string(id)),
+                _ => None,
+            }
+        };
+
+        assert!(filter.matches(&value_of("1")));
+        assert!(!filter.matches(&value_of("2")));
+        assert!(parse_filter("externalId eq null")
+            .unwrap()
+            .matches(&value_of("1")));
+        assert!(!parse_filter("externalId pr")
+            .unwrap()
+            .matches(&value_of("1")));
+    }
+}
@@ -1,23 +1,171 @@
+use crate::domain::account_status::{AccountState, AccountStatus, StatusChange};
+use crate::domain::attribute::{AttributeFilter, Attributes};
+use crate::domain::audit::AuditContext;
 use crate::domain::errors::DomainError;
-use crate::domain::role::Role;
+use crate::domain::pagination::{Cursor, Page};
+use crate::domain::permission::Permission;
+use crate::domain::scim::Filter;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
 use uuid::Uuid;
 
+/// Look-alike characters folded by [`User::username_skeleton`], after diacritics are
+/// stripped and the username is lowercased, so the table only lists lowercase forms.
+/// The backfill in the `20250216000000_normalize_identities` migration mirrors it.
+const CONFUSABLES: &[(char, char)] = &[
+    ('0', 'o'),
+    ('1', 'l'),
+    ('а', 'a'),
+    ('е', 'e'),
+    ('о', 'o'),
+    ('р', 'p'),
+    ('с', 'c'),
+    ('у', 'y'),
+    ('х', 'x'),
+    ('і', 'i'),
+    ('ј', 'j'),
+    ('ѕ', 's'),
+    ('ԁ', 'd'),
+    ('һ', 'h'),
+    ('ӏ', 'l'),
+    ('α', 'a'),
+    ('ι', 'i'),
+    ('κ', 'k'),
+    ('ν', 'v'),
+    ('ο', 'o'),
+    ('ρ', 'p'),
+];
+
 #[derive(Debug, Clone)]
 pub struct User {
     pub id: Uuid,
     pub email: String,
     pub username: String,
-    pub role: Role,
-    pub is_active: bool,
+    pub roles: Vec<String>,
+    pub permissions: Vec<Permission>,
+    /// Effective group names, including groups inherited through nesting.
+    pub groups: Vec<String>,
+    /// Earliest expiry among the user's time-bound role grants, if any.
+    pub roles_expire_at: Option<DateTime<Utc>>,
+    pub status: AccountStatus,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    /// Address of a requested email change that still awaits confirmation.
+    pub pending_email: Option<String>,
+    pub profile: UserProfile,
+    /// Custom attributes, validated against their definitions on write.
+    pub attributes: Attributes,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 impl User {
+    /// Whether the account is active right now, counting expired suspensions as over.
     pub fn is_active(&self) -> bool {
-        self.is_active
+        self.status.effective_state(Utc::now()) == AccountState::Active
+    }
+
+    pub fn is_email_verified(&self) -> bool {
+        self.email_verified_at.is_some()
+    }
+
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|name| name == role)
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions.contains(&permission)
+    }
+
+    pub fn cursor(&self) -> Cursor {
+        Cursor {
+            created_at: self.created_at,
+            id: self.id,
+        }
+    }
+
+    /// Canonical form of an email address as it is stored and compared: trimmed, the
+    /// local part lowercased and the domain converted to lowercase ASCII (IDNA).
+    pub fn normalize_email(email: &str) -> Result<String, DomainError> {
+        let invalid = || DomainError::ValidationError("invalid email".to_string());
+        let (local, domain) = email.trim().rsplit_once('@').ok_or_else(invalid)?;
+        if local.is_empty() || domain.is_empty() {
+            return Err(invalid());
+        }
+        let domain = idna::domain_to_ascii(domain).map_err(|_| invalid())?;
+
+        Ok(format!("{}@{domain}", local.to_lowercase()))
+    }
+
+    /// Canonical form of a username as it is stored: trimmed and in Unicode NFKC, so
+    /// compatibility variants such as full-width letters collapse to their plain form.
+    /// Case is kept for display; uniqueness goes by [`User::username_skeleton`].
+    pub fn normalize_username(username: &str) -> String {
+        username.trim().nfkc().collect()
+    }
+
+    /// Key under which usernames must be unique: diacritics stripped, look-alike
+    /// characters (digits, Cyrillic and Greek homoglyphs) folded and lowercased, so that
+    /// `Alice`, `alice` and `аlice` with a Cyrillic `а` cannot all be registered.
+    pub fn username_skeleton(username: &str) -> String {
+        username
+            .trim()
+            .nfkd()
+            .filter(|c| !is_combining_mark(*c))
+            .flat_map(char::to_lowercase)
+            .map(|c| {
+                CONFUSABLES
+                    .iter()
+                    .find(|(confusable, _)| *confusable == c)
+                    .map_or(c, |(_, plain)| *plain)
+            })
+            .collect()
+    }
+}
+
+/// Optional details a user keeps about themselves. The API exposes them under the
+/// standard OpenID Connect claim names given below.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserProfile {
+    /// `name`.
+    pub display_name: Option<String>,
+    /// `given_name`.
+    pub given_name: Option<String>,
+    /// `family_name`.
+    pub family_name: Option<String>,
+    /// `locale`, a BCP 47 language tag such as `en-US`.
+    pub locale: Option<String>,
+    /// `zoneinfo`, an IANA time zone such as `Europe/Paris`.
+    pub timezone: Option<String>,
+    /// `picture`.
+    pub avatar_url: Option<String>,
+}
+
+impl UserProfile {
+    /// Whether `tag` is a well-formed BCP 47 language tag: a 2-3 letter language (or a
+    /// registered 5-8 letter one) followed by subtags of 1-8 letters or digits.
+    pub fn is_valid_locale(tag: &str) -> bool {
+        let mut subtags = tag.split('-');
+        let language_ok = subtags.next().is_some_and(|language| {
+            matches!(language.len(), 2..=3 | 5..=8)
+                && language.chars().all(|c| c.is_ascii_alphabetic())
+        });
+
+        tag.len() <= 35
+            && language_ok
+            && subtags.all(|subtag| {
+                (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+            })
+    }
+
+    /// Whether `name` is a time zone of the IANA database, e.g. `America/New_York` or `UTC`.
+    pub fn is_valid_timezone(name: &str) -> bool {
+        name.parse::<chrono_tz::Tz>().is_ok()
     }
 }
 
@@ -32,33 +180,319 @@ pub struct NewUser {
     pub email: String,
     pub username: String,
     pub password_hash: String,
-    pub role: Role,
-    pub is_active: bool,
+    pub roles: Vec<String>,
+    pub state: AccountState,
+    /// Set when ownership of `email` is already proven, e.g. through an invitation.
+    pub email_verified: bool,
 }
 
+/// Changes a user makes to their own account. Email changes need confirmation and go
+/// through [`crate::domain::EmailChangeRepository`] instead.
 #[derive(Debug, Clone, Default)]
 pub struct UpdateProfile {
-    pub email: Option<String>,
     pub username: Option<String>,
+    /// Profile fields to change; `None` keeps a field and an empty string clears it.
+    pub display_name: Option<String>,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub avatar_url: Option<String>,
+    /// Custom attributes to set, merged into the stored ones; a `null` value removes one.
+    pub attributes: Option<Attributes>,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct AdminUpdateUser {
     pub email: Option<String>,
     pub username: Option<String>,
-    pub role: Option<Role>,
+    /// Applied as [`AccountStatus::activation_change`], in the same transaction.
     pub is_active: Option<bool>,
+    /// Merged like [`UpdateProfile::attributes`].
+    pub attributes: Option<Attributes>,
+}
+
+/// Which of a set of emails and usernames already belong to an account.
+#[derive(Debug, Clone, Default)]
+pub struct ExistingIdentities {
+    /// Lowercased.
+    pub emails: HashSet<String>,
+    /// As [`User::username_skeleton`]s.
+    pub usernames: HashSet<String>,
+}
+
+/// Indexed columns the user list can be ordered by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserSortField {
+    CreatedAt,
+    Email,
+    Username,
+}
+
+impl UserSortField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserSortField::CreatedAt => "created_at",
+            UserSortField::Email => "email",
+            UserSortField::Username => "username",
+        }
+    }
+}
+
+/// Order of the user list, written as the field name with a leading `-` for descending,
+/// e.g. `-created_at`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserSort {
+    pub field: UserSortField,
+    pub descending: bool,
+}
+
+impl Default for UserSort {
+    fn default() -> Self {
+        Self {
+            field: UserSortField::CreatedAt,
+            descending: true,
+        }
+    }
 }
 
+impl fmt::Display for UserSort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.descending {
+            f.write_str("-")?;
+        }
+        f.write_str(self.field.as_str())
+    }
+}
+
+impl FromStr for UserSort {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (descending, name) = match value.strip_prefix('-') {
+            Some(name) => (true, name),
+            None => (false, value),
+        };
+        let field = match name {
+            "created_at" => UserSortField::CreatedAt,
+            "email" => UserSortField::Email,
+            "username" => UserSortField::Username,
+            _ => return Err(format!("invalid sort field: {name}")),
+        };
+        Ok(Self { field, descending })
+    }
+}
+
+/// How a user listing is paged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserPaging {
+    /// Skips this many users; pages shift when users are added meanwhile.
+    Offset(i64),
+    /// Continues after the cursor (from the start when `None`), newest first. Stable under
+    /// concurrent inserts, but only for the default `-created_at` order.
+    After(Option<Cursor>),
+}
+
+impl Default for UserPaging {
+    fn default() -> Self {
+        UserPaging::Offset(0)
+    }
+}
+
+/// User attributes a [`UserFilter`] can test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserField {
+    Id,
+    Username,
+    Email,
+    IsActive,
+    CreatedAt,
+    UpdatedAt,
+}
+
+/// Boolean expression over user attributes, such as a translated SCIM filter. String
+/// comparisons ignore case; timestamps are given as RFC 3339 strings.
+pub type UserFilter = Filter<UserField>;
+
+/// Filters and order for listing users. All filters are optional and combine with AND.
+#[derive(Debug, Clone, Default)]
+pub struct UserQuery {
+    /// Case-insensitive, typo-tolerant match against email and username.
+    pub search: Option<String>,
+    /// Holds this role, directly or through a group.
+    pub role: Option<String>,
+    /// Status in force is active or not, as [`AccountStatus::effective_state`] has it: a
+    /// suspension past its end already counts as active.
+    pub is_active: Option<bool>,
+    /// Status in force, like `is_active`.
+    pub status: Option<AccountState>,
+    /// Created at or after.
+    pub created_from: Option<DateTime<Utc>>,
+    /// Created before.
+    pub created_to: Option<DateTime<Utc>>,
+    /// Email domain, without the `@`; compared case-insensitively.
+    pub email_domain: Option<String>,
+    /// Equality tests on indexed custom attributes.
+    pub attributes: Vec<AttributeFilter>,
+    pub filter: Option<UserFilter>,
+    /// Defaults to best search match first when searching, newest first otherwise.
+    pub sort: Option<UserSort>,
+    pub paging: UserPaging,
+    pub limit: i64,
+}
+
+/// Change applied to every user of a bulk request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkUserAction {
+    /// Moves the account to [`AccountState::Deactivated`].
+    Deactivate,
+    /// Moves the account back to [`AccountState::Active`], whatever blocked it.
+    Reactivate,
+    /// Makes this role the user's only direct role. Roles inherited through groups stay.
+    /// Deleted accounts are left alone.
+    SetRole(String),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkUserOutcome {
+    Updated,
+    /// The user was already in the requested state; nothing was written.
+    Unchanged,
+    /// The account's status does not allow the change, e.g. it is deleted; nothing was
+    /// written.
+    InvalidTransition,
+    NotFound,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BulkUserResult {
+    pub user_id: Uuid,
+    pub outcome: BulkUserOutcome,
+}
+
+/// Persistence of accounts. Changes that can take administrator access away
+/// (`update_user`, `set_status`, `bulk_update`) must refuse to leave no active admin,
+/// with `ErrorCode::LastAdmin`, checked in the same transaction as the change.
 #[async_trait]
 pub trait UserRepository: Send + Sync {
+    /// Matches the email case-insensitively.
     async fn find_by_email(&self, email: &str) -> Result<Option<UserWithPassword>, DomainError>;
+    /// Finds the account whose username has the same [`User::username_skeleton`], i.e. the
+    /// one `username` would collide with.
     async fn find_by_username(&self, username: &str) -> Result<Option<User>, DomainError>;
     async fn find_by_id(&self, id: Uuid) -> Result<Option<UserWithPassword>, DomainError>;
-    async fn create(&self, new_user: NewUser) -> Result<User, DomainError>;
-    async fn update_profile(&self, id: Uuid, input: UpdateProfile) -> Result<User, DomainError>;
-    async fn update_user(&self, id: Uuid, input: AdminUpdateUser) -> Result<User, DomainError>;
-    async fn set_role(&self, id: Uuid, role: Role) -> Result<User, DomainError>;
-    async fn set_active(&self, id: Uuid, is_active: bool) -> Result<(), DomainError>;
-    async fn list(&self, limit: i64, offset: i64) -> Result<Vec<User>, DomainError>;
-}
\ No newline at end of file
+    // Mutations record their audit event in the same transaction as the change.
+    async fn create(&self, new_user: NewUser, audit: &AuditContext) -> Result<User, DomainError>;
+    /// Creates all users in one transaction: either every one is created or none.
+    async fn create_many(
+        &self,
+        new_users: Vec<NewUser>,
+        audit: &AuditContext,
+    ) -> Result<Vec<User>, DomainError>;
+    async fn find_existing(
+        &self,
+        emails: &[String],
+        usernames: &[String],
+    ) -> Result<ExistingIdentities, DomainError>;
+    /// Replaces the password hash and marks the email verified.
+    async fn set_password(
+        &self,
+        id: Uuid,
+        password_hash: String,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError>;
+    async fn update_profile(
+        &self,
+        id: Uuid,
+        input: UpdateProfile,
+        audit: &AuditContext,
+    ) -> Result<User, DomainError>;
+    async fn update_user(
+        &self,
+        id: Uuid,
+        input: AdminUpdateUser,
+        audit: &AuditContext,
+    ) -> Result<User, DomainError>;
+    /// Moves the account to another status if [`AccountStatus::transition`] allows it;
+    /// leaving no active admin is refused.
+    async fn set_status(
+        &self,
+        id: Uuid,
+        change: StatusChange,
+        audit: &AuditContext,
+    ) -> Result<User, DomainError>;
+    /// Reactivates every account whose suspension has ended; returns how many.
+    async fn lift_expired_suspensions(&self, audit: &AuditContext) -> Result<u64, DomainError>;
+    /// Applies `action` to every user in one transaction; a refused change (such as
+    /// removing the last admin) rolls back all of them. Unknown ids are reported, not fatal.
+    async fn bulk_update(
+        &self,
+        ids: &[Uuid],
+        action: &BulkUserAction,
+        audit: &AuditContext,
+    ) -> Result<Vec<BulkUserResult>, DomainError>;
+    async fn list(&self, query: &UserQuery) -> Result<Page<User>, DomainError>;
+    /// Number of users matching the filters of `query`, ignoring paging.
+    async fn count(&self, query: &UserQuery) -> Result<i64, DomainError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locale_and_timezone_validation() {
+        for tag in ["en", "en-US", "zh-Hant-TW", "de-CH-1996", "es-419"] {
+            assert!(UserProfile::is_valid_locale(tag), "{tag}");
+        }
+        for tag in ["", "e", "en_US", "en-", "e1-US", "en-toolongsubtag"] {
+            assert!(!UserProfile::is_valid_locale(tag), "{tag}");
+        }
+
+        assert!(UserProfile::is_valid_timezone("Europe/Paris"));
+        assert!(UserProfile::is_valid_timezone("UTC"));
+        assert!(!UserProfile::is_valid_timezone("Mars/Olympus_Mons"));
+    }
+
+    #[test]
+    fn emails_are_lowercased_with_ascii_domains() {
+        assert_eq!(
+            User::normalize_email(" Alice@Example.COM ").unwrap(),
+            "alice@example.com"
+        );
+        assert_eq!(
+            User::normalize_email("bob@Bücher.example").unwrap(),
+            "bob@xn--bcher-kva.example"
+        );
+        for email in ["alice", "@example.com", "alice@"] {
+            assert!(User::normalize_email(email).is_err(), "{email}");
+        }
+    }
+
+    #[test]
+    fn usernames_are_nfkc_and_unique_by_skeleton() {
+        assert_eq!(User::normalize_username(" Ａｌｉｃｅ "), "Alice");
+
+        let skeleton = User::username_skeleton("alice");
+        for lookalike in [
+            "Alice",
+            "ALICE",
+            "аlice",
+            "Аlice",
+            "ΑLICE",
+            "a1ice",
+            "álice",
+            "ａｌｉｃｅ",
+        ] {
+            assert_eq!(User::username_skeleton(lookalike), skeleton, "{lookalike}");
+        }
+        assert_ne!(User::username_skeleton("alicia"), skeleton);
+
+        // Uppercase homoglyphs are lowercased before they are folded.
+        assert_eq!(
+            User::username_skeleton("ΑDMIN"),
+            User::username_skeleton("admin")
+        );
+    }
+}
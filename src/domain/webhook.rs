@@ -0,0 +1,143 @@
+use crate::domain::errors::DomainError;
+use crate::domain::event::DomainEventType;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    Pending,
+    Succeeded,
+    /// Every attempt failed; only a manual redelivery sends it again.
+    Dead,
+}
+
+impl fmt::Display for DeliveryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeliveryStatus::Pending => write!(f, "pending"),
+            DeliveryStatus::Succeeded => write!(f, "succeeded"),
+            DeliveryStatus::Dead => write!(f, "dead"),
+        }
+    }
+}
+
+impl FromStr for DeliveryStatus {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "pending" => Ok(DeliveryStatus::Pending),
+            "succeeded" => Ok(DeliveryStatus::Succeeded),
+            "dead" => Ok(DeliveryStatus::Dead),
+            _ => Err(format!("invalid delivery status: {value}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WebhookEndpoint {
+    pub id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub event_types: Vec<DomainEventType>,
+    pub description: Option<String>,
+    pub is_active: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewWebhookEndpoint {
+    pub url: String,
+    pub secret: String,
+    pub event_types: Vec<DomainEventType>,
+    pub description: Option<String>,
+    pub created_by: Uuid,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UpdateWebhookEndpoint {
+    pub url: Option<String>,
+    pub event_types: Option<Vec<DomainEventType>>,
+    pub description: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub endpoint_id: Uuid,
+    pub event_type: DomainEventType,
+    pub payload: Value,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Result of one delivery attempt. `status_code` is absent when no response arrived.
+#[derive(Debug, Clone)]
+pub struct DeliveryAttempt {
+    pub status_code: Option<i32>,
+    pub error: Option<String>,
+}
+
+impl DeliveryAttempt {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+            && self
+                .status_code
+                .is_some_and(|code| (200..300).contains(&code))
+    }
+}
+
+#[async_trait]
+pub trait WebhookRepository: Send + Sync {
+    async fn list_endpoints(&self) -> Result<Vec<WebhookEndpoint>, DomainError>;
+    async fn find_endpoint(&self, id: Uuid) -> Result<Option<WebhookEndpoint>, DomainError>;
+    async fn create_endpoint(
+        &self,
+        input: NewWebhookEndpoint,
+    ) -> Result<WebhookEndpoint, DomainError>;
+    async fn update_endpoint(
+        &self,
+        id: Uuid,
+        input: UpdateWebhookEndpoint,
+    ) -> Result<WebhookEndpoint, DomainError>;
+    async fn delete_endpoint(&self, id: Uuid) -> Result<(), DomainError>;
+    /// Most recent deliveries to `endpoint_id`, newest first.
+    async fn list_deliveries(
+        &self,
+        endpoint_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, DomainError>;
+    async fn find_delivery(&self, id: Uuid) -> Result<Option<WebhookDelivery>, DomainError>;
+    /// Takes up to `limit` due pending deliveries and hides them from other workers
+    /// until `lease_until`, so an instance that dies mid-delivery only delays them.
+    async fn claim_due(
+        &self,
+        limit: i64,
+        lease_until: DateTime<Utc>,
+    ) -> Result<Vec<WebhookDelivery>, DomainError>;
+    /// Records an attempt. Failed deliveries are retried at `retry_at`, or marked dead
+    /// when it is `None`.
+    async fn record_attempt(
+        &self,
+        id: Uuid,
+        attempt: DeliveryAttempt,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), DomainError>;
+    /// Queues a delivery again with a fresh attempt budget.
+    async fn redeliver(&self, id: Uuid) -> Result<WebhookDelivery, DomainError>;
+}
@@ -1,8 +1,10 @@
 use crate::config::AppConfig;
-use crate::domain::{DomainError, User};
-use chrono::{Duration, Utc};
+use crate::domain::{DomainError, EmailChange, Invitation, User, UserProfile};
+use chrono::{DateTime, Duration, Utc};
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -15,16 +17,177 @@ pub enum TokenType {
 pub struct Claims {
     pub sub: String,
     pub email: String,
-    pub role: String,
+    pub roles: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub org_id: Option<String>,
+    /// Effective group names; only present when `JWT_GROUPS_CLAIM` is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Vec<String>>,
+    /// Admin acting as `sub`; only present on impersonation tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<ActorClaim>,
+    /// Standard OpenID Connect profile claims; only on access tokens, and only those set.
+    #[serde(flatten)]
+    pub profile: ProfileClaims,
     pub token_type: TokenType,
     pub exp: usize,
 }
 
+impl Claims {
+    pub fn org_id(&self) -> Option<Uuid> {
+        self.org_id
+            .as_deref()
+            .and_then(|value| Uuid::parse_str(value).ok())
+    }
+
+    pub fn actor_id(&self) -> Option<Uuid> {
+        self.act
+            .as_ref()
+            .and_then(|actor| Uuid::parse_str(&actor.sub).ok())
+    }
+
+    pub fn is_impersonation(&self) -> bool {
+        self.act.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileClaims {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub given_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zoneinfo: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub picture: Option<String>,
+}
+
+impl From<&UserProfile> for ProfileClaims {
+    fn from(value: &UserProfile) -> Self {
+        Self {
+            name: value.display_name.clone(),
+            given_name: value.given_name.clone(),
+            family_name: value.family_name.clone(),
+            locale: value.locale.clone(),
+            zoneinfo: value.timezone.clone(),
+            picture: value.avatar_url.clone(),
+        }
+    }
+}
+
+/// Actor claim as defined by RFC 8693: the party acting on behalf of the subject.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActorClaim {
+    pub sub: String,
+    pub email: String,
+}
+
+/// Claims of a signed invitation link. The nonce must still match the stored invitation,
+/// so resending or revoking invalidates links issued earlier.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvitationClaims {
+    pub sub: String,
+    pub nonce: String,
+    pub aud: String,
+    pub exp: usize,
+}
+
+impl InvitationClaims {
+    const AUDIENCE: &'static str = "invitation";
+
+    pub fn invitation_id(&self) -> Result<Uuid, DomainError> {
+        Uuid::parse_str(&self.sub)
+            .map_err(|_| DomainError::Unauthorized("invalid invitation".to_string()))
+    }
+
+    pub fn nonce(&self) -> Result<Uuid, DomainError> {
+        Uuid::parse_str(&self.nonce)
+            .map_err(|_| DomainError::Unauthorized("invalid invitation".to_string()))
+    }
+}
+
+/// Claims of an account setup link, which lets an account without a password choose one.
+/// `fingerprint` is derived from the password hash at issue time, so the link stops
+/// working once a password has been set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountSetupClaims {
+    pub sub: String,
+    pub fingerprint: String,
+    pub aud: String,
+    pub exp: usize,
+}
+
+impl AccountSetupClaims {
+    const AUDIENCE: &'static str = "account_setup";
+
+    pub fn user_id(&self) -> Result<Uuid, DomainError> {
+        Uuid::parse_str(&self.sub)
+            .map_err(|_| DomainError::Unauthorized("invalid setup link".to_string()))
+    }
+
+    pub fn fingerprint(password_hash: &str) -> String {
+        hex::encode(&Sha256::digest(password_hash.as_bytes())[..16])
+    }
+}
+
+/// Which of the two links sent for an email change a token belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailChangeLink {
+    /// Sent to the new address; commits the change.
+    Confirm,
+    /// Sent to the old address; cancels or undoes the change.
+    Revert,
+}
+
+impl EmailChangeLink {
+    fn audience(self) -> &'static str {
+        match self {
+            EmailChangeLink::Confirm => "email_change_confirm",
+            EmailChangeLink::Revert => "email_change_revert",
+        }
+    }
+}
+
+/// Claims of an email change link. The audience tells the two links apart, and the nonce
+/// must still match the stored change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailChangeClaims {
+    pub sub: String,
+    pub nonce: String,
+    pub aud: String,
+    pub exp: usize,
+}
+
+impl EmailChangeClaims {
+    pub fn change_id(&self) -> Result<Uuid, DomainError> {
+        Uuid::parse_str(&self.sub)
+            .map_err(|_| DomainError::Unauthorized("invalid email change link".to_string()))
+    }
+
+    pub fn nonce(&self) -> Result<Uuid, DomainError> {
+        Uuid::parse_str(&self.nonce)
+            .map_err(|_| DomainError::Unauthorized("invalid email change link".to_string()))
+    }
+}
+
+/// Optional context embedded into minted tokens.
+#[derive(Debug, Clone, Default)]
+pub struct TokenContext {
+    pub org_id: Option<Uuid>,
+}
+
 #[derive(Clone)]
 pub struct JwtService {
     secret: String,
     access_token_minutes: i64,
+    impersonation_token_minutes: i64,
     refresh_token_days: i64,
+    include_groups: bool,
 }
 
 impl JwtService {
@@ -32,16 +195,71 @@ impl JwtService {
         Self {
             secret: config.jwt_secret.clone(),
             access_token_minutes: config.access_token_minutes,
+            impersonation_token_minutes: config.impersonation_token_minutes,
             refresh_token_days: config.refresh_token_days,
+            include_groups: config.jwt_groups_claim,
         }
     }
 
     pub fn create_access_token(&self, user: &User) -> Result<String, DomainError> {
-        self.create_token(user, TokenType::Access)
+        self.create_token(user, TokenType::Access, &TokenContext::default())
     }
 
     pub fn create_refresh_token(&self, user: &User) -> Result<String, DomainError> {
-        self.create_token(user, TokenType::Refresh)
+        self.create_token(user, TokenType::Refresh, &TokenContext::default())
+    }
+
+    pub fn create_access_token_with(
+        &self,
+        user: &User,
+        context: &TokenContext,
+    ) -> Result<String, DomainError> {
+        self.create_token(user, TokenType::Access, context)
+    }
+
+    pub fn create_refresh_token_with(
+        &self,
+        user: &User,
+        context: &TokenContext,
+    ) -> Result<String, DomainError> {
+        self.create_token(user, TokenType::Refresh, context)
+    }
+
+    /// Mints a short-lived access token for `user` that names `actor` in the `act` claim.
+    /// No refresh token is issued, so the session ends when this token expires.
+    pub fn create_impersonation_token(
+        &self,
+        user: &User,
+        actor: &User,
+    ) -> Result<(String, DateTime<Utc>), DomainError> {
+        let expiration = Utc::now() + Duration::minutes(self.impersonation_token_minutes);
+        let expiration = user.roles_expire_at.map_or(expiration, |roles_expire_at| {
+            expiration.min(roles_expire_at)
+        });
+
+        let claims = Claims {
+            sub: user.id.to_string(),
+            email: user.email.clone(),
+            roles: user.roles.clone(),
+            org_id: None,
+            groups: self.include_groups.then(|| user.groups.clone()),
+            act: Some(ActorClaim {
+                sub: actor.id.to_string(),
+                email: actor.email.clone(),
+            }),
+            profile: ProfileClaims::from(&user.profile),
+            token_type: TokenType::Access,
+            exp: expiration.timestamp() as usize,
+        };
+
+        let token = encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &EncodingKey::from_secret(self.secret.as_bytes()),
+        )
+        .map_err(|err| DomainError::Internal(err.to_string()))?;
+
+        Ok((token, expiration))
     }
 
     pub fn decode_token(&self, token: &str) -> Result<Claims, DomainError> {
@@ -56,16 +274,141 @@ impl JwtService {
         Ok(token_data.claims)
     }
 
-    fn create_token(&self, user: &User, token_type: TokenType) -> Result<String, DomainError> {
+    pub fn create_invitation_token(&self, invitation: &Invitation) -> Result<String, DomainError> {
+        let claims = InvitationClaims {
+            sub: invitation.id.to_string(),
+            nonce: invitation.nonce.to_string(),
+            aud: InvitationClaims::AUDIENCE.to_string(),
+            exp: invitation.expires_at.timestamp() as usize,
+        };
+
+        encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &EncodingKey::from_secret(self.secret.as_bytes()),
+        )
+        .map_err(|err| DomainError::Internal(err.to_string()))
+    }
+
+    pub fn decode_invitation_token(&self, token: &str) -> Result<InvitationClaims, DomainError> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_audience(&[InvitationClaims::AUDIENCE]);
+        let token_data = decode::<InvitationClaims>(
+            token,
+            &DecodingKey::from_secret(self.secret.as_bytes()),
+            &validation,
+        )
+        .map_err(|err| DomainError::Unauthorized(format!("invalid invitation: {err}")))?;
+
+        Ok(token_data.claims)
+    }
+
+    pub fn create_account_setup_token(
+        &self,
+        user_id: Uuid,
+        password_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<String, DomainError> {
+        let claims = AccountSetupClaims {
+            sub: user_id.to_string(),
+            fingerprint: AccountSetupClaims::fingerprint(password_hash),
+            aud: AccountSetupClaims::AUDIENCE.to_string(),
+            exp: expires_at.timestamp() as usize,
+        };
+
+        encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &EncodingKey::from_secret(self.secret.as_bytes()),
+        )
+        .map_err(|err| DomainError::Internal(err.to_string()))
+    }
+
+    pub fn decode_account_setup_token(
+        &self,
+        token: &str,
+    ) -> Result<AccountSetupClaims, DomainError> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_audience(&[AccountSetupClaims::AUDIENCE]);
+        let token_data = decode::<AccountSetupClaims>(
+            token,
+            &DecodingKey::from_secret(self.secret.as_bytes()),
+            &validation,
+        )
+        .map_err(|err| DomainError::Unauthorized(format!("invalid setup link: {err}")))?;
+
+        Ok(token_data.claims)
+    }
+
+    pub fn create_email_change_token(
+        &self,
+        change: &EmailChange,
+        link: EmailChangeLink,
+    ) -> Result<String, DomainError> {
+        let expires_at = match link {
+            EmailChangeLink::Confirm => change.expires_at,
+            EmailChangeLink::Revert => change.revert_expires_at,
+        };
+        let claims = EmailChangeClaims {
+            sub: change.id.to_string(),
+            nonce: change.nonce.to_string(),
+            aud: link.audience().to_string(),
+            exp: expires_at.timestamp() as usize,
+        };
+
+        encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &EncodingKey::from_secret(self.secret.as_bytes()),
+        )
+        .map_err(|err| DomainError::Internal(err.to_string()))
+    }
+
+    pub fn decode_email_change_token(
+        &self,
+        token: &str,
+        link: EmailChangeLink,
+    ) -> Result<EmailChangeClaims, DomainError> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_audience(&[link.audience()]);
+        let token_data = decode::<EmailChangeClaims>(
+            token,
+            &DecodingKey::from_secret(self.secret.as_bytes()),
+            &validation,
+        )
+        .map_err(|err| DomainError::Unauthorized(format!("invalid email change link: {err}")))?;
+
+        Ok(token_data.claims)
+    }
+
+    fn create_token(
+        &self,
+        user: &User,
+        token_type: TokenType,
+        context: &TokenContext,
+    ) -> Result<String, DomainError> {
         let expiration = match token_type {
-            TokenType::Access => Utc::now() + Duration::minutes(self.access_token_minutes),
+            // Access tokens carry the role list, so they must not outlive a time-bound grant.
+            TokenType::Access => {
+                let expiration = Utc::now() + Duration::minutes(self.access_token_minutes);
+                user.roles_expire_at.map_or(expiration, |roles_expire_at| {
+                    expiration.min(roles_expire_at)
+                })
+            }
             TokenType::Refresh => Utc::now() + Duration::days(self.refresh_token_days),
         };
 
         let claims = Claims {
             sub: user.id.to_string(),
             email: user.email.clone(),
-            role: user.role.to_string(),
+            roles: user.roles.clone(),
+            org_id: context.org_id.map(|id| id.to_string()),
+            groups: self.include_groups.then(|| user.groups.clone()),
+            act: None,
+            profile: match token_type {
+                TokenType::Access => ProfileClaims::from(&user.profile),
+                TokenType::Refresh => ProfileClaims::default(),
+            },
             token_type,
             exp: expiration.timestamp() as usize,
         };
@@ -82,7 +425,8 @@ impl JwtService {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::Role;
+    use crate::config::BlobStoreKind;
+    use crate::domain::{AccountStatus, Role};
     use chrono::Utc;
     use uuid::Uuid;
 
@@ -94,7 +438,38 @@ mod tests {
             database_url: "postgres://localhost".to_string(),
             jwt_secret: "secret".to_string(),
             access_token_minutes: 10,
+            impersonation_token_minutes: 10,
             refresh_token_days: 7,
+            jwt_groups_claim: false,
+            invitation_ttl_hours: 72,
+            role_sweep_interval_seconds: 60,
+            status_sweep_interval_seconds: 60,
+            invitation_accept_url: "http://localhost:3000/invitations/accept".to_string(),
+            account_setup_url: "http://localhost:3000/account/setup".to_string(),
+            email_change_ttl_hours: 24,
+            email_change_revert_days: 7,
+            email_change_confirm_url: "http://localhost:3000/account/confirm-email".to_string(),
+            email_change_revert_url: "http://localhost:3000/account/revert-email".to_string(),
+            trust_proxy_headers: false,
+            webhook_max_attempts: 8,
+            webhook_retry_base_seconds: 30,
+            webhook_poll_interval_seconds: 5,
+            webhook_timeout_seconds: 10,
+            outbox_poll_interval_seconds: 1,
+            scim_token: None,
+            blob_store: BlobStoreKind::Local,
+            blob_local_dir: "./data/blobs".to_string(),
+            s3_endpoint: None,
+            s3_bucket: None,
+            s3_region: "us-east-1".to_string(),
+            s3_access_key_id: None,
+            s3_secret_access_key: None,
+            avatar_max_bytes: 1024 * 1024,
+            reserved_usernames: Vec::new(),
+            blocked_username_words: Vec::new(),
+            blocked_email_domains: Vec::new(),
+            allowed_email_domains: Vec::new(),
+            disposable_email_domains_file: None,
             cors_allowed_origins: vec!["http://localhost:3000".to_string()],
         };
 
@@ -103,8 +478,15 @@ mod tests {
             id: Uuid::new_v4(),
             email: "test@example.com".to_string(),
             username: "tester".to_string(),
-            role: Role::User,
-            is_active: true,
+            roles: vec![Role::USER.to_string()],
+            permissions: Vec::new(),
+            groups: vec!["engineering".to_string()],
+            roles_expire_at: None,
+            status: AccountStatus::active(Utc::now()),
+            email_verified_at: None,
+            pending_email: None,
+            profile: Default::default(),
+            attributes: Default::default(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -113,7 +495,57 @@ mod tests {
         let claims = service.decode_token(&token).unwrap();
 
         assert_eq!(claims.sub, user.id.to_string());
-        assert_eq!(claims.role, "user");
+        assert_eq!(claims.roles, vec!["user".to_string()]);
         assert_eq!(claims.token_type, TokenType::Access);
+        assert_eq!(claims.org_id(), None);
+        assert_eq!(claims.groups, None);
+        assert_eq!(claims.profile.zoneinfo, None);
+
+        let org_id = Uuid::new_v4();
+        let context = TokenContext {
+            org_id: Some(org_id),
+        };
+        let token = service.create_refresh_token_with(&user, &context).unwrap();
+        let claims = service.decode_token(&token).unwrap();
+
+        assert_eq!(claims.org_id(), Some(org_id));
+        assert_eq!(claims.token_type, TokenType::Refresh);
+
+        let service = JwtService::new(&AppConfig {
+            jwt_groups_claim: true,
+            ..config
+        });
+        let token = service.create_access_token(&user).unwrap();
+        let claims = service.decode_token(&token).unwrap();
+
+        assert_eq!(claims.groups, Some(vec!["engineering".to_string()]));
+
+        let user = User {
+            profile: UserProfile {
+                display_name: Some("Test User".to_string()),
+                timezone: Some("Europe/Paris".to_string()),
+                ..Default::default()
+            },
+            ..user
+        };
+        let claims = service
+            .decode_token(&service.create_access_token(&user).unwrap())
+            .unwrap();
+        assert_eq!(claims.profile.name.as_deref(), Some("Test User"));
+        assert_eq!(claims.profile.zoneinfo.as_deref(), Some("Europe/Paris"));
+        let claims = service
+            .decode_token(&service.create_refresh_token(&user).unwrap())
+            .unwrap();
+        assert_eq!(claims.profile.name, None);
+
+        let roles_expire_at = Utc::now() + chrono::Duration::minutes(2);
+        let elevated = User {
+            roles_expire_at: Some(roles_expire_at),
+            ..user
+        };
+        let token = service.create_access_token(&elevated).unwrap();
+        let claims = service.decode_token(&token).unwrap();
+
+        assert_eq!(claims.exp, roles_expire_at.timestamp() as usize);
     }
 }
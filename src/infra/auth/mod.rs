@@ -1 +1 @@
-pub mod jwt;
\ No newline at end of file
+pub mod jwt;
@@ -0,0 +1,227 @@
+use crate::domain::{
+    AttributeDefinition, AttributeDefinitionRepository, AttributeType, DomainError,
+    NewAttributeDefinition, UpdateAttributeDefinition,
+};
+use crate::infra::db::map_db_error;
+use crate::infra::db::models::DbAttributeDefinition;
+use async_trait::async_trait;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+const DEFINITION_COLUMNS: &str =
+    "id, name, attribute_type, description, is_required, is_unique, is_indexed, \
+    user_editable, created_at, updated_at";
+
+/// Prefix of the expression indexes kept on `users.attributes`.
+const INDEX_PREFIX: &str = "idx_users_attr_";
+
+/// SQL expression reading attribute `name` of a user row, typed so that comparisons and
+/// uniqueness follow the attribute type (`1` and `1.0` are the same number). Filters and
+/// indexes share it, so the planner can match them. `name` must be a valid attribute name,
+/// as it is inlined.
+pub(crate) fn attribute_expression(name: &str, attribute_type: AttributeType) -> String {
+    debug_assert!(AttributeDefinition::is_valid_name(name));
+    match attribute_type {
+        AttributeType::String | AttributeType::Date => format!("(users.attributes ->> '{name}')"),
+        AttributeType::Number => format!("((users.attributes ->> '{name}')::NUMERIC)"),
+        AttributeType::Boolean => format!("((users.attributes ->> '{name}')::BOOLEAN)"),
+    }
+}
+
+/// Like [`map_db_error`], but names the attribute whose unique index a user write violated.
+pub(crate) fn map_attribute_error(error: sqlx::Error) -> DomainError {
+    if let sqlx::Error::Database(db_error) = &error {
+        let attribute = db_error
+            .constraint()
+            .and_then(|constraint| constraint.strip_prefix(INDEX_PREFIX))
+            .and_then(|constraint| constraint.strip_suffix("_unique"));
+        if let Some(name) = attribute {
+            return DomainError::Conflict(format!(
+                "attribute `{name}` is already taken by another user"
+            ));
+        }
+    }
+    map_db_error(error)
+}
+
+#[derive(Clone)]
+pub struct SqlxAttributeDefinitionRepository {
+    pool: PgPool,
+}
+
+impl SqlxAttributeDefinitionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    fn map_db_definition(row: DbAttributeDefinition) -> Result<AttributeDefinition, DomainError> {
+        AttributeDefinition::try_from(row).map_err(DomainError::Internal)
+    }
+
+    async fn lock_definition(
+        conn: &mut PgConnection,
+        id: Uuid,
+    ) -> Result<AttributeDefinition, DomainError> {
+        let row = sqlx::query_as::<_, DbAttributeDefinition>(&format!(
+            "SELECT {DEFINITION_COLUMNS} FROM attribute_definitions WHERE id = $1 FOR UPDATE"
+        ))
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(map_db_error)?
+        .ok_or_else(|| DomainError::NotFound("attribute not found".to_string()))?;
+
+        Self::map_db_definition(row)
+    }
+
+    /// Creates or drops the indexes of `definition` so they match its flags. Building a
+    /// unique index fails when users already share a value.
+    async fn sync_indexes(
+        conn: &mut PgConnection,
+        definition: &AttributeDefinition,
+    ) -> Result<(), DomainError> {
+        let name = &definition.name;
+        let expression = attribute_expression(name, definition.attribute_type);
+        let statements = [
+            if definition.indexed {
+                format!("CREATE INDEX IF NOT EXISTS {INDEX_PREFIX}{name} ON users ({expression})")
+            } else {
+                format!("DROP INDEX IF EXISTS {INDEX_PREFIX}{name}")
+            },
+            if definition.unique {
+                format!("CREATE UNIQUE INDEX IF NOT EXISTS {INDEX_PREFIX}{name}_unique ON users ({expression})")
+            } else {
+                format!("DROP INDEX IF EXISTS {INDEX_PREFIX}{name}_unique")
+            },
+        ];
+
+        for statement in statements {
+            sqlx::query(&statement).execute(&mut *conn).await.map_err(
+                |err| match map_db_error(err) {
+                    DomainError::Conflict(_) => DomainError::Conflict(format!(
+                        "users already share values of attribute `{name}`"
+                    )),
+                    err => err,
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AttributeDefinitionRepository for SqlxAttributeDefinitionRepository {
+    async fn list(&self) -> Result<Vec<AttributeDefinition>, DomainError> {
+        let rows = sqlx::query_as::<_, DbAttributeDefinition>(&format!(
+            "SELECT {DEFINITION_COLUMNS} FROM attribute_definitions ORDER BY name"
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        rows.into_iter().map(Self::map_db_definition).collect()
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<AttributeDefinition>, DomainError> {
+        let result = sqlx::query_as::<_, DbAttributeDefinition>(&format!(
+            "SELECT {DEFINITION_COLUMNS} FROM attribute_definitions WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        result.map(Self::map_db_definition).transpose()
+    }
+
+    async fn create(
+        &self,
+        input: NewAttributeDefinition,
+    ) -> Result<AttributeDefinition, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+
+        let row = sqlx::query_as::<_, DbAttributeDefinition>(&format!(
+            "INSERT INTO attribute_definitions (id, name, attribute_type, description, is_required, is_unique, is_indexed, user_editable) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING {DEFINITION_COLUMNS}"
+        ))
+        .bind(Uuid::new_v4())
+        .bind(&input.name)
+        .bind(input.attribute_type.as_str())
+        .bind(input.description)
+        .bind(input.required)
+        .bind(input.unique)
+        .bind(input.indexed)
+        .bind(input.user_editable)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|err| match map_db_error(err) {
+            DomainError::Conflict(_) => DomainError::Conflict(format!("attribute `{}` already exists", input.name)),
+            err => err,
+        })?;
+        let definition = Self::map_db_definition(row)?;
+
+        Self::sync_indexes(&mut tx, &definition).await?;
+        tx.commit().await.map_err(map_db_error)?;
+
+        Ok(definition)
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        input: UpdateAttributeDefinition,
+    ) -> Result<AttributeDefinition, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+        let before = Self::lock_definition(&mut tx, id).await?;
+
+        let row = sqlx::query_as::<_, DbAttributeDefinition>(&format!(
+            "UPDATE attribute_definitions SET description = COALESCE($1, description), is_required = COALESCE($2, is_required), \
+                is_unique = COALESCE($3, is_unique), is_indexed = COALESCE($4, is_indexed), \
+                user_editable = COALESCE($5, user_editable), updated_at = NOW() \
+             WHERE id = $6 RETURNING {DEFINITION_COLUMNS}"
+        ))
+        .bind(input.description)
+        .bind(input.required)
+        .bind(input.unique)
+        .bind(input.indexed)
+        .bind(input.user_editable)
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(map_db_error)?;
+        let after = Self::map_db_definition(row)?;
+
+        if (before.unique, before.indexed) != (after.unique, after.indexed) {
+            Self::sync_indexes(&mut tx, &after).await?;
+        }
+        tx.commit().await.map_err(map_db_error)?;
+
+        Ok(after)
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<(), DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+        let definition = Self::lock_definition(&mut tx, id).await?;
+
+        let dropped = AttributeDefinition {
+            unique: false,
+            indexed: false,
+            ..definition.clone()
+        };
+        Self::sync_indexes(&mut tx, &dropped).await?;
+        sqlx::query("UPDATE users SET attributes = attributes - $1 WHERE attributes ? $1")
+            .bind(&definition.name)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_db_error)?;
+        sqlx::query("DELETE FROM attribute_definitions WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_db_error)?;
+
+        tx.commit().await.map_err(map_db_error)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use crate::domain::{
+    ActivityEntry, AuditEvent, AuditEventType, AuditQuery, AuditRepository, AuditSink, DomainError,
+    DomainEvent, NewAuditEvent, Page,
+};
+use crate::infra::db::map_db_error;
+use crate::infra::db::models::DbAuditEvent;
+use crate::infra::db::outbox_repo::SqlxOutboxRepository;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgConnection, PgPool};
+use uuid::Uuid;
+
+const AUDIT_COLUMNS: &str = "audit_events.id, audit_events.event_type, audit_events.actor_id, \
+    audit_events.impersonator_id, audit_events.target_user_id, audit_events.ip, audit_events.user_agent, \
+    audit_events.request_id, audit_events.before, audit_events.after, audit_events.metadata, audit_events.created_at";
+
+#[derive(FromRow)]
+struct DbActivityEntry {
+    #[sqlx(flatten)]
+    event: DbAuditEvent,
+    new_ip: bool,
+}
+
+#[derive(Clone)]
+pub struct SqlxAuditRepository {
+    pool: PgPool,
+}
+
+impl SqlxAuditRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Writes `event` on `conn`. Repositories call this inside the transaction of the
+    /// change being audited, so the event and the change commit or roll back together.
+    /// Events that have a domain event put it in the outbox alongside.
+    pub(crate) async fn insert(
+        conn: &mut PgConnection,
+        event: NewAuditEvent,
+    ) -> Result<(), DomainError> {
+        let id = Uuid::new_v4();
+        let created_at = sqlx::query_scalar::<_, DateTime<Utc>>(
+            "INSERT INTO audit_events (id, event_type, actor_id, impersonator_id, target_user_id, ip, user_agent, request_id, before, after, metadata) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) RETURNING created_at",
+        )
+        .bind(id)
+        .bind(event.event_type.as_str())
+        .bind(event.actor_id)
+        .bind(event.impersonator_id)
+        .bind(event.target_user_id)
+        .bind(&event.ip)
+        .bind(&event.user_agent)
+        .bind(&event.request_id)
+        .bind(&event.before)
+        .bind(&event.after)
+        .bind(&event.metadata)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(map_db_error)?;
+
+        if let Some(domain_event) = DomainEvent::from_audit(id, created_at, &event) {
+            SqlxOutboxRepository::insert(conn, &domain_event).await?;
+        }
+
+        Ok(())
+    }
+
+    fn map_db_event(row: DbAuditEvent) -> Result<AuditEvent, DomainError> {
+        AuditEvent::try_from(row).map_err(DomainError::Internal)
+    }
+}
+
+#[async_trait]
+impl AuditRepository for SqlxAuditRepository {
+    async fn search(&self, query: &AuditQuery) -> Result<Page<AuditEvent>, DomainError> {
+        let (after_created_at, after_id) = query
+            .after
+            .map(|cursor| (cursor.created_at, cursor.id))
+            .unzip();
+
+        // One extra row tells whether another page follows.
+        let rows = sqlx::query_as::<_, DbAuditEvent>(&format!(
+            "SELECT {AUDIT_COLUMNS} FROM audit_events \
+             WHERE ($1::UUID IS NULL OR actor_id = $1) \
+               AND ($2::UUID IS NULL OR target_user_id = $2) \
+               AND ($3::TEXT IS NULL OR event_type = $3) \
+               AND ($4::TIMESTAMPTZ IS NULL OR created_at >= $4) \
+               AND ($5::TIMESTAMPTZ IS NULL OR created_at < $5) \
+               AND ($6::TIMESTAMPTZ IS NULL OR (created_at, id) < ($6, $7)) \
+             ORDER BY created_at DESC, id DESC LIMIT $8"
+        ))
+        .bind(query.actor_id)
+        .bind(query.target_user_id)
+        .bind(query.event_type.map(|event_type| event_type.as_str()))
+        .bind(query.from)
+        .bind(query.to)
+        .bind(after_created_at)
+        .bind(after_id)
+        .bind(query.limit + 1)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        let events = rows
+            .into_iter()
+            .map(Self::map_db_event)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Page::from_rows(events, query.limit, AuditEvent::cursor))
+    }
+
+    async fn activity(&self, user_id: Uuid, limit: i64) -> Result<Vec<ActivityEntry>, DomainError> {
+        let rows = sqlx::query_as::<_, DbActivityEntry>(&format!(
+            "SELECT {AUDIT_COLUMNS}, \
+                (audit_events.event_type = $2 AND audit_events.ip IS NOT NULL AND NOT EXISTS ( \
+                    SELECT 1 FROM audit_events earlier \
+                    WHERE earlier.target_user_id = audit_events.target_user_id \
+                      AND earlier.event_type = $2 \
+                      AND earlier.ip = audit_events.ip \
+                      AND earlier.created_at < audit_events.created_at \
+                )) AS new_ip \
+             FROM audit_events WHERE audit_events.target_user_id = $1 \
+             ORDER BY audit_events.created_at DESC, audit_events.id DESC LIMIT $3"
+        ))
+        .bind(user_id)
+        .bind(AuditEventType::LoginSucceeded.as_str())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(ActivityEntry {
+                    event: Self::map_db_event(row.event)?,
+                    new_ip: row.new_ip,
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl AuditSink for SqlxAuditRepository {
+    async fn record(&self, event: NewAuditEvent) -> Result<(), DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+        Self::insert(&mut tx, event).await?;
+        tx.commit().await.map_err(map_db_error)
+    }
+}
@@ -0,0 +1,187 @@
+use crate::domain::{
+    DomainError, ElevationRepository, ElevationRequest, ElevationStatus, NewElevationRequest,
+};
+use crate::infra::db::models::DbElevationRequest;
+use crate::infra::db::role_repo::SqlxRoleRepository;
+use crate::infra::db::{map_db_error, RoleSnapshot};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const ELEVATION_COLUMNS: &str = "elevation_requests.id, elevation_requests.user_id, elevation_requests.role_id, \
+    roles.name AS role_name, elevation_requests.duration_minutes, elevation_requests.reason, \
+    elevation_requests.status, elevation_requests.decided_by, elevation_requests.decision_note, \
+    elevation_requests.decided_at, elevation_requests.grant_expires_at, elevation_requests.created_at";
+
+#[derive(Clone)]
+pub struct SqlxElevationRepository {
+    pool: PgPool,
+}
+
+impl SqlxElevationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    fn map_db_request(row: DbElevationRequest) -> Result<ElevationRequest, DomainError> {
+        ElevationRequest::try_from(row).map_err(DomainError::Internal)
+    }
+
+    fn decided_conflict() -> DomainError {
+        DomainError::Conflict("elevation request was already decided".to_string())
+    }
+}
+
+#[async_trait]
+impl ElevationRepository for SqlxElevationRepository {
+    async fn create(&self, input: NewElevationRequest) -> Result<ElevationRequest, DomainError> {
+        let id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO elevation_requests (id, user_id, role_id, duration_minutes, reason) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(id)
+        .bind(input.user_id)
+        .bind(input.role_id)
+        .bind(input.duration_minutes)
+        .bind(input.reason)
+        .execute(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        self.find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::Internal("elevation request vanished".to_string()))
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<ElevationRequest>, DomainError> {
+        let result = sqlx::query_as::<_, DbElevationRequest>(&format!(
+            "SELECT {ELEVATION_COLUMNS} FROM elevation_requests JOIN roles ON roles.id = elevation_requests.role_id WHERE elevation_requests.id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        result.map(Self::map_db_request).transpose()
+    }
+
+    async fn list(
+        &self,
+        status: Option<ElevationStatus>,
+        limit: i64,
+    ) -> Result<Vec<ElevationRequest>, DomainError> {
+        let rows = sqlx::query_as::<_, DbElevationRequest>(&format!(
+            "SELECT {ELEVATION_COLUMNS} FROM elevation_requests JOIN roles ON roles.id = elevation_requests.role_id \
+             WHERE $1::TEXT IS NULL OR elevation_requests.status = $1 \
+             ORDER BY elevation_requests.created_at DESC LIMIT $2"
+        ))
+        .bind(status.map(|status| status.to_string()))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        rows.into_iter()
+            .map(Self::map_db_request)
+            .collect::<Result<Vec<_>, _>>()
+    }
+
+    async fn list_for_user(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<ElevationRequest>, DomainError> {
+        let rows = sqlx::query_as::<_, DbElevationRequest>(&format!(
+            "SELECT {ELEVATION_COLUMNS} FROM elevation_requests JOIN roles ON roles.id = elevation_requests.role_id \
+             WHERE elevation_requests.user_id = $1 ORDER BY elevation_requests.created_at DESC LIMIT $2"
+        ))
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        rows.into_iter()
+            .map(Self::map_db_request)
+            .collect::<Result<Vec<_>, _>>()
+    }
+
+    async fn approve(
+        &self,
+        id: Uuid,
+        approver_id: Uuid,
+        note: Option<String>,
+        grant_expires_at: DateTime<Utc>,
+    ) -> Result<ElevationRequest, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+
+        let (user_id, role_id) = sqlx::query_as::<_, (Uuid, Uuid)>(
+            "UPDATE elevation_requests SET status = $1, decided_by = $2, decision_note = $3, decided_at = NOW(), grant_expires_at = $4 \
+             WHERE id = $5 AND status = $6 RETURNING user_id, role_id",
+        )
+        .bind(ElevationStatus::Approved.to_string())
+        .bind(approver_id)
+        .bind(note)
+        .bind(grant_expires_at)
+        .bind(id)
+        .bind(ElevationStatus::Pending.to_string())
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(map_db_error)?
+        .ok_or_else(Self::decided_conflict)?;
+
+        let snapshot = RoleSnapshot::take(&mut tx, &[user_id]).await?;
+        SqlxRoleRepository::grant(
+            &mut tx,
+            user_id,
+            role_id,
+            Some(approver_id),
+            Some(grant_expires_at),
+        )
+        .await?;
+        snapshot
+            .publish(
+                &mut tx,
+                Some(approver_id),
+                "elevated",
+                json!({ "elevation_id": id, "role_id": role_id, "expires_at": grant_expires_at }),
+            )
+            .await?;
+        tx.commit().await.map_err(map_db_error)?;
+
+        self.find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("elevation request not found".to_string()))
+    }
+
+    async fn deny(
+        &self,
+        id: Uuid,
+        approver_id: Uuid,
+        note: Option<String>,
+    ) -> Result<ElevationRequest, DomainError> {
+        let affected = sqlx::query(
+            "UPDATE elevation_requests SET status = $1, decided_by = $2, decision_note = $3, decided_at = NOW() \
+             WHERE id = $4 AND status = $5",
+        )
+        .bind(ElevationStatus::Denied.to_string())
+        .bind(approver_id)
+        .bind(note)
+        .bind(id)
+        .bind(ElevationStatus::Pending.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(map_db_error)?
+        .rows_affected();
+
+        if affected == 0 {
+            return Err(Self::decided_conflict());
+        }
+
+        self.find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("elevation request not found".to_string()))
+    }
+}
@@ -0,0 +1,211 @@
+use crate::domain::{
+    AuditContext, AuditEventType, DomainError, EmailChange, EmailChangeRepository, NewEmailChange,
+    User,
+};
+use crate::infra::db::audit_repo::SqlxAuditRepository;
+use crate::infra::db::map_db_error;
+use crate::infra::db::models::DbEmailChange;
+use crate::infra::db::user_repo::SqlxUserRepository;
+use async_trait::async_trait;
+use serde_json::json;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+const EMAIL_CHANGE_COLUMNS: &str =
+    "id, user_id, old_email, new_email, nonce, expires_at, revert_expires_at, \
+    confirmed_at, cancelled_at, reverted_at, created_at";
+
+#[derive(Clone)]
+pub struct SqlxEmailChangeRepository {
+    pool: PgPool,
+}
+
+impl SqlxEmailChangeRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    fn invalid_link() -> DomainError {
+        DomainError::Unauthorized("email change link is no longer valid".to_string())
+    }
+
+    /// Cancels the user's unconfirmed change, if any.
+    async fn cancel_open(conn: &mut PgConnection, user_id: Uuid) -> Result<(), DomainError> {
+        sqlx::query(
+            "UPDATE email_changes SET cancelled_at = NOW() WHERE user_id = $1 AND confirmed_at IS NULL AND cancelled_at IS NULL",
+        )
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(map_db_error)?;
+
+        Ok(())
+    }
+
+    /// Points the account at `email`, which the caller has just proven to own.
+    async fn write_email(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        email: &str,
+    ) -> Result<User, DomainError> {
+        sqlx::query("UPDATE users SET email = $1, email_verified_at = NOW(), updated_at = NOW() WHERE id = $2")
+            .bind(email)
+            .bind(user_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|err| match map_db_error(err) {
+                DomainError::Conflict(_) => DomainError::Conflict("email already exists".to_string()),
+                other => other,
+            })?;
+
+        SqlxUserRepository::fetch_user(conn, user_id).await
+    }
+}
+
+#[async_trait]
+impl EmailChangeRepository for SqlxEmailChangeRepository {
+    async fn create(
+        &self,
+        input: NewEmailChange,
+        audit: &AuditContext,
+    ) -> Result<EmailChange, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+        Self::cancel_open(&mut tx, input.user_id).await?;
+
+        let row = sqlx::query_as::<_, DbEmailChange>(&format!(
+            "INSERT INTO email_changes (id, user_id, old_email, new_email, nonce, expires_at, revert_expires_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING {EMAIL_CHANGE_COLUMNS}"
+        ))
+        .bind(Uuid::new_v4())
+        .bind(input.user_id)
+        .bind(&input.old_email)
+        .bind(&input.new_email)
+        .bind(Uuid::new_v4())
+        .bind(input.expires_at)
+        .bind(input.revert_expires_at)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(map_db_error)?;
+
+        let event = audit
+            .event(AuditEventType::EmailChangeRequested, Some(input.user_id))
+            .with_metadata(json!({ "new_email": input.new_email }));
+        SqlxAuditRepository::insert(&mut tx, event).await?;
+        tx.commit().await.map_err(map_db_error)?;
+
+        Ok(EmailChange::from(row))
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<EmailChange>, DomainError> {
+        let result = sqlx::query_as::<_, DbEmailChange>(&format!(
+            "SELECT {EMAIL_CHANGE_COLUMNS} FROM email_changes WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        Ok(result.map(EmailChange::from))
+    }
+
+    async fn confirm(
+        &self,
+        id: Uuid,
+        nonce: Uuid,
+        audit: &AuditContext,
+    ) -> Result<User, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+
+        // The guarded update doubles as a lock: a concurrent confirm or revert finds no row.
+        let change = sqlx::query_as::<_, DbEmailChange>(&format!(
+            "UPDATE email_changes SET confirmed_at = NOW() \
+             WHERE id = $1 AND nonce = $2 AND confirmed_at IS NULL AND cancelled_at IS NULL AND expires_at > NOW() \
+             RETURNING {EMAIL_CHANGE_COLUMNS}"
+        ))
+        .bind(id)
+        .bind(nonce)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(map_db_error)?
+        .ok_or_else(Self::invalid_link)?;
+
+        // An admin may have changed the address in the meantime.
+        let before = SqlxUserRepository::lock_user(&mut tx, change.user_id).await?;
+        if before.email != change.old_email {
+            return Err(Self::invalid_link());
+        }
+
+        let after = Self::write_email(&mut tx, change.user_id, &change.new_email).await?;
+        SqlxUserRepository::audit_change(
+            &mut tx,
+            audit,
+            AuditEventType::EmailChanged,
+            &before,
+            &after,
+        )
+        .await?;
+        tx.commit().await.map_err(map_db_error)?;
+
+        Ok(after)
+    }
+
+    async fn revert(
+        &self,
+        id: Uuid,
+        nonce: Uuid,
+        audit: &AuditContext,
+    ) -> Result<Option<User>, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+
+        let change = sqlx::query_as::<_, DbEmailChange>(&format!(
+            "SELECT {EMAIL_CHANGE_COLUMNS} FROM email_changes WHERE id = $1 AND nonce = $2 FOR UPDATE"
+        ))
+        .bind(id)
+        .bind(nonce)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(map_db_error)?
+        .map(EmailChange::from)
+        .filter(EmailChange::is_revertible)
+        .ok_or_else(Self::invalid_link)?;
+
+        if change.confirmed_at.is_none() {
+            sqlx::query("UPDATE email_changes SET cancelled_at = NOW() WHERE id = $1")
+                .bind(change.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(map_db_error)?;
+
+            let event = audit
+                .event(AuditEventType::EmailChangeCancelled, Some(change.user_id))
+                .with_metadata(json!({ "new_email": change.new_email }));
+            SqlxAuditRepository::insert(&mut tx, event).await?;
+            tx.commit().await.map_err(map_db_error)?;
+
+            return Ok(None);
+        }
+
+        // Whoever confirmed the change may have requested another one since; the old
+        // address takes the account back regardless.
+        let before = SqlxUserRepository::lock_user(&mut tx, change.user_id).await?;
+        Self::cancel_open(&mut tx, change.user_id).await?;
+        sqlx::query("UPDATE email_changes SET reverted_at = NOW() WHERE id = $1")
+            .bind(change.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_db_error)?;
+
+        let after = Self::write_email(&mut tx, change.user_id, &change.old_email).await?;
+        SqlxUserRepository::audit_change(
+            &mut tx,
+            audit,
+            AuditEventType::EmailChangeReverted,
+            &before,
+            &after,
+        )
+        .await?;
+        tx.commit().await.map_err(map_db_error)?;
+
+        Ok(Some(after))
+    }
+}
@@ -0,0 +1,416 @@
+use crate::domain::{
+    AuditContext, DomainError, Group, GroupMembers, GroupRepository, NewGroup, UpdateGroup, User,
+};
+use crate::infra::db::models::{DbGroup, DbUser};
+use crate::infra::db::user_repo::USER_COLUMNS;
+use crate::infra::db::{map_db_error, AdminGuard, RoleSnapshot};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+const GROUP_COLUMNS: &str = "groups.id, groups.name, groups.description, \
+    ARRAY(SELECT roles.name FROM group_roles JOIN roles ON roles.id = group_roles.role_id WHERE group_roles.group_id = groups.id ORDER BY roles.name) AS roles, \
+    groups.created_at, groups.updated_at";
+
+/// Users whose effective roles a group edit can change.
+enum Affected {
+    User(Uuid),
+    /// Direct and nested members of the group.
+    MembersOf(Uuid),
+}
+
+impl Affected {
+    async fn snapshot(self, conn: &mut PgConnection) -> Result<RoleSnapshot, DomainError> {
+        let user_ids = match self {
+            Affected::User(user_id) => vec![user_id],
+            Affected::MembersOf(group_id) => sqlx::query_scalar::<_, Uuid>(
+                "WITH RECURSIVE descendants (id) AS ( \
+                    SELECT $1::UUID \
+                    UNION \
+                    SELECT group_group_members.member_group_id FROM group_group_members JOIN descendants ON group_group_members.group_id = descendants.id \
+                ) SELECT DISTINCT user_id FROM group_user_members WHERE group_id IN (SELECT id FROM descendants)",
+            )
+            .bind(group_id)
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(map_db_error)?,
+        };
+
+        RoleSnapshot::take(conn, &user_ids).await
+    }
+}
+
+/// What a group edit does to members' roles, published as `user.role_changed`.
+struct Impact {
+    users: Affected,
+    change: &'static str,
+    metadata: Value,
+}
+
+#[derive(Clone)]
+pub struct SqlxGroupRepository {
+    pool: PgPool,
+}
+
+impl SqlxGroupRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Inserts a membership or role edge, publishing the role changes it causes.
+    async fn insert_edge(
+        &self,
+        query: &str,
+        left: Uuid,
+        right: Uuid,
+        impact: Impact,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+        let snapshot = impact.users.snapshot(&mut tx).await?;
+
+        sqlx::query(query)
+            .bind(left)
+            .bind(right)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_db_error)?;
+
+        snapshot
+            .publish(&mut tx, audit.actor_id, impact.change, impact.metadata)
+            .await?;
+        tx.commit().await.map_err(map_db_error)?;
+        Ok(())
+    }
+
+    /// Removes a membership or role edge. Any of them can take the `admin` role away from
+    /// someone, so the removal runs under the [`AdminGuard`].
+    async fn delete_edge(
+        &self,
+        query: &str,
+        left: Uuid,
+        right: Uuid,
+        missing: &str,
+        impact: Impact,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+        let guard = AdminGuard::acquire(&mut tx).await?;
+        let snapshot = impact.users.snapshot(&mut tx).await?;
+
+        let affected = sqlx::query(query)
+            .bind(left)
+            .bind(right)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_db_error)?
+            .rows_affected();
+
+        if affected == 0 {
+            return Err(DomainError::NotFound(missing.to_string()));
+        }
+
+        guard.verify(&mut tx).await?;
+        snapshot
+            .publish(&mut tx, audit.actor_id, impact.change, impact.metadata)
+            .await?;
+        tx.commit().await.map_err(map_db_error)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl GroupRepository for SqlxGroupRepository {
+    async fn list(&self) -> Result<Vec<Group>, DomainError> {
+        let rows = sqlx::query_as::<_, DbGroup>(&format!(
+            "SELECT {GROUP_COLUMNS} FROM groups ORDER BY groups.name"
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        Ok(rows.into_iter().map(Group::from).collect())
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Group>, DomainError> {
+        let result = sqlx::query_as::<_, DbGroup>(&format!(
+            "SELECT {GROUP_COLUMNS} FROM groups WHERE groups.id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        Ok(result.map(Group::from))
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Option<Group>, DomainError> {
+        let result = sqlx::query_as::<_, DbGroup>(&format!(
+            "SELECT {GROUP_COLUMNS} FROM groups WHERE groups.name = $1"
+        ))
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        Ok(result.map(Group::from))
+    }
+
+    async fn create(&self, input: NewGroup) -> Result<Group, DomainError> {
+        let row = sqlx::query_as::<_, DbGroup>(&format!(
+            "INSERT INTO groups (id, name, description) VALUES ($1, $2, $3) RETURNING {GROUP_COLUMNS}"
+        ))
+        .bind(Uuid::new_v4())
+        .bind(input.name)
+        .bind(input.description)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        Ok(row.into())
+    }
+
+    async fn update(&self, id: Uuid, input: UpdateGroup) -> Result<Group, DomainError> {
+        let result = sqlx::query_as::<_, DbGroup>(&format!(
+            "UPDATE groups SET name = COALESCE($1, name), description = COALESCE($2, description), updated_at = NOW() WHERE id = $3 RETURNING {GROUP_COLUMNS}"
+        ))
+        .bind(input.name)
+        .bind(input.description)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        result
+            .map(Group::from)
+            .ok_or_else(|| DomainError::NotFound("group not found".to_string()))
+    }
+
+    async fn delete(&self, id: Uuid, audit: &AuditContext) -> Result<(), DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+        let guard = AdminGuard::acquire(&mut tx).await?;
+        let snapshot = Affected::MembersOf(id).snapshot(&mut tx).await?;
+
+        let affected = sqlx::query("DELETE FROM groups WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_db_error)?
+            .rows_affected();
+
+        if affected == 0 {
+            return Err(DomainError::NotFound("group not found".to_string()));
+        }
+
+        guard.verify(&mut tx).await?;
+        snapshot
+            .publish(
+                &mut tx,
+                audit.actor_id,
+                "group_deleted",
+                json!({ "group_id": id }),
+            )
+            .await?;
+        tx.commit().await.map_err(map_db_error)?;
+        Ok(())
+    }
+
+    async fn members(&self, id: Uuid) -> Result<GroupMembers, DomainError> {
+        let users = sqlx::query_as::<_, DbUser>(&format!(
+            "SELECT {USER_COLUMNS} FROM group_user_members JOIN users ON users.id = group_user_members.user_id WHERE group_user_members.group_id = $1 ORDER BY users.username"
+        ))
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_db_error)?
+        .into_iter()
+        .map(|row| User::try_from(row).map_err(DomainError::Internal))
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let groups = sqlx::query_as::<_, DbGroup>(&format!(
+            "SELECT {GROUP_COLUMNS} FROM group_group_members JOIN groups ON groups.id = group_group_members.member_group_id WHERE group_group_members.group_id = $1 ORDER BY groups.name"
+        ))
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_db_error)?
+        .into_iter()
+        .map(Group::from)
+        .collect();
+
+        Ok(GroupMembers { users, groups })
+    }
+
+    async fn add_user(
+        &self,
+        group_id: Uuid,
+        user_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError> {
+        self.insert_edge(
+            "INSERT INTO group_user_members (group_id, user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            group_id,
+            user_id,
+            Impact {
+                users: Affected::User(user_id),
+                change: "group_member_added",
+                metadata: json!({ "group_id": group_id }),
+            },
+            audit,
+        )
+        .await
+    }
+
+    async fn remove_user(
+        &self,
+        group_id: Uuid,
+        user_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError> {
+        self.delete_edge(
+            "DELETE FROM group_user_members WHERE group_id = $1 AND user_id = $2",
+            group_id,
+            user_id,
+            "group member not found",
+            Impact {
+                users: Affected::User(user_id),
+                change: "group_member_removed",
+                metadata: json!({ "group_id": group_id }),
+            },
+            audit,
+        )
+        .await
+    }
+
+    async fn add_group(
+        &self,
+        group_id: Uuid,
+        member_group_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+
+        // Serialize nesting changes so two concurrent inserts cannot close a cycle together.
+        sqlx::query("LOCK TABLE group_group_members IN SHARE ROW EXCLUSIVE MODE")
+            .execute(&mut *tx)
+            .await
+            .map_err(map_db_error)?;
+
+        let creates_cycle = sqlx::query_scalar::<_, bool>(
+            "WITH RECURSIVE descendants (id) AS ( \
+                SELECT $2::UUID \
+                UNION \
+                SELECT group_group_members.member_group_id FROM group_group_members JOIN descendants ON group_group_members.group_id = descendants.id \
+            ) SELECT EXISTS (SELECT 1 FROM descendants WHERE id = $1)",
+        )
+        .bind(group_id)
+        .bind(member_group_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(map_db_error)?;
+
+        if creates_cycle {
+            return Err(DomainError::Conflict(
+                "group nesting would create a cycle".to_string(),
+            ));
+        }
+
+        let snapshot = Affected::MembersOf(member_group_id)
+            .snapshot(&mut tx)
+            .await?;
+        sqlx::query(
+            "INSERT INTO group_group_members (group_id, member_group_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        )
+        .bind(group_id)
+        .bind(member_group_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(map_db_error)?;
+
+        snapshot
+            .publish(
+                &mut tx,
+                audit.actor_id,
+                "group_member_added",
+                json!({ "group_id": group_id, "member_group_id": member_group_id }),
+            )
+            .await?;
+        tx.commit().await.map_err(map_db_error)?;
+        Ok(())
+    }
+
+    async fn remove_group(
+        &self,
+        group_id: Uuid,
+        member_group_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError> {
+        self.delete_edge(
+            "DELETE FROM group_group_members WHERE group_id = $1 AND member_group_id = $2",
+            group_id,
+            member_group_id,
+            "nested group not found",
+            Impact {
+                users: Affected::MembersOf(member_group_id),
+                change: "group_member_removed",
+                metadata: json!({ "group_id": group_id, "member_group_id": member_group_id }),
+            },
+            audit,
+        )
+        .await
+    }
+
+    async fn assign_role(
+        &self,
+        group_id: Uuid,
+        role_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError> {
+        self.insert_edge(
+            "INSERT INTO group_roles (group_id, role_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            group_id,
+            role_id,
+            Impact {
+                users: Affected::MembersOf(group_id),
+                change: "group_role_assigned",
+                metadata: json!({ "group_id": group_id, "role_id": role_id }),
+            },
+            audit,
+        )
+        .await
+    }
+
+    async fn unassign_role(
+        &self,
+        group_id: Uuid,
+        role_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError> {
+        self.delete_edge(
+            "DELETE FROM group_roles WHERE group_id = $1 AND role_id = $2",
+            group_id,
+            role_id,
+            "group role not found",
+            Impact {
+                users: Affected::MembersOf(group_id),
+                change: "group_role_unassigned",
+                metadata: json!({ "group_id": group_id, "role_id": role_id }),
+            },
+            audit,
+        )
+        .await
+    }
+
+    async fn effective_groups(&self, user_id: Uuid) -> Result<Vec<Group>, DomainError> {
+        let rows = sqlx::query_as::<_, DbGroup>(&format!(
+            "SELECT {GROUP_COLUMNS} FROM groups WHERE groups.id IN (SELECT group_id FROM user_effective_groups($1)) ORDER BY groups.name"
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        Ok(rows.into_iter().map(Group::from).collect())
+    }
+}
@@ -0,0 +1,169 @@
+use crate::domain::{DomainError, Invitation, InvitationRepository, Membership, NewInvitation};
+use crate::infra::db::map_db_error;
+use crate::infra::db::models::{DbInvitation, DbMembership};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const INVITATION_COLUMNS: &str = "id, org_id, email, role, invited_by, nonce, expires_at, \
+    accepted_at, accepted_by, revoked_at, created_at, updated_at";
+
+#[derive(Clone)]
+pub struct SqlxInvitationRepository {
+    pool: PgPool,
+}
+
+impl SqlxInvitationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    fn map_db_invitation(row: DbInvitation) -> Result<Invitation, DomainError> {
+        Invitation::try_from(row).map_err(DomainError::Internal)
+    }
+}
+
+#[async_trait]
+impl InvitationRepository for SqlxInvitationRepository {
+    async fn create(&self, input: NewInvitation) -> Result<Invitation, DomainError> {
+        let row = sqlx::query_as::<_, DbInvitation>(&format!(
+            "INSERT INTO invitations (id, org_id, email, role, invited_by, nonce, expires_at) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING {INVITATION_COLUMNS}"
+        ))
+        .bind(Uuid::new_v4())
+        .bind(input.org_id)
+        .bind(input.email)
+        .bind(input.role.to_string())
+        .bind(input.invited_by)
+        .bind(Uuid::new_v4())
+        .bind(input.expires_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        Self::map_db_invitation(row)
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Invitation>, DomainError> {
+        let result = sqlx::query_as::<_, DbInvitation>(&format!(
+            "SELECT {INVITATION_COLUMNS} FROM invitations WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        result.map(Self::map_db_invitation).transpose()
+    }
+
+    async fn find_open(
+        &self,
+        org_id: Uuid,
+        email: &str,
+    ) -> Result<Option<Invitation>, DomainError> {
+        let result = sqlx::query_as::<_, DbInvitation>(&format!(
+            "SELECT {INVITATION_COLUMNS} FROM invitations WHERE org_id = $1 AND LOWER(email) = LOWER($2) AND accepted_at IS NULL AND revoked_at IS NULL"
+        ))
+        .bind(org_id)
+        .bind(email)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        result.map(Self::map_db_invitation).transpose()
+    }
+
+    async fn list_open(&self, org_id: Uuid) -> Result<Vec<Invitation>, DomainError> {
+        let rows = sqlx::query_as::<_, DbInvitation>(&format!(
+            "SELECT {INVITATION_COLUMNS} FROM invitations WHERE org_id = $1 AND accepted_at IS NULL AND revoked_at IS NULL ORDER BY created_at DESC"
+        ))
+        .bind(org_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        rows.into_iter()
+            .map(Self::map_db_invitation)
+            .collect::<Result<Vec<_>, _>>()
+    }
+
+    async fn renew(&self, id: Uuid, expires_at: DateTime<Utc>) -> Result<Invitation, DomainError> {
+        let result = sqlx::query_as::<_, DbInvitation>(&format!(
+            "UPDATE invitations SET nonce = $1, expires_at = $2, updated_at = NOW() WHERE id = $3 AND accepted_at IS NULL AND revoked_at IS NULL RETURNING {INVITATION_COLUMNS}"
+        ))
+        .bind(Uuid::new_v4())
+        .bind(expires_at)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        match result {
+            Some(row) => Self::map_db_invitation(row),
+            None => Err(DomainError::NotFound("invitation not found".to_string())),
+        }
+    }
+
+    async fn revoke(&self, id: Uuid) -> Result<(), DomainError> {
+        let affected = sqlx::query(
+            "UPDATE invitations SET revoked_at = NOW(), updated_at = NOW() WHERE id = $1 AND accepted_at IS NULL AND revoked_at IS NULL",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(map_db_error)?
+        .rows_affected();
+
+        if affected == 0 {
+            return Err(DomainError::NotFound("invitation not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn accept(
+        &self,
+        id: Uuid,
+        nonce: Uuid,
+        user_id: Uuid,
+    ) -> Result<Membership, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+
+        // The guarded update doubles as a lock: a concurrent accept or revoke finds no row.
+        let invitation = sqlx::query_as::<_, DbInvitation>(&format!(
+            "UPDATE invitations SET accepted_at = NOW(), accepted_by = $1, updated_at = NOW() \
+             WHERE id = $2 AND nonce = $3 AND accepted_at IS NULL AND revoked_at IS NULL AND expires_at > NOW() \
+             RETURNING {INVITATION_COLUMNS}"
+        ))
+        .bind(user_id)
+        .bind(id)
+        .bind(nonce)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(map_db_error)?
+        .ok_or_else(|| DomainError::Unauthorized("invitation is no longer valid".to_string()))?;
+
+        let membership = sqlx::query_as::<_, DbMembership>(
+            "INSERT INTO memberships (org_id, user_id, role) VALUES ($1, $2, $3) RETURNING org_id, user_id, role, created_at, updated_at",
+        )
+        .bind(invitation.org_id)
+        .bind(user_id)
+        .bind(&invitation.role)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(map_db_error)?;
+
+        sqlx::query(
+            "UPDATE users SET email_verified_at = COALESCE(email_verified_at, NOW()), updated_at = NOW() WHERE id = $1 AND LOWER(email) = LOWER($2)",
+        )
+        .bind(user_id)
+        .bind(&invitation.email)
+        .execute(&mut *tx)
+        .await
+        .map_err(map_db_error)?;
+
+        tx.commit().await.map_err(map_db_error)?;
+
+        Membership::try_from(membership).map_err(DomainError::Internal)
+    }
+}
@@ -1,7 +1,22 @@
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use crate::domain::{DomainError, DomainEvent, Role};
+use crate::infra::db::outbox_repo::SqlxOutboxRepository;
+use serde_json::{json, Value};
+use sqlx::{postgres::PgPoolOptions, PgConnection, PgPool};
+use std::collections::BTreeMap;
+use uuid::Uuid;
 
+pub mod attribute_repo;
+pub mod audit_repo;
+pub mod elevation_repo;
+pub mod email_change_repo;
+pub mod group_repo;
+pub mod invitation_repo;
 pub mod models;
+pub mod org_repo;
+pub mod outbox_repo;
+pub mod role_repo;
 pub mod user_repo;
+pub mod webhook_repo;
 
 pub type DbPool = PgPool;
 
@@ -10,4 +25,128 @@ pub async fn create_pool(database_url: &str) -> Result<DbPool, sqlx::Error> {
         .max_connections(10)
         .connect(database_url)
         .await
-}
\ No newline at end of file
+}
+
+pub(crate) fn map_db_error(error: sqlx::Error) -> DomainError {
+    if let sqlx::Error::Database(db_error) = &error {
+        match db_error.code().as_deref() {
+            Some("23505") => return DomainError::Conflict("resource already exists".to_string()),
+            Some("23503") => {
+                return DomainError::NotFound("referenced resource not found".to_string())
+            }
+            _ => {}
+        }
+    }
+    DomainError::Internal(error.to_string())
+}
+
+/// Advisory lock key shared by every transaction that can take administrator access away.
+const ADMIN_GUARD_LOCK_KEY: i64 = 0x6164_6d69_6e73;
+
+/// Applies [`Role::ensure_admin_remains`] to a transaction. Acquire it at the start of a
+/// transaction that may deactivate an admin or strip the `admin` role (directly or
+/// through groups), and call [`AdminGuard::verify`] before committing. Only permanent
+/// grants count, since time-bound ones lapse on their own.
+pub(crate) struct AdminGuard {
+    admins_before: i64,
+}
+
+impl AdminGuard {
+    pub(crate) async fn acquire(conn: &mut PgConnection) -> Result<Self, DomainError> {
+        sqlx::query("SELECT pg_advisory_xact_lock($1)")
+            .bind(ADMIN_GUARD_LOCK_KEY)
+            .execute(&mut *conn)
+            .await
+            .map_err(map_db_error)?;
+
+        let admins_before = Self::count_active_admins(conn).await?;
+        Ok(Self { admins_before })
+    }
+
+    pub(crate) async fn verify(self, conn: &mut PgConnection) -> Result<(), DomainError> {
+        let admins_after = Self::count_active_admins(conn).await?;
+        Role::ensure_admin_remains(self.admins_before, admins_after)
+    }
+
+    async fn count_active_admins(conn: &mut PgConnection) -> Result<i64, DomainError> {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM users \
+                WHERE user_effective_status(users.status, users.status_until) = 'active' \
+                AND EXISTS ( \
+                SELECT 1 FROM user_effective_role_ids(users.id, FALSE) AS effective \
+                JOIN roles ON roles.id = effective.role_id WHERE roles.name = $1)",
+        )
+        .bind(Role::ADMIN)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(map_db_error)
+    }
+}
+
+/// Effective roles of some users, taken before a change that may alter them without an
+/// assignment of their own (group edits, role deletion). [`RoleSnapshot::publish`] then
+/// writes `user.role_changed` to the outbox, in the same transaction, for every user whose
+/// roles did change.
+pub(crate) struct RoleSnapshot {
+    roles: BTreeMap<Uuid, Vec<String>>,
+}
+
+impl RoleSnapshot {
+    pub(crate) async fn take(
+        conn: &mut PgConnection,
+        user_ids: &[Uuid],
+    ) -> Result<Self, DomainError> {
+        let roles = Self::effective_roles(conn, user_ids).await?;
+        Ok(Self { roles })
+    }
+
+    pub(crate) async fn publish(
+        self,
+        conn: &mut PgConnection,
+        actor_id: Option<Uuid>,
+        change: &str,
+        metadata: Value,
+    ) -> Result<(), DomainError> {
+        let user_ids: Vec<Uuid> = self.roles.keys().copied().collect();
+        let mut after = Self::effective_roles(conn, &user_ids).await?;
+
+        for (user_id, before) in self.roles {
+            let after = after.remove(&user_id).unwrap_or_default();
+            if before == after {
+                continue;
+            }
+
+            let event = DomainEvent::role_changed(
+                user_id,
+                actor_id,
+                json!({
+                    "change": change,
+                    "before": { "roles": before },
+                    "after": { "roles": after },
+                    "metadata": metadata,
+                }),
+            );
+            SqlxOutboxRepository::insert(conn, &event).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn effective_roles(
+        conn: &mut PgConnection,
+        user_ids: &[Uuid],
+    ) -> Result<BTreeMap<Uuid, Vec<String>>, DomainError> {
+        let rows = sqlx::query_as::<_, (Uuid, Vec<String>)>(
+            "SELECT users.id, ARRAY( \
+                SELECT roles.name FROM user_effective_role_ids(users.id) AS effective \
+                JOIN roles ON roles.id = effective.role_id ORDER BY roles.name) \
+             FROM users WHERE users.id = ANY($1)",
+        )
+        .bind(user_ids)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(map_db_error)?;
+
+        Ok(rows.into_iter().collect())
+    }
+}
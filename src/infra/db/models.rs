@@ -1,5 +1,11 @@
-use crate::domain::{Role, User};
+use crate::domain::{
+    AccountState, AccountStatus, AttributeDefinition, AttributeType, AuditEvent, AuditEventType,
+    DeliveryStatus, DomainEventType, ElevationRequest, ElevationStatus, EmailChange, Group,
+    Invitation, Member, Membership, OrgRole, Organization, Permission, Role, RoleAssignmentAction,
+    RoleAssignmentEvent, User, UserProfile, WebhookDelivery, WebhookEndpoint,
+};
 use chrono::{DateTime, Utc};
+use serde_json::Value;
 use sqlx::FromRow;
 use std::str::FromStr;
 use uuid::Uuid;
@@ -10,8 +16,23 @@ pub struct DbUser {
     pub email: String,
     pub username: String,
     pub password_hash: String,
-    pub role: String,
-    pub is_active: bool,
+    pub roles: Vec<String>,
+    pub permissions: Vec<String>,
+    pub groups: Vec<String>,
+    pub roles_expire_at: Option<DateTime<Utc>>,
+    pub status: String,
+    pub status_reason: Option<String>,
+    pub status_until: Option<DateTime<Utc>>,
+    pub status_changed_at: DateTime<Utc>,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    pub pending_email: Option<String>,
+    pub display_name: Option<String>,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub avatar_url: Option<String>,
+    pub attributes: Value,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -20,13 +41,452 @@ impl TryFrom<DbUser> for User {
     type Error = String;
 
     fn try_from(value: DbUser) -> Result<Self, Self::Error> {
-        let role = Role::from_str(&value.role)?;
+        let permissions = value
+            .permissions
+            .iter()
+            .map(|permission| Permission::from_str(permission))
+            .collect::<Result<Vec<_>, _>>()?;
+        let attributes = match value.attributes {
+            Value::Object(attributes) => attributes,
+            other => return Err(format!("invalid user attributes: {other}")),
+        };
+        let status = AccountStatus {
+            state: AccountState::from_str(&value.status)?,
+            reason: value.status_reason,
+            until: value.status_until,
+            changed_at: value.status_changed_at,
+        };
         Ok(User {
             id: value.id,
             email: value.email,
             username: value.username,
+            roles: value.roles,
+            permissions,
+            groups: value.groups,
+            roles_expire_at: value.roles_expire_at,
+            status,
+            email_verified_at: value.email_verified_at,
+            pending_email: value.pending_email,
+            profile: UserProfile {
+                display_name: value.display_name,
+                given_name: value.given_name,
+                family_name: value.family_name,
+                locale: value.locale,
+                timezone: value.timezone,
+                avatar_url: value.avatar_url,
+            },
+            attributes,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        })
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbRole {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub is_builtin: bool,
+    pub permissions: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<DbRole> for Role {
+    type Error = String;
+
+    fn try_from(value: DbRole) -> Result<Self, Self::Error> {
+        let permissions = value
+            .permissions
+            .iter()
+            .map(|permission| Permission::from_str(permission))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Role {
+            id: value.id,
+            name: value.name,
+            description: value.description,
+            is_builtin: value.is_builtin,
+            permissions,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        })
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbRoleAssignmentEvent {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub role_id: Uuid,
+    pub role_name: String,
+    pub action: String,
+    pub actor_id: Option<Uuid>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<DbRoleAssignmentEvent> for RoleAssignmentEvent {
+    type Error = String;
+
+    fn try_from(value: DbRoleAssignmentEvent) -> Result<Self, Self::Error> {
+        let action = RoleAssignmentAction::from_str(&value.action)?;
+        Ok(RoleAssignmentEvent {
+            id: value.id,
+            user_id: value.user_id,
+            role_id: value.role_id,
+            role_name: value.role_name,
+            action,
+            actor_id: value.actor_id,
+            expires_at: value.expires_at,
+            created_at: value.created_at,
+        })
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbOrganization {
+    pub id: Uuid,
+    pub name: String,
+    pub slug: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<DbOrganization> for Organization {
+    fn from(value: DbOrganization) -> Self {
+        Organization {
+            id: value.id,
+            name: value.name,
+            slug: value.slug,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbOrganizationWithRole {
+    #[sqlx(flatten)]
+    pub organization: DbOrganization,
+    pub org_role: String,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbMembership {
+    pub org_id: Uuid,
+    pub user_id: Uuid,
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<DbMembership> for Membership {
+    type Error = String;
+
+    fn try_from(value: DbMembership) -> Result<Self, Self::Error> {
+        let role = OrgRole::from_str(&value.role)?;
+        Ok(Membership {
+            org_id: value.org_id,
+            user_id: value.user_id,
+            role,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        })
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbMember {
+    #[sqlx(flatten)]
+    pub user: DbUser,
+    pub org_role: String,
+    pub joined_at: DateTime<Utc>,
+}
+
+impl TryFrom<DbMember> for Member {
+    type Error = String;
+
+    fn try_from(value: DbMember) -> Result<Self, Self::Error> {
+        let role = OrgRole::from_str(&value.org_role)?;
+        Ok(Member {
+            user: User::try_from(value.user)?,
             role,
+            joined_at: value.joined_at,
+        })
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbGroup {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub roles: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<DbGroup> for Group {
+    fn from(value: DbGroup) -> Self {
+        Group {
+            id: value.id,
+            name: value.name,
+            description: value.description,
+            roles: value.roles,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbInvitation {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub email: String,
+    pub role: String,
+    pub invited_by: Option<Uuid>,
+    pub nonce: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub accepted_by: Option<Uuid>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<DbInvitation> for Invitation {
+    type Error = String;
+
+    fn try_from(value: DbInvitation) -> Result<Self, Self::Error> {
+        let role = OrgRole::from_str(&value.role)?;
+        Ok(Invitation {
+            id: value.id,
+            org_id: value.org_id,
+            email: value.email,
+            role,
+            invited_by: value.invited_by,
+            nonce: value.nonce,
+            expires_at: value.expires_at,
+            accepted_at: value.accepted_at,
+            accepted_by: value.accepted_by,
+            revoked_at: value.revoked_at,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        })
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbEmailChange {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub old_email: String,
+    pub new_email: String,
+    pub nonce: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub revert_expires_at: DateTime<Utc>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub reverted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<DbEmailChange> for EmailChange {
+    fn from(value: DbEmailChange) -> Self {
+        EmailChange {
+            id: value.id,
+            user_id: value.user_id,
+            old_email: value.old_email,
+            new_email: value.new_email,
+            nonce: value.nonce,
+            expires_at: value.expires_at,
+            revert_expires_at: value.revert_expires_at,
+            confirmed_at: value.confirmed_at,
+            cancelled_at: value.cancelled_at,
+            reverted_at: value.reverted_at,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbElevationRequest {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub role_id: Uuid,
+    pub role_name: String,
+    pub duration_minutes: i64,
+    pub reason: String,
+    pub status: String,
+    pub decided_by: Option<Uuid>,
+    pub decision_note: Option<String>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub grant_expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<DbElevationRequest> for ElevationRequest {
+    type Error = String;
+
+    fn try_from(value: DbElevationRequest) -> Result<Self, Self::Error> {
+        let status = ElevationStatus::from_str(&value.status)?;
+        Ok(ElevationRequest {
+            id: value.id,
+            user_id: value.user_id,
+            role_id: value.role_id,
+            role_name: value.role_name,
+            duration_minutes: value.duration_minutes,
+            reason: value.reason,
+            status,
+            decided_by: value.decided_by,
+            decision_note: value.decision_note,
+            decided_at: value.decided_at,
+            grant_expires_at: value.grant_expires_at,
+            created_at: value.created_at,
+        })
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbAuditEvent {
+    pub id: Uuid,
+    pub event_type: String,
+    pub actor_id: Option<Uuid>,
+    pub impersonator_id: Option<Uuid>,
+    pub target_user_id: Option<Uuid>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub metadata: Option<Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<DbAuditEvent> for AuditEvent {
+    type Error = String;
+
+    fn try_from(value: DbAuditEvent) -> Result<Self, Self::Error> {
+        Ok(AuditEvent {
+            id: value.id,
+            event_type: AuditEventType::from_str(&value.event_type)?,
+            actor_id: value.actor_id,
+            impersonator_id: value.impersonator_id,
+            target_user_id: value.target_user_id,
+            ip: value.ip,
+            user_agent: value.user_agent,
+            request_id: value.request_id,
+            before: value.before,
+            after: value.after,
+            metadata: value.metadata,
+            created_at: value.created_at,
+        })
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbWebhookEndpoint {
+    pub id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub event_types: Vec<String>,
+    pub description: Option<String>,
+    pub is_active: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<DbWebhookEndpoint> for WebhookEndpoint {
+    type Error = String;
+
+    fn try_from(value: DbWebhookEndpoint) -> Result<Self, Self::Error> {
+        let event_types = value
+            .event_types
+            .iter()
+            .map(|event_type| DomainEventType::from_str(event_type))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(WebhookEndpoint {
+            id: value.id,
+            url: value.url,
+            secret: value.secret,
+            event_types,
+            description: value.description,
             is_active: value.is_active,
+            created_by: value.created_by,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        })
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbWebhookDelivery {
+    pub id: Uuid,
+    pub endpoint_id: Uuid,
+    pub event_type: String,
+    pub payload: Value,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<DbWebhookDelivery> for WebhookDelivery {
+    type Error = String;
+
+    fn try_from(value: DbWebhookDelivery) -> Result<Self, Self::Error> {
+        Ok(WebhookDelivery {
+            id: value.id,
+            endpoint_id: value.endpoint_id,
+            event_type: DomainEventType::from_str(&value.event_type)?,
+            payload: value.payload,
+            status: DeliveryStatus::from_str(&value.status)?,
+            attempts: value.attempts,
+            next_attempt_at: value.next_attempt_at,
+            last_status_code: value.last_status_code,
+            last_error: value.last_error,
+            delivered_at: value.delivered_at,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        })
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbAttributeDefinition {
+    pub id: Uuid,
+    pub name: String,
+    pub attribute_type: String,
+    pub description: Option<String>,
+    pub is_required: bool,
+    pub is_unique: bool,
+    pub is_indexed: bool,
+    pub user_editable: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<DbAttributeDefinition> for AttributeDefinition {
+    type Error = String;
+
+    fn try_from(value: DbAttributeDefinition) -> Result<Self, Self::Error> {
+        Ok(AttributeDefinition {
+            id: value.id,
+            name: value.name,
+            attribute_type: AttributeType::from_str(&value.attribute_type)?,
+            description: value.description,
+            required: value.is_required,
+            unique: value.is_unique,
+            indexed: value.is_indexed,
+            user_editable: value.user_editable,
             created_at: value.created_at,
             updated_at: value.updated_at,
         })
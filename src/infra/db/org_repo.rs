@@ -0,0 +1,243 @@
+use crate::domain::{
+    DomainError, Member, Membership, NewOrganization, OrgRole, Organization,
+    OrganizationRepository, UpdateOrganization,
+};
+use crate::infra::db::map_db_error;
+use crate::infra::db::models::{DbMember, DbMembership, DbOrganization, DbOrganizationWithRole};
+use crate::infra::db::user_repo::USER_COLUMNS;
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct SqlxOrganizationRepository {
+    pool: PgPool,
+}
+
+impl SqlxOrganizationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    fn map_db_membership(row: DbMembership) -> Result<Membership, DomainError> {
+        Membership::try_from(row).map_err(DomainError::Internal)
+    }
+}
+
+#[async_trait]
+impl OrganizationRepository for SqlxOrganizationRepository {
+    async fn create(
+        &self,
+        input: NewOrganization,
+        owner_id: Uuid,
+    ) -> Result<Organization, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+
+        let org = sqlx::query_as::<_, DbOrganization>(
+            "INSERT INTO organizations (id, name, slug) VALUES ($1, $2, $3) RETURNING id, name, slug, created_at, updated_at",
+        )
+        .bind(Uuid::new_v4())
+        .bind(input.name)
+        .bind(input.slug)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(map_db_error)?;
+
+        sqlx::query("INSERT INTO memberships (org_id, user_id, role) VALUES ($1, $2, $3)")
+            .bind(org.id)
+            .bind(owner_id)
+            .bind(OrgRole::Owner.to_string())
+            .execute(&mut *tx)
+            .await
+            .map_err(map_db_error)?;
+
+        tx.commit().await.map_err(map_db_error)?;
+        Ok(org.into())
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Organization>, DomainError> {
+        let result = sqlx::query_as::<_, DbOrganization>(
+            "SELECT id, name, slug, created_at, updated_at FROM organizations WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        Ok(result.map(Organization::from))
+    }
+
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<Organization>, DomainError> {
+        let result = sqlx::query_as::<_, DbOrganization>(
+            "SELECT id, name, slug, created_at, updated_at FROM organizations WHERE slug = $1",
+        )
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        Ok(result.map(Organization::from))
+    }
+
+    async fn list_for_user(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<(Organization, OrgRole)>, DomainError> {
+        let rows = sqlx::query_as::<_, DbOrganizationWithRole>(
+            "SELECT organizations.id, organizations.name, organizations.slug, organizations.created_at, organizations.updated_at, memberships.role AS org_role FROM organizations JOIN memberships ON memberships.org_id = organizations.id WHERE memberships.user_id = $1 ORDER BY organizations.name",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        rows.into_iter()
+            .map(|row| {
+                let role = OrgRole::from_str(&row.org_role).map_err(DomainError::Internal)?;
+                Ok((Organization::from(row.organization), role))
+            })
+            .collect()
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        input: UpdateOrganization,
+    ) -> Result<Organization, DomainError> {
+        let result = sqlx::query_as::<_, DbOrganization>(
+            "UPDATE organizations SET name = COALESCE($1, name), slug = COALESCE($2, slug), updated_at = NOW() WHERE id = $3 RETURNING id, name, slug, created_at, updated_at",
+        )
+        .bind(input.name)
+        .bind(input.slug)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        result
+            .map(Organization::from)
+            .ok_or_else(|| DomainError::NotFound("organization not found".to_string()))
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<(), DomainError> {
+        let affected = sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(map_db_error)?
+            .rows_affected();
+
+        if affected == 0 {
+            return Err(DomainError::NotFound("organization not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn find_membership(
+        &self,
+        org_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<Membership>, DomainError> {
+        let result = sqlx::query_as::<_, DbMembership>(
+            "SELECT org_id, user_id, role, created_at, updated_at FROM memberships WHERE org_id = $1 AND user_id = $2",
+        )
+        .bind(org_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        result.map(Self::map_db_membership).transpose()
+    }
+
+    async fn add_member(
+        &self,
+        org_id: Uuid,
+        user_id: Uuid,
+        role: OrgRole,
+    ) -> Result<Membership, DomainError> {
+        let row = sqlx::query_as::<_, DbMembership>(
+            "INSERT INTO memberships (org_id, user_id, role) VALUES ($1, $2, $3) RETURNING org_id, user_id, role, created_at, updated_at",
+        )
+        .bind(org_id)
+        .bind(user_id)
+        .bind(role.to_string())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        Self::map_db_membership(row)
+    }
+
+    async fn set_member_role(
+        &self,
+        org_id: Uuid,
+        user_id: Uuid,
+        role: OrgRole,
+    ) -> Result<Membership, DomainError> {
+        let result = sqlx::query_as::<_, DbMembership>(
+            "UPDATE memberships SET role = $1, updated_at = NOW() WHERE org_id = $2 AND user_id = $3 RETURNING org_id, user_id, role, created_at, updated_at",
+        )
+        .bind(role.to_string())
+        .bind(org_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        match result {
+            Some(row) => Self::map_db_membership(row),
+            None => Err(DomainError::NotFound("membership not found".to_string())),
+        }
+    }
+
+    async fn remove_member(&self, org_id: Uuid, user_id: Uuid) -> Result<(), DomainError> {
+        let affected = sqlx::query("DELETE FROM memberships WHERE org_id = $1 AND user_id = $2")
+            .bind(org_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(map_db_error)?
+            .rows_affected();
+
+        if affected == 0 {
+            return Err(DomainError::NotFound("membership not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn count_owners(&self, org_id: Uuid) -> Result<i64, DomainError> {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM memberships WHERE org_id = $1 AND role = $2",
+        )
+        .bind(org_id)
+        .bind(OrgRole::Owner.to_string())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(map_db_error)
+    }
+
+    async fn list_members(
+        &self,
+        org_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Member>, DomainError> {
+        let rows = sqlx::query_as::<_, DbMember>(&format!(
+            "SELECT {USER_COLUMNS}, memberships.role AS org_role, memberships.created_at AS joined_at FROM memberships JOIN users ON users.id = memberships.user_id WHERE memberships.org_id = $1 ORDER BY memberships.created_at DESC LIMIT $2 OFFSET $3"
+        ))
+        .bind(org_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        rows.into_iter()
+            .map(|row| Member::try_from(row).map_err(DomainError::Internal))
+            .collect()
+    }
+}
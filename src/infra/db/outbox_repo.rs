@@ -0,0 +1,134 @@
+use crate::domain::{DomainError, DomainEvent, OutboxMessage, OutboxRepository};
+use crate::infra::db::map_db_error;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{FromRow, PgConnection, PgPool};
+use uuid::Uuid;
+
+#[derive(FromRow)]
+struct DbOutboxMessage {
+    position: i64,
+    payload: Value,
+    attempts: i32,
+}
+
+#[derive(Clone)]
+pub struct SqlxOutboxRepository {
+    pool: PgPool,
+}
+
+impl SqlxOutboxRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Writes `event` on `conn`, inside the transaction of the change it describes.
+    pub(crate) async fn insert(
+        conn: &mut PgConnection,
+        event: &DomainEvent,
+    ) -> Result<(), DomainError> {
+        let payload =
+            serde_json::to_value(event).map_err(|err| DomainError::Internal(err.to_string()))?;
+
+        sqlx::query("INSERT INTO outbox (event_id, event_type, payload) VALUES ($1, $2, $3)")
+            .bind(event.id)
+            .bind(event.event_type.as_str())
+            .bind(payload)
+            .execute(&mut *conn)
+            .await
+            .map_err(map_db_error)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OutboxRepository for SqlxOutboxRepository {
+    async fn claim(
+        &self,
+        limit: i64,
+        lease_until: DateTime<Utc>,
+    ) -> Result<Vec<OutboxMessage>, DomainError> {
+        let rows = sqlx::query_as::<_, DbOutboxMessage>(
+            "WITH claimed AS ( \
+                 UPDATE outbox SET available_at = $1 \
+                 WHERE position IN ( \
+                     SELECT position FROM outbox \
+                     WHERE published_at IS NULL AND available_at <= NOW() \
+                     ORDER BY position LIMIT $2 \
+                     FOR UPDATE SKIP LOCKED \
+                 ) RETURNING position, payload, attempts \
+             ) SELECT position, payload, attempts FROM claimed ORDER BY position",
+        )
+        .bind(lease_until)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        rows.into_iter()
+            .map(|row| {
+                let event = serde_json::from_value(row.payload)
+                    .map_err(|err| DomainError::Internal(err.to_string()))?;
+                Ok(OutboxMessage {
+                    position: row.position,
+                    event,
+                    attempts: row.attempts,
+                })
+            })
+            .collect()
+    }
+
+    async fn mark_published(&self, position: i64) -> Result<(), DomainError> {
+        sqlx::query("UPDATE outbox SET published_at = NOW(), attempts = attempts + 1, last_error = NULL WHERE position = $1")
+            .bind(position)
+            .execute(&self.pool)
+            .await
+            .map_err(map_db_error)?;
+
+        Ok(())
+    }
+
+    async fn mark_failed(
+        &self,
+        position: i64,
+        error: &str,
+        retry_at: DateTime<Utc>,
+    ) -> Result<(), DomainError> {
+        sqlx::query("UPDATE outbox SET available_at = $1, attempts = attempts + 1, last_error = $2 WHERE position = $3")
+            .bind(retry_at)
+            .bind(error)
+            .bind(position)
+            .execute(&self.pool)
+            .await
+            .map_err(map_db_error)?;
+
+        Ok(())
+    }
+
+    async fn events_after(
+        &self,
+        event_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<DomainEvent>, DomainError> {
+        let payloads: Vec<(Value,)> = sqlx::query_as(
+            "SELECT payload FROM outbox \
+             WHERE position > (SELECT position FROM outbox WHERE event_id = $1) \
+             ORDER BY position LIMIT $2",
+        )
+        .bind(event_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        payloads
+            .into_iter()
+            .map(|(payload,)| {
+                serde_json::from_value(payload)
+                    .map_err(|err| DomainError::Internal(err.to_string()))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,519 @@
+use crate::domain::audit::role_snapshot;
+use crate::domain::{
+    AuditContext, AuditEventType, DomainError, DomainEvent, NewRole, Permission, Role,
+    RoleAssignmentAction, RoleAssignmentEvent, RoleRepository, UpdateRole,
+};
+use crate::infra::db::audit_repo::SqlxAuditRepository;
+use crate::infra::db::models::{DbRole, DbRoleAssignmentEvent};
+use crate::infra::db::outbox_repo::SqlxOutboxRepository;
+use crate::infra::db::{map_db_error, AdminGuard, RoleSnapshot};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+const ROLE_COLUMNS: &str = "roles.id, roles.name, roles.description, roles.is_builtin, \
+    ARRAY(SELECT role_permissions.permission FROM role_permissions WHERE role_permissions.role_id = roles.id ORDER BY 1) AS permissions, \
+    roles.created_at, roles.updated_at";
+
+#[derive(Clone)]
+pub struct SqlxRoleRepository {
+    pool: PgPool,
+}
+
+impl SqlxRoleRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    fn map_db_role(db_role: DbRole) -> Result<Role, DomainError> {
+        Role::try_from(db_role).map_err(DomainError::Internal)
+    }
+
+    async fn fetch_role(conn: &mut PgConnection, id: Uuid) -> Result<Role, DomainError> {
+        let row = sqlx::query_as::<_, DbRole>(&format!(
+            "SELECT {ROLE_COLUMNS} FROM roles WHERE roles.id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(map_db_error)?
+        .ok_or_else(|| DomainError::NotFound("role not found".to_string()))?;
+
+        Self::map_db_role(row)
+    }
+
+    async fn lock_role(conn: &mut PgConnection, id: Uuid) -> Result<Role, DomainError> {
+        let row = sqlx::query_as::<_, DbRole>(&format!(
+            "SELECT {ROLE_COLUMNS} FROM roles WHERE roles.id = $1 FOR UPDATE"
+        ))
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(map_db_error)?
+        .ok_or_else(|| DomainError::NotFound("role not found".to_string()))?;
+
+        Self::map_db_role(row)
+    }
+
+    async fn replace_permissions(
+        conn: &mut PgConnection,
+        id: Uuid,
+        permissions: &[Permission],
+    ) -> Result<(), DomainError> {
+        let permissions: Vec<String> = permissions.iter().map(|p| p.to_string()).collect();
+
+        sqlx::query("DELETE FROM role_permissions WHERE role_id = $1")
+            .bind(id)
+            .execute(&mut *conn)
+            .await
+            .map_err(map_db_error)?;
+
+        sqlx::query(
+            "INSERT INTO role_permissions (role_id, permission) SELECT $1, UNNEST($2::TEXT[]) ON CONFLICT DO NOTHING",
+        )
+        .bind(id)
+        .bind(&permissions)
+        .execute(&mut *conn)
+        .await
+        .map_err(map_db_error)?;
+
+        Ok(())
+    }
+
+    async fn record_assignment(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        role_id: Uuid,
+        action: RoleAssignmentAction,
+        actor_id: Option<Uuid>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<(), DomainError> {
+        sqlx::query(
+            "INSERT INTO role_assignment_events (id, user_id, role_id, role_name, action, actor_id, expires_at) SELECT $1, $2, roles.id, roles.name, $3, $4, $5 FROM roles WHERE roles.id = $6",
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(action.to_string())
+        .bind(actor_id)
+        .bind(expires_at)
+        .bind(role_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(map_db_error)?;
+
+        Ok(())
+    }
+
+    /// Records a change to a role definition; `before` is `None` for a new role and
+    /// `after` for a deleted one.
+    async fn audit_definition(
+        conn: &mut PgConnection,
+        audit: &AuditContext,
+        event_type: AuditEventType,
+        before: Option<&Role>,
+        after: Option<&Role>,
+    ) -> Result<(), DomainError> {
+        let snapshot = |role: Option<&Role>| role.map_or_else(|| json!({}), role_snapshot);
+        let mut event = audit
+            .event(event_type, None)
+            .with_diff(&snapshot(before), &snapshot(after));
+        if let Some(role) = after.or(before) {
+            event = event.with_metadata(json!({ "role_id": role.id, "role": role.name }));
+        }
+
+        SqlxAuditRepository::insert(conn, event).await
+    }
+
+    async fn audit_assignment(
+        conn: &mut PgConnection,
+        audit: &AuditContext,
+        event_type: AuditEventType,
+        user_id: Uuid,
+        role_id: Uuid,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<(), DomainError> {
+        let role = Self::fetch_role(conn, role_id).await?;
+        let event = audit.event(event_type, Some(user_id)).with_metadata(json!({
+            "role_id": role.id,
+            "role": role.name,
+            "expires_at": expires_at,
+        }));
+
+        SqlxAuditRepository::insert(conn, event).await
+    }
+
+    /// Upserts a grant and records it. Shared with elevation approvals so that both paths
+    /// apply the same expiry rules inside the caller's transaction.
+    pub(crate) async fn grant(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        role_id: Uuid,
+        actor_id: Option<Uuid>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<bool, DomainError> {
+        // Permanent grants are never shortened; time-bound ones keep the later expiry.
+        let affected = sqlx::query(
+            "INSERT INTO user_roles (user_id, role_id, expires_at) VALUES ($1, $2, $3) \
+             ON CONFLICT (user_id, role_id) DO UPDATE SET expires_at = CASE \
+                 WHEN EXCLUDED.expires_at IS NULL THEN NULL \
+                 ELSE GREATEST(user_roles.expires_at, EXCLUDED.expires_at) END \
+             WHERE user_roles.expires_at IS NOT NULL",
+        )
+        .bind(user_id)
+        .bind(role_id)
+        .bind(expires_at)
+        .execute(&mut *conn)
+        .await
+        .map_err(map_db_error)?
+        .rows_affected();
+
+        if affected == 0 {
+            return Ok(false);
+        }
+
+        Self::record_assignment(
+            conn,
+            user_id,
+            role_id,
+            RoleAssignmentAction::Assigned,
+            actor_id,
+            expires_at,
+        )
+        .await?;
+
+        Ok(true)
+    }
+
+    /// Makes `role_id` the user's only direct, permanent role, recording and auditing every
+    /// grant and revocation. Returns whether anything changed.
+    pub(crate) async fn replace_direct_roles(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        role_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<bool, DomainError> {
+        let removed = sqlx::query_scalar::<_, Uuid>(
+            "DELETE FROM user_roles WHERE user_id = $1 AND role_id <> $2 RETURNING role_id",
+        )
+        .bind(user_id)
+        .bind(role_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(map_db_error)?;
+
+        for removed_id in &removed {
+            Self::record_assignment(
+                conn,
+                user_id,
+                *removed_id,
+                RoleAssignmentAction::Unassigned,
+                audit.actor_id,
+                None,
+            )
+            .await?;
+            Self::audit_assignment(
+                conn,
+                audit,
+                AuditEventType::RoleUnassigned,
+                user_id,
+                *removed_id,
+                None,
+            )
+            .await?;
+        }
+
+        let granted = Self::grant(conn, user_id, role_id, audit.actor_id, None).await?;
+        if granted {
+            Self::audit_assignment(
+                conn,
+                audit,
+                AuditEventType::RoleAssigned,
+                user_id,
+                role_id,
+                None,
+            )
+            .await?;
+        }
+
+        Ok(granted || !removed.is_empty())
+    }
+}
+
+#[async_trait]
+impl RoleRepository for SqlxRoleRepository {
+    async fn list(&self) -> Result<Vec<Role>, DomainError> {
+        let rows = sqlx::query_as::<_, DbRole>(&format!(
+            "SELECT {ROLE_COLUMNS} FROM roles ORDER BY roles.is_builtin DESC, roles.name"
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        rows.into_iter()
+            .map(Self::map_db_role)
+            .collect::<Result<Vec<_>, _>>()
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Role>, DomainError> {
+        let result = sqlx::query_as::<_, DbRole>(&format!(
+            "SELECT {ROLE_COLUMNS} FROM roles WHERE roles.id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        result.map(Self::map_db_role).transpose()
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Option<Role>, DomainError> {
+        let result = sqlx::query_as::<_, DbRole>(&format!(
+            "SELECT {ROLE_COLUMNS} FROM roles WHERE roles.name = $1"
+        ))
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        result.map(Self::map_db_role).transpose()
+    }
+
+    async fn create(&self, new_role: NewRole, audit: &AuditContext) -> Result<Role, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+        let id = Uuid::new_v4();
+
+        sqlx::query("INSERT INTO roles (id, name, description) VALUES ($1, $2, $3)")
+            .bind(id)
+            .bind(new_role.name)
+            .bind(new_role.description)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_db_error)?;
+
+        Self::replace_permissions(&mut tx, id, &new_role.permissions).await?;
+        let role = Self::fetch_role(&mut tx, id).await?;
+        Self::audit_definition(
+            &mut tx,
+            audit,
+            AuditEventType::RoleCreated,
+            None,
+            Some(&role),
+        )
+        .await?;
+        tx.commit().await.map_err(map_db_error)?;
+
+        Ok(role)
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        input: UpdateRole,
+        audit: &AuditContext,
+    ) -> Result<Role, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+        let before = Self::lock_role(&mut tx, id).await?;
+
+        sqlx::query(
+            "UPDATE roles SET name = COALESCE($1, name), description = COALESCE($2, description), updated_at = NOW() WHERE id = $3",
+        )
+        .bind(input.name)
+        .bind(input.description)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(map_db_error)?;
+
+        if let Some(permissions) = input.permissions {
+            Self::replace_permissions(&mut tx, id, &permissions).await?;
+        }
+
+        let role = Self::fetch_role(&mut tx, id).await?;
+        Self::audit_definition(
+            &mut tx,
+            audit,
+            AuditEventType::RoleUpdated,
+            Some(&before),
+            Some(&role),
+        )
+        .await?;
+        tx.commit().await.map_err(map_db_error)?;
+
+        Ok(role)
+    }
+
+    async fn delete(&self, id: Uuid, audit: &AuditContext) -> Result<(), DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+        let before = Self::lock_role(&mut tx, id).await?;
+
+        let holders = sqlx::query_scalar::<_, Uuid>(
+            "SELECT users.id FROM users WHERE EXISTS ( \
+                SELECT 1 FROM user_effective_role_ids(users.id) AS effective WHERE effective.role_id = $1)",
+        )
+        .bind(id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(map_db_error)?;
+        let snapshot = RoleSnapshot::take(&mut tx, &holders).await?;
+
+        sqlx::query(
+            "INSERT INTO role_assignment_events (id, user_id, role_id, role_name, action, actor_id) SELECT gen_random_uuid(), user_roles.user_id, roles.id, roles.name, $1, $2 FROM user_roles JOIN roles ON roles.id = user_roles.role_id WHERE roles.id = $3",
+        )
+        .bind(RoleAssignmentAction::Unassigned.to_string())
+        .bind(audit.actor_id)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(map_db_error)?;
+
+        sqlx::query("DELETE FROM roles WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_db_error)?;
+
+        snapshot
+            .publish(
+                &mut tx,
+                audit.actor_id,
+                "role_deleted",
+                json!({ "role_id": before.id, "role": before.name }),
+            )
+            .await?;
+        Self::audit_definition(
+            &mut tx,
+            audit,
+            AuditEventType::RoleDeleted,
+            Some(&before),
+            None,
+        )
+        .await?;
+        tx.commit().await.map_err(map_db_error)?;
+        Ok(())
+    }
+
+    async fn assign(
+        &self,
+        user_id: Uuid,
+        role_id: Uuid,
+        expires_at: Option<DateTime<Utc>>,
+        audit: &AuditContext,
+    ) -> Result<bool, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+        let granted = Self::grant(&mut tx, user_id, role_id, audit.actor_id, expires_at).await?;
+        if granted {
+            Self::audit_assignment(
+                &mut tx,
+                audit,
+                AuditEventType::RoleAssigned,
+                user_id,
+                role_id,
+                expires_at,
+            )
+            .await?;
+        }
+        tx.commit().await.map_err(map_db_error)?;
+
+        Ok(granted)
+    }
+
+    async fn unassign(
+        &self,
+        user_id: Uuid,
+        role_id: Uuid,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+        let guard = AdminGuard::acquire(&mut tx).await?;
+
+        let affected = sqlx::query("DELETE FROM user_roles WHERE user_id = $1 AND role_id = $2")
+            .bind(user_id)
+            .bind(role_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_db_error)?
+            .rows_affected();
+
+        if affected == 0 {
+            return Err(DomainError::NotFound(
+                "role assignment not found".to_string(),
+            ));
+        }
+
+        Self::record_assignment(
+            &mut tx,
+            user_id,
+            role_id,
+            RoleAssignmentAction::Unassigned,
+            audit.actor_id,
+            None,
+        )
+        .await?;
+        Self::audit_assignment(
+            &mut tx,
+            audit,
+            AuditEventType::RoleUnassigned,
+            user_id,
+            role_id,
+            None,
+        )
+        .await?;
+        guard.verify(&mut tx).await?;
+        tx.commit().await.map_err(map_db_error)?;
+
+        Ok(())
+    }
+
+    async fn assignment_history(
+        &self,
+        role_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<RoleAssignmentEvent>, DomainError> {
+        let rows = sqlx::query_as::<_, DbRoleAssignmentEvent>(
+            "SELECT id, user_id, role_id, role_name, action, actor_id, expires_at, created_at FROM role_assignment_events WHERE role_id = $1 ORDER BY created_at DESC LIMIT $2",
+        )
+        .bind(role_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        rows.into_iter()
+            .map(|row| RoleAssignmentEvent::try_from(row).map_err(DomainError::Internal))
+            .collect::<Result<Vec<_>, _>>()
+    }
+
+    async fn sweep_expired(&self) -> Result<u64, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+
+        let swept = sqlx::query_as::<_, (Uuid, Uuid, String, Option<DateTime<Utc>>)>(
+            "WITH expired AS ( \
+                DELETE FROM user_roles WHERE expires_at <= NOW() RETURNING user_id, role_id, expires_at \
+            ) INSERT INTO role_assignment_events (id, user_id, role_id, role_name, action, expires_at) \
+            SELECT gen_random_uuid(), expired.user_id, roles.id, roles.name, $1, expired.expires_at \
+            FROM expired JOIN roles ON roles.id = expired.role_id \
+            RETURNING user_id, role_id, role_name, expires_at",
+        )
+        .bind(RoleAssignmentAction::Expired.to_string())
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(map_db_error)?;
+
+        for (user_id, role_id, role_name, expires_at) in &swept {
+            let event = DomainEvent::role_changed(
+                *user_id,
+                None,
+                json!({
+                    "change": "expired",
+                    "metadata": { "role_id": role_id, "role": role_name, "expires_at": expires_at },
+                }),
+            );
+            SqlxOutboxRepository::insert(&mut tx, &event).await?;
+        }
+
+        tx.commit().await.map_err(map_db_error)?;
+        Ok(swept.len() as u64)
+    }
+}
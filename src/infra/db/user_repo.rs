@@ -1,9 +1,65 @@
-use crate::domain::{AdminUpdateUser, DomainError, NewUser, Role, UpdateProfile, User, UserRepository, UserWithPassword};
+use crate::domain::audit::user_snapshot;
+use crate::domain::scim::{CompareOp, Filter, FilterValue};
+use crate::domain::{
+    AccountState, AdminUpdateUser, AttributeDefinition, AttributeType, AuditContext,
+    AuditEventType, BulkUserAction, BulkUserOutcome, BulkUserResult, DomainError,
+    ExistingIdentities, NewUser, Page, StatusChange, UpdateProfile, User, UserField, UserFilter,
+    UserPaging, UserQuery, UserRepository, UserSortField, UserWithPassword,
+};
+use crate::infra::db::attribute_repo::{attribute_expression, map_attribute_error};
+use crate::infra::db::audit_repo::SqlxAuditRepository;
 use crate::infra::db::models::DbUser;
+use crate::infra::db::role_repo::SqlxRoleRepository;
+use crate::infra::db::{map_db_error, AdminGuard};
 use async_trait::async_trait;
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgArguments;
+use sqlx::query::QueryAs;
+use sqlx::{PgConnection, PgPool, Postgres};
 use uuid::Uuid;
 
+/// Column list shared by every user query. Roles, permissions and groups are the effective
+/// sets, including everything inherited through (nested) group membership.
+pub(crate) const USER_COLUMNS: &str = "users.id, users.email, users.username, users.password_hash, \
+    ARRAY(SELECT roles.name FROM roles WHERE roles.id IN (SELECT role_id FROM user_effective_role_ids(users.id)) ORDER BY roles.name) AS roles, \
+    ARRAY(SELECT DISTINCT role_permissions.permission FROM role_permissions WHERE role_permissions.role_id IN (SELECT role_id FROM user_effective_role_ids(users.id)) ORDER BY 1) AS permissions, \
+    ARRAY(SELECT groups.name FROM groups WHERE groups.id IN (SELECT group_id FROM user_effective_groups(users.id)) ORDER BY groups.name) AS groups, \
+    (SELECT MIN(user_roles.expires_at) FROM user_roles WHERE user_roles.user_id = users.id AND user_roles.expires_at > NOW()) AS roles_expire_at, \
+    users.status, users.status_reason, users.status_until, users.status_changed_at, users.email_verified_at, \
+    (SELECT email_changes.new_email FROM email_changes WHERE email_changes.user_id = users.id \
+        AND email_changes.confirmed_at IS NULL AND email_changes.cancelled_at IS NULL \
+        AND email_changes.expires_at > NOW()) AS pending_email, \
+    users.display_name, users.given_name, users.family_name, \
+    users.locale, users.timezone, users.avatar_url, users.attributes, users.created_at, users.updated_at";
+
+/// `WHERE` conditions of a [`UserQuery`], bound by `SqlxUserRepository::bind_filters`.
+/// Status goes by `user_effective_status`, so an ended suspension already counts as active.
+const USER_FILTERS: &str = "($1::TEXT IS NULL OR users.email ILIKE $2 OR users.username ILIKE $2 \
+        OR users.email % $1 OR users.username % $1) \
+    AND ($3::TEXT IS NULL OR EXISTS ( \
+        SELECT 1 FROM roles \
+        WHERE roles.name = $3 AND roles.id IN (SELECT role_id FROM user_effective_role_ids(users.id)))) \
+    AND ($4::BOOLEAN IS NULL OR (user_effective_status(users.status, users.status_until) = 'active') = $4) \
+    AND ($5::TIMESTAMPTZ IS NULL OR users.created_at >= $5) \
+    AND ($6::TIMESTAMPTZ IS NULL OR users.created_at < $6) \
+    AND ($7::TEXT IS NULL OR LOWER(SPLIT_PART(users.email, '@', 2)) = LOWER($7)) \
+    AND ($8::TEXT IS NULL OR user_effective_status(users.status, users.status_until) = $8)";
+
+/// Value of a placeholder in a rendered [`UserFilter`].
+enum FilterBind {
+    Text(String),
+    Bool(bool),
+    Timestamp(DateTime<Utc>),
+}
+
+/// Escapes `LIKE` wildcards so `value` only matches literally.
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
 #[derive(Clone)]
 pub struct SqlxUserRepository {
     pool: PgPool,
@@ -21,29 +77,364 @@ impl SqlxUserRepository {
     fn map_db_user_with_password(db_user: DbUser) -> Result<UserWithPassword, DomainError> {
         let password_hash = db_user.password_hash.clone();
         let user = Self::map_db_user(db_user)?;
-        Ok(UserWithPassword { user, password_hash })
+        Ok(UserWithPassword {
+            user,
+            password_hash,
+        })
+    }
+
+    /// `ORDER BY` clause for `query`; `$1` is the search term. The id keeps pages stable.
+    fn order_by(query: &UserQuery) -> String {
+        if let UserPaging::After(_) = query.paging {
+            return "users.created_at DESC, users.id DESC".to_string();
+        }
+
+        match (query.sort, &query.search) {
+            (Some(sort), _) => {
+                let column = match sort.field {
+                    UserSortField::CreatedAt => "users.created_at",
+                    UserSortField::Email => "users.email",
+                    UserSortField::Username => "users.username",
+                };
+                let direction = if sort.descending { "DESC" } else { "ASC" };
+                format!("{column} {direction}, users.id {direction}")
+            }
+            (None, Some(_)) => {
+                "GREATEST(similarity(users.email, $1), similarity(users.username, $1)) DESC, \
+                 users.created_at DESC, users.id DESC"
+                    .to_string()
+            }
+            (None, None) => "users.created_at DESC, users.id DESC".to_string(),
+        }
     }
 
-    fn map_db_error(error: sqlx::Error) -> DomainError {
-        if let sqlx::Error::Database(db_error) = &error {
-            if db_error.code().as_deref() == Some("23505") {
-                return DomainError::Conflict("resource already exists".to_string());
+    /// Binds `$1`..`$8` of [`USER_FILTERS`].
+    fn bind_filters<'q, O>(
+        statement: QueryAs<'q, Postgres, O, PgArguments>,
+        query: &'q UserQuery,
+    ) -> QueryAs<'q, Postgres, O, PgArguments> {
+        // Substring matches are always found; `%` adds trigram matches that tolerate typos.
+        let pattern = query
+            .search
+            .as_deref()
+            .map(|search| format!("%{}%", escape_like(search)));
+
+        statement
+            .bind(query.search.as_deref())
+            .bind(pattern)
+            .bind(query.role.as_deref())
+            .bind(query.is_active)
+            .bind(query.created_from)
+            .bind(query.created_to)
+            .bind(query.email_domain.as_deref())
+            .bind(query.status.map(|status| status.as_str()))
+    }
+
+    /// `AND ...` conditions for `query.filter` and `query.attributes`, or nothing. Their
+    /// placeholders start at `$first`; their values are pushed onto `binds` for
+    /// [`Self::bind_filter_values`].
+    fn filter_clause(
+        query: &UserQuery,
+        first: usize,
+        binds: &mut Vec<FilterBind>,
+    ) -> Result<String, DomainError> {
+        let mut clause = match &query.filter {
+            Some(filter) => format!(" AND ({})", Self::render_filter(filter, first, binds)?),
+            None => String::new(),
+        };
+
+        for attribute in &query.attributes {
+            // The name is inlined into the statement, so it must not be arbitrary input.
+            if !AttributeDefinition::is_valid_name(&attribute.name) {
+                return Err(DomainError::ValidationError(format!(
+                    "invalid attribute name: {}",
+                    attribute.name
+                )));
             }
+            binds.push(FilterBind::Text(attribute.value.clone()));
+            let cast = match attribute.attribute_type {
+                AttributeType::Number => "::NUMERIC",
+                AttributeType::Boolean => "::BOOLEAN",
+                AttributeType::String | AttributeType::Date => "",
+            };
+            clause.push_str(&format!(
+                " AND {} = ${}{cast}",
+                attribute_expression(&attribute.name, attribute.attribute_type),
+                first + binds.len() - 1
+            ));
+        }
+
+        Ok(clause)
+    }
+
+    fn render_filter(
+        filter: &UserFilter,
+        first: usize,
+        binds: &mut Vec<FilterBind>,
+    ) -> Result<String, DomainError> {
+        let (field, op, value) = match filter {
+            Filter::And(left, right) => {
+                let left = Self::render_filter(left, first, binds)?;
+                return Ok(format!(
+                    "({left} AND {})",
+                    Self::render_filter(right, first, binds)?
+                ));
+            }
+            Filter::Or(left, right) => {
+                let left = Self::render_filter(left, first, binds)?;
+                return Ok(format!(
+                    "({left} OR {})",
+                    Self::render_filter(right, first, binds)?
+                ));
+            }
+            Filter::Not(inner) => {
+                return Ok(format!(
+                    "NOT ({})",
+                    Self::render_filter(inner, first, binds)?
+                ))
+            }
+            // Every filterable column is NOT NULL.
+            Filter::Present(_) => return Ok("TRUE".to_string()),
+            Filter::Compare(field, op, value) => (*field, *op, value),
+        };
+
+        let unsupported =
+            || DomainError::ValidationError(format!("unsupported comparison {op:?} on {field:?}"));
+        let column = match field {
+            UserField::Id => "users.id::TEXT",
+            UserField::Username => "users.username",
+            UserField::Email => "users.email",
+            UserField::IsActive => {
+                "(user_effective_status(users.status, users.status_until) = 'active')"
+            }
+            UserField::CreatedAt => "users.created_at",
+            UserField::UpdatedAt => "users.updated_at",
+        };
+        let operator = match op {
+            CompareOp::Eq => "=",
+            CompareOp::Ne => "<>",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Co | CompareOp::Sw | CompareOp::Ew => "ILIKE",
+        };
+        let mut placeholder = |bind: FilterBind| {
+            binds.push(bind);
+            format!("${}", first + binds.len() - 1)
+        };
+
+        match (field, value) {
+            (_, FilterValue::Null) => match op {
+                CompareOp::Eq => Ok("FALSE".to_string()),
+                CompareOp::Ne => Ok("TRUE".to_string()),
+                _ => Err(unsupported()),
+            },
+            (UserField::IsActive, FilterValue::Bool(value))
+                if matches!(op, CompareOp::Eq | CompareOp::Ne) =>
+            {
+                Ok(format!(
+                    "{column} {operator} {}",
+                    placeholder(FilterBind::Bool(*value))
+                ))
+            }
+            (UserField::CreatedAt | UserField::UpdatedAt, FilterValue::String(value))
+                if !op.is_substring() =>
+            {
+                let timestamp = DateTime::parse_from_rfc3339(value).map_err(|_| {
+                    DomainError::ValidationError(format!("invalid timestamp: {value}"))
+                })?;
+                let timestamp = FilterBind::Timestamp(timestamp.with_timezone(&Utc));
+                Ok(format!("{column} {operator} {}", placeholder(timestamp)))
+            }
+            (
+                UserField::Id | UserField::Username | UserField::Email,
+                FilterValue::String(value),
+            ) => {
+                if op.is_substring() {
+                    let pattern = match op {
+                        CompareOp::Co => format!("%{}%", escape_like(value)),
+                        CompareOp::Sw => format!("{}%", escape_like(value)),
+                        _ => format!("%{}", escape_like(value)),
+                    };
+                    Ok(format!(
+                        "{column} ILIKE {}",
+                        placeholder(FilterBind::Text(pattern))
+                    ))
+                } else {
+                    let value = placeholder(FilterBind::Text(value.clone()));
+                    Ok(format!("LOWER({column}) {operator} LOWER({value})"))
+                }
+            }
+            _ => Err(unsupported()),
+        }
+    }
+
+    fn bind_filter_values<'q, O>(
+        mut statement: QueryAs<'q, Postgres, O, PgArguments>,
+        binds: Vec<FilterBind>,
+    ) -> QueryAs<'q, Postgres, O, PgArguments> {
+        for bind in binds {
+            statement = match bind {
+                FilterBind::Text(value) => statement.bind(value),
+                FilterBind::Bool(value) => statement.bind(value),
+                FilterBind::Timestamp(value) => statement.bind(value),
+            };
+        }
+        statement
+    }
+
+    pub(crate) async fn fetch_user(conn: &mut PgConnection, id: Uuid) -> Result<User, DomainError> {
+        let row = sqlx::query_as::<_, DbUser>(&format!(
+            "SELECT {USER_COLUMNS} FROM users WHERE users.id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(map_db_error)?
+        .ok_or_else(|| DomainError::NotFound("user not found".to_string()))?;
+
+        Self::map_db_user(row)
+    }
+
+    /// Loads the user and locks the row, so the audited "before" state is the one replaced.
+    pub(crate) async fn lock_user(conn: &mut PgConnection, id: Uuid) -> Result<User, DomainError> {
+        let row = sqlx::query_as::<_, DbUser>(&format!(
+            "SELECT {USER_COLUMNS} FROM users WHERE users.id = $1 FOR UPDATE"
+        ))
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(map_db_error)?
+        .ok_or_else(|| DomainError::NotFound("user not found".to_string()))?;
+
+        Self::map_db_user(row)
+    }
+
+    pub(crate) async fn audit_change(
+        conn: &mut PgConnection,
+        audit: &AuditContext,
+        event_type: AuditEventType,
+        before: &User,
+        after: &User,
+    ) -> Result<(), DomainError> {
+        let event_type = match (before.status.state, after.status.state) {
+            (from, AccountState::Deactivated) if from != AccountState::Deactivated => {
+                AuditEventType::UserDeactivated
+            }
+            (from, to) if from != to => AuditEventType::UserStatusChanged,
+            _ => event_type,
+        };
+        let event = audit
+            .event(event_type, Some(after.id))
+            .with_diff(&user_snapshot(before), &user_snapshot(after));
+
+        SqlxAuditRepository::insert(conn, event).await
+    }
+
+    /// Applies `change` to a locked user if its status allows it, and audits the change
+    /// against `before`.
+    async fn write_status(
+        conn: &mut PgConnection,
+        before: &User,
+        change: StatusChange,
+        audit: &AuditContext,
+    ) -> Result<User, DomainError> {
+        let status = before.status.transition(change, Utc::now())?;
+        sqlx::query(
+            "UPDATE users SET status = $1, status_reason = $2, status_until = $3, status_changed_at = $4, \
+                updated_at = NOW() WHERE id = $5",
+        )
+        .bind(status.state.as_str())
+        .bind(status.reason)
+        .bind(status.until)
+        .bind(status.changed_at)
+        .bind(before.id)
+        .execute(&mut *conn)
+        .await
+        .map_err(map_db_error)?;
+        let after = Self::fetch_user(conn, before.id).await?;
+
+        Self::audit_change(
+            conn,
+            audit,
+            AuditEventType::UserStatusChanged,
+            before,
+            &after,
+        )
+        .await?;
+        Ok(after)
+    }
+
+    async fn insert_user(
+        conn: &mut PgConnection,
+        new_user: NewUser,
+        audit: &AuditContext,
+    ) -> Result<User, DomainError> {
+        let id = Uuid::new_v4();
+        let username_skeleton = User::username_skeleton(&new_user.username);
+
+        sqlx::query(
+            "INSERT INTO users (id, email, username, username_skeleton, password_hash, status, email_verified_at) VALUES ($1, $2, $3, $4, $5, $6, CASE WHEN $7 THEN NOW() END)",
+        )
+        .bind(id)
+        .bind(new_user.email)
+        .bind(new_user.username)
+        .bind(username_skeleton)
+        .bind(new_user.password_hash)
+        .bind(new_user.state.as_str())
+        .bind(new_user.email_verified)
+        .execute(&mut *conn)
+        .await
+        .map_err(map_db_error)?;
+
+        Self::insert_roles(conn, id, new_user.roles).await?;
+        let user = Self::fetch_user(conn, id).await?;
+
+        let event = audit
+            .event(AuditEventType::UserRegistered, Some(id))
+            .with_after(user_snapshot(&user));
+        SqlxAuditRepository::insert(conn, event).await?;
+
+        Ok(user)
+    }
+
+    async fn insert_roles(
+        conn: &mut PgConnection,
+        id: Uuid,
+        mut roles: Vec<String>,
+    ) -> Result<(), DomainError> {
+        roles.sort();
+        roles.dedup();
+
+        let inserted = sqlx::query(
+            "INSERT INTO user_roles (user_id, role_id) SELECT $1, id FROM roles WHERE name = ANY($2)",
+        )
+        .bind(id)
+        .bind(&roles)
+        .execute(&mut *conn)
+        .await
+        .map_err(map_db_error)?
+        .rows_affected();
+
+        if inserted as usize != roles.len() {
+            return Err(DomainError::ValidationError("unknown role".to_string()));
         }
-        DomainError::Internal(error.to_string())
+
+        Ok(())
     }
 }
 
 #[async_trait]
 impl UserRepository for SqlxUserRepository {
     async fn find_by_email(&self, email: &str) -> Result<Option<UserWithPassword>, DomainError> {
-        let result = sqlx::query_as::<_, DbUser>(
-            "SELECT id, email, username, password_hash, role, is_active, created_at, updated_at FROM users WHERE email = $1",
-        )
+        let result = sqlx::query_as::<_, DbUser>(&format!(
+            "SELECT {USER_COLUMNS} FROM users WHERE LOWER(users.email) = LOWER($1)"
+        ))
         .bind(email)
         .fetch_optional(&self.pool)
         .await
-        .map_err(Self::map_db_error)?;
+        .map_err(map_db_error)?;
 
         match result {
             Some(row) => Ok(Some(Self::map_db_user_with_password(row)?)),
@@ -52,13 +443,13 @@ impl UserRepository for SqlxUserRepository {
     }
 
     async fn find_by_username(&self, username: &str) -> Result<Option<User>, DomainError> {
-        let result = sqlx::query_as::<_, DbUser>(
-            "SELECT id, email, username, password_hash, role, is_active, created_at, updated_at FROM users WHERE username = $1",
-        )
-        .bind(username)
+        let result = sqlx::query_as::<_, DbUser>(&format!(
+            "SELECT {USER_COLUMNS} FROM users WHERE users.username_skeleton = $1"
+        ))
+        .bind(User::username_skeleton(username))
         .fetch_optional(&self.pool)
         .await
-        .map_err(Self::map_db_error)?;
+        .map_err(map_db_error)?;
 
         match result {
             Some(row) => Ok(Some(Self::map_db_user(row)?)),
@@ -67,13 +458,13 @@ impl UserRepository for SqlxUserRepository {
     }
 
     async fn find_by_id(&self, id: Uuid) -> Result<Option<UserWithPassword>, DomainError> {
-        let result = sqlx::query_as::<_, DbUser>(
-            "SELECT id, email, username, password_hash, role, is_active, created_at, updated_at FROM users WHERE id = $1",
-        )
+        let result = sqlx::query_as::<_, DbUser>(&format!(
+            "SELECT {USER_COLUMNS} FROM users WHERE users.id = $1"
+        ))
         .bind(id)
         .fetch_optional(&self.pool)
         .await
-        .map_err(Self::map_db_error)?;
+        .map_err(map_db_error)?;
 
         match result {
             Some(row) => Ok(Some(Self::map_db_user_with_password(row)?)),
@@ -81,104 +472,345 @@ impl UserRepository for SqlxUserRepository {
         }
     }
 
-    async fn create(&self, new_user: NewUser) -> Result<User, DomainError> {
-        let result = sqlx::query_as::<_, DbUser>(
-            "INSERT INTO users (id, email, username, password_hash, role, is_active) VALUES ($1, $2, $3, $4, $5, $6) RETURNING id, email, username, password_hash, role, is_active, created_at, updated_at",
+    async fn create(&self, new_user: NewUser, audit: &AuditContext) -> Result<User, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+        let user = Self::insert_user(&mut tx, new_user, audit).await?;
+        tx.commit().await.map_err(map_db_error)?;
+
+        Ok(user)
+    }
+
+    async fn create_many(
+        &self,
+        new_users: Vec<NewUser>,
+        audit: &AuditContext,
+    ) -> Result<Vec<User>, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+        let mut users = Vec::with_capacity(new_users.len());
+        for new_user in new_users {
+            users.push(Self::insert_user(&mut tx, new_user, audit).await?);
+        }
+        tx.commit().await.map_err(map_db_error)?;
+
+        Ok(users)
+    }
+
+    async fn find_existing(
+        &self,
+        emails: &[String],
+        usernames: &[String],
+    ) -> Result<ExistingIdentities, DomainError> {
+        let emails: Vec<String> = emails.iter().map(|email| email.to_lowercase()).collect();
+        let skeletons: Vec<String> = usernames
+            .iter()
+            .map(|username| User::username_skeleton(username))
+            .collect();
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            "SELECT LOWER(email), username_skeleton FROM users WHERE LOWER(email) = ANY($1) OR username_skeleton = ANY($2)",
         )
-        .bind(Uuid::new_v4())
-        .bind(new_user.email)
-        .bind(new_user.username)
-        .bind(new_user.password_hash)
-        .bind(new_user.role.to_string())
-        .bind(new_user.is_active)
-        .fetch_one(&self.pool)
+        .bind(&emails)
+        .bind(&skeletons)
+        .fetch_all(&self.pool)
         .await
-        .map_err(Self::map_db_error)?;
+        .map_err(map_db_error)?;
 
-        Self::map_db_user(result)
+        let mut existing = ExistingIdentities::default();
+        for (email, skeleton) in rows {
+            existing.emails.insert(email);
+            existing.usernames.insert(skeleton);
+        }
+        Ok(existing)
     }
 
-    async fn update_profile(&self, id: Uuid, input: UpdateProfile) -> Result<User, DomainError> {
-        let result = sqlx::query_as::<_, DbUser>(
-            "UPDATE users SET email = COALESCE($1, email), username = COALESCE($2, username), updated_at = NOW() WHERE id = $3 RETURNING id, email, username, password_hash, role, is_active, created_at, updated_at",
+    async fn set_password(
+        &self,
+        id: Uuid,
+        password_hash: String,
+        audit: &AuditContext,
+    ) -> Result<(), DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+
+        let affected = sqlx::query(
+            "UPDATE users SET password_hash = $1, email_verified_at = COALESCE(email_verified_at, NOW()), updated_at = NOW() WHERE id = $2",
         )
-        .bind(input.email)
-        .bind(input.username)
+        .bind(password_hash)
         .bind(id)
-        .fetch_optional(&self.pool)
+        .execute(&mut *tx)
         .await
-        .map_err(Self::map_db_error)?;
+        .map_err(map_db_error)?
+        .rows_affected();
 
-        match result {
-            Some(row) => Self::map_db_user(row),
-            None => Err(DomainError::NotFound("user not found".to_string())),
+        if affected == 0 {
+            return Err(DomainError::NotFound("user not found".to_string()));
         }
+
+        SqlxAuditRepository::insert(&mut tx, audit.event(AuditEventType::PasswordSet, Some(id)))
+            .await?;
+        tx.commit().await.map_err(map_db_error)?;
+
+        Ok(())
     }
 
-    async fn update_user(&self, id: Uuid, input: AdminUpdateUser) -> Result<User, DomainError> {
-        let role_value = input.role.map(|role| role.to_string());
-        let result = sqlx::query_as::<_, DbUser>(
-            "UPDATE users SET email = COALESCE($1, email), username = COALESCE($2, username), role = COALESCE($3, role), is_active = COALESCE($4, is_active), updated_at = NOW() WHERE id = $5 RETURNING id, email, username, password_hash, role, is_active, created_at, updated_at",
+    async fn update_profile(
+        &self,
+        id: Uuid,
+        input: UpdateProfile,
+        audit: &AuditContext,
+    ) -> Result<User, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+        let before = Self::lock_user(&mut tx, id).await?;
+
+        let row = sqlx::query_as::<_, DbUser>(&format!(
+            "UPDATE users SET username = COALESCE($1, username), \
+                username_skeleton = COALESCE($10, username_skeleton), \
+                display_name = CASE WHEN $2::TEXT IS NULL THEN display_name ELSE NULLIF($2, '') END, \
+                given_name = CASE WHEN $3::TEXT IS NULL THEN given_name ELSE NULLIF($3, '') END, \
+                family_name = CASE WHEN $4::TEXT IS NULL THEN family_name ELSE NULLIF($4, '') END, \
+                locale = CASE WHEN $5::TEXT IS NULL THEN locale ELSE NULLIF($5, '') END, \
+                timezone = CASE WHEN $6::TEXT IS NULL THEN timezone ELSE NULLIF($6, '') END, \
+                avatar_url = CASE WHEN $7::TEXT IS NULL THEN avatar_url ELSE NULLIF($7, '') END, \
+                attributes = CASE WHEN $8::JSONB IS NULL THEN attributes ELSE jsonb_strip_nulls(attributes || $8) END, \
+                updated_at = NOW() WHERE id = $9 RETURNING {USER_COLUMNS}"
+        ))
+        .bind(&input.username)
+        .bind(input.display_name)
+        .bind(input.given_name)
+        .bind(input.family_name)
+        .bind(input.locale)
+        .bind(input.timezone)
+        .bind(input.avatar_url)
+        .bind(input.attributes.map(serde_json::Value::Object))
+        .bind(id)
+        .bind(input.username.as_deref().map(User::username_skeleton))
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(map_attribute_error)?;
+        let after = Self::map_db_user(row)?;
+
+        Self::audit_change(
+            &mut tx,
+            audit,
+            AuditEventType::ProfileUpdated,
+            &before,
+            &after,
         )
+        .await?;
+        tx.commit().await.map_err(map_db_error)?;
+
+        Ok(after)
+    }
+
+    async fn update_user(
+        &self,
+        id: Uuid,
+        input: AdminUpdateUser,
+        audit: &AuditContext,
+    ) -> Result<User, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+        let guard = AdminGuard::acquire(&mut tx).await?;
+        let mut before = Self::lock_user(&mut tx, id).await?;
+        if let Some(change) = input
+            .is_active
+            .and_then(|active| before.status.activation_change(active, Utc::now()))
+        {
+            before = Self::write_status(&mut tx, &before, change, audit).await?;
+        }
+
+        let row = sqlx::query_as::<_, DbUser>(&format!(
+            "UPDATE users SET email_verified_at = CASE WHEN $1 IS DISTINCT FROM email AND $1 IS NOT NULL THEN NULL ELSE email_verified_at END, email = COALESCE($1, email), username = COALESCE($2, username), \
+                username_skeleton = COALESCE($5, username_skeleton), \
+                attributes = CASE WHEN $3::JSONB IS NULL THEN attributes ELSE jsonb_strip_nulls(attributes || $3) END, \
+                updated_at = NOW() WHERE id = $4 RETURNING {USER_COLUMNS}"
+        ))
         .bind(input.email)
-        .bind(input.username)
-        .bind(role_value)
-        .bind(input.is_active)
+        .bind(&input.username)
+        .bind(input.attributes.map(serde_json::Value::Object))
         .bind(id)
-        .fetch_optional(&self.pool)
+        .bind(input.username.as_deref().map(User::username_skeleton))
+        .fetch_one(&mut *tx)
         .await
-        .map_err(Self::map_db_error)?;
+        .map_err(map_attribute_error)?;
+        let after = Self::map_db_user(row)?;
 
-        match result {
-            Some(row) => Self::map_db_user(row),
-            None => Err(DomainError::NotFound("user not found".to_string())),
-        }
+        guard.verify(&mut tx).await?;
+        Self::audit_change(&mut tx, audit, AuditEventType::UserUpdated, &before, &after).await?;
+        tx.commit().await.map_err(map_db_error)?;
+
+        Ok(after)
     }
 
-    async fn set_role(&self, id: Uuid, role: Role) -> Result<User, DomainError> {
-        let result = sqlx::query_as::<_, DbUser>(
-            "UPDATE users SET role = $1, updated_at = NOW() WHERE id = $2 RETURNING id, email, username, password_hash, role, is_active, created_at, updated_at",
+    async fn set_status(
+        &self,
+        id: Uuid,
+        change: StatusChange,
+        audit: &AuditContext,
+    ) -> Result<User, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+        let guard = AdminGuard::acquire(&mut tx).await?;
+        let before = Self::lock_user(&mut tx, id).await?;
+
+        let after = Self::write_status(&mut tx, &before, change, audit).await?;
+
+        guard.verify(&mut tx).await?;
+        tx.commit().await.map_err(map_db_error)?;
+        Ok(after)
+    }
+
+    async fn lift_expired_suspensions(&self, audit: &AuditContext) -> Result<u64, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+        // Rows locked elsewhere are being changed right now; a later sweep retries them.
+        let ids = sqlx::query_scalar::<_, Uuid>(
+            "SELECT id FROM users WHERE status = 'suspended' AND status_until <= NOW() \
+             ORDER BY status_until FOR UPDATE SKIP LOCKED",
         )
-        .bind(role.to_string())
-        .bind(id)
-        .fetch_optional(&self.pool)
+        .fetch_all(&mut *tx)
         .await
-        .map_err(Self::map_db_error)?;
+        .map_err(map_db_error)?;
 
-        match result {
-            Some(row) => Self::map_db_user(row),
-            None => Err(DomainError::NotFound("user not found".to_string())),
+        for &id in &ids {
+            let before = Self::lock_user(&mut tx, id).await?;
+            Self::write_status(
+                &mut tx,
+                &before,
+                StatusChange::to(AccountState::Active),
+                audit,
+            )
+            .await?;
         }
+
+        tx.commit().await.map_err(map_db_error)?;
+        Ok(ids.len() as u64)
     }
 
-    async fn set_active(&self, id: Uuid, is_active: bool) -> Result<(), DomainError> {
-        let affected = sqlx::query("UPDATE users SET is_active = $1, updated_at = NOW() WHERE id = $2")
-            .bind(is_active)
-            .bind(id)
-            .execute(&self.pool)
-            .await
-            .map_err(Self::map_db_error)?
-            .rows_affected();
+    async fn bulk_update(
+        &self,
+        ids: &[Uuid],
+        action: &BulkUserAction,
+        audit: &AuditContext,
+    ) -> Result<Vec<BulkUserResult>, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(map_db_error)?;
+        let guard = AdminGuard::acquire(&mut tx).await?;
 
-        if affected == 0 {
-            return Err(DomainError::NotFound("user not found".to_string()));
+        let role_id = match action {
+            BulkUserAction::SetRole(role) => Some(
+                sqlx::query_scalar::<_, Uuid>("SELECT id FROM roles WHERE name = $1")
+                    .bind(role)
+                    .fetch_optional(&mut *tx)
+                    .await
+                    .map_err(map_db_error)?
+                    .ok_or_else(|| DomainError::ValidationError(format!("unknown role: {role}")))?,
+            ),
+            _ => None,
+        };
+
+        let mut results = Vec::with_capacity(ids.len());
+        for &user_id in ids {
+            let before = match Self::lock_user(&mut tx, user_id).await {
+                Ok(user) => user,
+                Err(DomainError::NotFound(_)) => {
+                    results.push(BulkUserResult {
+                        user_id,
+                        outcome: BulkUserOutcome::NotFound,
+                    });
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+
+            // A user the action does not apply to is reported; it does not fail the batch.
+            let outcome = match (action, role_id) {
+                (BulkUserAction::SetRole(_), Some(_))
+                    if before.status.state == AccountState::Deleted =>
+                {
+                    BulkUserOutcome::InvalidTransition
+                }
+                (BulkUserAction::SetRole(_), Some(role_id)) => {
+                    if SqlxRoleRepository::replace_direct_roles(&mut tx, user_id, role_id, audit)
+                        .await?
+                    {
+                        BulkUserOutcome::Updated
+                    } else {
+                        BulkUserOutcome::Unchanged
+                    }
+                }
+                _ => {
+                    let state = match action {
+                        BulkUserAction::Reactivate => AccountState::Active,
+                        _ => AccountState::Deactivated,
+                    };
+                    if before.status.effective_state(Utc::now()) == state {
+                        BulkUserOutcome::Unchanged
+                    } else if !before.status.state.can_become(state) {
+                        BulkUserOutcome::InvalidTransition
+                    } else {
+                        Self::write_status(&mut tx, &before, StatusChange::to(state), audit)
+                            .await?;
+                        BulkUserOutcome::Updated
+                    }
+                }
+            };
+            results.push(BulkUserResult { user_id, outcome });
         }
 
-        Ok(())
+        guard.verify(&mut tx).await?;
+        tx.commit().await.map_err(map_db_error)?;
+        Ok(results)
     }
 
-    async fn list(&self, limit: i64, offset: i64) -> Result<Vec<User>, DomainError> {
-        let rows = sqlx::query_as::<_, DbUser>(
-            "SELECT id, email, username, password_hash, role, is_active, created_at, updated_at FROM users ORDER BY created_at DESC LIMIT $1 OFFSET $2",
-        )
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&self.pool)
-        .await
-        .map_err(Self::map_db_error)?;
+    async fn list(&self, query: &UserQuery) -> Result<Page<User>, DomainError> {
+        let order_by = Self::order_by(query);
+        let mut filter_binds = Vec::new();
+        let first_filter_param = match query.paging {
+            UserPaging::Offset(_) => 11,
+            UserPaging::After(_) => 12,
+        };
+        let filter = Self::filter_clause(query, first_filter_param, &mut filter_binds)?;
+        // One extra row tells whether another page follows.
+        let statement = match query.paging {
+            UserPaging::Offset(_) => format!(
+                "SELECT {USER_COLUMNS} FROM users WHERE {USER_FILTERS}{filter} \
+                 ORDER BY {order_by} LIMIT $9 OFFSET $10"
+            ),
+            UserPaging::After(_) => format!(
+                "SELECT {USER_COLUMNS} FROM users WHERE {USER_FILTERS}{filter} \
+                   AND ($10::TIMESTAMPTZ IS NULL OR (users.created_at, users.id) < ($10, $11)) \
+                 ORDER BY {order_by} LIMIT $9"
+            ),
+        };
 
-        rows.into_iter()
+        let mut rows = Self::bind_filters(sqlx::query_as::<_, DbUser>(&statement), query)
+            .bind(query.limit + 1);
+        rows = match query.paging {
+            UserPaging::Offset(offset) => rows.bind(offset),
+            UserPaging::After(after) => {
+                let (after_created_at, after_id) =
+                    after.map(|cursor| (cursor.created_at, cursor.id)).unzip();
+                rows.bind(after_created_at).bind(after_id)
+            }
+        };
+        let rows = Self::bind_filter_values(rows, filter_binds)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(map_db_error)?;
+
+        let users = rows
+            .into_iter()
             .map(Self::map_db_user)
-            .collect::<Result<Vec<_>, _>>()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Page::from_rows(users, query.limit, User::cursor))
+    }
+
+    async fn count(&self, query: &UserQuery) -> Result<i64, DomainError> {
+        let mut filter_binds = Vec::new();
+        let filter = Self::filter_clause(query, 9, &mut filter_binds)?;
+        let statement = format!("SELECT COUNT(*) FROM users WHERE {USER_FILTERS}{filter}");
+        let rows = Self::bind_filters(sqlx::query_as::<_, (i64,)>(&statement), query);
+        let (total,) = Self::bind_filter_values(rows, filter_binds)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(map_db_error)?;
+
+        Ok(total)
     }
-}
\ No newline at end of file
+}
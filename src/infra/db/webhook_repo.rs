@@ -0,0 +1,262 @@
+use crate::domain::{
+    DeliveryAttempt, DeliveryStatus, DomainError, DomainEvent, EventPublisher, NewWebhookEndpoint,
+    UpdateWebhookEndpoint, WebhookDelivery, WebhookEndpoint, WebhookRepository,
+};
+use crate::infra::db::map_db_error;
+use crate::infra::db::models::{DbWebhookDelivery, DbWebhookEndpoint};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const ENDPOINT_COLUMNS: &str = "id, url, secret, event_types, description, is_active, created_by, \
+    created_at, updated_at";
+
+const DELIVERY_COLUMNS: &str =
+    "id, endpoint_id, event_type, payload, status, attempts, next_attempt_at, \
+    last_status_code, last_error, delivered_at, created_at, updated_at";
+
+#[derive(Clone)]
+pub struct SqlxWebhookRepository {
+    pool: PgPool,
+}
+
+impl SqlxWebhookRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    fn map_db_endpoint(row: DbWebhookEndpoint) -> Result<WebhookEndpoint, DomainError> {
+        WebhookEndpoint::try_from(row).map_err(DomainError::Internal)
+    }
+
+    fn map_db_delivery(row: DbWebhookDelivery) -> Result<WebhookDelivery, DomainError> {
+        WebhookDelivery::try_from(row).map_err(DomainError::Internal)
+    }
+}
+
+#[async_trait]
+impl WebhookRepository for SqlxWebhookRepository {
+    async fn list_endpoints(&self) -> Result<Vec<WebhookEndpoint>, DomainError> {
+        let rows = sqlx::query_as::<_, DbWebhookEndpoint>(&format!(
+            "SELECT {ENDPOINT_COLUMNS} FROM webhook_endpoints ORDER BY created_at"
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        rows.into_iter().map(Self::map_db_endpoint).collect()
+    }
+
+    async fn find_endpoint(&self, id: Uuid) -> Result<Option<WebhookEndpoint>, DomainError> {
+        let result = sqlx::query_as::<_, DbWebhookEndpoint>(&format!(
+            "SELECT {ENDPOINT_COLUMNS} FROM webhook_endpoints WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        result.map(Self::map_db_endpoint).transpose()
+    }
+
+    async fn create_endpoint(
+        &self,
+        input: NewWebhookEndpoint,
+    ) -> Result<WebhookEndpoint, DomainError> {
+        let event_types: Vec<&str> = input
+            .event_types
+            .iter()
+            .map(|event_type| event_type.as_str())
+            .collect();
+
+        let row = sqlx::query_as::<_, DbWebhookEndpoint>(&format!(
+            "INSERT INTO webhook_endpoints (id, url, secret, event_types, description, created_by) VALUES ($1, $2, $3, $4, $5, $6) RETURNING {ENDPOINT_COLUMNS}"
+        ))
+        .bind(Uuid::new_v4())
+        .bind(input.url)
+        .bind(input.secret)
+        .bind(event_types)
+        .bind(input.description)
+        .bind(input.created_by)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        Self::map_db_endpoint(row)
+    }
+
+    async fn update_endpoint(
+        &self,
+        id: Uuid,
+        input: UpdateWebhookEndpoint,
+    ) -> Result<WebhookEndpoint, DomainError> {
+        let event_types: Option<Vec<&str>> = input.event_types.as_ref().map(|event_types| {
+            event_types
+                .iter()
+                .map(|event_type| event_type.as_str())
+                .collect()
+        });
+
+        let result = sqlx::query_as::<_, DbWebhookEndpoint>(&format!(
+            "UPDATE webhook_endpoints SET url = COALESCE($1, url), event_types = COALESCE($2, event_types), description = COALESCE($3, description), is_active = COALESCE($4, is_active), updated_at = NOW() WHERE id = $5 RETURNING {ENDPOINT_COLUMNS}"
+        ))
+        .bind(input.url)
+        .bind(event_types)
+        .bind(input.description)
+        .bind(input.is_active)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        result
+            .map(Self::map_db_endpoint)
+            .transpose()?
+            .ok_or_else(|| DomainError::NotFound("webhook not found".to_string()))
+    }
+
+    async fn delete_endpoint(&self, id: Uuid) -> Result<(), DomainError> {
+        let affected = sqlx::query("DELETE FROM webhook_endpoints WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(map_db_error)?
+            .rows_affected();
+
+        if affected == 0 {
+            return Err(DomainError::NotFound("webhook not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn list_deliveries(
+        &self,
+        endpoint_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, DomainError> {
+        let rows = sqlx::query_as::<_, DbWebhookDelivery>(&format!(
+            "SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries WHERE endpoint_id = $1 ORDER BY created_at DESC, id DESC LIMIT $2"
+        ))
+        .bind(endpoint_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        rows.into_iter().map(Self::map_db_delivery).collect()
+    }
+
+    async fn find_delivery(&self, id: Uuid) -> Result<Option<WebhookDelivery>, DomainError> {
+        let result = sqlx::query_as::<_, DbWebhookDelivery>(&format!(
+            "SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        result.map(Self::map_db_delivery).transpose()
+    }
+
+    async fn claim_due(
+        &self,
+        limit: i64,
+        lease_until: DateTime<Utc>,
+    ) -> Result<Vec<WebhookDelivery>, DomainError> {
+        let rows = sqlx::query_as::<_, DbWebhookDelivery>(&format!(
+            "WITH claimed AS ( \
+                 UPDATE webhook_deliveries SET next_attempt_at = $1, updated_at = NOW() \
+                 WHERE id IN ( \
+                     SELECT id FROM webhook_deliveries \
+                     WHERE status = $2 AND next_attempt_at <= NOW() \
+                     ORDER BY next_attempt_at, created_at LIMIT $3 \
+                     FOR UPDATE SKIP LOCKED \
+                 ) RETURNING {DELIVERY_COLUMNS} \
+             ) SELECT * FROM claimed ORDER BY created_at, id"
+        ))
+        .bind(lease_until)
+        .bind(DeliveryStatus::Pending.to_string())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        rows.into_iter().map(Self::map_db_delivery).collect()
+    }
+
+    async fn record_attempt(
+        &self,
+        id: Uuid,
+        attempt: DeliveryAttempt,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), DomainError> {
+        let status = if attempt.succeeded() {
+            DeliveryStatus::Succeeded
+        } else if retry_at.is_some() {
+            DeliveryStatus::Pending
+        } else {
+            DeliveryStatus::Dead
+        };
+
+        sqlx::query(
+            "UPDATE webhook_deliveries SET status = $1, attempts = attempts + 1, \
+                 next_attempt_at = COALESCE($2, next_attempt_at), last_status_code = $3, last_error = $4, \
+                 delivered_at = CASE WHEN $1 = 'succeeded' THEN NOW() ELSE delivered_at END, updated_at = NOW() \
+             WHERE id = $5",
+        )
+        .bind(status.to_string())
+        .bind(retry_at)
+        .bind(attempt.status_code)
+        .bind(attempt.error)
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        Ok(())
+    }
+
+    async fn redeliver(&self, id: Uuid) -> Result<WebhookDelivery, DomainError> {
+        let result = sqlx::query_as::<_, DbWebhookDelivery>(&format!(
+            "UPDATE webhook_deliveries SET status = $1, attempts = 0, next_attempt_at = NOW(), updated_at = NOW() \
+             WHERE id = $2 RETURNING {DELIVERY_COLUMNS}"
+        ))
+        .bind(DeliveryStatus::Pending.to_string())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        result
+            .map(Self::map_db_delivery)
+            .transpose()?
+            .ok_or_else(|| DomainError::NotFound("delivery not found".to_string()))
+    }
+}
+
+/// Fans relayed events out into a pending delivery per subscribed, active endpoint.
+/// Publishing the same event again adds no deliveries.
+#[async_trait]
+impl EventPublisher for SqlxWebhookRepository {
+    async fn publish(&self, event: &DomainEvent) -> Result<(), DomainError> {
+        let payload =
+            serde_json::to_value(event).map_err(|err| DomainError::Internal(err.to_string()))?;
+
+        sqlx::query(
+            "INSERT INTO webhook_deliveries (id, endpoint_id, event_id, event_type, payload) \
+             SELECT gen_random_uuid(), id, $1, $2, $3 FROM webhook_endpoints \
+             WHERE is_active AND $2 = ANY (event_types) \
+             ON CONFLICT (endpoint_id, event_id) DO NOTHING",
+        )
+        .bind(event.id)
+        .bind(event.event_type.as_str())
+        .bind(payload)
+        .execute(&self.pool)
+        .await
+        .map_err(map_db_error)?;
+
+        Ok(())
+    }
+}
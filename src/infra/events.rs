@@ -0,0 +1,70 @@
+use crate::domain::{DomainError, DomainEvent, EventPublisher};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+/// Events buffered per subscriber before a slow one starts missing events.
+const BUS_CAPACITY: usize = 1024;
+
+/// In-process fan-out of relayed events to live subscribers such as the admin event
+/// stream. Clones share the same channel.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<DomainEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(BUS_CAPACITY);
+        Self { sender }
+    }
+
+    /// Receives every event published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<DomainEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl EventPublisher for EventBus {
+    async fn publish(&self, event: &DomainEvent) -> Result<(), DomainError> {
+        // Sending only fails when nobody is listening, which is not an error here.
+        let _ = self.sender.send(event.clone());
+        Ok(())
+    }
+}
+
+/// Keeps published events in memory. Clones share the same list, so a test can hand one
+/// clone to a relay and inspect the other.
+#[derive(Clone, Default)]
+pub struct InMemoryEventPublisher {
+    events: Arc<Mutex<Vec<DomainEvent>>>,
+}
+
+impl InMemoryEventPublisher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Everything published so far, in publish order.
+    pub fn events(&self) -> Vec<DomainEvent> {
+        self.events.lock().expect("event list poisoned").clone()
+    }
+}
+
+#[async_trait]
+impl EventPublisher for InMemoryEventPublisher {
+    async fn publish(&self, event: &DomainEvent) -> Result<(), DomainError> {
+        self.events
+            .lock()
+            .expect("event list poisoned")
+            .push(event.clone());
+        Ok(())
+    }
+}
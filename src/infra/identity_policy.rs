@@ -0,0 +1,37 @@
+use crate::config::AppConfig;
+use crate::domain::{DomainError, IdentityPolicy};
+
+/// Builds the policy from the configured lists and the disposable email domain file. An
+/// empty file path disables the disposable domain check.
+pub fn from_config(config: &AppConfig) -> Result<IdentityPolicy, DomainError> {
+    let disposable = match config
+        .disposable_email_domains_file
+        .as_deref()
+        .filter(|path| !path.is_empty())
+    {
+        Some(path) => {
+            let contents = std::fs::read_to_string(path)
+                .map_err(|err| DomainError::Internal(format!("cannot read {path}: {err}")))?;
+            parse_domain_list(&contents)
+        }
+        None => Vec::new(),
+    };
+
+    Ok(IdentityPolicy::new(
+        &config.reserved_usernames,
+        &config.blocked_username_words,
+        &config.blocked_email_domains,
+        &config.allowed_email_domains,
+        &disposable,
+    ))
+}
+
+/// One domain per line; blank lines and everything after a `#` are ignored.
+fn parse_domain_list(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
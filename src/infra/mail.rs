@@ -0,0 +1,27 @@
+use crate::domain::DomainError;
+use async_trait::async_trait;
+
+#[derive(Debug, Clone)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: Email) -> Result<(), DomainError>;
+}
+
+/// Writes outgoing mail to the log instead of delivering it. Stands in until an SMTP or
+/// provider-backed mailer is configured.
+#[derive(Clone, Default)]
+pub struct LogMailer;
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, email: Email) -> Result<(), DomainError> {
+        tracing::info!(to = %email.to, subject = %email.subject, body = %email.body, "outgoing email");
+        Ok(())
+    }
+}
@@ -1,3 +1,8 @@
 pub mod auth;
 pub mod db;
-pub mod security;
\ No newline at end of file
+pub mod events;
+pub mod identity_policy;
+pub mod mail;
+pub mod security;
+pub mod storage;
+pub mod webhook;
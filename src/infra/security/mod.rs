@@ -1 +1 @@
-pub mod password;
\ No newline at end of file
+pub mod password;
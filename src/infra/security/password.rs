@@ -2,11 +2,12 @@ use crate::domain::DomainError;
 use argon2::{Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version};
 use password_hash::SaltString;
 use rand::rngs::OsRng;
+use std::str::FromStr;
 
 pub fn hash_password(plain: &str) -> Result<String, DomainError> {
     let salt = SaltString::generate(&mut OsRng);
-    let params = Params::new(19_456, 2, 1, None)
-        .map_err(|err| DomainError::Internal(err.to_string()))?;
+    let params =
+        Params::new(19_456, 2, 1, None).map_err(|err| DomainError::Internal(err.to_string()))?;
     let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
     let hash = argon2
         .hash_password(plain.as_bytes(), &salt)
@@ -15,7 +16,43 @@ pub fn hash_password(plain: &str) -> Result<String, DomainError> {
     Ok(hash)
 }
 
+/// Stored for accounts that have no password yet, such as imported users who were sent an
+/// account setup link. It is not a valid hash, so no password matches it.
+pub const UNUSABLE_PASSWORD_HASH: &str = "!";
+
+const BCRYPT_PREFIXES: [&str; 3] = ["$2a$", "$2b$", "$2y$"];
+
+fn is_bcrypt(hash: &str) -> bool {
+    BCRYPT_PREFIXES
+        .iter()
+        .any(|prefix| hash.starts_with(prefix))
+}
+
+/// Checks that a hash taken over from another system is one `verify_password` understands:
+/// argon2 in PHC format or bcrypt.
+pub fn validate_imported_hash(hash: &str) -> Result<(), String> {
+    if is_bcrypt(hash) {
+        return bcrypt::HashParts::from_str(hash)
+            .map(|_| ())
+            .map_err(|_| "invalid bcrypt hash".to_string());
+    }
+
+    match PasswordHash::new(hash) {
+        Ok(parsed) if parsed.algorithm.as_str().starts_with("argon2") => Ok(()),
+        _ => Err("password_hash must be an argon2 (PHC) or bcrypt hash".to_string()),
+    }
+}
+
+/// Verifies against argon2 hashes and, for imported accounts, bcrypt hashes.
 pub fn verify_password(hash: &str, candidate: &str) -> Result<bool, DomainError> {
+    if hash == UNUSABLE_PASSWORD_HASH {
+        return Ok(false);
+    }
+    if is_bcrypt(hash) {
+        return bcrypt::verify(candidate, hash)
+            .map_err(|err| DomainError::Internal(err.to_string()));
+    }
+
     let parsed = PasswordHash::new(hash).map_err(|err| DomainError::Internal(err.to_string()))?;
     Ok(Argon2::default()
         .verify_password(candidate.as_bytes(), &parsed)
@@ -32,4 +69,17 @@ mod tests {
         assert!(verify_password(&hash, "p@ssword").unwrap());
         assert!(!verify_password(&hash, "wrong").unwrap());
     }
+
+    #[test]
+    fn imported_hashes_are_verified() {
+        let bcrypt_hash = bcrypt::hash("p@ssword", 4).unwrap();
+        assert!(validate_imported_hash(&bcrypt_hash).is_ok());
+        assert!(verify_password(&bcrypt_hash, "p@ssword").unwrap());
+        assert!(!verify_password(&bcrypt_hash, "wrong").unwrap());
+
+        assert!(validate_imported_hash(&hash_password("p@ssword").unwrap()).is_ok());
+        assert!(validate_imported_hash("plaintext").is_err());
+        assert!(validate_imported_hash("$2b$10$short").is_err());
+        assert!(!verify_password(UNUSABLE_PASSWORD_HASH, "").unwrap());
+    }
 }
@@ -0,0 +1,80 @@
+use super::{validate_key, Blob, BlobStore};
+use crate::domain::DomainError;
+use async_trait::async_trait;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Keeps blobs as files below a root directory, with the content type in a `.type`
+/// file next to each one.
+#[derive(Debug, Clone)]
+pub struct LocalBlobStore {
+    root: PathBuf,
+}
+
+impl LocalBlobStore {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    fn paths(&self, key: &str) -> Result<(PathBuf, PathBuf), DomainError> {
+        validate_key(key)?;
+        let path = self.root.join(key);
+        let type_path = self.root.join(format!("{key}.type"));
+        Ok((path, type_path))
+    }
+}
+
+fn io_error(err: std::io::Error) -> DomainError {
+    DomainError::Internal(format!("blob storage: {err}"))
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    async fn put(&self, key: &str, blob: Blob) -> Result<(), DomainError> {
+        let (path, type_path) = self.paths(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(io_error)?;
+        }
+
+        // Write to a temporary file first so readers never see a partial image.
+        let partial = self.root.join(format!("{key}.partial"));
+        tokio::fs::write(&partial, &blob.bytes)
+            .await
+            .map_err(io_error)?;
+        tokio::fs::write(&type_path, blob.content_type.as_bytes())
+            .await
+            .map_err(io_error)?;
+        tokio::fs::rename(&partial, &path).await.map_err(io_error)
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Blob>, DomainError> {
+        let (path, type_path) = self.paths(key)?;
+        let bytes = match tokio::fs::read(&path).await {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(io_error(err)),
+        };
+        let content_type = tokio::fs::read_to_string(&type_path)
+            .await
+            .unwrap_or_else(|_| "application/octet-stream".to_string());
+
+        Ok(Some(Blob {
+            content_type,
+            bytes,
+        }))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), DomainError> {
+        let (path, type_path) = self.paths(key)?;
+        for path in [path, type_path] {
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => {}
+                Err(err) if err.kind() == ErrorKind::NotFound => {}
+                Err(err) => return Err(io_error(err)),
+            }
+        }
+        Ok(())
+    }
+}
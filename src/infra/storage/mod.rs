@@ -0,0 +1,53 @@
+mod local;
+mod s3;
+
+pub use local::LocalBlobStore;
+pub use s3::{S3BlobStore, S3Settings};
+
+use crate::config::{AppConfig, BlobStoreKind};
+use crate::domain::DomainError;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub struct Blob {
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Binary objects such as avatar images, addressed by `/`-separated keys.
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// Stores `blob` under `key`, replacing what was there.
+    async fn put(&self, key: &str, blob: Blob) -> Result<(), DomainError>;
+    async fn get(&self, key: &str) -> Result<Option<Blob>, DomainError>;
+    /// Removes `key`; a missing key is not an error.
+    async fn delete(&self, key: &str) -> Result<(), DomainError>;
+}
+
+/// Builds the store selected by `BLOB_STORE`.
+pub fn from_config(config: &AppConfig) -> Result<Arc<dyn BlobStore>, DomainError> {
+    match config.blob_store {
+        BlobStoreKind::Local => Ok(Arc::new(LocalBlobStore::new(&config.blob_local_dir))),
+        BlobStoreKind::S3 => Ok(Arc::new(S3BlobStore::new(S3Settings::from_config(config)?))),
+    }
+}
+
+/// Keys are generated by the services, but are still checked so that no backend can be
+/// steered outside its root.
+fn validate_key(key: &str) -> Result<(), DomainError> {
+    let valid = !key.is_empty()
+        && key.split('/').all(|segment| {
+            !segment.is_empty()
+                && segment != "."
+                && segment != ".."
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(DomainError::Internal(format!("invalid blob key: {key}")))
+    }
+}
@@ -0,0 +1,221 @@
+use super::{validate_key, Blob, BlobStore};
+use crate::config::AppConfig;
+use crate::domain::DomainError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::{Method, StatusCode, Url};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct S3Settings {
+    /// Base URL of the service, e.g. `https://s3.eu-west-1.amazonaws.com` or
+    /// `http://localhost:9000` for MinIO.
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+impl S3Settings {
+    pub fn from_config(config: &AppConfig) -> Result<Self, DomainError> {
+        let required = |value: &Option<String>, name: &str| {
+            value.clone().ok_or_else(|| {
+                DomainError::Internal(format!("{name} must be set when BLOB_STORE=s3"))
+            })
+        };
+        Ok(Self {
+            endpoint: required(&config.s3_endpoint, "S3_ENDPOINT")?,
+            bucket: required(&config.s3_bucket, "S3_BUCKET")?,
+            region: config.s3_region.clone(),
+            access_key_id: required(&config.s3_access_key_id, "S3_ACCESS_KEY_ID")?,
+            secret_access_key: required(&config.s3_secret_access_key, "S3_SECRET_ACCESS_KEY")?,
+        })
+    }
+}
+
+/// Stores blobs in an S3-compatible bucket (AWS S3, MinIO, ...), addressed path-style and
+/// signed with AWS Signature Version 4.
+#[derive(Clone)]
+pub struct S3BlobStore {
+    client: reqwest::Client,
+    settings: S3Settings,
+}
+
+impl S3BlobStore {
+    pub fn new(settings: S3Settings) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("S3 HTTP client configuration is valid");
+        Self { client, settings }
+    }
+
+    fn object_url(&self, key: &str) -> Result<Url, DomainError> {
+        validate_key(key)?;
+        let url = format!(
+            "{}/{}/{}",
+            self.settings.endpoint.trim_end_matches('/'),
+            self.settings.bucket,
+            key
+        );
+        Url::parse(&url).map_err(|err| DomainError::Internal(format!("invalid S3 URL: {err}")))
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        body: Vec<u8>,
+        content_type: Option<&str>,
+    ) -> Result<reqwest::Response, DomainError> {
+        let url = self.object_url(key)?;
+        let mut headers = sign_request(&self.settings, &method, &url, &body, Utc::now())?;
+        if let Some(content_type) = content_type {
+            headers.insert(CONTENT_TYPE, header_value(content_type)?);
+        }
+
+        self.client
+            .request(method, url)
+            .headers(headers)
+            .body(body)
+            .send()
+            .await
+            .map_err(|err| DomainError::Internal(format!("S3 request failed: {err}")))
+    }
+}
+
+async fn s3_error(action: &str, response: reqwest::Response) -> DomainError {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    DomainError::Internal(format!("S3 {action} failed with {status}: {body}"))
+}
+
+#[async_trait]
+impl BlobStore for S3BlobStore {
+    async fn put(&self, key: &str, blob: Blob) -> Result<(), DomainError> {
+        let response = self
+            .send(Method::PUT, key, blob.bytes, Some(&blob.content_type))
+            .await?;
+        if !response.status().is_success() {
+            return Err(s3_error("put", response).await);
+        }
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Blob>, DomainError> {
+        let response = self.send(Method::GET, key, Vec::new(), None).await?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => {
+                let content_type = response
+                    .headers()
+                    .get(CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or("application/octet-stream")
+                    .to_string();
+                let bytes = response
+                    .bytes()
+                    .await
+                    .map_err(|err| DomainError::Internal(format!("S3 read failed: {err}")))?;
+                Ok(Some(Blob {
+                    content_type,
+                    bytes: bytes.to_vec(),
+                }))
+            }
+            _ => Err(s3_error("get", response).await),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), DomainError> {
+        let response = self.send(Method::DELETE, key, Vec::new(), None).await?;
+        if !response.status().is_success() && response.status() != StatusCode::NOT_FOUND {
+            return Err(s3_error("delete", response).await);
+        }
+        Ok(())
+    }
+}
+
+fn header_value(value: &str) -> Result<HeaderValue, DomainError> {
+    HeaderValue::from_str(value).map_err(|err| DomainError::Internal(err.to_string()))
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn signing_key(secret_access_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac_sha256(format!("AWS4{secret_access_key}").as_bytes(), date);
+    let key = hmac_sha256(&key, region);
+    let key = hmac_sha256(&key, service);
+    hmac_sha256(&key, "aws4_request")
+}
+
+/// Headers that authenticate a request without query parameters.
+fn sign_request(
+    settings: &S3Settings,
+    method: &Method,
+    url: &Url,
+    body: &[u8],
+    now: DateTime<Utc>,
+) -> Result<HeaderMap, DomainError> {
+    let host = match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{host}:{port}"),
+        (Some(host), None) => host.to_string(),
+        (None, _) => return Err(DomainError::Internal("S3 endpoint has no host".to_string())),
+    };
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let payload_hash = hex::encode(Sha256::digest(body));
+
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    // Keys are limited to unreserved characters, so the path needs no further encoding.
+    let canonical_request = format!(
+        "{method}\n{}\n\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\n{signed_headers}\n{payload_hash}",
+        url.path()
+    );
+    let scope = format!("{date}/{}/s3/aws4_request", settings.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let key = signing_key(&settings.secret_access_key, &date, &settings.region, "s3");
+    let signature = hex::encode(hmac_sha256(&key, &string_to_sign));
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-amz-date", header_value(&amz_date)?);
+    headers.insert("x-amz-content-sha256", header_value(&payload_hash)?);
+    headers.insert(
+        AUTHORIZATION,
+        header_value(&format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            settings.access_key_id
+        ))?,
+    );
+    Ok(headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signing_key_matches_aws_example() {
+        // Example from the AWS Signature Version 4 documentation.
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex::encode(key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+}
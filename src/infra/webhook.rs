@@ -0,0 +1,94 @@
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::Duration;
+
+pub const SIGNATURE_HEADER: &str = "x-webhook-signature";
+pub const TIMESTAMP_HEADER: &str = "x-webhook-timestamp";
+pub const EVENT_HEADER: &str = "x-webhook-event";
+pub const DELIVERY_HEADER: &str = "x-webhook-delivery";
+
+/// A signed request ready to be posted to a webhook endpoint.
+#[derive(Debug, Clone)]
+pub struct WebhookRequest {
+    pub url: String,
+    pub delivery_id: String,
+    pub event_type: String,
+    /// Unix seconds; part of the signed content so receivers can reject replays.
+    pub timestamp: i64,
+    pub body: String,
+    pub signature: String,
+}
+
+impl WebhookRequest {
+    pub fn new(
+        url: String,
+        secret: &str,
+        delivery_id: String,
+        event_type: String,
+        timestamp: i64,
+        body: String,
+    ) -> Self {
+        let signature = sign(secret, timestamp, &body);
+        Self {
+            url,
+            delivery_id,
+            event_type,
+            timestamp,
+            body,
+            signature,
+        }
+    }
+}
+
+/// `sha256=` followed by the hex HMAC-SHA256 of `"{timestamp}.{body}"` keyed with the
+/// endpoint secret.
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[async_trait]
+pub trait WebhookSender: Send + Sync {
+    /// Posts the request and returns the response status. `Err` means no response
+    /// arrived (connection refused, timeout, ...).
+    async fn send(&self, request: &WebhookRequest) -> Result<u16, String>;
+}
+
+#[derive(Clone)]
+pub struct HttpWebhookSender {
+    client: reqwest::Client,
+}
+
+impl HttpWebhookSender {
+    pub fn new(timeout: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .expect("webhook HTTP client configuration is valid");
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl WebhookSender for HttpWebhookSender {
+    async fn send(&self, request: &WebhookRequest) -> Result<u16, String> {
+        let response = self
+            .client
+            .post(&request.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, &request.signature)
+            .header(TIMESTAMP_HEADER, request.timestamp.to_string())
+            .header(EVENT_HEADER, &request.event_type)
+            .header(DELIVERY_HEADER, &request.delivery_id)
+            .body(request.body.clone())
+            .send()
+            .await
+            .map_err(|err| err.to_string())?;
+
+        Ok(response.status().as_u16())
+    }
+}
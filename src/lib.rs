@@ -6,10 +6,20 @@ pub mod infra;
 pub mod utils;
 
 use crate::config::AppConfig;
+use crate::domain::IdentityPolicy;
+use crate::infra::events::EventBus;
+use crate::infra::storage::BlobStore;
 use sqlx::PgPool;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: PgPool,
     pub config: AppConfig,
-}
\ No newline at end of file
+    /// Live feed of relayed domain events.
+    pub events: EventBus,
+    /// Uploaded files, e.g. avatars.
+    pub blobs: Arc<dyn BlobStore>,
+    /// Reserved usernames and blocked email domains.
+    pub identity_policy: Arc<IdentityPolicy>,
+}
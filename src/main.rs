@@ -1,10 +1,16 @@
-use axum::http::Method;
+use axum::body::Body;
+use axum::http::{HeaderName, Method, Request};
 use axum::routing::get;
 use std::net::SocketAddr;
-use tracing_subscriber::EnvFilter;
-use user_management_backend_rust::{api, config::AppConfig, infra::db, AppState};
+use std::sync::Arc;
 use tower_http::cors::{AllowOrigin, CorsLayer};
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
 use tower_http::trace::TraceLayer;
+use tracing_subscriber::EnvFilter;
+use user_management_backend_rust::api::middleware::audit::REQUEST_ID_HEADER;
+use user_management_backend_rust::infra::events::EventBus;
+use user_management_backend_rust::infra::{identity_policy, storage};
+use user_management_backend_rust::{api, app::jobs, config::AppConfig, infra::db, AppState};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -22,8 +28,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let state = AppState {
         db: pool,
         config: config.clone(),
+        events: EventBus::new(),
+        blobs: storage::from_config(&config)?,
+        identity_policy: Arc::new(identity_policy::from_config(&config)?),
     };
 
+    jobs::spawn_role_grant_sweeper(state.clone());
+    jobs::spawn_suspension_sweeper(state.clone());
+    jobs::spawn_outbox_relay(state.clone());
+    jobs::spawn_webhook_dispatcher(state.clone());
+
     let allowed_origins: Vec<_> = config
         .cors_allowed_origins
         .iter()
@@ -31,18 +45,52 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .collect();
     let cors = CorsLayer::new()
         .allow_origin(AllowOrigin::list(allowed_origins))
-        .allow_methods([Method::GET, Method::POST, Method::PATCH, Method::DELETE]);
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+        ]);
 
     let app = api::routes::create_router(state)
         .route("/health", get(|| async { "ok" }))
         .layer(cors)
-        .layer(TraceLayer::new_for_http());
+        .layer(PropagateRequestIdLayer::new(HeaderName::from_static(
+            REQUEST_ID_HEADER,
+        )))
+        .layer(
+            TraceLayer::new_for_http().make_span_with(|request: &Request<Body>| {
+                // Filled in by the auth extractor; `actor_id` is set for impersonated requests.
+                let request_id = request
+                    .headers()
+                    .get(REQUEST_ID_HEADER)
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or_default();
+                tracing::info_span!(
+                    "request",
+                    method = %request.method(),
+                    uri = %request.uri(),
+                    request_id,
+                    user_id = tracing::field::Empty,
+                    actor_id = tracing::field::Empty,
+                )
+            }),
+        )
+        .layer(SetRequestIdLayer::new(
+            HeaderName::from_static(REQUEST_ID_HEADER),
+            MakeRequestUuid,
+        ));
 
     let addr: SocketAddr = format!("{}:{}", config.app_host, config.app_port).parse()?;
     tracing::info!("listening on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
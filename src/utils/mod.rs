@@ -1 +1,10 @@
-// Shared helpers can live here when needed.
\ No newline at end of file
+// Shared helpers can live here when needed.
+
+/// Quotes a CSV field when it contains a delimiter, quote or line break (RFC 4180).
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,255 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{
+    empty_request, json_request, read_json, register, register_admin, reset_db, send, setup_app,
+};
+use serde_json::{json, Value};
+use serial_test::serial;
+
+async fn define(app: &axum::Router, token: &str, body: Value) -> String {
+    let response = send(app, json_request("POST", "/attributes", Some(token), body)).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    read_json(response).await["id"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+async fn set_attributes(
+    app: &axum::Router,
+    token: &str,
+    uri: &str,
+    attributes: Value,
+) -> StatusCode {
+    let body = json!({ "attributes": attributes });
+    send(app, json_request("PATCH", uri, Some(token), body))
+        .await
+        .status()
+}
+
+async fn usernames(app: &axum::Router, token: &str, query: &str) -> Vec<String> {
+    let response = send(
+        app,
+        empty_request("GET", &format!("/users?sort=username&{query}"), Some(token)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK, "{query}");
+    read_json(response)
+        .await
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|user| user["username"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+#[serial]
+async fn attribute_writes_follow_their_definitions() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_, admin_token) = register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    let (alice, alice_token) = register(&state, &app, "alice@example.com", "aliceuser").await;
+    let (bob, _) = register(&state, &app, "bob@example.com", "bobuser").await;
+
+    let body = json!({ "name": "department", "type": "string" });
+    let response = send(
+        &app,
+        json_request("POST", "/attributes", Some(&alice_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    define(
+        &app,
+        &admin_token,
+        json!({ "name": "department", "type": "string", "user_editable": true }),
+    )
+    .await;
+    define(
+        &app,
+        &admin_token,
+        json!({ "name": "employee_id", "type": "string", "required": true, "unique": true }),
+    )
+    .await;
+    define(
+        &app,
+        &admin_token,
+        json!({ "name": "start_date", "type": "date" }),
+    )
+    .await;
+
+    // Users read the schema, but only write what is user-editable.
+    let response = send(
+        &app,
+        empty_request("GET", "/attributes", Some(&alice_token)),
+    )
+    .await;
+    assert_eq!(read_json(response).await.as_array().unwrap().len(), 3);
+    assert_eq!(
+        set_attributes(
+            &app,
+            &alice_token,
+            "/users/me",
+            json!({ "department": "sales" })
+        )
+        .await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        set_attributes(
+            &app,
+            &alice_token,
+            "/users/me",
+            json!({ "employee_id": "E1" })
+        )
+        .await,
+        StatusCode::FORBIDDEN
+    );
+    for attributes in [json!({ "department": 7 }), json!({ "shoe_size": 42 })] {
+        let status = set_attributes(&app, &alice_token, "/users/me", attributes.clone()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{attributes}");
+    }
+
+    let alice_uri = format!("/users/{}", alice.id);
+    let bob_uri = format!("/users/{}", bob.id);
+    let status = set_attributes(
+        &app,
+        &admin_token,
+        &alice_uri,
+        json!({ "start_date": "2025-02-31" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    // Admin writes must leave the required `employee_id` set.
+    let status = set_attributes(
+        &app,
+        &admin_token,
+        &alice_uri,
+        json!({ "start_date": "2025-03-01" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let body = json!({ "employee_id": "E1", "start_date": "2025-03-01" });
+    assert_eq!(
+        set_attributes(&app, &admin_token, &alice_uri, body).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        set_attributes(&app, &admin_token, &bob_uri, json!({ "employee_id": "E1" })).await,
+        StatusCode::CONFLICT
+    );
+    assert_eq!(
+        set_attributes(
+            &app,
+            &admin_token,
+            &alice_uri,
+            json!({ "employee_id": null })
+        )
+        .await,
+        StatusCode::BAD_REQUEST
+    );
+
+    // Values merge into the stored ones, and `null` removes one.
+    let status = set_attributes(
+        &app,
+        &admin_token,
+        &alice_uri,
+        json!({ "start_date": null }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let response = send(&app, empty_request("GET", "/users/me", Some(&alice_token))).await;
+    assert_eq!(
+        read_json(response).await["attributes"],
+        json!({ "department": "sales", "employee_id": "E1" })
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn indexed_attributes_filter_the_user_list() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_, token) = register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    let department = define(
+        &app,
+        &token,
+        json!({ "name": "department", "type": "string", "indexed": true }),
+    )
+    .await;
+    define(
+        &app,
+        &token,
+        json!({ "name": "level", "type": "number", "indexed": true }),
+    )
+    .await;
+    define(&app, &token, json!({ "name": "plan", "type": "string" })).await;
+
+    for (email, username, attributes) in [
+        (
+            "alice@example.com",
+            "alice",
+            json!({ "department": "sales", "level": 2, "plan": "pro" }),
+        ),
+        (
+            "bob@example.com",
+            "bob",
+            json!({ "department": "sales", "level": 3 }),
+        ),
+        (
+            "carol@example.com",
+            "carol",
+            json!({ "department": "support", "level": 2 }),
+        ),
+    ] {
+        let (user, _) = register(&state, &app, email, username).await;
+        let status = set_attributes(&app, &token, &format!("/users/{}", user.id), attributes).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    assert_eq!(
+        usernames(&app, &token, "attr.department=sales").await,
+        vec!["alice", "bob"]
+    );
+    assert_eq!(
+        usernames(&app, &token, "attr.level=2.0").await,
+        vec!["alice", "carol"]
+    );
+    assert_eq!(
+        usernames(&app, &token, "attr.department=sales&attr.level=2").await,
+        vec!["alice"]
+    );
+    for query in ["attr.plan=pro", "attr.level=high", "attr.shoe_size=42"] {
+        let response = send(
+            &app,
+            empty_request("GET", &format!("/users?{query}"), Some(&token)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{query}");
+    }
+
+    // Values already shared by two users cannot be made unique.
+    let uri = format!("/attributes/{department}");
+    let response = send(
+        &app,
+        json_request("PATCH", &uri, Some(&token), json!({ "unique": true })),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response = send(&app, empty_request("DELETE", &uri, Some(&token))).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = send(
+        &app,
+        empty_request("GET", "/users?search=alice", Some(&token)),
+    )
+    .await;
+    assert_eq!(
+        read_json(response).await[0]["attributes"],
+        json!({ "level": 2, "plan": "pro" })
+    );
+}
@@ -0,0 +1,160 @@
+mod common;
+
+use axum::extract::ConnectInfo;
+use axum::http::StatusCode;
+use common::{
+    empty_request, json_request, read_json, register, register_admin, reset_db, send, setup_app,
+};
+use serde_json::{json, Value};
+use serial_test::serial;
+use std::net::SocketAddr;
+
+async fn login_from(app: &axum::Router, email: &str, addr: &str) {
+    let body = json!({ "email": email, "password": "password123" });
+    let mut request = json_request("POST", "/auth/login", None, body);
+    let addr: SocketAddr = addr.parse().unwrap();
+    request.extensions_mut().insert(ConnectInfo(addr));
+
+    let response = send(app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+#[serial]
+async fn audit_events_can_be_searched_and_exported() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (admin, admin_token) = register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    let (user, user_token) = register(&state, &app, "user@example.com", "userone").await;
+    login_from(&app, "user@example.com", "10.0.0.1:4000").await;
+    login_from(&app, "user@example.com", "10.0.0.1:4001").await;
+
+    let response = send(
+        &app,
+        empty_request("GET", "/audit/events", Some(&user_token)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let uri = format!("/audit/events?target_user_id={}&limit=1", user.id);
+    let response = send(&app, empty_request("GET", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut page = read_json(response).await;
+
+    // Walk the pages newest first until the cursor runs out.
+    let mut types = Vec::new();
+    loop {
+        let items = page["items"].as_array().unwrap();
+        assert_eq!(items.len(), 1);
+        types.push(items[0]["event_type"].as_str().unwrap().to_string());
+
+        let Some(cursor) = page["next_cursor"].as_str() else {
+            break;
+        };
+        let next = format!("{uri}&cursor={cursor}");
+        let response = send(&app, empty_request("GET", &next, Some(&admin_token))).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        page = read_json(response).await;
+    }
+    assert_eq!(
+        types,
+        vec![
+            "auth.login_succeeded",
+            "auth.login_succeeded",
+            "user.registered"
+        ]
+    );
+
+    let uri = format!(
+        "/audit/events?event_type=role.assigned&target_user_id={}",
+        admin.id
+    );
+    let response = send(&app, empty_request("GET", &uri, Some(&admin_token))).await;
+    let page = read_json(response).await;
+    assert_eq!(page["items"].as_array().unwrap().len(), 1);
+    assert_eq!(page["items"][0]["metadata"]["role"], "admin");
+
+    let response = send(
+        &app,
+        empty_request("GET", "/audit/events?cursor=garbage", Some(&admin_token)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let uri = format!("/audit/events/export?target_user_id={}", user.id);
+    let response = send(&app, empty_request("GET", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "application/x-ndjson");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let lines: Vec<Value> = std::str::from_utf8(&body)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0]["ip"], "10.0.0.1");
+
+    let uri = format!("/audit/events/export?format=csv&target_user_id={}", user.id);
+    let response = send(&app, empty_request("GET", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let csv = String::from_utf8(body.to_vec()).unwrap();
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows.len(), 4);
+    assert!(rows[0].starts_with("id,event_type,created_at"));
+    assert!(rows[3].contains("user.registered"));
+}
+
+#[tokio::test]
+#[serial]
+async fn users_see_their_own_activity_with_new_ips_flagged() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_, user_token) = register(&state, &app, "user@example.com", "userone").await;
+    login_from(&app, "user@example.com", "10.0.0.1:4000").await;
+    login_from(&app, "user@example.com", "10.0.0.1:4001").await;
+    login_from(&app, "user@example.com", "192.168.1.7:4000").await;
+
+    let response = send(
+        &app,
+        empty_request("GET", "/users/me/activity", Some(&user_token)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let activity = read_json(response).await;
+    let entries = activity.as_array().unwrap();
+
+    let flags: Vec<(&str, bool)> = entries
+        .iter()
+        .map(|entry| {
+            (
+                entry["event_type"].as_str().unwrap(),
+                entry["new_ip"].as_bool().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        flags,
+        vec![
+            ("auth.login_succeeded", true),
+            ("auth.login_succeeded", false),
+            ("auth.login_succeeded", true),
+            ("user.registered", false),
+        ]
+    );
+    assert_eq!(entries[0]["ip"], "192.168.1.7");
+    assert_eq!(entries[0]["impersonated"], false);
+
+    let response = send(
+        &app,
+        empty_request("GET", "/users/me/activity?limit=1", Some(&user_token)),
+    )
+    .await;
+    assert_eq!(read_json(response).await.as_array().unwrap().len(), 1);
+}
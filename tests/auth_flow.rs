@@ -1,50 +1,13 @@
-use axum::body::Body;
-use axum::http::{Request, StatusCode};
-use serde_json::json;
+mod common;
+
+use axum::http::StatusCode;
+use common::{
+    empty_request, json_request, read_json, register, register_admin, reset_db, send, setup_app,
+};
+use serde_json::{json, Value};
 use serial_test::serial;
-use tower::ServiceExt;
-use user_management_backend_rust::api;
-use user_management_backend_rust::config::AppConfig;
+use user_management_backend_rust::domain::Role;
 use user_management_backend_rust::infra::auth::jwt::JwtService;
-use user_management_backend_rust::infra::db;
-use user_management_backend_rust::infra::db::user_repo::SqlxUserRepository;
-use user_management_backend_rust::AppState;
-
-async fn setup_app() -> (AppState, axum::Router) {
-    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
-    let config = AppConfig {
-        app_host: "0.0.0.0".to_string(),
-        app_port: 0,
-        database_url,
-        jwt_secret: "test-secret".to_string(),
-        access_token_minutes: 15,
-        refresh_token_days: 7,
-        cors_allowed_origins: vec!["http://localhost:3000".to_string()],
-    };
-
-    let pool = db::create_pool(&config.database_url)
-        .await
-        .expect("failed to create pool");
-    sqlx::migrate!("./migrations")
-        .run(&pool)
-        .await
-        .expect("failed to run migrations");
-
-    let state = AppState {
-        db: pool,
-        config,
-    };
-
-    let app = api::routes::create_router(state.clone());
-    (state, app)
-}
-
-async fn reset_db(state: &AppState) {
-    sqlx::query("TRUNCATE TABLE users")
-        .execute(&state.db)
-        .await
-        .expect("failed to truncate users");
-}
 
 #[tokio::test]
 #[serial]
@@ -58,16 +21,11 @@ async fn register_login_and_profile_flow() {
         "password": "password123"
     });
 
-    let response = app
-        .clone()
-        .oneshot(
-            Request::post("/auth/register")
-                .header("content-type", "application/json")
-                .body(Body::from(register_body.to_string()))
-                .unwrap(),
-        )
-        .await
-        .unwrap();
+    let response = send(
+        &app,
+        json_request("POST", "/auth/register", None, register_body),
+    )
+    .await;
 
     assert_eq!(response.status(), StatusCode::CREATED);
 
@@ -76,31 +34,13 @@ async fn register_login_and_profile_flow() {
         "password": "password123"
     });
 
-    let response = app
-        .clone()
-        .oneshot(
-            Request::post("/auth/login")
-                .header("content-type", "application/json")
-                .body(Body::from(login_body.to_string()))
-                .unwrap(),
-        )
-        .await
-        .unwrap();
+    let response = send(&app, json_request("POST", "/auth/login", None, login_body)).await;
 
     assert_eq!(response.status(), StatusCode::OK);
-    let body_bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
-    let body: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    let body = read_json(response).await;
     let access_token = body.get("access_token").unwrap().as_str().unwrap();
 
-    let response = app
-        .oneshot(
-            Request::get("/users/me")
-                .header("authorization", format!("Bearer {}", access_token))
-                .body(Body::empty())
-                .unwrap(),
-        )
-        .await
-        .unwrap();
+    let response = send(&app, empty_request("GET", "/users/me", Some(access_token))).await;
 
     assert_eq!(response.status(), StatusCode::OK);
 }
@@ -111,54 +51,196 @@ async fn admin_list_users_flow() {
     let (state, app) = setup_app().await;
     reset_db(&state).await;
 
-    let repo = SqlxUserRepository::new(state.db.clone());
-    let jwt = JwtService::new(&state.config);
+    let (_admin, access_token) =
+        register_admin(&state, &app, "admin@example.com", "adminuser").await;
 
-    let register_body = json!({
-        "email": "admin@example.com",
-        "username": "adminuser",
-        "password": "password123"
-    });
+    let response = send(&app, empty_request("GET", "/users", Some(&access_token))).await;
 
-    let response = app
-        .clone()
-        .oneshot(
-            Request::post("/auth/register")
-                .header("content-type", "application/json")
-                .body(Body::from(register_body.to_string()))
-                .unwrap(),
-        )
-        .await
-        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
 
-    assert_eq!(response.status(), StatusCode::CREATED);
+#[tokio::test]
+#[serial]
+async fn user_without_permission_is_forbidden() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (user, access_token) = register(&state, &app, "plain@example.com", "plainuser").await;
+    assert_eq!(user.roles, vec![Role::USER.to_string()]);
+    assert!(user.permissions.is_empty());
 
-    let admin_user = repo
-        .find_by_email("admin@example.com")
-        .await
+    let response = send(&app, empty_request("GET", "/users", Some(&access_token))).await;
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+#[serial]
+async fn impersonation_tokens_are_marked_and_restricted() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (admin, admin_token) = register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    let (user, user_token) = register(&state, &app, "user@example.com", "userone").await;
+
+    let uri = format!("/users/{}/impersonate", admin.id);
+    let response = send(&app, empty_request("POST", &uri, Some(&user_token))).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let uri = format!("/users/{}/impersonate", user.id);
+    let response = send(&app, empty_request("POST", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = read_json(response).await;
+    assert_eq!(body["actor_id"], admin.id.to_string());
+    assert_eq!(body["user"]["id"], user.id.to_string());
+    let token = body["access_token"].as_str().unwrap().to_string();
+
+    let claims = JwtService::new(&state.config).decode_token(&token).unwrap();
+    assert_eq!(claims.sub, user.id.to_string());
+    assert_eq!(claims.actor_id(), Some(admin.id));
+
+    let response = send(&app, empty_request("GET", "/users/me", Some(&token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json(response).await["id"], user.id.to_string());
+
+    let body = json!({ "email": "takeover@example.com" });
+    let response = send(&app, json_request("PATCH", "/users/me", Some(&token), body)).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = send(&app, empty_request("POST", &uri, Some(&token))).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let body = json!({ "refresh_token": token });
+    let response = send(&app, json_request("POST", "/auth/refresh", None, body)).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Support staff cannot borrow the identity of someone holding permissions they lack.
+    let (support, support_token) =
+        register(&state, &app, "support@example.com", "supportuser").await;
+    let body = json!({ "name": "support", "permissions": ["users:impersonate"] });
+    let response = send(
+        &app,
+        json_request("POST", "/roles", Some(&admin_token), body),
+    )
+    .await;
+    let role_id = read_json(response).await["id"]
+        .as_str()
         .unwrap()
-        .unwrap();
-    let _ = repo
-        .set_role(admin_user.user.id, user_management_backend_rust::domain::Role::Admin)
-        .await
-        .unwrap();
-
-    let admin_user = repo
-        .find_by_id(admin_user.user.id)
-        .await
+        .to_string();
+    let assign_uri = format!("/roles/{role_id}/users/{}", support.id);
+    let response = send(&app, empty_request("PUT", &assign_uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = send(&app, empty_request("POST", &uri, Some(&support_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let uri = format!("/users/{}/impersonate", admin.id);
+    let response = send(&app, empty_request("POST", &uri, Some(&support_token))).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+#[serial]
+async fn security_events_are_audited_with_the_change() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (admin, admin_token) = register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    let (user, user_token) = register(&state, &app, "user@example.com", "userone").await;
+
+    let body = json!({ "email": "user@example.com", "password": "wrong-password" });
+    let response = send(&app, json_request("POST", "/auth/login", None, body)).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let body = json!({ "username": "renamed" });
+    let mut request = json_request("PATCH", "/users/me", Some(&user_token), body);
+    request
+        .headers_mut()
+        .insert("user-agent", "audit-test/1.0".parse().unwrap());
+    request
+        .headers_mut()
+        .insert("x-request-id", "req-123".parse().unwrap());
+    let response = send(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // A refused change leaves no trace: the event shares the transaction.
+    let uri = format!("/users/{}?confirm=true", admin.id);
+    let response = send(&app, empty_request("DELETE", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let events: Vec<Value> = sqlx::query_scalar(
+        "SELECT to_jsonb(audit_events) FROM audit_events WHERE target_user_id = $1 ORDER BY created_at",
+    )
+    .bind(user.id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap();
+    let types: Vec<&str> = events
+        .iter()
+        .map(|event| event["event_type"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            "user.registered",
+            "auth.login_failed",
+            "user.profile_updated"
+        ]
+    );
+    assert_eq!(events[1]["metadata"]["reason"], "invalid_password");
+
+    let profile_update = &events[2];
+    assert_eq!(profile_update["actor_id"], user.id.to_string());
+    assert_eq!(profile_update["before"], json!({ "username": "userone" }));
+    assert_eq!(profile_update["after"], json!({ "username": "renamed" }));
+    assert_eq!(profile_update["user_agent"], "audit-test/1.0");
+    assert_eq!(profile_update["request_id"], "req-123");
+
+    let deactivations: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM audit_events WHERE event_type = 'user.deactivated'",
+    )
+    .fetch_one(&state.db)
+    .await
+    .unwrap();
+    assert_eq!(deactivations, 0);
+}
+
+#[tokio::test]
+#[serial]
+async fn emails_and_usernames_are_normalized_and_unique_regardless_of_case() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let body = json!({ "email": "Alice@Example.COM", "username": "Ａｌｉｃｅ", "password": "password123" });
+    let response = send(&app, json_request("POST", "/auth/register", None, body)).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = read_json(response).await;
+    assert_eq!(body["email"], "alice@example.com");
+    assert_eq!(body["username"], "Alice");
+
+    let body =
+        json!({ "email": "alice@example.com", "username": "another", "password": "password123" });
+    let response = send(&app, json_request("POST", "/auth/register", None, body)).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // Differs only by case, or by a Cyrillic `а` and a `1` standing in for `l`.
+    for username in ["alice", "а1ice"] {
+        let body = json!({ "email": "other@example.com", "username": username, "password": "password123" });
+        let response = send(&app, json_request("POST", "/auth/register", None, body)).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT, "{username}");
+    }
+
+    let body = json!({ "email": "ALICE@example.com", "password": "password123" });
+    let response = send(&app, json_request("POST", "/auth/login", None, body)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let token = read_json(response).await["access_token"]
+        .as_str()
         .unwrap()
-        .unwrap();
-    let access_token = jwt.create_access_token(&admin_user.user).unwrap();
-
-    let response = app
-        .oneshot(
-            Request::get("/users")
-                .header("authorization", format!("Bearer {}", access_token))
-                .body(Body::empty())
-                .unwrap(),
-        )
-        .await
-        .unwrap();
+        .to_string();
 
+    // Changing the case of your own username is not a collision with yourself.
+    let body = json!({ "username": "ALICE" });
+    let response = send(&app, json_request("PATCH", "/users/me", Some(&token), body)).await;
     assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json(response).await["username"], "ALICE");
 }
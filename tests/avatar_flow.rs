@@ -0,0 +1,181 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use common::{empty_request, read_json, register, reset_db, send, setup_app};
+use image::{ImageFormat, Rgba, RgbaImage};
+use serial_test::serial;
+use std::io::Cursor;
+
+const BOUNDARY: &str = "avatar-test-boundary";
+
+fn upload_request(token: &str, field: &str, content_type: &str, bytes: &[u8]) -> Request<Body> {
+    let mut body = format!(
+        "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"{field}\"; filename=\"avatar\"\r\nContent-Type: {content_type}\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend_from_slice(bytes);
+    body.extend_from_slice(format!("\r\n--{BOUNDARY}--\r\n").as_bytes());
+
+    Request::builder()
+        .method("PUT")
+        .uri("/users/me/avatar")
+        .header("authorization", format!("Bearer {token}"))
+        .header(
+            "content-type",
+            format!("multipart/form-data; boundary={BOUNDARY}"),
+        )
+        .body(Body::from(body))
+        .unwrap()
+}
+
+fn encode(image: &RgbaImage, format: ImageFormat) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    match format {
+        ImageFormat::Jpeg => image::DynamicImage::ImageRgba8(image.clone())
+            .to_rgb8()
+            .write_to(&mut Cursor::new(&mut bytes), format)
+            .unwrap(),
+        _ => image
+            .write_to(&mut Cursor::new(&mut bytes), format)
+            .unwrap(),
+    }
+    bytes
+}
+
+/// A JPEG carrying an EXIF segment with an orientation tag.
+fn jpeg_with_exif(image: &RgbaImage) -> Vec<u8> {
+    let jpeg = encode(image, ImageFormat::Jpeg);
+    let mut payload = b"Exif\0\0MM\0\x2a\0\0\0\x08".to_vec();
+    payload.extend_from_slice(&[0, 1, 0x01, 0x12, 0, 3, 0, 0, 0, 1, 0, 6, 0, 0, 0, 0, 0, 0]);
+    payload.extend_from_slice(b"GPS 52.5200 13.4050");
+
+    let mut bytes = jpeg[..2].to_vec();
+    bytes.extend_from_slice(&[0xFF, 0xE1]);
+    bytes.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+    bytes.extend_from_slice(&payload);
+    bytes.extend_from_slice(&jpeg[2..]);
+    bytes
+}
+
+async fn read_bytes(response: axum::response::Response) -> Vec<u8> {
+    axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap()
+        .to_vec()
+}
+
+#[tokio::test]
+#[serial]
+async fn avatar_upload_is_resized_and_served_with_caching() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (user, token) = register(&state, &app, "alice@example.com", "aliceuser").await;
+    let source = RgbaImage::from_pixel(300, 200, Rgba([200, 30, 30, 128]));
+
+    let response = send(
+        &app,
+        upload_request(
+            &token,
+            "avatar",
+            "image/png",
+            &encode(&source, ImageFormat::Png),
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let picture = read_json(response).await["picture"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(
+        picture.starts_with(&format!("/users/{}/avatar?v=", user.id)),
+        "{picture}"
+    );
+
+    // Served without authentication, as browsers load it from an <img> tag.
+    let response = send(&app, empty_request("GET", &picture, None)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "image/jpeg");
+    assert_eq!(response.headers()["cache-control"], "public, max-age=86400");
+    let etag = response.headers()["etag"].to_str().unwrap().to_string();
+    let image = image::load_from_memory(&read_bytes(response).await).unwrap();
+    assert_eq!((image.width(), image.height()), (512, 512));
+
+    let uri = format!("/users/{}/avatar?size=100", user.id);
+    let response = send(&app, empty_request("GET", &uri, None)).await;
+    let image = image::load_from_memory(&read_bytes(response).await).unwrap();
+    assert_eq!((image.width(), image.height()), (128, 128));
+
+    let request = Request::builder()
+        .uri(&picture)
+        .header("if-none-match", &etag)
+        .body(Body::empty())
+        .unwrap();
+    let response = send(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    let response = send(
+        &app,
+        upload_request(&token, "avatar", "image/jpeg", &jpeg_with_exif(&source)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let new_picture = read_json(response).await["picture"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert_ne!(new_picture, picture);
+    let response = send(&app, empty_request("GET", &new_picture, None)).await;
+    let bytes = read_bytes(response).await;
+    assert!(!bytes.windows(4).any(|window| window == b"Exif"));
+    assert!(!bytes.windows(3).any(|window| window == b"GPS"));
+
+    let (other, _) = register(&state, &app, "bob@example.com", "bobuser").await;
+    let uri = format!("/users/{}/avatar", other.id);
+    let response = send(&app, empty_request("GET", &uri, None)).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[serial]
+async fn avatar_upload_rejects_bad_files() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_user, token) = register(&state, &app, "alice@example.com", "aliceuser").await;
+    let png = encode(
+        &RgbaImage::from_pixel(64, 64, Rgba([0, 0, 0, 255])),
+        ImageFormat::Png,
+    );
+
+    let response = send(
+        &app,
+        upload_request(&token, "avatar", "text/plain", b"hello"),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // The declared type must match the content.
+    let response = send(&app, upload_request(&token, "avatar", "image/jpeg", &png)).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send(
+        &app,
+        upload_request(&token, "avatar", "image/png", &png[..png.len() / 2]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send(&app, upload_request(&token, "picture", "image/png", &png)).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let oversized = vec![0u8; 2 * 1024 * 1024];
+    let response = send(
+        &app,
+        upload_request(&token, "avatar", "image/png", &oversized),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
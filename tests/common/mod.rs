@@ -0,0 +1,193 @@
+#![allow(dead_code)]
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::response::Response;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tower::ServiceExt;
+use user_management_backend_rust::api;
+use user_management_backend_rust::config::{AppConfig, BlobStoreKind};
+use user_management_backend_rust::domain::{
+    AttributeDefinitionRepository, AuditContext, Role, RoleRepository, User, UserRepository,
+};
+use user_management_backend_rust::infra::auth::jwt::JwtService;
+use user_management_backend_rust::infra::db;
+use user_management_backend_rust::infra::db::attribute_repo::SqlxAttributeDefinitionRepository;
+use user_management_backend_rust::infra::db::role_repo::SqlxRoleRepository;
+use user_management_backend_rust::infra::db::user_repo::SqlxUserRepository;
+use user_management_backend_rust::infra::events::EventBus;
+use user_management_backend_rust::infra::identity_policy;
+use user_management_backend_rust::infra::storage::LocalBlobStore;
+use user_management_backend_rust::AppState;
+
+pub async fn setup_app() -> (AppState, axum::Router) {
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+    let config = AppConfig {
+        app_host: "0.0.0.0".to_string(),
+        app_port: 0,
+        database_url,
+        jwt_secret: "test-secret".to_string(),
+        access_token_minutes: 15,
+        impersonation_token_minutes: 10,
+        refresh_token_days: 7,
+        jwt_groups_claim: false,
+        invitation_ttl_hours: 72,
+        role_sweep_interval_seconds: 60,
+        status_sweep_interval_seconds: 60,
+        invitation_accept_url: "http://localhost:3000/invitations/accept".to_string(),
+        account_setup_url: "http://localhost:3000/account/setup".to_string(),
+        email_change_ttl_hours: 24,
+        email_change_revert_days: 7,
+        email_change_confirm_url: "http://localhost:3000/account/confirm-email".to_string(),
+        email_change_revert_url: "http://localhost:3000/account/revert-email".to_string(),
+        trust_proxy_headers: false,
+        webhook_max_attempts: 2,
+        webhook_retry_base_seconds: 30,
+        webhook_poll_interval_seconds: 5,
+        webhook_timeout_seconds: 2,
+        outbox_poll_interval_seconds: 1,
+        scim_token: Some("scim-test-token".to_string()),
+        blob_store: BlobStoreKind::Local,
+        blob_local_dir: std::env::temp_dir()
+            .join("user-management-test-blobs")
+            .to_string_lossy()
+            .into_owned(),
+        s3_endpoint: None,
+        s3_bucket: None,
+        s3_region: "us-east-1".to_string(),
+        s3_access_key_id: None,
+        s3_secret_access_key: None,
+        avatar_max_bytes: 1024 * 1024,
+        reserved_usernames: vec!["admin".to_string(), "support".to_string()],
+        blocked_username_words: vec!["badword".to_string()],
+        blocked_email_domains: Vec::new(),
+        allowed_email_domains: Vec::new(),
+        disposable_email_domains_file: Some(
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/config/disposable_email_domains.txt"
+            )
+            .to_string(),
+        ),
+        cors_allowed_origins: vec!["http://localhost:3000".to_string()],
+    };
+
+    let pool = db::create_pool(&config.database_url)
+        .await
+        .expect("failed to create pool");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("failed to run migrations");
+
+    let blobs = Arc::new(LocalBlobStore::new(&config.blob_local_dir));
+    let identity_policy =
+        Arc::new(identity_policy::from_config(&config).expect("failed to load identity policy"));
+    let state = AppState {
+        db: pool,
+        config,
+        events: EventBus::new(),
+        blobs,
+        identity_policy,
+    };
+
+    let app = api::routes::create_router(state.clone());
+    (state, app)
+}
+
+pub async fn reset_db(state: &AppState) {
+    // Deleted one by one so that their indexes on `users` are dropped too.
+    let attributes = SqlxAttributeDefinitionRepository::new(state.db.clone());
+    for definition in attributes.list().await.expect("failed to list attributes") {
+        attributes
+            .delete(definition.id)
+            .await
+            .expect("failed to delete attribute");
+    }
+    sqlx::query("TRUNCATE TABLE users, organizations, groups, audit_events, webhook_endpoints, outbox CASCADE")
+        .execute(&state.db)
+        .await
+        .expect("failed to truncate users");
+    sqlx::query("DELETE FROM roles WHERE NOT is_builtin")
+        .execute(&state.db)
+        .await
+        .expect("failed to delete custom roles");
+}
+
+pub async fn send(app: &axum::Router, request: Request<Body>) -> Response {
+    app.clone().oneshot(request).await.unwrap()
+}
+
+pub async fn read_json(response: Response) -> Value {
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body_bytes).unwrap()
+}
+
+pub fn json_request(method: &str, uri: &str, token: Option<&str>, body: Value) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json");
+    if let Some(token) = token {
+        builder = builder.header("authorization", format!("Bearer {}", token));
+    }
+    builder.body(Body::from(body.to_string())).unwrap()
+}
+
+pub fn empty_request(method: &str, uri: &str, token: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder().method(method).uri(uri);
+    if let Some(token) = token {
+        builder = builder.header("authorization", format!("Bearer {}", token));
+    }
+    builder.body(Body::empty()).unwrap()
+}
+
+/// Registers a user through the public endpoint and returns it with a fresh access token.
+pub async fn register(
+    state: &AppState,
+    app: &axum::Router,
+    email: &str,
+    username: &str,
+) -> (User, String) {
+    let body = json!({
+        "email": email,
+        "username": username,
+        "password": "password123"
+    });
+    let response = send(app, json_request("POST", "/auth/register", None, body)).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let repo = SqlxUserRepository::new(state.db.clone());
+    let user = repo.find_by_email(email).await.unwrap().unwrap().user;
+    let token = JwtService::new(&state.config)
+        .create_access_token(&user)
+        .unwrap();
+    (user, token)
+}
+
+/// Registers a user and grants it the built-in `admin` role.
+pub async fn register_admin(
+    state: &AppState,
+    app: &axum::Router,
+    email: &str,
+    username: &str,
+) -> (User, String) {
+    let (user, _) = register(state, app, email, username).await;
+
+    let roles = SqlxRoleRepository::new(state.db.clone());
+    let admin_role = roles.find_by_name(Role::ADMIN).await.unwrap().unwrap();
+    roles
+        .assign(user.id, admin_role.id, None, &AuditContext::default())
+        .await
+        .unwrap();
+
+    let repo = SqlxUserRepository::new(state.db.clone());
+    let user = repo.find_by_id(user.id).await.unwrap().unwrap().user;
+    let token = JwtService::new(&state.config)
+        .create_access_token(&user)
+        .unwrap();
+    (user, token)
+}
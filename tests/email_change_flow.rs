@@ -0,0 +1,167 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{empty_request, json_request, read_json, register, reset_db, send, setup_app};
+use serde_json::{json, Value};
+use serial_test::serial;
+use user_management_backend_rust::domain::EmailChangeRepository;
+use user_management_backend_rust::infra::auth::jwt::{EmailChangeLink, JwtService};
+use user_management_backend_rust::infra::db::email_change_repo::SqlxEmailChangeRepository;
+use user_management_backend_rust::AppState;
+use uuid::Uuid;
+
+/// The links mailed for the user's latest email change.
+async fn links(state: &AppState, user_id: Uuid) -> (String, String) {
+    let id: Uuid = sqlx::query_scalar(
+        "SELECT id FROM email_changes WHERE user_id = $1 ORDER BY created_at DESC LIMIT 1",
+    )
+    .bind(user_id)
+    .fetch_one(&state.db)
+    .await
+    .unwrap();
+    let change = SqlxEmailChangeRepository::new(state.db.clone())
+        .find_by_id(id)
+        .await
+        .unwrap()
+        .unwrap();
+    let jwt = JwtService::new(&state.config);
+    (
+        jwt.create_email_change_token(&change, EmailChangeLink::Confirm)
+            .unwrap(),
+        jwt.create_email_change_token(&change, EmailChangeLink::Revert)
+            .unwrap(),
+    )
+}
+
+async fn follow(app: &axum::Router, path: &str, token: &str) -> StatusCode {
+    send(
+        app,
+        json_request("POST", path, None, json!({ "token": token })),
+    )
+    .await
+    .status()
+}
+
+async fn me(app: &axum::Router, token: &str) -> Value {
+    read_json(send(app, empty_request("GET", "/users/me", Some(token))).await).await
+}
+
+#[tokio::test]
+#[serial]
+async fn email_changes_wait_for_the_new_address() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (alice, token) = register(&state, &app, "alice@example.com", "aliceuser").await;
+    register(&state, &app, "bob@example.com", "bobuser").await;
+
+    let body = json!({ "email": "bob@example.com" });
+    let response = send(&app, json_request("PATCH", "/users/me", Some(&token), body)).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // Nothing is requested or mailed when the rest of the update is refused.
+    let body = json!({ "email": "alice@work.example.com", "username": "bobuser" });
+    let response = send(&app, json_request("PATCH", "/users/me", Some(&token), body)).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let requested: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM email_changes WHERE user_id = $1")
+            .bind(alice.id)
+            .fetch_one(&state.db)
+            .await
+            .unwrap();
+    assert_eq!(requested, 0);
+
+    let body = json!({ "email": "alice@work.example.com" });
+    let response = send(&app, json_request("PATCH", "/users/me", Some(&token), body)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let profile = read_json(response).await;
+    assert_eq!(profile["email"], "alice@example.com");
+    assert_eq!(profile["pending_email"], "alice@work.example.com");
+
+    // Each link only works at its own endpoint.
+    let (confirm, revert) = links(&state, alice.id).await;
+    assert_eq!(
+        follow(&app, "/auth/confirm-email", &revert).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        follow(&app, "/auth/confirm-email", &confirm).await,
+        StatusCode::NO_CONTENT
+    );
+    assert_eq!(
+        follow(&app, "/auth/confirm-email", &confirm).await,
+        StatusCode::UNAUTHORIZED
+    );
+
+    let profile = me(&app, &token).await;
+    assert_eq!(profile["email"], "alice@work.example.com");
+    assert_eq!(profile["email_verified"], true);
+    assert_eq!(profile["pending_email"], Value::Null);
+
+    // The old address can still take the account back.
+    assert_eq!(
+        follow(&app, "/auth/revert-email", &revert).await,
+        StatusCode::NO_CONTENT
+    );
+    assert_eq!(me(&app, &token).await["email"], "alice@example.com");
+    assert_eq!(
+        follow(&app, "/auth/revert-email", &revert).await,
+        StatusCode::UNAUTHORIZED
+    );
+
+    let events: Vec<String> = sqlx::query_scalar(
+        "SELECT event_type FROM audit_events WHERE target_user_id = $1 AND event_type LIKE 'user.email%' ORDER BY created_at",
+    )
+    .bind(alice.id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap();
+    assert_eq!(
+        events,
+        vec![
+            "user.email_change_requested",
+            "user.email_changed",
+            "user.email_change_reverted"
+        ]
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn unconfirmed_email_changes_can_be_cancelled_or_superseded() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (alice, token) = register(&state, &app, "alice@example.com", "aliceuser").await;
+
+    let body = json!({ "email": "first@example.com" });
+    let response = send(&app, json_request("PATCH", "/users/me", Some(&token), body)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (first_confirm, _) = links(&state, alice.id).await;
+
+    // A second request replaces the first, whose link stops working.
+    let body = json!({ "email": "second@example.com" });
+    let response = send(&app, json_request("PATCH", "/users/me", Some(&token), body)).await;
+    assert_eq!(
+        read_json(response).await["pending_email"],
+        "second@example.com"
+    );
+    assert_eq!(
+        follow(&app, "/auth/confirm-email", &first_confirm).await,
+        StatusCode::UNAUTHORIZED
+    );
+
+    let (confirm, revert) = links(&state, alice.id).await;
+    assert_eq!(
+        follow(&app, "/auth/revert-email", &revert).await,
+        StatusCode::NO_CONTENT
+    );
+    assert_eq!(
+        follow(&app, "/auth/confirm-email", &confirm).await,
+        StatusCode::UNAUTHORIZED
+    );
+
+    let profile = me(&app, &token).await;
+    assert_eq!(profile["email"], "alice@example.com");
+    assert_eq!(profile["pending_email"], Value::Null);
+}
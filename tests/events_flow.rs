@@ -0,0 +1,182 @@
+mod common;
+
+use axum::body::{Body, BodyDataStream};
+use axum::http::{Request, StatusCode};
+use common::{empty_request, json_request, register, register_admin, reset_db, send, setup_app};
+use futures_util::StreamExt;
+use serde_json::{json, Value};
+use serial_test::serial;
+use std::time::Duration;
+use user_management_backend_rust::app::services::outbox_service::OutboxRelay;
+use user_management_backend_rust::infra::db::outbox_repo::SqlxOutboxRepository;
+use user_management_backend_rust::AppState;
+use uuid::Uuid;
+
+async fn relay(state: &AppState) {
+    let relay = OutboxRelay::new(
+        SqlxOutboxRepository::new(state.db.clone()),
+        state.events.clone(),
+    );
+    while relay.relay_pending().await.unwrap() > 0 {}
+}
+
+/// Reads the next event from an SSE body as `(id, event, data)`.
+async fn next_event(body: &mut BodyDataStream, buffer: &mut String) -> (String, String, Value) {
+    loop {
+        if let Some(end) = buffer.find("\n\n") {
+            let frame: String = buffer.drain(..end + 2).collect();
+            let field = |name: &str| {
+                frame
+                    .lines()
+                    .find_map(|line| line.strip_prefix(name))
+                    .map(|value| value.trim_start().to_string())
+            };
+            // Keep-alive comments carry no fields.
+            if let Some(data) = field("data:") {
+                return (
+                    field("id:").unwrap(),
+                    field("event:").unwrap(),
+                    serde_json::from_str(&data).unwrap(),
+                );
+            }
+            continue;
+        }
+
+        let chunk = tokio::time::timeout(Duration::from_secs(5), body.next())
+            .await
+            .expect("no event within 5 seconds")
+            .unwrap()
+            .unwrap();
+        buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+    }
+}
+
+fn stream_request(uri: &str, token: &str, last_event_id: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder()
+        .uri(uri)
+        .header("authorization", format!("Bearer {token}"));
+    if let Some(id) = last_event_id {
+        builder = builder.header("last-event-id", id);
+    }
+    builder.body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn admins_stream_filtered_events_and_resume() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (admin, admin_token) = register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    let (user, user_token) = register(&state, &app, "user@example.com", "userone").await;
+    relay(&state).await;
+
+    let response = send(
+        &app,
+        empty_request("GET", "/admin/events/stream", Some(&user_token)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = send(
+        &app,
+        empty_request(
+            "GET",
+            "/admin/events/stream?types=user.bogus",
+            Some(&admin_token),
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send(
+        &app,
+        stream_request(
+            "/admin/events/stream?types=user.logged_in",
+            &admin_token,
+            None,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+    let mut live = response.into_body().into_data_stream();
+    let mut live_buffer = String::new();
+
+    // Not of a requested type: skipped.
+    let response = send(
+        &app,
+        json_request(
+            "PATCH",
+            "/users/me",
+            Some(&user_token),
+            json!({ "username": "renamed" }),
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let login = json!({ "email": "user@example.com", "password": "password123" });
+    let response = send(&app, json_request("POST", "/auth/login", None, login)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    relay(&state).await;
+
+    let (login_id, event, data) = next_event(&mut live, &mut live_buffer).await;
+    assert_eq!(event, "user.logged_in");
+    assert_eq!(data["id"], login_id.as_str());
+    assert_eq!(data["user_id"], user.id.to_string());
+
+    // Resuming after the admin's registration replays everything since, then goes live.
+    let (first_id,): (Uuid,) =
+        sqlx::query_as("SELECT event_id FROM outbox ORDER BY position LIMIT 1")
+            .fetch_one(&state.db)
+            .await
+            .unwrap();
+    let response = send(
+        &app,
+        stream_request(
+            "/admin/events/stream",
+            &admin_token,
+            Some(&first_id.to_string()),
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut resumed = response.into_body().into_data_stream();
+    let mut buffer = String::new();
+
+    let mut replayed = Vec::new();
+    for _ in 0..4 {
+        let (id, event, data) = next_event(&mut resumed, &mut buffer).await;
+        replayed.push((id, event, data["user_id"].as_str().unwrap().to_string()));
+    }
+    let admin_id = admin.id.to_string();
+    let user_id = user.id.to_string();
+    assert_eq!(
+        replayed
+            .iter()
+            .map(|(_, event, user_id)| (event.as_str(), user_id.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("user.role_changed", admin_id.as_str()),
+            ("user.created", user_id.as_str()),
+            ("user.updated", user_id.as_str()),
+            ("user.logged_in", user_id.as_str()),
+        ]
+    );
+    assert_eq!(replayed[3].0, login_id);
+
+    let response = send(
+        &app,
+        json_request(
+            "PATCH",
+            "/users/me",
+            Some(&user_token),
+            json!({ "username": "again" }),
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    relay(&state).await;
+    let (_, event, data) = next_event(&mut resumed, &mut buffer).await;
+    assert_eq!(event, "user.updated");
+    assert_eq!(data["data"]["after"]["username"], "again");
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{
+    empty_request, json_request, read_json, register, register_admin, reset_db, send, setup_app,
+};
+use serde_json::json;
+use serial_test::serial;
+
+#[tokio::test]
+#[serial]
+async fn nested_group_roles_are_inherited() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_admin, admin_token) =
+        register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    let (user, _) = register(&state, &app, "user@example.com", "plainuser").await;
+
+    let body = json!({ "name": "readers", "permissions": ["users:read"] });
+    let response = send(
+        &app,
+        json_request("POST", "/roles", Some(&admin_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let role_id = read_json(response).await["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let mut group_ids = Vec::new();
+    for name in ["engineering", "backend"] {
+        let body = json!({ "name": name });
+        let response = send(
+            &app,
+            json_request("POST", "/groups", Some(&admin_token), body),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        group_ids.push(
+            read_json(response).await["id"]
+                .as_str()
+                .unwrap()
+                .to_string(),
+        );
+    }
+    let (parent, child) = (&group_ids[0], &group_ids[1]);
+
+    // backend ⊂ engineering; the user joins only backend.
+    let uri = format!("/groups/{parent}/groups/{child}");
+    let response = send(&app, empty_request("PUT", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let uri = format!("/groups/{child}/users/{}", user.id);
+    let response = send(&app, empty_request("PUT", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let uri = format!("/groups/{parent}/roles/{role_id}");
+    let response = send(&app, empty_request("PUT", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json(response).await["roles"], json!(["readers"]));
+
+    // Closing the loop is refused.
+    let uri = format!("/groups/{child}/groups/{parent}");
+    let response = send(&app, empty_request("PUT", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let uri = format!("/users/{}", user.id);
+    let response = send(&app, empty_request("GET", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = read_json(response).await;
+    assert!(body["roles"]
+        .as_array()
+        .unwrap()
+        .contains(&json!("readers")));
+    assert!(body["permissions"]
+        .as_array()
+        .unwrap()
+        .contains(&json!("users:read")));
+
+    let uri = format!("/users/{}/groups", user.id);
+    let response = send(&app, empty_request("GET", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let groups = read_json(response).await;
+    let names: Vec<&str> = groups
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|group| group["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["backend", "engineering"]);
+
+    // Unnesting drops the inherited permission on the next lookup.
+    let uri = format!("/groups/{parent}/groups/{child}");
+    let response = send(&app, empty_request("DELETE", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let uri = format!("/users/{}", user.id);
+    let response = send(&app, empty_request("GET", &uri, Some(&admin_token))).await;
+    let body = read_json(response).await;
+    assert!(!body["permissions"]
+        .as_array()
+        .unwrap()
+        .contains(&json!("users:read")));
+}
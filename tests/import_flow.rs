@@ -0,0 +1,190 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use chrono::{Duration, Utc};
+use common::{json_request, read_json, register, register_admin, reset_db, send, setup_app};
+use serde_json::{json, Value};
+use serial_test::serial;
+use user_management_backend_rust::domain::{AccountState, UserRepository};
+use user_management_backend_rust::infra::auth::jwt::JwtService;
+use user_management_backend_rust::infra::db::user_repo::SqlxUserRepository;
+use user_management_backend_rust::infra::security::password::{self, UNUSABLE_PASSWORD_HASH};
+
+fn import_request(query: &str, content_type: &str, token: &str, body: String) -> Request<Body> {
+    Request::builder()
+        .method("POST")
+        .uri(format!("/users/import{query}"))
+        .header("content-type", content_type)
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::from(body))
+        .unwrap()
+}
+
+async fn login_status(app: &axum::Router, email: &str, password: &str) -> StatusCode {
+    let body = json!({ "email": email, "password": password });
+    send(app, json_request("POST", "/auth/login", None, body))
+        .await
+        .status()
+}
+
+#[tokio::test]
+#[serial]
+async fn csv_import_reports_row_errors_and_is_idempotent() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_, token) = register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    let bcrypt_hash = bcrypt::hash("legacy-secret", 4).unwrap();
+    let argon2_hash = password::hash_password("argon-secret").unwrap();
+    let csv = format!(
+        "email,username,role,is_active,password_hash\n\
+         ann@example.com,ann,admin,true,{bcrypt_hash}\n\
+         ben@example.com,ben,,false,\"{argon2_hash}\"\n\
+         not-an-email,cat,,,{bcrypt_hash}\n\
+         dan@example.com,dan,wizard,,{bcrypt_hash}\n\
+         eve@example.com,eve,,,plaintext\n\
+         ann@example.com,ann2,,,{bcrypt_hash}\n\
+         admin@example.com,someone,,,{bcrypt_hash}\n"
+    );
+
+    let response = send(
+        &app,
+        import_request("?dry_run=true", "text/csv", &token, csv.clone()),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let report = read_json(response).await;
+    assert_eq!(report["dry_run"], true);
+    assert_eq!(report["total"], 7);
+    assert_eq!(report["created"], 2);
+    assert_eq!(report["skipped"], 1);
+    assert_eq!(report["failed"], 4);
+    let lines: Vec<u64> = report["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|error: &Value| error["line"].as_u64().unwrap())
+        .collect();
+    assert_eq!(lines, vec![4, 5, 6, 7]);
+    assert_eq!(
+        login_status(&app, "ann@example.com", "legacy-secret").await,
+        StatusCode::UNAUTHORIZED
+    );
+
+    let response = send(&app, import_request("", "text/csv", &token, csv.clone())).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let report = read_json(response).await;
+    assert_eq!(report["dry_run"], false);
+    assert_eq!(report["created"], 2);
+
+    assert_eq!(
+        login_status(&app, "ann@example.com", "legacy-secret").await,
+        StatusCode::OK
+    );
+    let repo = SqlxUserRepository::new(state.db.clone());
+    let ben = repo
+        .find_by_email("ben@example.com")
+        .await
+        .unwrap()
+        .unwrap()
+        .user;
+    assert!(!ben.is_active());
+    let ann = repo
+        .find_by_email("ann@example.com")
+        .await
+        .unwrap()
+        .unwrap()
+        .user;
+    assert!(ann.roles.iter().any(|role| role == "admin"));
+
+    // Re-running the same file creates nothing new.
+    let response = send(&app, import_request("", "text/csv", &token, csv)).await;
+    let report = read_json(response).await;
+    assert_eq!(report["created"], 0);
+    assert_eq!(report["skipped"], 3);
+}
+
+#[tokio::test]
+#[serial]
+async fn invited_users_set_their_password_once() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_, token) = register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    let ndjson =
+        "{\"email\":\"fay@example.com\",\"username\":\"fay\"}\n\n{\"email\":\"gus@example.com\"}\n"
+            .to_string();
+
+    let response = send(
+        &app,
+        import_request("", "application/x-ndjson", &token, ndjson.clone()),
+    )
+    .await;
+    let report = read_json(response).await;
+    assert_eq!(report["created"], 0);
+    assert_eq!(report["failed"], 2);
+
+    let response = send(
+        &app,
+        import_request("?format=ndjson&invite=true", "text/plain", &token, ndjson),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let report = read_json(response).await;
+    assert_eq!(report["created"], 1);
+    assert_eq!(report["invited"], 1);
+    assert_eq!(report["errors"][0]["line"], 3);
+
+    let repo = SqlxUserRepository::new(state.db.clone());
+    let fay = repo
+        .find_by_email("fay@example.com")
+        .await
+        .unwrap()
+        .unwrap()
+        .user;
+    assert_eq!(fay.status.state, AccountState::PendingVerification);
+    let setup_token = JwtService::new(&state.config)
+        .create_account_setup_token(
+            fay.id,
+            UNUSABLE_PASSWORD_HASH,
+            Utc::now() + Duration::hours(1),
+        )
+        .unwrap();
+
+    let body = json!({ "token": setup_token, "password": "brand-new-pass" });
+    let response = send(
+        &app,
+        json_request("POST", "/auth/setup-password", None, body.clone()),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        login_status(&app, "fay@example.com", "brand-new-pass").await,
+        StatusCode::OK
+    );
+
+    // The link stops working once a password is set.
+    let response = send(
+        &app,
+        json_request("POST", "/auth/setup-password", None, body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+#[serial]
+async fn import_requires_admin() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_, token) = register(&state, &app, "user@example.com", "plainuser").await;
+    let body = "email,username\nx@example.com,xavier\n".to_string();
+    let response = send(
+        &app,
+        import_request("?invite=true", "text/csv", &token, body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,236 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use common::{empty_request, json_request, read_json, register, reset_db, send, setup_app};
+use serde_json::json;
+use serial_test::serial;
+
+#[tokio::test]
+#[serial]
+async fn org_admin_manages_only_their_members() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (owner, owner_token) = register(&state, &app, "owner@example.com", "owneruser").await;
+    let (member, member_token) = register(&state, &app, "member@example.com", "memberuser").await;
+    let (_outsider, outsider_token) =
+        register(&state, &app, "outsider@example.com", "outsider").await;
+
+    let body = json!({ "name": "Acme", "slug": "acme" });
+    let response = send(
+        &app,
+        json_request("POST", "/orgs", Some(&owner_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let org = read_json(response).await;
+    let org_id = org["id"].as_str().unwrap().to_string();
+    assert_eq!(org["role"], "owner");
+
+    let members_uri = format!("/orgs/{org_id}/members");
+    let body = json!({ "email": "member@example.com", "role": "member" });
+    let response = send(
+        &app,
+        json_request("POST", &members_uri, Some(&owner_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // Org owners list their tenant's users without the global `users:read` permission.
+    let request = Request::get("/users")
+        .header("authorization", format!("Bearer {}", owner_token))
+        .header("x-org-id", &org_id)
+        .body(Body::empty())
+        .unwrap();
+    let response = send(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let users = read_json(response).await;
+    assert_eq!(users.as_array().unwrap().len(), 2);
+
+    // Plain members cannot list the tenant, outsiders cannot select it at all.
+    let request = Request::get("/users")
+        .header("authorization", format!("Bearer {}", member_token))
+        .header("x-org-id", &org_id)
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(send(&app, request).await.status(), StatusCode::FORBIDDEN);
+
+    let request = Request::get("/users")
+        .header("authorization", format!("Bearer {}", outsider_token))
+        .header("x-org-id", &org_id)
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(send(&app, request).await.status(), StatusCode::FORBIDDEN);
+
+    let body = json!({ "email": "outsider@example.com", "role": "member" });
+    let response = send(
+        &app,
+        json_request("POST", &members_uri, Some(&member_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // The last owner can neither leave nor be demoted.
+    let owner_uri = format!("/orgs/{org_id}/members/{}", owner.id);
+    let response = send(
+        &app,
+        empty_request("DELETE", &owner_uri, Some(&owner_token)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let member_uri = format!("/orgs/{org_id}/members/{}", member.id);
+    let body = json!({ "role": "admin" });
+    let response = send(
+        &app,
+        json_request("PATCH", &member_uri, Some(&owner_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // A token minted for the org carries the `org_id` claim, so no header is needed.
+    let token_uri = format!("/orgs/{org_id}/token");
+    let response = send(&app, empty_request("POST", &token_uri, Some(&member_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let tokens = read_json(response).await;
+    let scoped_token = tokens["access_token"].as_str().unwrap();
+
+    let response = send(&app, empty_request("GET", "/users", Some(scoped_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let users = read_json(response).await;
+    assert_eq!(users.as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+#[serial]
+async fn invitations_create_or_attach_accounts() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_owner, owner_token) = register(&state, &app, "owner@example.com", "owneruser").await;
+    register(&state, &app, "existing@example.com", "existing").await;
+
+    let body = json!({ "name": "Acme", "slug": "acme" });
+    let response = send(
+        &app,
+        json_request("POST", "/orgs", Some(&owner_token), body),
+    )
+    .await;
+    let org_id = read_json(response).await["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let invitations_uri = format!("/orgs/{org_id}/invitations");
+
+    let invite = |email: &str, role: &str| {
+        json_request(
+            "POST",
+            &invitations_uri,
+            Some(&owner_token),
+            json!({ "email": email, "role": role }),
+        )
+    };
+    let token_of = |invitation: &serde_json::Value| {
+        let url = invitation["url"].as_str().unwrap();
+        url.split_once("?token=").unwrap().1.to_string()
+    };
+
+    // A new address gets an account with the pre-assigned role and a verified email.
+    let response = send(&app, invite("new@example.com", "admin")).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let invitation = read_json(response).await;
+    assert_eq!(invitation["status"], "pending");
+    let stale_token = token_of(&invitation);
+
+    let resend_uri = format!(
+        "{invitations_uri}/{}/resend",
+        invitation["id"].as_str().unwrap()
+    );
+    let response = send(&app, empty_request("POST", &resend_uri, Some(&owner_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let fresh_token = token_of(&read_json(response).await);
+
+    // Resending invalidates the earlier link.
+    let body = json!({ "token": stale_token, "username": "newuser", "password": "password123" });
+    let response = send(
+        &app,
+        json_request("POST", "/invitations/accept", None, body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let body = json!({ "token": fresh_token, "username": "newuser", "password": "password123" });
+    let response = send(
+        &app,
+        json_request("POST", "/invitations/accept", None, body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let session = read_json(response).await;
+    assert_eq!(session["user"]["email_verified"], true);
+
+    // The returned token is already scoped to the organization.
+    let access_token = session["access_token"].as_str().unwrap();
+    let response = send(&app, empty_request("GET", "/users", Some(access_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = json!({ "token": fresh_token, "username": "newuser2", "password": "password123" });
+    let response = send(
+        &app,
+        json_request("POST", "/invitations/accept", None, body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // An existing account is attached after proving its password.
+    let response = send(&app, invite("existing@example.com", "member")).await;
+    let token = token_of(&read_json(response).await);
+
+    let body = json!({ "token": token, "password": "wrong-password" });
+    let response = send(
+        &app,
+        json_request("POST", "/invitations/accept", None, body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let body = json!({ "token": token, "password": "password123" });
+    let response = send(
+        &app,
+        json_request("POST", "/invitations/accept", None, body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = send(&app, invite("existing@example.com", "member")).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // Revoked invitations leave the pending list and can no longer be accepted.
+    let response = send(&app, invite("revoked@example.com", "member")).await;
+    let invitation = read_json(response).await;
+    let token = token_of(&invitation);
+    let revoke_uri = format!("{invitations_uri}/{}", invitation["id"].as_str().unwrap());
+    let response = send(
+        &app,
+        empty_request("DELETE", &revoke_uri, Some(&owner_token)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = send(
+        &app,
+        empty_request("GET", &invitations_uri, Some(&owner_token)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(read_json(response).await.as_array().unwrap().is_empty());
+
+    let body = json!({ "token": token, "username": "revoked", "password": "password123" });
+    let response = send(
+        &app,
+        json_request("POST", "/invitations/accept", None, body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,198 @@
+mod common;
+
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use common::{
+    empty_request, json_request, read_json, register, register_admin, reset_db, send, setup_app,
+};
+use serde_json::{json, Value};
+use serial_test::serial;
+use user_management_backend_rust::app::services::outbox_service::OutboxRelay;
+use user_management_backend_rust::domain::{
+    DomainError, DomainEvent, DomainEventType, EventPublisher, RoleRepository,
+};
+use user_management_backend_rust::infra::db::outbox_repo::SqlxOutboxRepository;
+use user_management_backend_rust::infra::db::role_repo::SqlxRoleRepository;
+use user_management_backend_rust::infra::events::InMemoryEventPublisher;
+use user_management_backend_rust::AppState;
+
+struct FailingPublisher;
+
+#[async_trait]
+impl EventPublisher for FailingPublisher {
+    async fn publish(&self, _event: &DomainEvent) -> Result<(), DomainError> {
+        Err(DomainError::Internal("broker unavailable".to_string()))
+    }
+}
+
+async fn unpublished(state: &AppState) -> Vec<(i32, Option<String>)> {
+    sqlx::query_as(
+        "SELECT attempts, last_error FROM outbox WHERE published_at IS NULL ORDER BY position",
+    )
+    .fetch_all(&state.db)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn committed_changes_are_relayed_once_in_order() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (user, _) = register(&state, &app, "user@example.com", "userone").await;
+    let login = json!({ "email": "user@example.com", "password": "password123" });
+    let response = send(&app, json_request("POST", "/auth/login", None, login)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Refused changes and events without a domain counterpart leave nothing to publish.
+    let duplicate =
+        json!({ "email": "user@example.com", "username": "other", "password": "password123" });
+    let response = send(
+        &app,
+        json_request("POST", "/auth/register", None, duplicate),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let wrong = json!({ "email": "user@example.com", "password": "wrong-password" });
+    let response = send(&app, json_request("POST", "/auth/login", None, wrong)).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Publishing fails: the events stay in the outbox and are retried later.
+    let failing = OutboxRelay::new(
+        SqlxOutboxRepository::new(state.db.clone()),
+        FailingPublisher,
+    );
+    assert_eq!(failing.relay_pending().await.unwrap(), 0);
+    assert_eq!(
+        unpublished(&state).await,
+        vec![
+            (1, Some("internal error: broker unavailable".to_string())),
+            (0, None)
+        ]
+    );
+
+    let publisher = InMemoryEventPublisher::new();
+    let relay = OutboxRelay::new(
+        SqlxOutboxRepository::new(state.db.clone()),
+        publisher.clone(),
+    );
+    // The failed message waits for its backoff and holds back the one behind it.
+    assert_eq!(relay.relay_pending().await.unwrap(), 0);
+    sqlx::query("UPDATE outbox SET available_at = NOW()")
+        .execute(&state.db)
+        .await
+        .unwrap();
+
+    assert_eq!(relay.relay_pending().await.unwrap(), 2);
+    assert_eq!(relay.relay_pending().await.unwrap(), 0);
+    assert!(unpublished(&state).await.is_empty());
+
+    let events = publisher.events();
+    let types: Vec<DomainEventType> = events.iter().map(|event| event.event_type).collect();
+    assert_eq!(
+        types,
+        vec![DomainEventType::UserCreated, DomainEventType::UserLoggedIn]
+    );
+    assert!(events.iter().all(|event| event.user_id == Some(user.id)));
+    assert_ne!(events[0].id, events[1].id);
+}
+
+#[tokio::test]
+#[serial]
+async fn role_changes_without_an_assignment_reach_the_outbox() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (admin, admin_token) = register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    let (user, _) = register(&state, &app, "user@example.com", "userone").await;
+
+    let body = json!({ "name": "support", "permissions": ["users:read"] });
+    let response = send(
+        &app,
+        json_request("POST", "/roles", Some(&admin_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let role_id = read_json(response).await["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let body = json!({ "name": "support-team" });
+    let response = send(
+        &app,
+        json_request("POST", "/groups", Some(&admin_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let group_id = read_json(response).await["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    // The group has no members yet, so giving it a role changes nobody's roles.
+    let uri = format!("/groups/{group_id}/roles/{role_id}");
+    let response = send(&app, empty_request("PUT", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let uri = format!("/groups/{group_id}/users/{}", user.id);
+    let response = send(&app, empty_request("PUT", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let uri = format!("/roles/{role_id}/users/{}?expires_in_minutes=15", user.id);
+    let response = send(&app, empty_request("PUT", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    sqlx::query("UPDATE user_roles SET expires_at = NOW() - INTERVAL '1 minute' WHERE user_id = $1 AND expires_at IS NOT NULL")
+        .bind(user.id)
+        .execute(&state.db)
+        .await
+        .unwrap();
+    let roles = SqlxRoleRepository::new(state.db.clone());
+    assert_eq!(roles.sweep_expired().await.unwrap(), 1);
+
+    let uri = format!("/roles/{role_id}");
+    let response = send(&app, empty_request("DELETE", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let events: Vec<Value> = sqlx::query_scalar(
+        "SELECT payload FROM outbox WHERE event_type = $1 AND payload->>'user_id' = $2 ORDER BY position",
+    )
+    .bind(DomainEventType::UserRoleChanged.as_str())
+    .bind(user.id.to_string())
+    .fetch_all(&state.db)
+    .await
+    .unwrap();
+    let changes: Vec<&str> = events
+        .iter()
+        .map(|event| event["data"]["change"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        changes,
+        vec!["group_member_added", "assigned", "expired", "role_deleted"]
+    );
+
+    let joined = &events[0];
+    assert_eq!(joined["actor_id"], admin.id.to_string());
+    assert_eq!(joined["data"]["metadata"]["group_id"], group_id);
+    assert!(!joined["data"]["before"]["roles"]
+        .as_array()
+        .unwrap()
+        .contains(&json!("support")));
+    assert!(joined["data"]["after"]["roles"]
+        .as_array()
+        .unwrap()
+        .contains(&json!("support")));
+
+    let expired = &events[2];
+    assert_eq!(expired["actor_id"], Value::Null);
+    assert_eq!(expired["data"]["metadata"]["role_id"], role_id);
+
+    let deleted = &events[3];
+    assert_eq!(deleted["actor_id"], admin.id.to_string());
+    assert_eq!(deleted["data"]["metadata"]["role"], "support");
+    assert!(!deleted["data"]["after"]["roles"]
+        .as_array()
+        .unwrap()
+        .contains(&json!("support")));
+}
@@ -0,0 +1,296 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{
+    empty_request, json_request, read_json, register, register_admin, reset_db, send, setup_app,
+};
+use serde_json::{json, Value};
+use serial_test::serial;
+use user_management_backend_rust::domain::{Role, RoleRepository};
+use user_management_backend_rust::infra::db::role_repo::SqlxRoleRepository;
+
+#[tokio::test]
+#[serial]
+async fn custom_role_grants_permissions_and_is_audited() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_admin, admin_token) =
+        register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    let (user, user_token) = register(&state, &app, "support@example.com", "supportuser").await;
+
+    let body = json!({
+        "name": "support",
+        "description": "Read-only user access",
+        "permissions": ["users:read"]
+    });
+    let response = send(
+        &app,
+        json_request("POST", "/roles", Some(&admin_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let role = read_json(response).await;
+    let role_id = role["id"].as_str().unwrap().to_string();
+    assert_eq!(role["permissions"], json!(["users:read"]));
+
+    let response = send(&app, empty_request("GET", "/users", Some(&user_token))).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let uri = format!("/roles/{role_id}/users/{}", user.id);
+    let response = send(&app, empty_request("PUT", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let updated = read_json(response).await;
+    assert_eq!(updated["roles"], json!(["support", "user"]));
+
+    let response = send(&app, empty_request("GET", "/users", Some(&user_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = send(&app, empty_request("DELETE", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let history_uri = format!("/roles/{role_id}/history");
+    let response = send(&app, empty_request("GET", &history_uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let history = read_json(response).await;
+    let actions: Vec<&str> = history
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|event| event["action"].as_str().unwrap())
+        .collect();
+    assert_eq!(actions, vec!["unassigned", "assigned"]);
+
+    let uri = format!("/roles/{role_id}");
+    let body = json!({ "permissions": ["users:read", "groups:read"] });
+    let response = send(&app, json_request("PATCH", &uri, Some(&admin_token), body)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = send(&app, empty_request("DELETE", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let events: Vec<(String, Value, Value)> = sqlx::query_as(
+        "SELECT event_type, before, after FROM audit_events \
+         WHERE metadata->>'role_id' = $1 AND event_type IN ('role.created', 'role.updated', 'role.deleted') \
+         ORDER BY created_at",
+    )
+    .bind(&role_id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap();
+    let types: Vec<&str> = events
+        .iter()
+        .map(|(event_type, _, _)| event_type.as_str())
+        .collect();
+    assert_eq!(types, vec!["role.created", "role.updated", "role.deleted"]);
+    assert_eq!(events[1].1, json!({ "permissions": ["users:read"] }));
+    assert_eq!(
+        events[1].2,
+        json!({ "permissions": ["groups:read", "users:read"] })
+    );
+    assert_eq!(events[2].1["name"], "support");
+}
+
+#[tokio::test]
+#[serial]
+async fn builtin_roles_cannot_be_deleted() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_admin, admin_token) =
+        register_admin(&state, &app, "admin@example.com", "adminuser").await;
+
+    let roles = SqlxRoleRepository::new(state.db.clone());
+    let user_role = roles.find_by_name(Role::USER).await.unwrap().unwrap();
+
+    let uri = format!("/roles/{}", user_role.id);
+    let response = send(&app, empty_request("DELETE", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let body = json!({ "name": "member" });
+    let response = send(&app, json_request("PATCH", &uri, Some(&admin_token), body)).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Emptying `admin` would leave nobody able to manage the deployment.
+    let admin_role = roles.find_by_name(Role::ADMIN).await.unwrap().unwrap();
+    let uri = format!("/roles/{}", admin_role.id);
+    let body = json!({ "permissions": [] });
+    let response = send(&app, json_request("PATCH", &uri, Some(&admin_token), body)).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(
+        roles
+            .find_by_id(admin_role.id)
+            .await
+            .unwrap()
+            .unwrap()
+            .permissions,
+        admin_role.permissions
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn last_admin_cannot_be_removed() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (first, first_token) =
+        register_admin(&state, &app, "first@example.com", "firstadmin").await;
+    let roles = SqlxRoleRepository::new(state.db.clone());
+    let admin_role = roles.find_by_name(Role::ADMIN).await.unwrap().unwrap();
+
+    // Locking yourself out needs an explicit confirmation...
+    let uri = format!("/users/{}", first.id);
+    let response = send(&app, empty_request("DELETE", &uri, Some(&first_token))).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(read_json(response).await["code"], "confirmation_required");
+
+    // ...and is still refused while you are the only active admin.
+    let uri = format!("/users/{}?confirm=true", first.id);
+    let response = send(&app, empty_request("DELETE", &uri, Some(&first_token))).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(read_json(response).await["code"], "last_admin");
+
+    let unassign_uri = format!("/roles/{}/users/{}?confirm=true", admin_role.id, first.id);
+    let response = send(
+        &app,
+        empty_request("DELETE", &unassign_uri, Some(&first_token)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(read_json(response).await["code"], "last_admin");
+
+    // With a second admin around, stepping down is fine.
+    let (second, second_token) =
+        register_admin(&state, &app, "second@example.com", "secondadmin").await;
+    let response = send(
+        &app,
+        empty_request("DELETE", &unassign_uri, Some(&first_token)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let uri = format!("/users/{}/status?confirm=true", second.id);
+    let body = json!({ "status": "locked", "reason": "rotating credentials" });
+    let response = send(&app, json_request("POST", &uri, Some(&second_token), body)).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(read_json(response).await["code"], "last_admin");
+}
+
+#[tokio::test]
+#[serial]
+async fn elevation_grants_expire() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_admin, admin_token) =
+        register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    let (oncall, oncall_token) =
+        register_admin(&state, &app, "oncall@example.com", "oncalladmin").await;
+    let (user, user_token) = register(&state, &app, "support@example.com", "supportuser").await;
+
+    let body = json!({ "name": "support", "permissions": ["users:read"] });
+    let response = send(
+        &app,
+        json_request("POST", "/roles", Some(&admin_token), body),
+    )
+    .await;
+    let role_id = read_json(response).await["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let body =
+        json!({ "role_id": role_id, "duration_minutes": 30, "reason": "Investigating ticket 42" });
+    let response = send(
+        &app,
+        json_request("POST", "/elevations", Some(&user_token), body.clone()),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let request_id = read_json(response).await["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let response = send(
+        &app,
+        json_request("POST", "/elevations", Some(&user_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let approve_uri = format!("/elevations/{request_id}/approve");
+    let response = send(
+        &app,
+        json_request("POST", &approve_uri, Some(&user_token), json!({})),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Privileges cannot be granted through an impersonation token, not even by the
+    // peer being impersonated.
+    let uri = format!("/users/{}/impersonate", oncall.id);
+    let response = send(&app, empty_request("POST", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let impersonation_token = read_json(response).await["access_token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let response = send(
+        &app,
+        json_request("POST", &approve_uri, Some(&impersonation_token), json!({})),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let uri = format!("/roles/{role_id}/users/{}", user.id);
+    let response = send(&app, empty_request("PUT", &uri, Some(&impersonation_token))).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = send(
+        &app,
+        json_request("POST", &approve_uri, Some(&oncall_token), json!({})),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let approved = read_json(response).await;
+    assert_eq!(approved["status"], "approved");
+    assert!(approved["grant_expires_at"].is_string());
+
+    let response = send(
+        &app,
+        json_request("POST", &approve_uri, Some(&admin_token), json!({})),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response = send(&app, empty_request("GET", "/users", Some(&user_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Once the grant lapses it stops counting immediately; the sweep records the expiry.
+    sqlx::query("UPDATE user_roles SET expires_at = NOW() - INTERVAL '1 minute' WHERE user_id = $1 AND expires_at IS NOT NULL")
+        .bind(user.id)
+        .execute(&state.db)
+        .await
+        .unwrap();
+
+    let response = send(&app, empty_request("GET", "/users", Some(&user_token))).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let roles = SqlxRoleRepository::new(state.db.clone());
+    assert_eq!(roles.sweep_expired().await.unwrap(), 1);
+
+    let history_uri = format!("/roles/{role_id}/history");
+    let response = send(&app, empty_request("GET", &history_uri, Some(&admin_token))).await;
+    let history = read_json(response).await;
+    assert_eq!(history[0]["action"], "expired");
+
+    // Admins can also grant directly for a bounded time.
+    let uri = format!("/roles/{role_id}/users/{}?expires_in_minutes=15", user.id);
+    let response = send(&app, empty_request("PUT", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = send(&app, empty_request("GET", &history_uri, Some(&admin_token))).await;
+    let history = read_json(response).await;
+    assert_eq!(history[0]["action"], "assigned");
+    assert!(history[0]["expires_at"].is_string());
+}
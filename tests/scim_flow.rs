@@ -0,0 +1,200 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{empty_request, json_request, read_json, register, reset_db, send, setup_app};
+use serde_json::json;
+use serial_test::serial;
+
+const SCIM_TOKEN: &str = "scim-test-token";
+
+#[tokio::test]
+#[serial]
+async fn scim_user_lifecycle() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let body = json!({
+        "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
+        "userName": "alice",
+        "externalId": "00u1",
+        "emails": [
+            { "value": "alice@old.example.com", "type": "home" },
+            { "value": "alice@example.com", "type": "work", "primary": true }
+        ],
+        "password": "password123"
+    });
+    let response = send(
+        &app,
+        json_request("POST", "/scim/v2/Users", Some(SCIM_TOKEN), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(response.headers()["content-type"], "application/scim+json");
+    let created = read_json(response).await;
+    assert_eq!(created["userName"], "alice");
+    assert_eq!(created["emails"][0]["value"], "alice@example.com");
+    assert_eq!(created["active"], true);
+    let id = created["id"].as_str().unwrap().to_string();
+
+    register(&state, &app, "bob@example.com", "bobuser").await;
+
+    let uri = "/scim/v2/Users?filter=userName%20eq%20%22ALICE%22";
+    let response = send(&app, empty_request("GET", uri, Some(SCIM_TOKEN))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let list = read_json(response).await;
+    assert_eq!(list["totalResults"], 1);
+    assert_eq!(list["Resources"][0]["id"], id.as_str());
+
+    let uri = "/scim/v2/Users?filter=emails.value%20co%20%22example.com%22%20and%20active%20eq%20true&count=1";
+    let response = send(&app, empty_request("GET", uri, Some(SCIM_TOKEN))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let list = read_json(response).await;
+    assert_eq!(list["totalResults"], 2);
+    assert_eq!(list["itemsPerPage"], 1);
+
+    let body = json!({ "email": "alice@example.com", "password": "password123" });
+    let response = send(&app, json_request("POST", "/auth/login", None, body)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = json!({
+        "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+        "Operations": [{ "op": "Replace", "path": "active", "value": "False" }]
+    });
+    let uri = format!("/scim/v2/Users/{id}");
+    let response = send(&app, json_request("PATCH", &uri, Some(SCIM_TOKEN), body)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json(response).await["active"], false);
+
+    let body = json!({ "email": "alice@example.com", "password": "password123" });
+    let response = send(&app, json_request("POST", "/auth/login", None, body)).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(read_json(response).await["code"], "account_deactivated");
+
+    let body = json!({ "Operations": [{ "op": "replace", "value": { "active": true, "userName": "alice2" } }] });
+    let response = send(&app, json_request("PATCH", &uri, Some(SCIM_TOKEN), body)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let user = read_json(response).await;
+    assert_eq!(user["active"], true);
+    assert_eq!(user["userName"], "alice2");
+
+    // Deprovisioning keeps the account, deactivated.
+    let response = send(&app, empty_request("DELETE", &uri, Some(SCIM_TOKEN))).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = send(&app, empty_request("GET", &uri, Some(SCIM_TOKEN))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json(response).await["active"], false);
+
+    let body = json!({ "userName": "bobuser", "emails": [{ "value": "other@example.com" }] });
+    let response = send(
+        &app,
+        json_request("POST", "/scim/v2/Users", Some(SCIM_TOKEN), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(read_json(response).await["scimType"], "uniqueness");
+}
+
+#[tokio::test]
+#[serial]
+async fn scim_groups_track_members() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (alice, _) = register(&state, &app, "alice@example.com", "aliceuser").await;
+    let (bob, _) = register(&state, &app, "bob@example.com", "bobuser").await;
+
+    let body = json!({ "displayName": "engineering", "members": [{ "value": alice.id }] });
+    let response = send(
+        &app,
+        json_request("POST", "/scim/v2/Groups", Some(SCIM_TOKEN), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let group = read_json(response).await;
+    assert_eq!(group["members"][0]["value"], alice.id.to_string());
+    let uri = format!("/scim/v2/Groups/{}", group["id"].as_str().unwrap());
+
+    let body = json!({
+        "Operations": [
+            { "op": "add", "path": "members", "value": [{ "value": bob.id }] },
+            { "op": "remove", "path": format!("members[value eq \"{}\"]", alice.id) }
+        ]
+    });
+    let response = send(&app, json_request("PATCH", &uri, Some(SCIM_TOKEN), body)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let members = read_json(response).await["members"].clone();
+    assert_eq!(
+        members,
+        json!([{
+            "value": bob.id.to_string(),
+            "display": "bobuser",
+            "type": "User",
+            "$ref": format!("/scim/v2/Users/{}", bob.id)
+        }])
+    );
+
+    let uri_filter = "/scim/v2/Groups?filter=displayName%20sw%20%22eng%22";
+    let response = send(&app, empty_request("GET", uri_filter, Some(SCIM_TOKEN))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json(response).await["totalResults"], 1);
+
+    let response = send(&app, empty_request("DELETE", &uri, Some(SCIM_TOKEN))).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = send(&app, empty_request("GET", &uri, Some(SCIM_TOKEN))).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[serial]
+async fn scim_rejects_bad_tokens_and_filters() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_, user_token) = register(&state, &app, "alice@example.com", "aliceuser").await;
+    for token in [None, Some("wrong-token"), Some(user_token.as_str())] {
+        let response = send(&app, empty_request("GET", "/scim/v2/Users", token)).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let body = read_json(response).await;
+        assert_eq!(
+            body["schemas"],
+            json!(["urn:ietf:params:scim:api:messages:2.0:Error"])
+        );
+        assert_eq!(body["status"], "401");
+    }
+
+    let uri = "/scim/v2/Users?filter=userName%20eq";
+    let response = send(&app, empty_request("GET", uri, Some(SCIM_TOKEN))).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(read_json(response).await["scimType"], "invalidFilter");
+
+    let uri = "/scim/v2/Users?filter=nickName%20pr";
+    let response = send(&app, empty_request("GET", uri, Some(SCIM_TOKEN))).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(read_json(response).await["scimType"], "invalidFilter");
+
+    let response = send(
+        &app,
+        empty_request("GET", "/scim/v2/ServiceProviderConfig", Some(SCIM_TOKEN)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let config = read_json(response).await;
+    assert_eq!(config["patch"]["supported"], true);
+    assert_eq!(config["filter"]["maxResults"], 200);
+
+    let response = send(
+        &app,
+        empty_request("GET", "/scim/v2/ResourceTypes", Some(SCIM_TOKEN)),
+    )
+    .await;
+    assert_eq!(read_json(response).await["totalResults"], 2);
+    let response = send(
+        &app,
+        empty_request("GET", "/scim/v2/Schemas", Some(SCIM_TOKEN)),
+    )
+    .await;
+    assert_eq!(
+        read_json(response).await["Resources"][0]["id"],
+        "urn:ietf:params:scim:schemas:core:2.0:User"
+    );
+}
@@ -0,0 +1,750 @@
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{Duration, SecondsFormat, Utc};
+use common::{
+    empty_request, json_request, read_json, register, register_admin, reset_db, send, setup_app,
+};
+use serde_json::{json, Value};
+use serial_test::serial;
+use user_management_backend_rust::domain::{AuditContext, UserRepository};
+use user_management_backend_rust::infra::db::user_repo::SqlxUserRepository;
+
+async fn usernames(app: &axum::Router, token: &str, query: &str) -> Vec<String> {
+    let response = send(
+        app,
+        empty_request("GET", &format!("/users?{query}"), Some(token)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK, "{query}");
+    read_json(response)
+        .await
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|user: &Value| user["username"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_user_list_filters_searches_and_sorts() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_, token) = register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    register(&state, &app, "alice@acme.io", "alice").await;
+    register(&state, &app, "bob@example.com", "bobby").await;
+    let (carol, _) = register(&state, &app, "carol@ACME.io", "carol").await;
+    let uri = format!("/users/{}", carol.id);
+    let response = send(&app, empty_request("DELETE", &uri, Some(&token))).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    assert_eq!(
+        usernames(&app, &token, "").await,
+        vec!["carol", "bobby", "alice", "adminuser"]
+    );
+    assert_eq!(
+        usernames(&app, &token, "sort=username").await,
+        vec!["adminuser", "alice", "bobby", "carol"]
+    );
+    assert_eq!(
+        usernames(&app, &token, "sort=-email&per_page=2").await,
+        vec!["carol", "bobby"]
+    );
+
+    // Substring matches ignore case; trigram matches tolerate typos.
+    assert_eq!(
+        usernames(&app, &token, "search=ACME&sort=username").await,
+        vec!["alice", "carol"]
+    );
+    assert_eq!(usernames(&app, &token, "search=alce").await, vec!["alice"]);
+    assert_eq!(
+        usernames(&app, &token, "search=100%25").await,
+        Vec::<String>::new()
+    );
+
+    assert_eq!(
+        usernames(&app, &token, "email_domain=acme.io&sort=username").await,
+        vec!["alice", "carol"]
+    );
+    assert_eq!(
+        usernames(&app, &token, "email_domain=%40acme.io&is_active=false").await,
+        vec!["carol"]
+    );
+    assert_eq!(
+        usernames(&app, &token, "role=admin").await,
+        vec!["adminuser"]
+    );
+
+    let later = (Utc::now() + Duration::hours(1)).to_rfc3339_opts(SecondsFormat::Secs, true);
+    let earlier = (Utc::now() - Duration::hours(1)).to_rfc3339_opts(SecondsFormat::Secs, true);
+    assert!(usernames(&app, &token, &format!("created_from={later}"))
+        .await
+        .is_empty());
+    assert_eq!(
+        usernames(
+            &app,
+            &token,
+            &format!("created_from={earlier}&created_to={later}")
+        )
+        .await
+        .len(),
+        4
+    );
+
+    for query in [
+        "sort=password_hash",
+        &format!("created_from={later}&created_to={earlier}"),
+    ] {
+        let response = send(
+            &app,
+            empty_request("GET", &format!("/users?{query}"), Some(&token)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{query}");
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn user_list_pages_by_cursor_with_links() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_, token) = register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    for name in ["userone", "usertwo", "userthree", "userfour"] {
+        register(&state, &app, &format!("{name}@example.com"), name).await;
+    }
+
+    let response = send(
+        &app,
+        empty_request("GET", "/users?limit=2&include_total=true", Some(&token)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let link = response.headers()["link"].to_str().unwrap().to_string();
+    let body = read_json(response).await;
+    let names: Vec<&str> = body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|user| user["username"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["userfour", "userthree"]);
+    assert_eq!(body["total"], 5);
+    let cursor = body["next_cursor"].as_str().unwrap().to_string();
+    assert_eq!(
+        link,
+        format!("</users?limit=2&include_total=true&cursor={cursor}>; rel=\"next\"")
+    );
+
+    // A user added meanwhile does not shift the following pages.
+    register(&state, &app, "late@example.com", "lateuser").await;
+
+    let uri = format!("/users?limit=2&cursor={cursor}");
+    let body = read_json(send(&app, empty_request("GET", &uri, Some(&token))).await).await;
+    assert_eq!(body["items"][0]["username"], "usertwo");
+    assert_eq!(body["items"][1]["username"], "userone");
+    assert!(body.get("total").unwrap().is_null());
+    let uri = format!(
+        "/users?limit=2&cursor={}",
+        body["next_cursor"].as_str().unwrap()
+    );
+    let response = send(&app, empty_request("GET", &uri, Some(&token))).await;
+    assert!(response.headers().get("link").is_none());
+    let body = read_json(response).await;
+    assert_eq!(body["items"].as_array().unwrap().len(), 1);
+    assert!(body["next_cursor"].is_null());
+
+    // The page/per_page mode still returns a bare array.
+    let response = send(
+        &app,
+        empty_request(
+            "GET",
+            "/users?page=2&per_page=2&include_total=true",
+            Some(&token),
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-total-count"], "6");
+    assert_eq!(
+        response.headers()["link"],
+        "</users?per_page=2&include_total=true&page=1>; rel=\"prev\", </users?per_page=2&include_total=true&page=3>; rel=\"next\""
+    );
+    assert_eq!(read_json(response).await.as_array().unwrap().len(), 2);
+
+    for query in [
+        "limit=2&page=2",
+        "limit=2&sort=email",
+        "cursor=not-a-cursor",
+    ] {
+        let response = send(
+            &app,
+            empty_request("GET", &format!("/users?{query}"), Some(&token)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{query}");
+    }
+}
+
+async fn read_text(response: axum::response::Response) -> String {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_exports_filtered_users() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_, token) = register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    register(&state, &app, "alice@acme.io", "alice").await;
+    let (_, user_token) = register(&state, &app, "bob@acme.io", "bobby").await;
+
+    let response = send(&app, empty_request("GET", "/users/export", Some(&token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "application/x-ndjson");
+    let body = read_text(response).await;
+    let users: Vec<Value> = body
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let names: Vec<&str> = users
+        .iter()
+        .map(|user| user["username"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["bobby", "alice", "adminuser"]);
+
+    let uri = "/users/export?format=csv&email_domain=acme.io";
+    let response = send(&app, empty_request("GET", uri, Some(&token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = read_text(response).await;
+    let lines: Vec<&str> = body.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("id,email,username,roles"));
+    assert!(lines[1].contains(",bob@acme.io,bobby,user,true,"));
+
+    for query in ["sort=username", "page=2", "limit=10"] {
+        let response = send(
+            &app,
+            empty_request("GET", &format!("/users/export?{query}"), Some(&token)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{query}");
+    }
+
+    let response = send(
+        &app,
+        empty_request("GET", "/users/export", Some(&user_token)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+#[serial]
+async fn bulk_operations_report_per_user_and_roll_back_together() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (admin, token) = register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    let (alice, _) = register(&state, &app, "alice@example.com", "alice").await;
+    let (bob, _) = register(&state, &app, "bob@example.com", "bobby").await;
+    let missing = uuid::Uuid::new_v4();
+
+    let body = json!({ "action": "deactivate", "user_ids": [alice.id, bob.id, missing, alice.id] });
+    let response = send(
+        &app,
+        json_request("POST", "/users/bulk", Some(&token), body.clone()),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let outcomes: Vec<Value> = read_json(response).await["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| result["outcome"].clone())
+        .collect();
+    assert_eq!(
+        outcomes,
+        vec![json!("updated"), json!("updated"), json!("not_found")]
+    );
+
+    let response = send(
+        &app,
+        json_request("POST", "/users/bulk", Some(&token), body),
+    )
+    .await;
+    assert_eq!(
+        read_json(response).await["results"][0]["outcome"],
+        "unchanged"
+    );
+    assert_eq!(
+        usernames(&app, &token, "is_active=false&sort=username").await,
+        vec!["alice", "bobby"]
+    );
+
+    let body = json!({ "action": "reactivate", "user_ids": [alice.id] });
+    let response = send(
+        &app,
+        json_request("POST", "/users/bulk", Some(&token), body),
+    )
+    .await;
+    assert_eq!(
+        read_json(response).await["results"][0]["outcome"],
+        "updated"
+    );
+
+    let body = json!({ "action": "set_role", "role": "admin", "user_ids": [alice.id] });
+    let response = send(
+        &app,
+        json_request("POST", "/users/bulk", Some(&token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        usernames(&app, &token, "role=admin&sort=username").await,
+        vec!["adminuser", "alice"]
+    );
+    assert!(usernames(&app, &token, "role=user")
+        .await
+        .contains(&"bobby".to_string()));
+    assert!(!usernames(&app, &token, "role=user")
+        .await
+        .contains(&"alice".to_string()));
+
+    // Demoting both admins would leave none, so neither change is kept.
+    let body = json!({ "action": "set_role", "role": "user", "user_ids": [alice.id, admin.id] });
+    let response = send(
+        &app,
+        json_request("POST", "/users/bulk", Some(&token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(read_json(response).await["code"], "last_admin");
+    assert_eq!(
+        usernames(&app, &token, "role=admin&sort=username").await,
+        vec!["adminuser", "alice"]
+    );
+
+    let body = json!({ "action": "deactivate", "user_ids": [admin.id] });
+    let response = send(
+        &app,
+        json_request("POST", "/users/bulk", Some(&token), body),
+    )
+    .await;
+    assert_eq!(read_json(response).await["code"], "confirmation_required");
+
+    for body in [
+        json!({ "action": "set_role", "user_ids": [alice.id] }),
+        json!({ "action": "reactivate", "role": "admin", "user_ids": [alice.id] }),
+        json!({ "action": "deactivate", "user_ids": [] }),
+        json!({ "action": "set_role", "role": "wizard", "user_ids": [alice.id] }),
+    ] {
+        let response = send(
+            &app,
+            json_request("POST", "/users/bulk", Some(&token), body.clone()),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{body}");
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn profile_fields_are_validated_and_clearable() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_user, token) = register(&state, &app, "alice@example.com", "aliceuser").await;
+
+    let body = json!({
+        "name": "Alice Liddell",
+        "given_name": "Alice",
+        "family_name": "Liddell",
+        "locale": "en-GB",
+        "zoneinfo": "Europe/London",
+        "picture": "https://cdn.example.com/alice.png"
+    });
+    let response = send(&app, json_request("PATCH", "/users/me", Some(&token), body)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let profile = read_json(response).await;
+    assert_eq!(profile["name"], "Alice Liddell");
+    assert_eq!(profile["locale"], "en-GB");
+    assert_eq!(profile["zoneinfo"], "Europe/London");
+    assert_eq!(profile["picture"], "https://cdn.example.com/alice.png");
+
+    for body in [
+        json!({ "locale": "en_GB" }),
+        json!({ "zoneinfo": "Europe/Atlantis" }),
+        json!({ "picture": "javascript:alert(1)" }),
+        json!({ "given_name": "a".repeat(65) }),
+    ] {
+        let response = send(
+            &app,
+            json_request("PATCH", "/users/me", Some(&token), body.clone()),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{body}");
+    }
+
+    // Omitted fields are kept; empty strings clear.
+    let body = json!({ "picture": "", "zoneinfo": "" });
+    let response = send(&app, json_request("PATCH", "/users/me", Some(&token), body)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let profile = read_json(response).await;
+    assert_eq!(profile["picture"], Value::Null);
+    assert_eq!(profile["zoneinfo"], Value::Null);
+    assert_eq!(profile["given_name"], "Alice");
+}
+
+#[tokio::test]
+#[serial]
+async fn reserved_usernames_and_blocked_domains_are_refused_unless_an_admin_bypasses() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_, admin_token) = register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    let (user, user_token) = register(&state, &app, "user@example.com", "userone").await;
+
+    for (email, username) in [
+        ("new@example.com", "Admin"),
+        ("new@example.com", "the_BadW0rd"),
+        ("new@mailinator.com", "newuser"),
+        ("new@eu.yopmail.com", "newuser"),
+    ] {
+        let body = json!({ "email": email, "username": username, "password": "password123" });
+        let response = send(&app, json_request("POST", "/auth/register", None, body)).await;
+        assert_eq!(
+            response.status(),
+            StatusCode::BAD_REQUEST,
+            "{email} {username}"
+        );
+    }
+
+    let body = json!({ "username": "support" });
+    let response = send(
+        &app,
+        json_request("PATCH", "/users/me", Some(&user_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = json!({ "email": "user@mailinator.com" });
+    let response = send(
+        &app,
+        json_request("PATCH", "/users/me", Some(&user_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let uri = format!("/users/{}", user.id);
+    let body = json!({ "username": "support" });
+    let response = send(
+        &app,
+        json_request("PATCH", &uri, Some(&admin_token), body.clone()),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let uri = format!("/users/{}?bypass_policy=true", user.id);
+    let response = send(&app, json_request("PATCH", &uri, Some(&admin_token), body)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json(response).await["username"], "support");
+
+    // Once granted, the user keeps the name through later profile changes.
+    let body = json!({ "username": "Support", "name": "Help Desk" });
+    let response = send(
+        &app,
+        json_request("PATCH", "/users/me", Some(&user_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+async fn login(app: &axum::Router, email: &str) -> axum::response::Response {
+    let body = json!({ "email": email, "password": "password123" });
+    send(app, json_request("POST", "/auth/login", None, body)).await
+}
+
+async fn bulk_outcomes(app: &axum::Router, token: &str, body: Value) -> Vec<Value> {
+    let response = send(app, json_request("POST", "/users/bulk", Some(token), body)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    read_json(response).await["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| result["outcome"].clone())
+        .collect()
+}
+
+#[tokio::test]
+#[serial]
+async fn bulk_operations_report_users_the_action_does_not_apply_to() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_, token) = register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    let (alice, _) = register(&state, &app, "alice@example.com", "alice").await;
+    let (bob, _) = register(&state, &app, "bob@example.com", "bobby").await;
+    let (carol, _) = register(&state, &app, "carol@example.com", "carol").await;
+
+    let uri = format!("/users/{}/status", carol.id);
+    let body = json!({ "status": "deleted" });
+    let response = send(&app, json_request("POST", &uri, Some(&token), body)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // A deleted account in the batch is reported and the others still change.
+    let body = json!({ "action": "deactivate", "user_ids": [alice.id, carol.id] });
+    assert_eq!(
+        bulk_outcomes(&app, &token, body).await,
+        vec![json!("updated"), json!("invalid_transition")]
+    );
+    assert_eq!(
+        read_json(login(&app, "alice@example.com").await).await["code"],
+        "account_deactivated"
+    );
+
+    // An ended suspension is already active.
+    let uri = format!("/users/{}/status", bob.id);
+    let until = (Utc::now() + Duration::hours(1)).to_rfc3339_opts(SecondsFormat::Secs, true);
+    let body = json!({ "status": "suspended", "reason": "spam", "until": until });
+    let response = send(&app, json_request("POST", &uri, Some(&token), body)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    sqlx::query("UPDATE users SET status_until = NOW() - INTERVAL '1 minute' WHERE id = $1")
+        .bind(bob.id)
+        .execute(&state.db)
+        .await
+        .unwrap();
+
+    let body = json!({ "action": "reactivate", "user_ids": [bob.id, carol.id, alice.id] });
+    assert_eq!(
+        bulk_outcomes(&app, &token, body).await,
+        vec![
+            json!("unchanged"),
+            json!("invalid_transition"),
+            json!("updated")
+        ]
+    );
+
+    let body = json!({ "action": "set_role", "role": "admin", "user_ids": [carol.id] });
+    assert_eq!(
+        bulk_outcomes(&app, &token, body).await,
+        vec![json!("invalid_transition")]
+    );
+    assert_eq!(
+        usernames(&app, &token, "role=admin").await,
+        vec!["adminuser"]
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn account_status_transitions_are_enforced_with_distinct_codes() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (_, admin_token) = register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    let (alice, alice_token) = register(&state, &app, "alice@example.com", "alice").await;
+    let status_uri = format!("/users/{}/status", alice.id);
+
+    for body in [
+        json!({ "status": "suspended" }),
+        json!({ "status": "suspended", "reason": "spam", "until": "2000-01-01T00:00:00Z" }),
+        json!({ "status": "deactivated", "until": "2999-01-01T00:00:00Z" }),
+        json!({ "status": "unknown" }),
+    ] {
+        let response = send(
+            &app,
+            json_request("POST", &status_uri, Some(&admin_token), body.clone()),
+        )
+        .await;
+        assert!(response.status().is_client_error(), "{body}");
+    }
+
+    let until = (Utc::now() + Duration::hours(1)).to_rfc3339_opts(SecondsFormat::Secs, true);
+    let body = json!({ "status": "suspended", "reason": "spam reports", "until": until });
+    let response = send(
+        &app,
+        json_request("POST", &status_uri, Some(&admin_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let user = read_json(response).await;
+    assert_eq!(user["status"], "suspended");
+    assert_eq!(user["status_reason"], "spam reports");
+    assert_eq!(user["is_active"], false);
+    assert_eq!(
+        usernames(&app, &admin_token, "status=suspended").await,
+        vec!["alice"]
+    );
+
+    // Existing tokens stop working along with new logins.
+    let response = send(&app, empty_request("GET", "/users/me", Some(&alice_token))).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(read_json(response).await["code"], "account_suspended");
+    let response = login(&app, "alice@example.com").await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(read_json(response).await["code"], "account_suspended");
+
+    // An ended suspension no longer blocks; the sweep then records the reactivation.
+    sqlx::query("UPDATE users SET status_until = NOW() - INTERVAL '1 minute' WHERE id = $1")
+        .bind(alice.id)
+        .execute(&state.db)
+        .await
+        .unwrap();
+    assert_eq!(
+        login(&app, "alice@example.com").await.status(),
+        StatusCode::OK
+    );
+    assert!(usernames(&app, &admin_token, "status=suspended")
+        .await
+        .is_empty());
+    assert!(usernames(&app, &admin_token, "is_active=false")
+        .await
+        .is_empty());
+    let repo = SqlxUserRepository::new(state.db.clone());
+    assert_eq!(
+        repo.lift_expired_suspensions(&AuditContext::default())
+            .await
+            .unwrap(),
+        1
+    );
+    let lifted: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM audit_events WHERE event_type = 'user.status_changed' AND target_user_id = $1 AND actor_id IS NULL",
+    )
+    .bind(alice.id)
+    .fetch_one(&state.db)
+    .await
+    .unwrap();
+    assert_eq!(lifted, 1);
+
+    let body = json!({ "status": "locked", "reason": "credential stuffing" });
+    let response = send(
+        &app,
+        json_request("POST", &status_uri, Some(&admin_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        read_json(login(&app, "alice@example.com").await).await["code"],
+        "account_locked"
+    );
+
+    let body = json!({ "status": "deactivated" });
+    let response = send(
+        &app,
+        json_request("POST", &status_uri, Some(&admin_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = json!({ "status": "locked", "reason": "credential stuffing" });
+    let response = send(
+        &app,
+        json_request("POST", &status_uri, Some(&admin_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(
+        read_json(response).await["code"],
+        "invalid_status_transition"
+    );
+
+    // Deletion is final.
+    let body = json!({ "status": "deleted" });
+    let response = send(
+        &app,
+        json_request("POST", &status_uri, Some(&admin_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = json!({ "status": "active" });
+    let response = send(
+        &app,
+        json_request("POST", &status_uri, Some(&admin_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(
+        read_json(login(&app, "alice@example.com").await).await["code"],
+        "account_deleted"
+    );
+
+    // Users can deactivate their own account, once confirmed.
+    let (_, bob_token) = register(&state, &app, "bob@example.com", "bobby").await;
+    let response = send(
+        &app,
+        empty_request("POST", "/users/me/deactivate", Some(&bob_token)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(read_json(response).await["code"], "confirmation_required");
+    let response = send(
+        &app,
+        empty_request(
+            "POST",
+            "/users/me/deactivate?confirm=true",
+            Some(&bob_token),
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        read_json(login(&app, "bob@example.com").await).await["code"],
+        "account_deactivated"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn is_active_switches_between_active_and_deactivated() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+
+    let (admin, admin_token) = register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    let (alice, _) = register(&state, &app, "alice@example.com", "alice").await;
+    let uri = format!("/users/{}", alice.id);
+
+    let body = json!({ "is_active": false });
+    let response = send(&app, json_request("PATCH", &uri, Some(&admin_token), body)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let user = read_json(response).await;
+    assert_eq!(user["status"], "deactivated");
+    assert_eq!(user["is_active"], false);
+    assert_eq!(
+        read_json(login(&app, "alice@example.com").await).await["code"],
+        "account_deactivated"
+    );
+
+    let body = json!({ "is_active": true });
+    let response = send(&app, json_request("PATCH", &uri, Some(&admin_token), body)).await;
+    assert_eq!(read_json(response).await["status"], "active");
+    assert_eq!(
+        login(&app, "alice@example.com").await.status(),
+        StatusCode::OK
+    );
+
+    // A lock is only lifted through the status endpoint.
+    let body = json!({ "status": "locked", "reason": "credential stuffing" });
+    let status_uri = format!("/users/{}/status", alice.id);
+    let response = send(
+        &app,
+        json_request("POST", &status_uri, Some(&admin_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = json!({ "is_active": true });
+    let response = send(&app, json_request("PATCH", &uri, Some(&admin_token), body)).await;
+    assert_eq!(read_json(response).await["status"], "locked");
+
+    let uri = format!("/users/{}", admin.id);
+    let body = json!({ "is_active": false });
+    let response = send(&app, json_request("PATCH", &uri, Some(&admin_token), body)).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(read_json(response).await["code"], "confirmation_required");
+}
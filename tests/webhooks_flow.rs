@@ -0,0 +1,240 @@
+mod common;
+
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use common::{
+    empty_request, json_request, read_json, register, register_admin, reset_db, send, setup_app,
+};
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use serial_test::serial;
+use sha2::Sha256;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::{Arc, Mutex};
+use user_management_backend_rust::app::services::outbox_service::OutboxRelay;
+use user_management_backend_rust::app::services::webhook_service::{
+    WebhookService, WebhookSettings,
+};
+use user_management_backend_rust::domain::{Role, RoleRepository};
+use user_management_backend_rust::infra::db::outbox_repo::SqlxOutboxRepository;
+use user_management_backend_rust::infra::db::role_repo::SqlxRoleRepository;
+use user_management_backend_rust::infra::db::webhook_repo::SqlxWebhookRepository;
+use user_management_backend_rust::infra::webhook::HttpWebhookSender;
+use user_management_backend_rust::AppState;
+
+/// Local HTTP endpoint that records what it receives and answers with `status`.
+#[derive(Default)]
+struct Receiver {
+    requests: Mutex<Vec<(HeaderMap, String)>>,
+    status: AtomicU16,
+}
+
+async fn receive(
+    State(receiver): State<Arc<Receiver>>,
+    headers: HeaderMap,
+    body: String,
+) -> StatusCode {
+    receiver.requests.lock().unwrap().push((headers, body));
+    StatusCode::from_u16(receiver.status.load(Ordering::SeqCst)).unwrap()
+}
+
+async fn spawn_receiver() -> (Arc<Receiver>, String) {
+    let receiver = Arc::new(Receiver {
+        status: AtomicU16::new(200),
+        ..Default::default()
+    });
+    let app = axum::Router::new()
+        .route("/hook", post(receive))
+        .with_state(receiver.clone());
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (receiver, url)
+}
+
+/// Relays pending domain events into deliveries, then attempts the due deliveries.
+async fn dispatch(state: &AppState) -> usize {
+    let relay = OutboxRelay::new(
+        SqlxOutboxRepository::new(state.db.clone()),
+        SqlxWebhookRepository::new(state.db.clone()),
+    );
+    while relay.relay_pending().await.unwrap() > 0 {}
+
+    let settings = WebhookSettings::from_config(&state.config);
+    let service = WebhookService::new(
+        SqlxWebhookRepository::new(state.db.clone()),
+        HttpWebhookSender::new(settings.timeout),
+        settings,
+    );
+    service.dispatch_due().await.unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn user_events_are_delivered_signed_and_retried() {
+    let (state, app) = setup_app().await;
+    reset_db(&state).await;
+    let (receiver, url) = spawn_receiver().await;
+
+    let (_, admin_token) = register_admin(&state, &app, "admin@example.com", "adminuser").await;
+    // Subscriptions apply from the moment events are relayed, so flush the admin's own.
+    assert_eq!(dispatch(&state).await, 0);
+
+    let body = json!({
+        "url": url,
+        "event_types": ["user.created", "user.logged_in", "user.role_changed"]
+    });
+    let response = send(
+        &app,
+        json_request("POST", "/webhooks", Some(&admin_token), body.clone()),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let webhook = read_json(response).await;
+    let webhook_id = webhook["id"].as_str().unwrap().to_string();
+    let secret = webhook["secret"].as_str().unwrap().to_string();
+    assert!(secret.starts_with("whsec_"));
+
+    let response = send(
+        &app,
+        empty_request(
+            "GET",
+            &format!("/webhooks/{webhook_id}"),
+            Some(&admin_token),
+        ),
+    )
+    .await;
+    assert!(read_json(response).await.get("secret").is_none());
+
+    let (user, user_token) = register(&state, &app, "user@example.com", "userone").await;
+    let response = send(
+        &app,
+        json_request("POST", "/webhooks", Some(&user_token), body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let login = json!({ "email": "user@example.com", "password": "password123" });
+    let response = send(&app, json_request("POST", "/auth/login", None, login)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    // Not subscribed: no delivery is queued for it.
+    let response = send(
+        &app,
+        json_request(
+            "PATCH",
+            "/users/me",
+            Some(&user_token),
+            json!({ "username": "renamed" }),
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert_eq!(dispatch(&state).await, 2);
+    {
+        let requests = receiver.requests.lock().unwrap();
+        let types: Vec<String> = requests
+            .iter()
+            .map(|(_, body)| {
+                serde_json::from_str::<Value>(body).unwrap()["type"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(types, vec!["user.created", "user.logged_in"]);
+
+        for (headers, body) in requests.iter() {
+            let timestamp = headers["x-webhook-timestamp"].to_str().unwrap();
+            let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+            mac.update(format!("{timestamp}.{body}").as_bytes());
+            let expected = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+            assert_eq!(headers["x-webhook-signature"], expected.as_str());
+
+            let payload: Value = serde_json::from_str(body).unwrap();
+            assert_eq!(payload["user_id"], user.id.to_string());
+            assert_eq!(
+                headers["x-webhook-event"],
+                payload["type"].as_str().unwrap()
+            );
+        }
+    }
+    assert_eq!(dispatch(&state).await, 0);
+
+    // A failing receiver gets one retry (the test config allows two attempts) and the
+    // delivery then goes dead until it is redelivered.
+    receiver.status.store(500, Ordering::SeqCst);
+    let admin_role = SqlxRoleRepository::new(state.db.clone())
+        .find_by_name(Role::ADMIN)
+        .await
+        .unwrap()
+        .unwrap();
+    let uri = format!("/roles/{}/users/{}", admin_role.id, user.id);
+    let response = send(&app, empty_request("PUT", &uri, Some(&admin_token))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert_eq!(dispatch(&state).await, 1);
+    let deliveries_uri = format!("/webhooks/{webhook_id}/deliveries");
+    let response = send(
+        &app,
+        empty_request("GET", &deliveries_uri, Some(&admin_token)),
+    )
+    .await;
+    let deliveries = read_json(response).await;
+    let delivery = &deliveries[0];
+    assert_eq!(delivery["event_type"], "user.role_changed");
+    assert_eq!(delivery["status"], "pending");
+    assert_eq!(delivery["attempts"], 1);
+    assert_eq!(delivery["last_status_code"], 500);
+    let delivery_id = delivery["id"].as_str().unwrap().to_string();
+
+    // Not due yet: the retry waits for the backoff.
+    assert_eq!(dispatch(&state).await, 0);
+    sqlx::query("UPDATE webhook_deliveries SET next_attempt_at = NOW() WHERE status = 'pending'")
+        .execute(&state.db)
+        .await
+        .unwrap();
+    assert_eq!(dispatch(&state).await, 1);
+
+    let response = send(
+        &app,
+        empty_request("GET", &deliveries_uri, Some(&admin_token)),
+    )
+    .await;
+    let deliveries = read_json(response).await;
+    assert_eq!(deliveries[0]["status"], "dead");
+    assert_eq!(deliveries[0]["attempts"], 2);
+
+    receiver.status.store(204, Ordering::SeqCst);
+    let redeliver_uri = format!("/webhooks/{webhook_id}/deliveries/{delivery_id}/redeliver");
+    let response = send(
+        &app,
+        empty_request("POST", &redeliver_uri, Some(&admin_token)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let response = send(
+        &app,
+        empty_request("POST", &redeliver_uri, Some(&admin_token)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    assert_eq!(dispatch(&state).await, 1);
+    let response = send(
+        &app,
+        empty_request("GET", &deliveries_uri, Some(&admin_token)),
+    )
+    .await;
+    let deliveries = read_json(response).await;
+    assert_eq!(deliveries[0]["status"], "succeeded");
+    assert!(deliveries[0]["delivered_at"].is_string());
+
+    let requests = receiver.requests.lock().unwrap();
+    let (_, body) = requests.last().unwrap();
+    let payload: Value = serde_json::from_str(body).unwrap();
+    assert_eq!(payload["data"]["change"], "assigned");
+    assert_eq!(payload["data"]["metadata"]["role"], "admin");
+}